@@ -0,0 +1,38 @@
+use futures_util::StreamExt;
+use poem_grpc::{ClientConfig, Request, Streaming};
+
+poem_grpc::include_proto!("echo");
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+    let client = EchoClient::new(
+        ClientConfig::builder()
+            .uri("http://localhost:3000")
+            .build()
+            .unwrap(),
+    );
+
+    let (tx, rx) = tokio::sync::mpsc::channel(16);
+    tokio::spawn(async move {
+        for message in ["hello", "poem", ""] {
+            let req = EchoRequest {
+                message: message.to_string(),
+            };
+            if tx.send(Ok(req)).await.is_err() {
+                break;
+            }
+        }
+    });
+
+    let response = client
+        .bidi_echo(Request::new(Streaming::from_receiver(rx)))
+        .await?;
+    let mut stream = response.into_inner();
+    while let Some(reply) = stream.next().await {
+        match reply {
+            Ok(reply) => println!("REPLY={}", reply.message),
+            Err(status) => println!("STATUS={status}"),
+        }
+    }
+    Ok(())
+}
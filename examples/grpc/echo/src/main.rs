@@ -0,0 +1,30 @@
+use poem::{listener::TcpListener, Server};
+use poem_grpc::{Code, Request, Response, RouteGrpc, Status, Streaming};
+
+poem_grpc::include_proto!("echo");
+
+struct EchoService;
+
+impl Echo for EchoService {
+    async fn bidi_echo(
+        &self,
+        request: Request<Streaming<EchoRequest>>,
+    ) -> Result<Response<Streaming<EchoReply>>, Status> {
+        Ok(Response::new(request.into_inner().try_map(|req| {
+            // an error ends the stream, the client receives it as the status
+            if req.message.is_empty() {
+                return Err(Status::new(Code::InvalidArgument).with_message("empty message"));
+            }
+            Ok(EchoReply {
+                message: req.message,
+            })
+        })))
+    }
+}
+
+#[tokio::main]
+async fn main() -> Result<(), std::io::Error> {
+    Server::new(TcpListener::bind("0.0.0.0:3000"))
+        .run(RouteGrpc::new().add_service(EchoServer::new(EchoService)))
+        .await
+}
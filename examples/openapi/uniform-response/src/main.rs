@@ -1,9 +1,10 @@
-use poem::{listener::TcpListener, Error, Route, Server};
+use poem::{
+    http::StatusCode, listener::TcpListener, EndpointExt, IntoResponse, Response, Route, Server,
+};
 use poem_openapi::{
-    error::ParseRequestPayloadError,
     payload::Json,
     types::{ParseFromJSON, ToJSON},
-    ApiResponse, Object, OpenApi, OpenApiService,
+    Object, OpenApi, OpenApiService,
 };
 use tokio::sync::Mutex;
 
@@ -33,36 +34,29 @@ impl<T: ParseFromJSON + ToJSON + Send + Sync> ResponseObject<T> {
         }
     }
 
-    pub fn not_found() -> Self {
+    pub fn error(code: i32, msg: impl Into<String>) -> Self {
         Self {
-            code: ERRCODE_NOT_FOUND,
-            msg: "Not found".to_string(),
+            code,
+            msg: msg.into(),
             data: None,
         }
     }
-}
 
-#[derive(ApiResponse)]
-#[oai(bad_request_handler = "my_bad_request_handler")]
-enum MyResponse<T: ParseFromJSON + ToJSON + Send + Sync> {
-    #[oai(status = 200)]
-    Ok(Json<ResponseObject<T>>),
+    pub fn not_found() -> Self {
+        Self::error(ERRCODE_NOT_FOUND, "Not found")
+    }
 }
 
-fn my_bad_request_handler<T: ParseFromJSON + ToJSON + Send + Sync>(err: Error) -> MyResponse<T> {
-    if err.is::<ParseRequestPayloadError>() {
-        MyResponse::Ok(Json(ResponseObject {
-            code: ERRCODE_INVALID_REQUEST,
-            msg: err.to_string(),
-            data: None,
-        }))
-    } else {
-        MyResponse::Ok(Json(ResponseObject {
-            code: ERRCODE_UNKNOWN,
-            msg: err.to_string(),
-            data: None,
-        }))
-    }
+/// Wraps the errors, such as the invalid requests, in the uniform response.
+async fn uniform_error(resp: Response) -> Response {
+    let code = match resp.status() {
+        status if status.is_success() => return resp,
+        StatusCode::NOT_FOUND => ERRCODE_NOT_FOUND,
+        StatusCode::BAD_REQUEST => ERRCODE_INVALID_REQUEST,
+        _ => ERRCODE_UNKNOWN,
+    };
+    let msg = resp.into_body().into_string().await.unwrap_or_default();
+    Json(ResponseObject::<String>::error(code, msg)).into_response()
 }
 
 struct Api {
@@ -72,18 +66,18 @@ struct Api {
 #[OpenApi]
 impl Api {
     #[oai(path = "/resource", method = "get")]
-    async fn get(&self) -> MyResponse<Resource> {
+    async fn get(&self) -> Json<ResponseObject<Resource>> {
         let res = self.resource.lock().await;
         match &*res {
-            Some(resource) => MyResponse::Ok(Json(ResponseObject::ok(resource.clone()))),
-            None => MyResponse::Ok(Json(ResponseObject::not_found())),
+            Some(resource) => Json(ResponseObject::ok(resource.clone())),
+            None => Json(ResponseObject::not_found()),
         }
     }
 
     #[oai(path = "/resource", method = "put")]
-    async fn put(&self, obj: Json<Resource>) -> MyResponse<bool> {
+    async fn put(&self, obj: Json<Resource>) -> Json<ResponseObject<bool>> {
         *self.resource.lock().await = Some(obj.0);
-        MyResponse::Ok(Json(ResponseObject::ok(true)))
+        Json(ResponseObject::ok(true))
     }
 }
 
@@ -105,6 +99,10 @@ async fn main() -> Result<(), std::io::Error> {
     let ui = api_service.swagger_ui();
 
     Server::new(TcpListener::bind("0.0.0.0:3000"))
-        .run(Route::new().nest("/api", api_service).nest("/", ui))
+        .run(
+            Route::new()
+                .nest("/api", api_service.map_response_async(uniform_error))
+                .nest("/", ui),
+        )
         .await
 }
@@ -58,8 +58,8 @@ enum FindUserResponse {
 #[derive(ApiResponse)]
 enum DeleteUserResponse {
     /// Returns when the user is successfully deleted.
-    #[oai(status = 200)]
-    Ok,
+    #[oai(status = 204)]
+    NoContent,
     /// Return when the specified user is not found.
     #[oai(status = 404)]
     NotFound,
@@ -68,8 +68,8 @@ enum DeleteUserResponse {
 #[derive(ApiResponse)]
 enum UpdateUserResponse {
     /// Returns when the user is successfully updated.
-    #[oai(status = 200)]
-    Ok,
+    #[oai(status = 204)]
+    NoContent,
     /// Return when the specified user is not found.
     #[oai(status = 404)]
     NotFound,
@@ -107,7 +107,7 @@ impl Api {
         let user_id = user_id.0 as usize;
         if users.contains(user_id) {
             users.remove(user_id);
-            DeleteUserResponse::Ok
+            DeleteUserResponse::NoContent
         } else {
             DeleteUserResponse::NotFound
         }
@@ -125,7 +125,7 @@ impl Api {
                 if let Some(password) = update.0.password {
                     user.password = password;
                 }
-                UpdateUserResponse::Ok
+                UpdateUserResponse::NoContent
             }
             None => UpdateUserResponse::NotFound,
         }
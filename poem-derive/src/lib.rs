@@ -22,25 +22,141 @@ use syn::{parse_macro_input, FnArg, GenericParam, ItemFn, Member, Result};
 /// async fn example() {
 /// }
 /// ```
+///
+/// Use `#[handler(blocking)]` on a synchronous function to run its body on
+/// the blocking thread pool, the extractors still run on the async side.
+///
+/// ```
+/// use poem::{handler, test::TestClient};
+///
+/// #[handler(blocking)]
+/// fn reverse(body: Vec<u8>) -> Vec<u8> {
+///     body.into_iter().rev().collect()
+/// }
+///
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// let resp = TestClient::new(reverse).post("/").body("abc").send().await;
+/// resp.assert_status_is_ok();
+/// resp.assert_text("cba").await;
+/// # });
+/// ```
+///
+/// The extracted arguments are moved to the blocking thread pool, so the
+/// extractors that borrow the request are rejected, use an owned extractor
+/// such as `Ext<T>` instead of `Data<&T>`:
+///
+/// ```compile_fail
+/// use poem::{handler, web::Data};
+///
+/// #[handler(blocking)]
+/// fn index(data: Data<&i32>) {}
+/// ```
+///
+/// Use `#[handler(passthrough)]` to generate a middleware that runs the
+/// function with the extractors that do not read the body, and then passes
+/// the untouched request to the inner endpoint, see `poem::middleware::Passthrough`.
 #[proc_macro_attribute]
 pub fn handler(args: TokenStream, input: TokenStream) -> TokenStream {
     let mut internal = false;
+    let mut blocking = false;
+    let mut passthrough = false;
 
     let arg_parser = syn::meta::parser(|meta| {
         if meta.path.is_ident("internal") {
             internal = true;
+        } else if meta.path.is_ident("blocking") {
+            blocking = true;
+        } else if meta.path.is_ident("passthrough") {
+            passthrough = true;
         }
         Ok(())
     });
     parse_macro_input!(args with arg_parser);
 
-    match generate_handler(internal, input) {
+    let res = if passthrough {
+        generate_passthrough(internal, blocking, input)
+    } else {
+        generate_handler(internal, blocking, input)
+    };
+    match res {
         Ok(stream) => stream,
         Err(err) => err.into_compile_error().into(),
     }
 }
 
-fn generate_handler(internal: bool, input: TokenStream) -> Result<TokenStream> {
+fn generate_passthrough(internal: bool, blocking: bool, input: TokenStream) -> Result<TokenStream> {
+    let crate_name = utils::get_crate_name(internal);
+    let item_fn = syn::parse::<ItemFn>(input)?;
+    let vis = &item_fn.vis;
+    let docs = item_fn
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("doc"))
+        .cloned()
+        .collect::<Vec<_>>();
+    let ident = &item_fn.sig.ident;
+    let call_await = if item_fn.sig.asyncness.is_some() {
+        Some(quote::quote!(.await))
+    } else {
+        None
+    };
+    if blocking {
+        return Err(syn::Error::new_spanned(
+            ident,
+            "passthrough handler cannot be blocking",
+        ));
+    }
+    if !item_fn.sig.generics.params.is_empty() {
+        return Err(syn::Error::new_spanned(
+            &item_fn.sig.generics,
+            "passthrough handler cannot be generic",
+        ));
+    }
+
+    let mut extractors = Vec::new();
+    let mut args = Vec::new();
+    for (idx, input) in item_fn.sig.inputs.clone().into_iter().enumerate() {
+        if let FnArg::Typed(pat) = input {
+            let ty = &pat.ty;
+            let id = quote::format_ident!("p{}", idx);
+            args.push(id.clone());
+            extractors.push(quote! {
+                let #id = #crate_name::middleware::extract_passthrough::<#ty>(req).await?;
+            });
+        }
+    }
+
+    let expanded = quote! {
+        #(#docs)*
+        #[allow(non_camel_case_types)]
+        #[derive(Clone, Copy)]
+        #vis struct #ident;
+
+        impl<E: #crate_name::Endpoint> #crate_name::Middleware<E> for #ident {
+            type Output = #crate_name::middleware::PassthroughEndpoint<Self, E>;
+
+            fn transform(&self, ep: E) -> Self::Output {
+                #crate_name::middleware::PassthroughEndpoint::new(*self, ep)
+            }
+        }
+
+        impl #crate_name::middleware::Passthrough for #ident {
+            async fn check(
+                &self,
+                req: &#crate_name::Request,
+            ) -> #crate_name::Result<::std::option::Option<#crate_name::Response>> {
+                #(#extractors)*
+                #item_fn
+                let res = #ident(#(#args),*)#call_await;
+                #crate_name::middleware::PassthroughOutput::into_passthrough(res)
+            }
+        }
+    };
+
+    Ok(expanded.into())
+}
+
+fn generate_handler(internal: bool, blocking: bool, input: TokenStream) -> Result<TokenStream> {
     let crate_name = utils::get_crate_name(internal);
     let item_fn = syn::parse::<ItemFn>(input)?;
     let (impl_generics, type_generics, where_clause) = item_fn.sig.generics.split_for_impl();
@@ -57,6 +173,12 @@ fn generate_handler(internal: bool, input: TokenStream) -> Result<TokenStream> {
     } else {
         None
     };
+    if blocking && item_fn.sig.asyncness.is_some() {
+        return Err(syn::Error::new_spanned(
+            item_fn.sig.asyncness,
+            "blocking handler must not be async",
+        ));
+    }
 
     let def_struct = if !item_fn.sig.generics.params.is_empty() {
         let iter = item_fn
@@ -100,6 +222,16 @@ fn generate_handler(internal: bool, input: TokenStream) -> Result<TokenStream> {
     for (idx, input) in item_fn.sig.inputs.clone().into_iter().enumerate() {
         if let FnArg::Typed(pat) = input {
             let ty = &pat.ty;
+            if blocking {
+                if let Some(borrow) = utils::find_borrow(ty) {
+                    return Err(syn::Error::new_spanned(
+                        borrow,
+                        "the arguments of a blocking handler must be owned, because they are \
+                         moved to the blocking thread pool, use an owned extractor such as \
+                         `Ext<T>` instead of `Data<&T>`",
+                    ));
+                }
+            }
             let id = quote::format_ident!("p{}", idx);
             args.push(id.clone());
             extractors.push(quote! {
@@ -108,6 +240,16 @@ fn generate_handler(internal: bool, input: TokenStream) -> Result<TokenStream> {
         }
     }
 
+    let call = if blocking {
+        quote! {
+            let res = #crate_name::endpoint::run_blocking(move || #ident(#(#args),*)).await?;
+        }
+    } else {
+        quote! {
+            let res = #ident(#(#args),*)#call_await;
+        }
+    };
+
     let expanded = quote! {
         #(#docs)*
         #[allow(non_camel_case_types)]
@@ -121,7 +263,7 @@ fn generate_handler(internal: bool, input: TokenStream) -> Result<TokenStream> {
                 let (req, mut body) = req.split();
                 #(#extractors)*
                 #item_fn
-                let res = #ident(#(#args),*)#call_await;
+                #call
                 let res = #crate_name::error::IntoResult::into_result(res);
                 std::result::Result::map(res, #crate_name::IntoResponse::into_response)
             }
@@ -1,7 +1,7 @@
 use proc_macro2::{Span, TokenStream};
 use proc_macro_crate::{crate_name, FoundCrate};
-use quote::quote;
-use syn::Ident;
+use quote::{quote, ToTokens};
+use syn::{GenericArgument, Ident, PathArguments, Type};
 
 pub(crate) fn get_crate_name(internal: bool) -> TokenStream {
     if internal {
@@ -15,3 +15,35 @@ pub(crate) fn get_crate_name(internal: bool) -> TokenStream {
         quote!(#name)
     }
 }
+
+/// Returns the first reference or non-`'static` lifetime in the type.
+pub(crate) fn find_borrow(ty: &Type) -> Option<&dyn ToTokens> {
+    match ty {
+        Type::Reference(r) => Some(r),
+        Type::Array(array) => find_borrow(&array.elem),
+        Type::Slice(slice) => find_borrow(&slice.elem),
+        Type::Paren(paren) => find_borrow(&paren.elem),
+        Type::Group(group) => find_borrow(&group.elem),
+        Type::Tuple(tuple) => tuple.elems.iter().find_map(find_borrow),
+        Type::Path(path) => {
+            if let Some(qself) = &path.qself {
+                if let Some(borrow) = find_borrow(&qself.ty) {
+                    return Some(borrow);
+                }
+            }
+            path.path.segments.iter().find_map(|segment| {
+                let PathArguments::AngleBracketed(args) = &segment.arguments else {
+                    return None;
+                };
+                args.args.iter().find_map(|arg| match arg {
+                    GenericArgument::Type(ty) => find_borrow(ty),
+                    GenericArgument::Lifetime(lifetime) if lifetime.ident != "static" => {
+                        Some(lifetime as &dyn ToTokens)
+                    }
+                    _ => None,
+                })
+            })
+        }
+        _ => None,
+    }
+}
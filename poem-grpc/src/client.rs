@@ -14,12 +14,15 @@ use poem::{
     Endpoint, EndpointExt, IntoEndpoint, Middleware, Request as HttpRequest,
     Response as HttpResponse,
 };
-use rustls::ClientConfig as TlsClientConfig;
+use rustls::{
+    pki_types::{CertificateDer, InvalidDnsNameError, PrivateKeyDer, ServerName},
+    ClientConfig as TlsClientConfig, RootCertStore,
+};
 
 use crate::{
     codec::Codec,
     compression::get_incoming_encodings,
-    connector::HttpsConnector,
+    connector::{webpki_root_cert_store, HttpsConnector, NoServerCertVerification},
     encoding::{create_decode_response_body, create_encode_request_body},
     Code, CompressionEncoding, Metadata, Request, Response, Status, Streaming,
 };
@@ -32,6 +35,7 @@ pub struct ClientConfig {
     origin: Option<Uri>,
     user_agent: Option<HeaderValue>,
     tls_config: Option<TlsClientConfig>,
+    tls_server_name: Option<ServerName<'static>>,
     max_header_list_size: u32,
 }
 
@@ -44,8 +48,12 @@ impl ClientConfig {
                 origin: None,
                 user_agent: None,
                 tls_config: None,
+                tls_server_name: None,
                 max_header_list_size: 16384,
             }),
+            tls_root_certificates: None,
+            tls_client_identity: None,
+            danger_accept_invalid_certs: false,
         }
     }
 }
@@ -63,11 +71,22 @@ pub enum ClientBuilderError {
     /// Invalid user-agent
     #[error("invalid user-agent: {0}")]
     InvalidUserAgent(InvalidHeaderValue),
+
+    /// Invalid TLS server name
+    #[error("invalid tls server name: {0}")]
+    InvalidTlsServerName(InvalidDnsNameError),
+
+    /// Invalid TLS client identity
+    #[error("invalid tls client identity: {0}")]
+    InvalidTlsClientIdentity(rustls::Error),
 }
 
 /// A `ClientConfig` builder
 pub struct ClientConfigBuilder {
     config: Result<ClientConfig, ClientBuilderError>,
+    tls_root_certificates: Option<RootCertStore>,
+    tls_client_identity: Option<(Vec<CertificateDer<'static>>, PrivateKeyDer<'static>)>,
+    danger_accept_invalid_certs: bool,
 }
 
 impl ClientConfigBuilder {
@@ -145,6 +164,11 @@ impl ClientConfigBuilder {
     }
 
     /// Set `TlsConfig` for `HTTPS` uri
+    ///
+    /// If it is set, [`tls_root_certificates`](Self::tls_root_certificates),
+    /// [`tls_client_identity`](Self::tls_client_identity) and
+    /// [`danger_accept_invalid_certs`](Self::danger_accept_invalid_certs) are
+    /// ignored.
     pub fn tls_config(mut self, tls_config: TlsClientConfig) -> Self {
         if let Ok(config) = &mut self.config {
             config.tls_config = Some(tls_config);
@@ -152,6 +176,52 @@ impl ClientConfigBuilder {
         self
     }
 
+    /// Set the root certificates used to verify the server certificate for
+    /// `HTTPS` uri.
+    ///
+    /// Default is the Mozilla root certificates from `webpki-roots`.
+    pub fn tls_root_certificates(mut self, roots: RootCertStore) -> Self {
+        self.tls_root_certificates = Some(roots);
+        self
+    }
+
+    /// Set the client certificate chain and private key used for mutual TLS
+    /// authentication.
+    pub fn tls_client_identity(
+        mut self,
+        cert_chain: Vec<CertificateDer<'static>>,
+        key: PrivateKeyDer<'static>,
+    ) -> Self {
+        self.tls_client_identity = Some((cert_chain, key));
+        self
+    }
+
+    /// Set the server name used for SNI and to verify the server certificate,
+    /// instead of the host of the uri.
+    pub fn tls_server_name(mut self, server_name: impl Into<String>) -> Self {
+        self.config = self.config.and_then(|mut config| {
+            config.tls_server_name = Some(
+                ServerName::try_from(server_name.into())
+                    .map_err(ClientBuilderError::InvalidTlsServerName)?,
+            );
+            Ok(config)
+        });
+        self
+    }
+
+    /// Accept any server certificate, including self-signed, expired or
+    /// mismatched ones.
+    ///
+    /// # Warning
+    ///
+    /// This disables the server authentication and makes the connection
+    /// vulnerable to man-in-the-middle attacks, it should only be used in
+    /// development.
+    pub fn danger_accept_invalid_certs(mut self, accept: bool) -> Self {
+        self.danger_accept_invalid_certs = accept;
+        self
+    }
+
     /// Sets the max size of received header frames.
     ///
     /// Default is `16384` bytes.
@@ -164,7 +234,37 @@ impl ClientConfigBuilder {
 
     /// Consumes this builder and returns the `ClientConfig`
     pub fn build(self) -> Result<ClientConfig, ClientBuilderError> {
-        self.config
+        let mut config = self.config?;
+
+        if config.tls_config.is_none()
+            && (self.tls_root_certificates.is_some()
+                || self.tls_client_identity.is_some()
+                || self.danger_accept_invalid_certs)
+        {
+            let builder = TlsClientConfig::builder();
+            let builder = if self.danger_accept_invalid_certs {
+                tracing::warn!(
+                    "the GRPC client accepts invalid server certificates, this is insecure \
+                     and should only be used in development"
+                );
+                builder
+                    .dangerous()
+                    .with_custom_certificate_verifier(Arc::new(NoServerCertVerification::new()))
+            } else {
+                builder.with_root_certificates(
+                    self.tls_root_certificates
+                        .unwrap_or_else(webpki_root_cert_store),
+                )
+            };
+            config.tls_config = Some(match self.tls_client_identity {
+                Some((cert_chain, key)) => builder
+                    .with_client_auth_cert(cert_chain, key)
+                    .map_err(ClientBuilderError::InvalidTlsClientIdentity)?,
+                None => builder.with_no_client_auth(),
+            });
+        }
+
+        Ok(config)
     }
 }
 
@@ -180,7 +280,7 @@ impl GrpcClient {
     #[inline]
     pub fn new(config: ClientConfig) -> Self {
         Self {
-            ep: create_client_endpoint(config),
+            ep: with_tracing(create_client_endpoint(config)),
             send_compressed: None,
             accept_compressed: Arc::new([]),
         }
@@ -193,7 +293,7 @@ impl GrpcClient {
         <T::Endpoint as Endpoint>::Output: 'static,
     {
         Self {
-            ep: Arc::new(ToDynEndpoint(ep.map_to_response())),
+            ep: with_tracing(Arc::new(ToDynEndpoint(ep.map_to_response()))),
             send_compressed: None,
             accept_compressed: Arc::new([]),
         }
@@ -252,8 +352,13 @@ impl GrpcClient {
 
         let body = resp.take_body();
         let incoming_encoding = get_incoming_encodings(resp.headers(), &self.accept_compressed)?;
-        let mut stream =
-            create_decode_response_body(codec.decoder(), resp.headers(), body, incoming_encoding)?;
+        let mut stream = create_decode_response_body(
+            codec.decoder(),
+            resp.headers(),
+            body,
+            incoming_encoding,
+            self.accept_compressed.clone(),
+        )?;
 
         let message = stream
             .try_next()
@@ -301,8 +406,13 @@ impl GrpcClient {
 
         let body = resp.take_body();
         let incoming_encoding = get_incoming_encodings(resp.headers(), &self.accept_compressed)?;
-        let mut stream =
-            create_decode_response_body(codec.decoder(), resp.headers(), body, incoming_encoding)?;
+        let mut stream = create_decode_response_body(
+            codec.decoder(),
+            resp.headers(),
+            body,
+            incoming_encoding,
+            self.accept_compressed.clone(),
+        )?;
 
         let message = stream
             .try_next()
@@ -350,8 +460,13 @@ impl GrpcClient {
 
         let body = resp.take_body();
         let incoming_encoding = get_incoming_encodings(resp.headers(), &self.accept_compressed)?;
-        let stream =
-            create_decode_response_body(codec.decoder(), resp.headers(), body, incoming_encoding)?;
+        let stream = create_decode_response_body(
+            codec.decoder(),
+            resp.headers(),
+            body,
+            incoming_encoding,
+            self.accept_compressed.clone(),
+        )?;
 
         Ok(Response {
             metadata: Metadata {
@@ -395,8 +510,13 @@ impl GrpcClient {
 
         let body = resp.take_body();
         let incoming_encoding = get_incoming_encodings(resp.headers(), &self.accept_compressed)?;
-        let stream =
-            create_decode_response_body(codec.decoder(), resp.headers(), body, incoming_encoding)?;
+        let stream = create_decode_response_body(
+            codec.decoder(),
+            resp.headers(),
+            body,
+            incoming_encoding,
+            self.accept_compressed.clone(),
+        )?;
 
         Ok(Response {
             metadata: Metadata {
@@ -407,6 +527,24 @@ impl GrpcClient {
     }
 }
 
+#[cfg(feature = "opentelemetry")]
+#[inline]
+fn with_tracing(
+    ep: Arc<dyn DynEndpoint<Output = HttpResponse> + 'static>,
+) -> Arc<dyn DynEndpoint<Output = HttpResponse> + 'static> {
+    Arc::new(ToDynEndpoint(
+        crate::client_tracing::ClientTracingEndpoint::new(ep),
+    ))
+}
+
+#[cfg(not(feature = "opentelemetry"))]
+#[inline]
+fn with_tracing(
+    ep: Arc<dyn DynEndpoint<Output = HttpResponse> + 'static>,
+) -> Arc<dyn DynEndpoint<Output = HttpResponse> + 'static> {
+    ep
+}
+
 fn create_http_request<T: Codec>(
     path: &str,
     metadata: Metadata,
@@ -468,7 +606,10 @@ fn create_client_endpoint(
     let cli = Client::builder(TokioExecutor::new())
         .http2_only(true)
         .http2_max_header_list_size(config.max_header_list_size)
-        .build(HttpsConnector::new(config.tls_config.take()));
+        .build(HttpsConnector::new(
+            config.tls_config.take(),
+            config.tls_server_name.take(),
+        ));
 
     let config = Arc::new(config);
 
@@ -0,0 +1,199 @@
+use std::sync::Arc;
+
+use http_body_util::BodyExt;
+use libopentelemetry::{
+    global,
+    trace::{FutureExt, SpanKind, Status as SpanStatus, TraceContextExt, Tracer},
+    Context, KeyValue,
+};
+use opentelemetry_http::HeaderInjector;
+use opentelemetry_semantic_conventions::attribute;
+use poem::{
+    endpoint::DynEndpoint, http::HeaderMap, Endpoint, Request as HttpRequest,
+    Response as HttpResponse,
+};
+
+use crate::{client::BoxBody, Code, Status};
+
+type DynHttpEndpoint = Arc<dyn DynEndpoint<Output = HttpResponse> + 'static>;
+
+/// Creates a client span for each RPC with the global tracer provider, and
+/// injects the span context into the request metadata with the global
+/// propagator.
+///
+/// The span ends when the `grpc-status` is received, which is in the trailers
+/// unless the response is trailers-only. When no tracer provider and
+/// propagator are installed, both of them are no-op.
+pub(crate) struct ClientTracingEndpoint {
+    inner: DynHttpEndpoint,
+}
+
+impl ClientTracingEndpoint {
+    pub(crate) fn new(inner: DynHttpEndpoint) -> Self {
+        Self { inner }
+    }
+}
+
+fn record_status(cx: &Context, headers: &HeaderMap) {
+    let span = cx.span();
+    let status = match Status::from_headers(headers) {
+        Ok(Some(status)) => status,
+        Ok(None) => return,
+        Err(status) => status,
+    };
+
+    span.set_attribute(KeyValue::new(
+        attribute::RPC_GRPC_STATUS_CODE,
+        status.code().as_u16() as i64,
+    ));
+    if status.code() != Code::Ok {
+        span.set_status(SpanStatus::error(
+            status.message().unwrap_or_default().to_string(),
+        ));
+    }
+    span.end();
+}
+
+impl Endpoint for ClientTracingEndpoint {
+    type Output = HttpResponse;
+
+    async fn call(&self, mut req: HttpRequest) -> poem::Result<Self::Output> {
+        let tracer = global::tracer("poem-grpc");
+        let path = req.uri().path().trim_start_matches('/').to_string();
+        let (service, method) = path.split_once('/').unwrap_or((path.as_str(), ""));
+
+        let span = tracer
+            .span_builder(path.clone())
+            .with_kind(SpanKind::Client)
+            .with_attributes([
+                KeyValue::new(attribute::RPC_SYSTEM, "grpc"),
+                KeyValue::new(attribute::RPC_SERVICE, service.to_string()),
+                KeyValue::new(attribute::RPC_METHOD, method.to_string()),
+            ])
+            .start(&tracer);
+        let cx = Context::current_with_span(span);
+
+        global::get_text_map_propagator(|propagator| {
+            propagator.inject_context(&cx, &mut HeaderInjector(req.headers_mut()))
+        });
+
+        match self.inner.call(req).with_context(cx.clone()).await {
+            Ok(mut resp) => {
+                if resp.headers().contains_key("grpc-status") {
+                    // trailers-only
+                    record_status(&cx, resp.headers());
+                    return Ok(resp);
+                }
+
+                let body: BoxBody = resp.take_body().into();
+                resp.set_body(
+                    body.map_frame(move |frame| {
+                        if let Some(trailers) = frame.trailers_ref() {
+                            record_status(&cx, trailers);
+                        }
+                        frame
+                    })
+                    .boxed(),
+                );
+                Ok(resp)
+            }
+            Err(err) => {
+                let span = cx.span();
+                span.set_status(SpanStatus::error(err.to_string()));
+                span.end();
+                Err(err)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+    use futures_util::stream;
+    use http_body_util::StreamBody;
+    use hyper::body::Frame;
+    use libopentelemetry::{
+        propagation::{text_map_propagator::FieldIter, Extractor, Injector, TextMapPropagator},
+        trace::{SpanContext, SpanId, TraceFlags, TraceId, TraceState},
+    };
+    use poem::endpoint::{make, ToDynEndpoint};
+
+    use super::*;
+
+    #[derive(Debug)]
+    struct TestPropagator(Vec<String>);
+
+    impl TextMapPropagator for TestPropagator {
+        fn inject_context(&self, cx: &Context, injector: &mut dyn Injector) {
+            let span = cx.span();
+            let span_context = span.span_context();
+            if span_context.is_valid() {
+                injector.set(
+                    "traceparent",
+                    format!(
+                        "00-{}-{}-{:02x}",
+                        span_context.trace_id(),
+                        span_context.span_id(),
+                        span_context.trace_flags().to_u8()
+                    ),
+                );
+            }
+        }
+
+        fn extract_with_context(&self, cx: &Context, _extractor: &dyn Extractor) -> Context {
+            cx.clone()
+        }
+
+        fn fields(&self) -> FieldIter<'_> {
+            FieldIter::new(&self.0)
+        }
+    }
+
+    #[tokio::test]
+    async fn propagate_context() {
+        global::set_text_map_propagator(TestPropagator(vec!["traceparent".to_string()]));
+
+        let ep = ClientTracingEndpoint::new(Arc::new(ToDynEndpoint(make(
+            |req: HttpRequest| async move {
+                let body = StreamBody::new(stream::iter([
+                    Ok::<_, std::io::Error>(Frame::data(Bytes::from_static(b"abc"))),
+                    Ok(Frame::trailers(Status::new(Code::Ok).to_headers())),
+                ]));
+                let mut resp = HttpResponse::builder().body(BoxBody::new(body));
+                if let Some(value) = req.headers().get("traceparent") {
+                    resp.headers_mut().insert("traceparent", value.clone());
+                }
+                resp
+            },
+        ))));
+
+        // no tracer provider is installed, so the client span is a no-op span
+        // that carries the parent span context
+        let cx = Context::new().with_remote_span_context(SpanContext::new(
+            TraceId::from_hex("4bf92f3577b34da6a3ce929d0e0e4736").unwrap(),
+            SpanId::from_hex("00f067aa0ba902b7").unwrap(),
+            TraceFlags::SAMPLED,
+            true,
+            TraceState::default(),
+        ));
+        let resp = ep
+            .call(
+                HttpRequest::builder()
+                    .uri_str("/helloworld.Greeter/SayHello")
+                    .finish(),
+            )
+            .with_context(cx)
+            .await
+            .unwrap();
+        assert_eq!(
+            resp.headers().get("traceparent").unwrap(),
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01"
+        );
+
+        // the body and trailers are passed through
+        let body = BoxBody::from(resp.into_body()).collect().await.unwrap();
+        assert_eq!(body.trailers().unwrap().get("grpc-status").unwrap(), "0");
+        assert_eq!(body.to_bytes(), "abc");
+    }
+}
@@ -148,7 +148,7 @@ impl CompressionEncoding {
     }
 }
 
-fn unimplemented(accept_compressed: &[CompressionEncoding]) -> Status {
+pub(crate) fn unimplemented(accept_compressed: &[CompressionEncoding]) -> Status {
     let mut md = Metadata::new();
     let mut accept_encoding = String::new();
     let mut iter = accept_compressed.iter();
@@ -172,6 +172,9 @@ pub(crate) fn get_incoming_encodings(
     let Some(value) = headers.get("grpc-encoding") else {
         return Ok(None);
     };
+    if value == "identity" {
+        return Ok(None);
+    }
     let Some(encoding) = value
         .to_str()
         .ok()
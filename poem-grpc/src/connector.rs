@@ -12,7 +12,12 @@ use hyper_util::{
     client::legacy::connect::{Connected, Connection},
     rt::TokioIo,
 };
-use rustls::{ClientConfig, RootCertStore};
+use rustls::{
+    client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier},
+    crypto::{verify_tls12_signature, verify_tls13_signature, CryptoProvider},
+    pki_types::{CertificateDer, ServerName, UnixTime},
+    ClientConfig, DigitallySignedStruct, RootCertStore, SignatureScheme,
+};
 use tokio::net::TcpStream;
 use tokio_rustls::{client::TlsStream, TlsConnector};
 use tower_service::Service;
@@ -83,12 +88,19 @@ impl Connection for MaybeHttpsStream {
 #[derive(Debug, Clone)]
 pub(crate) struct HttpsConnector {
     tls_config: Option<ClientConfig>,
+    server_name: Option<ServerName<'static>>,
 }
 
 impl HttpsConnector {
     #[inline]
-    pub(crate) fn new(tls_config: Option<ClientConfig>) -> Self {
-        HttpsConnector { tls_config }
+    pub(crate) fn new(
+        tls_config: Option<ClientConfig>,
+        server_name: Option<ServerName<'static>>,
+    ) -> Self {
+        HttpsConnector {
+            tls_config,
+            server_name,
+        }
     }
 }
 
@@ -102,21 +114,82 @@ impl Service<Uri> for HttpsConnector {
     }
 
     fn call(&mut self, uri: Uri) -> Self::Future {
-        do_connect(uri, self.tls_config.clone()).boxed()
+        do_connect(uri, self.tls_config.clone(), self.server_name.clone()).boxed()
     }
 }
 
-fn default_tls_config() -> ClientConfig {
+/// A certificate verifier that accepts any server certificate, only the
+/// handshake signatures are checked.
+#[derive(Debug)]
+pub(crate) struct NoServerCertVerification(CryptoProvider);
+
+impl NoServerCertVerification {
+    pub(crate) fn new() -> Self {
+        Self(rustls::crypto::ring::default_provider())
+    }
+}
+
+impl ServerCertVerifier for NoServerCertVerification {
+    fn verify_server_cert(
+        &self,
+        _end_entity: &CertificateDer<'_>,
+        _intermediates: &[CertificateDer<'_>],
+        _server_name: &ServerName<'_>,
+        _ocsp_response: &[u8],
+        _now: UnixTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        Ok(ServerCertVerified::assertion())
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        verify_tls12_signature(
+            message,
+            cert,
+            dss,
+            &self.0.signature_verification_algorithms,
+        )
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        verify_tls13_signature(
+            message,
+            cert,
+            dss,
+            &self.0.signature_verification_algorithms,
+        )
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.0.signature_verification_algorithms.supported_schemes()
+    }
+}
+
+pub(crate) fn webpki_root_cert_store() -> RootCertStore {
     let mut root_cert_store = RootCertStore::empty();
     root_cert_store.extend(webpki_roots::TLS_SERVER_ROOTS.iter().cloned());
+    root_cert_store
+}
+
+fn default_tls_config() -> ClientConfig {
     ClientConfig::builder()
-        .with_root_certificates(root_cert_store)
+        .with_root_certificates(webpki_root_cert_store())
         .with_no_client_auth()
 }
 
 async fn do_connect(
     uri: Uri,
     tls_config: Option<ClientConfig>,
+    server_name: Option<ServerName<'static>>,
 ) -> Result<MaybeHttpsStream, IoError> {
     let scheme = uri
         .scheme()
@@ -135,10 +208,14 @@ async fn do_connect(
         Ok(MaybeHttpsStream::TcpStream(TokioIo::new(stream)))
     } else if scheme == Scheme::HTTPS {
         let mut tls_config = tls_config.unwrap_or_else(default_tls_config);
-        tls_config.alpn_protocols = vec![b"h2".to_vec(), b"http/1.1".to_vec()];
+        // the client only speaks HTTP/2, which is required by GRPC
+        tls_config.alpn_protocols = vec![b"h2".to_vec()];
         let connector = TlsConnector::from(Arc::new(tls_config));
         let stream = TcpStream::connect(format!("{}:{}", host, port)).await?;
-        let domain = host.try_into().map_err(IoError::other)?;
+        let domain = match server_name {
+            Some(server_name) => server_name,
+            None => host.try_into().map_err(IoError::other)?,
+        };
         let mut is_http2 = false;
         let stream = connector
             .connect_with(domain, stream, |conn| {
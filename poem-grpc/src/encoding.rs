@@ -1,4 +1,7 @@
-use std::io::Result as IoResult;
+use std::{
+    io::{Error as IoError, Result as IoResult},
+    sync::Arc,
+};
 
 use bytes::{Buf, BufMut, Bytes, BytesMut};
 use futures_util::StreamExt;
@@ -10,6 +13,7 @@ use sync_wrapper::SyncStream;
 use crate::{
     client::BoxBody,
     codec::{Decoder, Encoder},
+    compression::unimplemented,
     Code, CompressionEncoding, Status, Streaming,
 };
 
@@ -35,17 +39,24 @@ async fn encode_data_frame<T: Encoder>(
     Ok(buf.split().freeze())
 }
 
+/// Decodes the length-prefixed messages, the compressed flag is checked for
+/// each message because the peer may send some messages uncompressed.
 struct DataFrameDecoder {
     buf: BytesMut,
     compression: Option<CompressionEncoding>,
+    accept_compressed: Arc<[CompressionEncoding]>,
 }
 
 impl DataFrameDecoder {
     #[inline]
-    fn new(compression: Option<CompressionEncoding>) -> Self {
+    fn new(
+        compression: Option<CompressionEncoding>,
+        accept_compressed: Arc<[CompressionEncoding]>,
+    ) -> Self {
         Self {
             buf: BytesMut::new(),
             compression,
+            accept_compressed,
         }
     }
 
@@ -80,10 +91,11 @@ impl DataFrameDecoder {
             let data = self.buf.split_to(len).freeze();
 
             if compressed {
-                let compression = self.compression.ok_or_else(|| {
-                    Status::new(Code::Unimplemented)
-                        .with_message(format!("unsupported compressed flag: {compressed}"))
-                })?;
+                // the message is compressed, but the `grpc-encoding` header is
+                // missing or `identity`
+                let compression = self
+                    .compression
+                    .ok_or_else(|| unimplemented(&self.accept_compressed))?;
                 let data = compression
                     .decode(&data)
                     .await
@@ -102,11 +114,12 @@ pub(crate) fn create_decode_request_body<T: Decoder>(
     mut decoder: T,
     body: Body,
     compression: Option<CompressionEncoding>,
+    accept_compressed: Arc<[CompressionEncoding]>,
 ) -> Streaming<T::Item> {
     let mut body: BoxBody = body.into();
 
     Streaming::new(async_stream::try_stream! {
-        let mut frame_decoder = DataFrameDecoder::new(compression);
+        let mut frame_decoder = DataFrameDecoder::new(compression, accept_compressed);
 
         loop {
             match body.frame().await.transpose().map_err(Status::from_std_error)? {
@@ -135,6 +148,7 @@ pub(crate) fn create_encode_response_body<T: Encoder>(
 ) -> Body {
     let stream = async_stream::try_stream! {
         let mut buf = BytesMut::new();
+        let mut status = Status::new(Code::Ok);
 
         while let Some(item) = stream.next().await {
             match item {
@@ -143,13 +157,15 @@ pub(crate) fn create_encode_response_body<T: Encoder>(
                         yield Frame::data(data);
                     }
                 }
-                Err(status) => {
-                    yield Frame::trailers(status.to_headers());
+                Err(err) => {
+                    // the status ends the RPC, the remaining messages are not sent
+                    status = err;
+                    break;
                 }
             }
         }
 
-        yield Frame::trailers(Status::new(Code::Ok).to_headers());
+        yield Frame::trailers(status.to_headers());
     };
 
     BodyExt::boxed(StreamBody::new(SyncStream::new(stream))).into()
@@ -163,9 +179,18 @@ pub(crate) fn create_encode_request_body<T: Encoder>(
     let stream = async_stream::try_stream! {
         let mut buf = BytesMut::new();
 
-        while let Some(Ok(message)) = stream.next().await {
-            if let Ok(data) = encode_data_frame(&mut encoder, &mut buf, message, compression).await {
-                yield Frame::data(data);
+        while let Some(item) = stream.next().await {
+            match item {
+                Ok(message) => {
+                    if let Ok(data) = encode_data_frame(&mut encoder, &mut buf, message, compression).await {
+                        yield Frame::data(data);
+                    }
+                }
+                Err(status) => {
+                    // abort the request, so the server does not take the messages sent
+                    // so far for a complete stream
+                    Err::<(), _>(IoError::other(status))?;
+                }
             }
         }
     };
@@ -178,6 +203,7 @@ pub(crate) fn create_decode_response_body<T: Decoder>(
     headers: &HeaderMap,
     body: Body,
     compression: Option<CompressionEncoding>,
+    accept_compressed: Arc<[CompressionEncoding]>,
 ) -> Result<Streaming<T::Item>, Status> {
     // check is trailers-only
     if let Some(status) = Status::from_headers(headers)? {
@@ -191,7 +217,7 @@ pub(crate) fn create_decode_response_body<T: Decoder>(
     let mut body: BoxBody = body.into();
 
     Ok(Streaming::new(async_stream::try_stream! {
-        let mut frame_decoder = DataFrameDecoder::new(compression);
+        let mut frame_decoder = DataFrameDecoder::new(compression, accept_compressed);
         let mut status = None;
 
         while let Some(frame) = body.frame().await.transpose().map_err(Status::from_std_error)? {
@@ -221,14 +247,20 @@ pub(crate) fn create_decode_response_body<T: Decoder>(
 
 #[cfg(test)]
 mod tests {
+    use std::sync::Arc;
+
     use bytes::Bytes;
     use futures_util::TryStreamExt;
     use http::HeaderMap;
     use poem::Body;
     use prost::Message;
 
-    use super::create_decode_response_body;
-    use crate::codec::{Codec, ProstCodec};
+    use super::{create_decode_request_body, create_decode_response_body};
+    use crate::{
+        codec::{Codec, ProstCodec},
+        compression::get_incoming_encodings,
+        Code,
+    };
 
     #[derive(Clone, PartialEq, Message)]
     struct TestMsg {
@@ -262,9 +294,14 @@ mod tests {
         let body = Body::from_bytes_stream(bytes_stream);
 
         let mut codec = ProstCodec::<TestMsg, TestMsg>::default();
-        let mut streaming =
-            create_decode_response_body(codec.decoder(), &HeaderMap::default(), body, None)
-                .expect("streaming");
+        let mut streaming = create_decode_response_body(
+            codec.decoder(),
+            &HeaderMap::default(),
+            body,
+            None,
+            Arc::new([]),
+        )
+        .expect("streaming");
 
         let stream_msg = streaming
             .try_next()
@@ -274,4 +311,68 @@ mod tests {
 
         assert_eq!(msg, stream_msg);
     }
+
+    fn data_frame(compressed: bool, data: &[u8]) -> Vec<u8> {
+        let mut buffer = vec![compressed as u8];
+        buffer.extend((data.len() as u32).to_be_bytes());
+        buffer.extend(data);
+        buffer
+    }
+
+    #[tokio::test]
+    async fn compressed_message_without_encoding() {
+        let msg = TestMsg {
+            value: "abc".into(),
+        };
+        let body = Body::from(data_frame(true, &msg.encode_to_vec()));
+
+        let mut codec = ProstCodec::<TestMsg, TestMsg>::default();
+        let mut streaming = create_decode_request_body(codec.decoder(), body, None, Arc::new([]));
+        let status = streaming.try_next().await.unwrap_err();
+        assert_eq!(status.code(), Code::Unimplemented);
+        assert_eq!(status.metadata().get("grpc-accept-encoding"), Some(""));
+    }
+
+    #[test]
+    fn identity_encoding() {
+        let mut headers = HeaderMap::new();
+        headers.insert("grpc-encoding", "identity".parse().unwrap());
+        assert_eq!(get_incoming_encodings(&headers, &[]).unwrap(), None);
+
+        headers.insert("grpc-encoding", "snappy".parse().unwrap());
+        assert_eq!(
+            get_incoming_encodings(&headers, &[]).unwrap_err().code(),
+            Code::Unimplemented
+        );
+    }
+
+    #[cfg(feature = "gzip")]
+    #[tokio::test]
+    async fn per_message_compressed_flag() {
+        use crate::CompressionEncoding;
+
+        let msgs = [
+            TestMsg {
+                value: "compressed".into(),
+            },
+            TestMsg {
+                value: "uncompressed".into(),
+            },
+        ];
+        let compressed = CompressionEncoding::GZIP
+            .encode(&msgs[0].encode_to_vec())
+            .await
+            .unwrap();
+        let mut buffer = data_frame(true, &compressed);
+        buffer.extend(data_frame(false, &msgs[1].encode_to_vec()));
+
+        let mut codec = ProstCodec::<TestMsg, TestMsg>::default();
+        let streaming = create_decode_request_body(
+            codec.decoder(),
+            Body::from(buffer),
+            Some(CompressionEncoding::GZIP),
+            Arc::new([CompressionEncoding::GZIP]),
+        );
+        assert_eq!(streaming.try_collect::<Vec<_>>().await.unwrap(), msgs);
+    }
 }
@@ -1,4 +1,7 @@
-use std::{collections::HashMap, sync::Mutex};
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
 
 use futures_util::StreamExt;
 use poem::{endpoint::BoxEndpoint, IntoEndpoint};
@@ -39,17 +42,50 @@ struct HealthService {
 }
 
 /// A handle providing methods to update the health status of GRPC services
+///
+/// Pass it to [`RouteGrpc::health_reporter`](crate::RouteGrpc::health_reporter)
+/// to register every mounted service as [`ServingStatus::Serving`]
+/// automatically.
+#[derive(Clone)]
 pub struct HealthReporter {
-    state: Mutex<(ServiceStatusMap, Sender<ServiceStatusMap>)>,
+    state: Arc<Mutex<(ServiceStatusMap, Sender<ServiceStatusMap>)>>,
 }
 
 impl HealthReporter {
     fn set_status<S: Service>(&self, status: ServingStatus) {
+        self.set_service_status(S::NAME, status);
+    }
+
+    /// Sets the status of the service with the specified name.
+    ///
+    /// The empty name is the overall health of the server.
+    pub fn set_service_status(&self, name: &str, status: ServingStatus) {
         let mut state = self.state.lock().unwrap();
-        state.0.insert(S::NAME.to_string(), status);
+        state.0.insert(name.to_string(), status);
         let _ = state.1.send(state.0.clone());
     }
 
+    /// Sets the status of all registered services to
+    /// [`ServingStatus::NotServing`], which is useful during shutdown.
+    pub fn set_all_not_serving(&self) {
+        let mut state = self.state.lock().unwrap();
+        state
+            .0
+            .values_mut()
+            .for_each(|status| *status = ServingStatus::NotServing);
+        let _ = state.1.send(state.0.clone());
+    }
+
+    /// Registers the service with the specified name as
+    /// [`ServingStatus::Serving`], unless it already has a status.
+    pub(crate) fn register(&self, name: &str) {
+        let mut state = self.state.lock().unwrap();
+        if !state.0.contains_key(name) {
+            state.0.insert(name.to_string(), ServingStatus::Serving);
+            let _ = state.1.send(state.0.clone());
+        }
+    }
+
     /// Sets the status of the service implemented by `S` to
     /// [`ServingStatus::Serving`]
     pub fn set_serving<S: Service>(&self) {
@@ -114,7 +150,7 @@ pub fn health_service() -> (
     (
         proto::HealthServer::new(HealthService { receiver }),
         HealthReporter {
-            state: Mutex::new((Default::default(), sender)),
+            state: Arc::new(Mutex::new((Default::default(), sender))),
         },
     )
 }
@@ -131,7 +167,7 @@ mod tests {
         (
             HealthService { receiver },
             HealthReporter {
-                state: Mutex::new((Default::default(), sender)),
+                state: Arc::new(Mutex::new((Default::default(), sender))),
             },
         )
     }
@@ -225,4 +261,50 @@ mod tests {
             Code::NotFound
         );
     }
+
+    #[tokio::test]
+    async fn auto_register() {
+        let (service, reporter) = create_service();
+        let (health, _) = health_service();
+        let check = |name: &str| {
+            let service = &service;
+            let name = name.to_string();
+            async move {
+                service
+                    .check(Request::new(proto::HealthCheckRequest { service: name }))
+                    .await
+                    .map(|resp| resp.into_inner().status)
+            }
+        };
+
+        reporter.set_not_serving::<proto::HealthServer<HealthService>>();
+        let _route = crate::RouteGrpc::new()
+            .add_service(health)
+            .health_reporter(&reporter);
+
+        // the status set before registration is kept
+        assert_eq!(
+            check(<proto::HealthServer<HealthService>>::NAME)
+                .await
+                .unwrap(),
+            proto::health_check_response::ServingStatus::NotServing as i32
+        );
+        assert_eq!(
+            check("").await.unwrap(),
+            proto::health_check_response::ServingStatus::Serving as i32
+        );
+
+        reporter.set_serving::<proto::HealthServer<HealthService>>();
+        reporter.set_all_not_serving();
+        assert_eq!(
+            check(<proto::HealthServer<HealthService>>::NAME)
+                .await
+                .unwrap(),
+            proto::health_check_response::ServingStatus::NotServing as i32
+        );
+        assert_eq!(
+            check("").await.unwrap(),
+            proto::health_check_response::ServingStatus::NotServing as i32
+        );
+    }
 }
@@ -20,12 +20,15 @@ pub mod service;
 pub mod codec;
 pub mod metadata;
 
+#[cfg(feature = "opentelemetry")]
+mod client_tracing;
 mod compression;
 mod connector;
 mod encoding;
 #[cfg(feature = "example_generated")]
 pub mod example_generated;
 mod health;
+mod mock;
 mod reflection;
 mod request;
 mod response;
@@ -39,10 +42,11 @@ pub use client::{ClientBuilderError, ClientConfig, ClientConfigBuilder};
 pub use compression::CompressionEncoding;
 pub use health::{health_service, HealthReporter, ServingStatus};
 pub use metadata::Metadata;
+pub use mock::MockServer;
 pub use reflection::Reflection;
 pub use request::Request;
 pub use response::Response;
 pub use route::RouteGrpc;
 pub use service::Service;
 pub use status::{Code, Status};
-pub use streaming::Streaming;
+pub use streaming::{Streaming, StreamingSender};
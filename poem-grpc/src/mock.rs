@@ -0,0 +1,227 @@
+use std::{collections::HashMap, future::Future, sync::Arc};
+
+use http::{header, HeaderValue};
+use poem::{endpoint::BoxEndpoint, Endpoint, EndpointExt};
+use prost::Message;
+
+use crate::{
+    codec::ProstCodec,
+    server::GrpcServer,
+    service::{
+        BidirectionalStreamingService, ClientStreamingService, ServerStreamingService, UnaryService,
+    },
+    Code, Request, Response, Status, Streaming,
+};
+
+/// An in-process GRPC server with mocked methods, to test the code that calls
+/// a GRPC client without starting a real server.
+///
+/// Each method is identified by its path, `/{package}.{Service}/{Method}`,
+/// and is implemented with a closure, which can return canned responses,
+/// streams or errors. The methods that are not mocked fail with
+/// [`Code::Unimplemented`]. Pass it to the `from_endpoint` function of a
+/// generated client, the requests are sent to the mock without any socket.
+///
+/// The messages are encoded with Protobuf.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use poem_grpc::{Code, MockServer, Request, Response, Status};
+///
+/// let mock = MockServer::new()
+///     .unary("/helloworld.Greeter/SayHello", |req: Request<HelloRequest>| async move {
+///         Ok(Response::new(HelloReply {
+///             message: format!("Hello {}!", req.name),
+///         }))
+///     })
+///     .unary("/helloworld.Greeter/SayGoodbye", |_: Request<HelloRequest>| async move {
+///         Err::<Response<HelloReply>, _>(Status::new(Code::Unavailable))
+///     });
+/// let client = GreeterClient::from_endpoint(mock);
+/// ```
+#[derive(Default)]
+pub struct MockServer {
+    methods: HashMap<String, BoxEndpoint<'static, poem::Response>>,
+}
+
+impl MockServer {
+    /// Create a `MockServer` without any mocked method.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    fn method<F, Fut>(mut self, path: &str, f: F) -> Self
+    where
+        F: Fn(poem::Request) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = poem::Response> + Send + 'static,
+    {
+        let ep = poem::endpoint::make(move |req| {
+            let resp = f(req);
+            async move { Ok::<_, poem::Error>(resp.await) }
+        });
+        self.methods.insert(path.to_string(), ep.boxed());
+        self
+    }
+
+    /// Mocks a unary method.
+    pub fn unary<F, Fut, Req, Resp>(self, path: &str, f: F) -> Self
+    where
+        F: Fn(Request<Req>) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<Response<Resp>, Status>> + Send + 'static,
+        Req: Message + Default + Send + 'static,
+        Resp: Message + Send + 'static,
+    {
+        let f = Arc::new(f);
+        self.method(path, move |req| {
+            let f = f.clone();
+            async move {
+                GrpcServer::new(ProstCodec::<Resp, Req>::default(), None, &[])
+                    .unary(FnService(f), req)
+                    .await
+            }
+        })
+    }
+
+    /// Mocks a client streaming method.
+    pub fn client_streaming<F, Fut, Req, Resp>(self, path: &str, f: F) -> Self
+    where
+        F: Fn(Request<Streaming<Req>>) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<Response<Resp>, Status>> + Send + 'static,
+        Req: Message + Default + Send + 'static,
+        Resp: Message + Send + 'static,
+    {
+        let f = Arc::new(f);
+        self.method(path, move |req| {
+            let f = f.clone();
+            async move {
+                GrpcServer::new(ProstCodec::<Resp, Req>::default(), None, &[])
+                    .client_streaming(FnService(f), req)
+                    .await
+            }
+        })
+    }
+
+    /// Mocks a server streaming method, the returned stream can end with an
+    /// error to test how the client handles the errors in the middle of a
+    /// stream.
+    pub fn server_streaming<F, Fut, Req, Resp>(self, path: &str, f: F) -> Self
+    where
+        F: Fn(Request<Req>) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<Response<Streaming<Resp>>, Status>> + Send + 'static,
+        Req: Message + Default + Send + 'static,
+        Resp: Message + Send + 'static,
+    {
+        let f = Arc::new(f);
+        self.method(path, move |req| {
+            let f = f.clone();
+            async move {
+                GrpcServer::new(ProstCodec::<Resp, Req>::default(), None, &[])
+                    .server_streaming(FnService(f), req)
+                    .await
+            }
+        })
+    }
+
+    /// Mocks a bidirectional streaming method.
+    pub fn bidirectional_streaming<F, Fut, Req, Resp>(self, path: &str, f: F) -> Self
+    where
+        F: Fn(Request<Streaming<Req>>) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<Response<Streaming<Resp>>, Status>> + Send + 'static,
+        Req: Message + Default + Send + 'static,
+        Resp: Message + Send + 'static,
+    {
+        let f = Arc::new(f);
+        self.method(path, move |req| {
+            let f = f.clone();
+            async move {
+                GrpcServer::new(ProstCodec::<Resp, Req>::default(), None, &[])
+                    .bidirectional_streaming(FnService(f), req)
+                    .await
+            }
+        })
+    }
+}
+
+impl Endpoint for MockServer {
+    type Output = poem::Response;
+
+    async fn call(&self, req: poem::Request) -> poem::Result<Self::Output> {
+        match self.methods.get(req.uri().path()) {
+            Some(ep) => ep.call(req).await,
+            None => {
+                let status = Status::new(Code::Unimplemented)
+                    .with_message(format!("method `{}` is not mocked", req.uri().path()));
+                let mut resp = poem::Response::default();
+                resp.headers_mut().insert(
+                    header::CONTENT_TYPE,
+                    HeaderValue::from_static("application/grpc"),
+                );
+                resp.headers_mut().extend(status.to_headers());
+                Ok(resp)
+            }
+        }
+    }
+}
+
+struct FnService<F>(Arc<F>);
+
+impl<F, Fut, Req, Resp> UnaryService<Req> for FnService<F>
+where
+    F: Fn(Request<Req>) -> Fut,
+    Fut: Future<Output = Result<Response<Resp>, Status>> + Send,
+{
+    type Response = Resp;
+
+    fn call(
+        &self,
+        request: Request<Req>,
+    ) -> impl Future<Output = Result<Response<Self::Response>, Status>> + Send {
+        (self.0)(request)
+    }
+}
+
+impl<F, Fut, Req, Resp> ClientStreamingService<Req> for FnService<F>
+where
+    F: Fn(Request<Streaming<Req>>) -> Fut,
+    Fut: Future<Output = Result<Response<Resp>, Status>> + Send,
+{
+    type Response = Resp;
+
+    fn call(
+        &self,
+        request: Request<Streaming<Req>>,
+    ) -> impl Future<Output = Result<Response<Self::Response>, Status>> + Send {
+        (self.0)(request)
+    }
+}
+
+impl<F, Fut, Req, Resp> ServerStreamingService<Req> for FnService<F>
+where
+    F: Fn(Request<Req>) -> Fut,
+    Fut: Future<Output = Result<Response<Streaming<Resp>>, Status>> + Send,
+{
+    type Response = Resp;
+
+    fn call(
+        &self,
+        request: Request<Req>,
+    ) -> impl Future<Output = Result<Response<Streaming<Self::Response>>, Status>> + Send {
+        (self.0)(request)
+    }
+}
+
+impl<F, Fut, Req, Resp> BidirectionalStreamingService<Req> for FnService<F>
+where
+    F: Fn(Request<Streaming<Req>>) -> Fut,
+    Fut: Future<Output = Result<Response<Streaming<Resp>>, Status>> + Send,
+{
+    type Response = Resp;
+
+    fn call(
+        &self,
+        request: Request<Streaming<Req>>,
+    ) -> impl Future<Output = Result<Response<Streaming<Self::Response>>, Status>> + Send {
+        (self.0)(request)
+    }
+}
@@ -1,11 +1,24 @@
-use poem::{endpoint::BoxEndpoint, IntoEndpoint, Response};
+use std::sync::Arc;
 
-use crate::Service;
+use futures_util::future::BoxFuture;
+use http::{header, HeaderValue, StatusCode};
+use poem::{endpoint::BoxEndpoint, Endpoint, EndpointExt, IntoEndpoint, Response};
+
+use crate::{health::HealthReporter, Code, Metadata, Request, Service, Status};
+
+type RequestInterceptor =
+    Arc<dyn for<'a> Fn(&'a mut Request<()>) -> BoxFuture<'a, Result<(), Status>> + Send + Sync>;
+
+type ResponseInterceptor =
+    Arc<dyn Fn(&Request<()>, &mut Result<crate::Response<()>, Status>) + Send + Sync>;
 
 /// A router for GRPC services
 #[derive(Default)]
 pub struct RouteGrpc {
-    route: poem::Route,
+    services: Vec<(&'static str, BoxEndpoint<'static, Response>)>,
+    health_reporter: Option<HealthReporter>,
+    request_interceptors: Vec<RequestInterceptor>,
+    response_interceptors: Vec<ResponseInterceptor>,
 }
 
 impl RouteGrpc {
@@ -19,7 +32,117 @@ impl RouteGrpc {
     where
         S: IntoEndpoint<Endpoint = BoxEndpoint<'static, Response>> + Service,
     {
-        self.route = self.route.nest(format!("/{}", S::NAME), service);
+        self.services.push((S::NAME, service.into_endpoint()));
+        if let Some(reporter) = &self.health_reporter {
+            reporter.register(S::NAME);
+        }
+        self
+    }
+
+    /// Registers every mounted service, and the overall server health (the
+    /// empty service name), to the [`HealthReporter`] as
+    /// [`ServingStatus::Serving`](crate::ServingStatus::Serving).
+    ///
+    /// The services added before and after calling this method are both
+    /// registered, and the status already set for a service is kept. Use
+    /// the reporter to flip individual services to `NOT_SERVING` during
+    /// shutdown or maintenance.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use poem_grpc::{health_service, RouteGrpc};
+    ///
+    /// let (health_service, health_reporter) = health_service();
+    ///
+    /// let route = RouteGrpc::new()
+    ///     .health_reporter(&health_reporter)
+    ///     .add_service(health_service);
+    ///
+    /// // during shutdown
+    /// health_reporter.set_all_not_serving();
+    /// ```
+    pub fn health_reporter(mut self, reporter: &HealthReporter) -> Self {
+        reporter.register("");
+        for (name, _) in &self.services {
+            reporter.register(name);
+        }
+        self.health_reporter = Some(reporter.clone());
+        self
+    }
+
+    /// Adds an interceptor that runs before every RPC of every service.
+    ///
+    /// The interceptor receives the request without its message, so it can
+    /// inspect and modify the metadata and the extensions, e.g. to check an
+    /// authentication token and to store the authenticated user for the
+    /// handler. If it returns an error, the RPC is rejected with that status
+    /// and the handler is not called.
+    ///
+    /// The interceptors run in the order they are added, and apply to the
+    /// services added before and after calling this method.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use poem_grpc::{Code, RouteGrpc, Status};
+    ///
+    /// let route = RouteGrpc::new().intercept(|req| {
+    ///     Box::pin(async move {
+    ///         match req.metadata().get("authorization") {
+    ///             Some("Bearer secret") => Ok(()),
+    ///             _ => Err(Status::new(Code::Unauthenticated)),
+    ///         }
+    ///     })
+    /// });
+    /// ```
+    pub fn intercept<F>(mut self, f: F) -> Self
+    where
+        F: for<'a> Fn(&'a mut Request<()>) -> BoxFuture<'a, Result<(), Status>>
+            + Send
+            + Sync
+            + 'static,
+    {
+        self.request_interceptors.push(Arc::new(f));
+        self
+    }
+
+    /// Adds an interceptor that runs after every RPC of every service,
+    /// including the RPCs rejected by the interceptors added with
+    /// [`RouteGrpc::intercept`].
+    ///
+    /// The interceptor receives the request without its message, and the
+    /// response without its message or the status returned before the
+    /// response messages are sent, which it can modify, e.g. to add metadata,
+    /// to log the status or to record the elapsed time.
+    ///
+    /// The status of an RPC that fails after its response messages have been
+    /// sent is carried in the trailers, and is not seen by the interceptors.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::time::Instant;
+    ///
+    /// use poem_grpc::RouteGrpc;
+    ///
+    /// let route = RouteGrpc::new()
+    ///     .intercept(|req| {
+    ///         req.extensions_mut().insert(Instant::now());
+    ///         Box::pin(async { Ok(()) })
+    ///     })
+    ///     .intercept_response(|req, res| {
+    ///         let elapsed = req.extensions().get::<Instant>().unwrap().elapsed();
+    ///         if let Err(status) = res {
+    ///             tracing::warn!(status = %status, elapsed = ?elapsed, "rpc failed");
+    ///         }
+    ///     });
+    /// ```
+    pub fn intercept_response<F>(mut self, f: F) -> Self
+    where
+        F: Fn(&Request<()>, &mut Result<crate::Response<()>, Status>) + Send + Sync + 'static,
+    {
+        self.response_interceptors.push(Arc::new(f));
         self
     }
 }
@@ -28,6 +151,150 @@ impl IntoEndpoint for RouteGrpc {
     type Endpoint = poem::Route;
 
     fn into_endpoint(self) -> Self::Endpoint {
-        self.route
+        let interceptors = Arc::new(Interceptors {
+            request: self.request_interceptors,
+            response: self.response_interceptors,
+        });
+
+        self.services
+            .into_iter()
+            .fold(poem::Route::new(), |route, (name, service)| {
+                let path = format!("/{name}");
+                if interceptors.is_empty() {
+                    route.nest(path, service)
+                } else {
+                    route.nest(
+                        path,
+                        Intercept {
+                            inner: service,
+                            interceptors: interceptors.clone(),
+                        }
+                        .boxed(),
+                    )
+                }
+            })
     }
 }
+
+struct Interceptors {
+    request: Vec<RequestInterceptor>,
+    response: Vec<ResponseInterceptor>,
+}
+
+impl Interceptors {
+    fn is_empty(&self) -> bool {
+        self.request.is_empty() && self.response.is_empty()
+    }
+}
+
+struct Intercept {
+    inner: BoxEndpoint<'static, Response>,
+    interceptors: Arc<Interceptors>,
+}
+
+impl Endpoint for Intercept {
+    type Output = Response;
+
+    async fn call(&self, req: poem::Request) -> poem::Result<Self::Output> {
+        let content_type = req
+            .headers()
+            .get(header::CONTENT_TYPE)
+            .cloned()
+            .unwrap_or_else(|| HeaderValue::from_static("application/grpc"));
+        let (mut parts, body) = req.into_parts();
+        let mut grpc_req = Request {
+            metadata: Metadata {
+                headers: std::mem::take(&mut parts.headers),
+            },
+            message: (),
+            extensions: std::mem::take(&mut parts.extensions),
+        };
+
+        let mut res = Ok(());
+        for interceptor in &self.interceptors.request {
+            res = interceptor(&mut grpc_req).await;
+            if res.is_err() {
+                break;
+            }
+        }
+
+        let mut http_resp = None;
+        let mut res = match res {
+            Ok(()) => {
+                parts.headers = grpc_req.metadata.headers.clone();
+                parts.extensions = grpc_req.extensions.clone();
+                match self
+                    .inner
+                    .call(poem::Request::from_parts(parts, body))
+                    .await
+                {
+                    Ok(resp) => match Status::from_headers(resp.headers()) {
+                        Ok(Some(status)) if !status.is_ok() => Err(status),
+                        _ => {
+                            let (mut parts, body) = resp.into_parts();
+                            let metadata = Metadata {
+                                headers: std::mem::take(&mut parts.headers),
+                            };
+                            http_resp = Some((parts, body));
+                            Ok(crate::Response {
+                                metadata,
+                                message: (),
+                            })
+                        }
+                    },
+                    // the error would bypass the response interceptors
+                    Err(err) => Err(status_from_error(err)),
+                }
+            }
+            Err(status) => Err(status),
+        };
+
+        for interceptor in &self.interceptors.response {
+            interceptor(&grpc_req, &mut res);
+        }
+
+        Ok(match (res, http_resp) {
+            (Ok(resp), Some((mut parts, body))) => {
+                parts.headers = resp.metadata.headers;
+                Response::from_parts(parts, body)
+            }
+            (Ok(resp), None) => {
+                // an interceptor turned a rejected RPC into a trailers-only success
+                let mut http_resp = Response::default();
+                *http_resp.headers_mut() = resp.metadata.headers;
+                http_resp
+                    .headers_mut()
+                    .insert(header::CONTENT_TYPE, content_type);
+                http_resp
+                    .headers_mut()
+                    .extend(Status::new(Code::Ok).to_headers());
+                http_resp
+            }
+            (Err(status), _) => {
+                let mut http_resp = Response::default();
+                http_resp
+                    .headers_mut()
+                    .insert(header::CONTENT_TYPE, content_type);
+                http_resp.headers_mut().extend(status.to_headers());
+                http_resp
+            }
+        })
+    }
+}
+
+/// Converts an error returned by a service to a status, according to the
+/// mapping of HTTP status codes in the gRPC specification.
+fn status_from_error(err: poem::Error) -> Status {
+    let code = match err.status() {
+        StatusCode::UNAUTHORIZED => Code::Unauthenticated,
+        StatusCode::FORBIDDEN => Code::PermissionDenied,
+        StatusCode::NOT_FOUND => Code::Unimplemented,
+        StatusCode::TOO_MANY_REQUESTS
+        | StatusCode::BAD_GATEWAY
+        | StatusCode::SERVICE_UNAVAILABLE
+        | StatusCode::GATEWAY_TIMEOUT => Code::Unavailable,
+        StatusCode::BAD_REQUEST => Code::Internal,
+        _ => Code::Unknown,
+    };
+    Status::new(code).with_message(err)
+}
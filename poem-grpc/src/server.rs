@@ -48,7 +48,12 @@ impl<'a, T: Codec> GrpcServer<'a, T> {
                 return resp;
             }
         };
-        let mut stream = create_decode_request_body(self.codec.decoder(), body, incoming_encoding);
+        let mut stream = create_decode_request_body(
+            self.codec.decoder(),
+            body,
+            incoming_encoding,
+            self.accept_compressed.into(),
+        );
 
         let res = match stream.next().await {
             Some(Ok(message)) => {
@@ -96,7 +101,12 @@ impl<'a, T: Codec> GrpcServer<'a, T> {
                 return resp;
             }
         };
-        let stream = create_decode_request_body(self.codec.decoder(), body, incoming_encoding);
+        let stream = create_decode_request_body(
+            self.codec.decoder(),
+            body,
+            incoming_encoding,
+            self.accept_compressed.into(),
+        );
 
         let res = service
             .call(GrpcRequest {
@@ -140,7 +150,12 @@ impl<'a, T: Codec> GrpcServer<'a, T> {
                 return resp;
             }
         };
-        let mut stream = create_decode_request_body(self.codec.decoder(), body, incoming_encoding);
+        let mut stream = create_decode_request_body(
+            self.codec.decoder(),
+            body,
+            incoming_encoding,
+            self.accept_compressed.into(),
+        );
 
         let res = match stream.next().await {
             Some(Ok(message)) => {
@@ -190,7 +205,12 @@ impl<'a, T: Codec> GrpcServer<'a, T> {
                 return resp;
             }
         };
-        let stream = create_decode_request_body(self.codec.decoder(), body, incoming_encoding);
+        let stream = create_decode_request_body(
+            self.codec.decoder(),
+            body,
+            incoming_encoding,
+            self.accept_compressed.into(),
+        );
 
         let res = service
             .call(GrpcRequest {
@@ -4,10 +4,22 @@ use std::{
 };
 
 use futures_util::{stream::BoxStream, Stream, StreamExt};
+use tokio::sync::mpsc::{Receiver, Sender};
+use tokio_stream::wrappers::ReceiverStream;
 
-use crate::Status;
+use crate::{Code, Status};
 
 /// Message stream
+///
+/// When it is the request of a client streaming or bidirectional streaming
+/// call, the messages are pulled from the stream only when the HTTP/2 flow
+/// control window of the call has room for them, so a slow server slows down
+/// the producer instead of making the client buffer the messages. At most one
+/// encoded message and the data allowed by the window are held in memory, in
+/// addition to what the stream itself buffers, e.g. the capacity of
+/// [`Streaming::channel`].
+///
+/// If the request stream yields an error, the call is aborted.
 pub struct Streaming<T>(BoxStream<'static, Result<T, Status>>);
 
 impl<T> Streaming<T> {
@@ -19,6 +31,161 @@ impl<T> Streaming<T> {
     {
         Self(stream.boxed())
     }
+
+    /// Create a message stream from a stream of messages that can not fail.
+    pub fn from_stream<S>(stream: S) -> Self
+    where
+        S: Stream<Item = T> + Send + 'static,
+    {
+        Self(stream.map(Ok).boxed())
+    }
+
+    /// Create a message stream from the receiver of a channel, the stream
+    /// ends when all the senders are dropped.
+    ///
+    /// Sending an `Err(status)` ends the RPC with that status after the
+    /// messages sent before it.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use poem_grpc::{Status, Streaming};
+    ///
+    /// fn numbers() -> Streaming<i32> {
+    ///     let (tx, rx) = tokio::sync::mpsc::channel::<Result<i32, Status>>(16);
+    ///     tokio::spawn(async move {
+    ///         for i in 0..10 {
+    ///             if tx.send(Ok(i)).await.is_err() {
+    ///                 // the client has gone away
+    ///                 break;
+    ///             }
+    ///         }
+    ///     });
+    ///     Streaming::from_receiver(rx)
+    /// }
+    /// ```
+    pub fn from_receiver(receiver: Receiver<Result<T, Status>>) -> Self
+    where
+        T: Send + 'static,
+    {
+        Self(ReceiverStream::new(receiver).boxed())
+    }
+
+    /// Create a message stream and a [`StreamingSender`] with a bounded
+    /// buffer of `buffer` messages, the stream ends when all the senders are
+    /// dropped.
+    ///
+    /// [`StreamingSender::send`] waits while the buffer is full, so when the
+    /// stream is the request of a client streaming call, the sender only
+    /// runs ahead of the server by `buffer` messages plus the HTTP/2 flow
+    /// control window.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use poem_grpc::{Status, Streaming};
+    ///
+    /// # async fn upload(_: Streaming<Vec<u8>>) {}
+    /// # async fn example() -> Result<(), Status> {
+    /// let (tx, stream) = Streaming::channel(16);
+    /// tokio::spawn(upload(stream));
+    /// for i in 0..1000u32 {
+    ///     // waits until the server has consumed enough messages
+    ///     tx.send(i.to_be_bytes().to_vec()).await?;
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn channel(buffer: usize) -> (StreamingSender<T>, Self)
+    where
+        T: Send + 'static,
+    {
+        let (tx, rx) = tokio::sync::mpsc::channel(buffer);
+        (StreamingSender(tx), Self::from_receiver(rx))
+    }
+
+    /// Maps the messages of this stream to other messages, the errors are
+    /// passed through.
+    pub fn map<U, F>(self, mut f: F) -> Streaming<U>
+    where
+        F: FnMut(T) -> U + Send + 'static,
+        T: 'static,
+        U: Send + 'static,
+    {
+        Streaming(self.0.map(move |item| item.map(&mut f)).boxed())
+    }
+
+    /// Maps the messages of this stream to other messages and skips the
+    /// messages for which the closure returns `None`, the errors are passed
+    /// through.
+    pub fn filter_map<U, F>(self, mut f: F) -> Streaming<U>
+    where
+        F: FnMut(T) -> Option<U> + Send + 'static,
+        T: 'static,
+        U: Send + 'static,
+    {
+        Streaming(
+            self.0
+                .filter_map(move |item| std::future::ready(item.map(&mut f).transpose()))
+                .boxed(),
+        )
+    }
+
+    /// Maps the messages of this stream with a closure that can fail, the
+    /// stream ends at the first error.
+    pub fn try_map<U, F>(self, mut f: F) -> Streaming<U>
+    where
+        F: FnMut(T) -> Result<U, Status> + Send + 'static,
+        T: 'static,
+        U: Send + 'static,
+    {
+        Streaming(
+            self.0
+                .map(move |item| item.and_then(&mut f))
+                .scan(false, |failed, item| {
+                    if *failed {
+                        return std::future::ready(None);
+                    }
+                    *failed = item.is_err();
+                    std::future::ready(Some(item))
+                })
+                .boxed(),
+        )
+    }
+}
+
+/// The sending half of [`Streaming::channel`].
+pub struct StreamingSender<T>(Sender<Result<T, Status>>);
+
+impl<T> Clone for StreamingSender<T> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<T> StreamingSender<T> {
+    /// Sends a message, waiting until there is room in the buffer.
+    ///
+    /// Returns an error with [`Code::Cancelled`] if the stream was dropped,
+    /// for example because the call has ended.
+    pub async fn send(&self, message: T) -> Result<(), Status> {
+        self.0
+            .send(Ok(message))
+            .await
+            .map_err(|_| Status::new(Code::Cancelled).with_message("the stream was dropped"))
+    }
+
+    /// Ends the stream with an error, the messages sent before it are still
+    /// received.
+    pub async fn abort(self, status: Status) {
+        let _ = self.0.send(Err(status)).await;
+    }
+
+    /// Returns `true` if the stream was dropped.
+    #[inline]
+    pub fn is_closed(&self) -> bool {
+        self.0.is_closed()
+    }
 }
 
 impl<T> Stream for Streaming<T> {
@@ -29,3 +196,66 @@ impl<T> Stream for Streaming<T> {
         self.0.poll_next_unpin(cx)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use futures_util::{FutureExt, TryStreamExt};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn map() {
+        let stream = Streaming::from_stream(futures_util::stream::iter(1..=4))
+            .map(|n| n * 10)
+            .filter_map(|n| (n != 20).then_some(n));
+        assert_eq!(
+            stream.try_collect::<Vec<_>>().await.unwrap(),
+            vec![10, 30, 40]
+        );
+    }
+
+    #[tokio::test]
+    async fn try_map() {
+        let stream = Streaming::from_stream(futures_util::stream::iter(1..=4)).try_map(|n| {
+            if n == 3 {
+                Err(Status::new(Code::InvalidArgument))
+            } else {
+                Ok(n)
+            }
+        });
+        let items = stream.collect::<Vec<_>>().await;
+        assert_eq!(items.len(), 3);
+        assert_eq!(items[0].as_ref().unwrap(), &1);
+        assert_eq!(items[1].as_ref().unwrap(), &2);
+        assert_eq!(items[2].as_ref().unwrap_err().code(), Code::InvalidArgument);
+    }
+
+    #[tokio::test]
+    async fn from_receiver() {
+        let (tx, rx) = tokio::sync::mpsc::channel(4);
+        tokio::spawn(async move {
+            tx.send(Ok(1)).await.unwrap();
+            tx.send(Ok(2)).await.unwrap();
+        });
+        let stream = Streaming::from_receiver(rx);
+        assert_eq!(stream.try_collect::<Vec<i32>>().await.unwrap(), vec![1, 2]);
+    }
+
+    #[tokio::test]
+    async fn channel() {
+        let (tx, mut stream) = Streaming::channel(1);
+        tx.send(1).await.unwrap();
+
+        // the buffer is full until the stream is polled
+        assert!(tx.send(2).now_or_never().is_none());
+        assert_eq!(stream.try_next().await.unwrap(), Some(1));
+        tx.send(2).await.unwrap();
+        assert_eq!(stream.try_next().await.unwrap(), Some(2));
+        tx.clone().abort(Status::new(Code::Aborted)).await;
+        assert_eq!(stream.try_next().await.unwrap_err().code(), Code::Aborted);
+
+        drop(stream);
+        assert!(tx.is_closed());
+        assert_eq!(tx.send(3).await.unwrap_err().code(), Code::Cancelled);
+    }
+}
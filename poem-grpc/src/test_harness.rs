@@ -79,7 +79,7 @@ mod tests {
     use proto::{TestHarnessClient, TestHarnessServer};
 
     use super::*;
-    use crate::RouteGrpc;
+    use crate::{Code, MockServer, RouteGrpc};
 
     fn create_cli() -> TestHarnessClient {
         let server = TestHarnessServer::new(TestHarnessService);
@@ -156,4 +156,312 @@ mod tests {
         assert_eq!(resp.metadata().get("mydata"), Some("abc"));
         assert_eq!(resp.into_inner(), ValueResponse { value: 30 });
     }
+
+    #[tokio::test]
+    async fn intercept() {
+        let server = TestHarnessServer::new(TestHarnessService);
+        let route = RouteGrpc::new()
+            .add_service(server)
+            .intercept(|req| {
+                Box::pin(async move {
+                    match req.metadata().get("token") {
+                        Some("secret") => Ok(()),
+                        _ => Err(Status::new(Code::Unauthenticated)),
+                    }
+                })
+            })
+            .intercept(|req| {
+                req.metadata_mut().insert("mydata", "intercepted");
+                Box::pin(async { Ok(()) })
+            })
+            .intercept_response(|req, res| match res {
+                Ok(resp) => {
+                    if let Some(value) = req.metadata().get("token") {
+                        resp.metadata_mut().insert("token", value);
+                    }
+                }
+                Err(status) => *status = Status::new(Code::PermissionDenied),
+            });
+        let cli = TestHarnessClient::from_endpoint(route);
+
+        let mut req = Request::new(UnaryRequest { a: 10, b: 20 });
+        req.metadata_mut().insert("token", "secret");
+        let resp = cli.unary_metadata(req).await.unwrap();
+        assert_eq!(resp.metadata().get("mydata"), Some("intercepted"));
+        assert_eq!(resp.metadata().get("token"), Some("secret"));
+        assert_eq!(resp.into_inner(), ValueResponse { value: 30 });
+
+        let status = cli
+            .unary(Request::new(UnaryRequest { a: 10, b: 20 }))
+            .await
+            .unwrap_err();
+        assert_eq!(status.code(), Code::PermissionDenied);
+    }
+
+    #[tokio::test]
+    async fn mock() {
+        let cli = TestHarnessClient::from_endpoint(
+            MockServer::new()
+                .unary(
+                    "/test_harness.TestHarness/Unary",
+                    |req: Request<UnaryRequest>| async move {
+                        Ok(Response::new(ValueResponse {
+                            value: req.a * req.b,
+                        }))
+                    },
+                )
+                .server_streaming(
+                    "/test_harness.TestHarness/ServerStreaming",
+                    |_: Request<ValueRequest>| async move {
+                        Ok(Response::new_streaming(futures_util::stream::iter(vec![
+                            Ok(ValueResponse { value: 1 }),
+                            Err(Status::new(Code::Unavailable)),
+                        ])))
+                    },
+                )
+                .client_streaming(
+                    "/test_harness.TestHarness/ClientStreaming",
+                    |_: Request<Streaming<ValueRequest>>| async move {
+                        Err::<Response<ValueResponse>, _>(Status::new(Code::ResourceExhausted))
+                    },
+                ),
+        );
+
+        let resp = cli
+            .unary(Request::new(UnaryRequest { a: 10, b: 20 }))
+            .await
+            .unwrap();
+        assert_eq!(resp.into_inner(), ValueResponse { value: 200 });
+
+        let mut stream = cli
+            .server_streaming(Request::new(ValueRequest { value: 5 }))
+            .await
+            .unwrap()
+            .into_inner();
+        assert_eq!(
+            stream.try_next().await.unwrap(),
+            Some(ValueResponse { value: 1 })
+        );
+        assert_eq!(
+            stream.try_next().await.unwrap_err().code(),
+            Code::Unavailable
+        );
+
+        let status = cli
+            .client_streaming(Request::new_streaming(futures_util::stream::empty()))
+            .await
+            .unwrap_err();
+        assert_eq!(status.code(), Code::ResourceExhausted);
+
+        let status = cli
+            .unary_metadata(Request::new(UnaryRequest { a: 10, b: 20 }))
+            .await
+            .unwrap_err();
+        assert_eq!(status.code(), Code::Unimplemented);
+    }
+
+    #[tokio::test]
+    async fn intercept_errors() {
+        use std::sync::{Arc, Mutex};
+
+        use poem::{Endpoint, IntoEndpoint};
+
+        let codes = Arc::new(Mutex::new(Vec::new()));
+        let route = RouteGrpc::new()
+            .add_service(TestHarnessServer::new(TestHarnessService))
+            .intercept(|req| {
+                let allowed = req.metadata().get("token").is_some();
+                Box::pin(async move {
+                    if allowed {
+                        Ok(())
+                    } else {
+                        Err(Status::new(Code::Unauthenticated))
+                    }
+                })
+            })
+            .intercept_response({
+                let codes = codes.clone();
+                move |req, res| {
+                    if let Err(status) = res {
+                        codes.lock().unwrap().push(status.code());
+                        if req.metadata().get("recover").is_some() {
+                            *res = Ok(Response::new(()));
+                        }
+                    }
+                }
+            })
+            .into_endpoint();
+        let call = |path: &str, headers: &[(&'static str, &'static str)]| {
+            let mut req = poem::Request::builder()
+                .method(poem::http::Method::POST)
+                .version(poem::http::Version::HTTP_2)
+                .uri_str(path)
+                .content_type("application/grpc");
+            for (name, value) in headers {
+                req = req.header(*name, *value);
+            }
+            route.call(req.finish())
+        };
+
+        // the errors of the service are seen by the response interceptors
+        let resp = call("/test_harness.TestHarness/Missing", &[("token", "a")])
+            .await
+            .unwrap();
+        assert_eq!(resp.headers().get("grpc-status").unwrap(), "12");
+
+        // a rejected RPC turned into a success has a grpc status
+        let resp = call("/test_harness.TestHarness/Unary", &[("recover", "1")])
+            .await
+            .unwrap();
+        assert_eq!(resp.headers().get("grpc-status").unwrap(), "0");
+        assert_eq!(resp.content_type(), Some("application/grpc"));
+
+        assert_eq!(
+            *codes.lock().unwrap(),
+            vec![Code::Unimplemented, Code::Unauthenticated]
+        );
+    }
+
+    struct TestCerts {
+        ca: String,
+        ca_der: Vec<u8>,
+        server_cert: String,
+        server_key: String,
+        client_cert: Vec<u8>,
+        client_key: Vec<u8>,
+    }
+
+    fn create_certs() -> TestCerts {
+        use rcgen::{BasicConstraints, Certificate, CertificateParams, DnType, IsCa};
+
+        let create = |name: &str, sans: Vec<String>| {
+            let mut params = CertificateParams::new(sans);
+            params.distinguished_name.push(DnType::CommonName, name);
+            params
+        };
+
+        let mut params = create("poem-grpc test ca", vec![]);
+        params.is_ca = IsCa::Ca(BasicConstraints::Unconstrained);
+        let ca = Certificate::from_params(params).unwrap();
+        let server =
+            Certificate::from_params(create("testserver.com", vec!["testserver.com".into()]))
+                .unwrap();
+        let client = Certificate::from_params(create("client", vec![])).unwrap();
+
+        TestCerts {
+            ca: ca.serialize_pem().unwrap(),
+            ca_der: ca.serialize_der().unwrap(),
+            server_cert: server.serialize_pem_with_signer(&ca).unwrap(),
+            server_key: server.serialize_private_key_pem(),
+            client_cert: client.serialize_der_with_signer(&ca).unwrap(),
+            client_key: client.serialize_private_key_der(),
+        }
+    }
+
+    #[tokio::test]
+    async fn tls_options() {
+        use poem::listener::{Listener, RustlsCertificate, RustlsConfig, TcpListener};
+        use rustls::{
+            pki_types::{CertificateDer, PrivateKeyDer},
+            RootCertStore,
+        };
+
+        let certs = create_certs();
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        let listener = TcpListener::bind("127.0.0.1:0").rustls(
+            RustlsConfig::new()
+                .fallback(
+                    RustlsCertificate::new()
+                        .cert(certs.server_cert.clone())
+                        .key(certs.server_key.clone()),
+                )
+                .client_auth_required(certs.ca.clone())
+                // the handshake fails if the client does not offer `h2`
+                .alpn_protocols(["h2"]),
+        );
+        let handle = tokio::spawn(
+            poem::Server::new(listener)
+                .on_bind(move |addrs| {
+                    let _ = tx.send(*addrs[0].as_socket_addr().unwrap());
+                })
+                .run(RouteGrpc::new().add_service(TestHarnessServer::new(TestHarnessService))),
+        );
+        let addr = rx.await.unwrap();
+
+        let mut roots = RootCertStore::empty();
+        roots
+            .add(CertificateDer::from(certs.ca_der.clone()))
+            .unwrap();
+        let identity = || {
+            (
+                vec![CertificateDer::from(certs.client_cert.clone())],
+                PrivateKeyDer::Pkcs8(certs.client_key.clone().into()),
+            )
+        };
+        let call = move |builder: crate::ClientConfigBuilder| async move {
+            let cli = TestHarnessClient::new(
+                builder
+                    .uri(format!("https://127.0.0.1:{}", addr.port()))
+                    .build()
+                    .unwrap(),
+            );
+            cli.unary(Request::new(UnaryRequest { a: 10, b: 20 }))
+                .await
+                .map(|resp| resp.into_inner())
+        };
+
+        // private root certificates, client identity and server name
+        let (cert_chain, key) = identity();
+        let resp = call(
+            crate::ClientConfig::builder()
+                .tls_root_certificates(roots.clone())
+                .tls_client_identity(cert_chain, key)
+                .tls_server_name("testserver.com"),
+        )
+        .await
+        .unwrap();
+        assert_eq!(resp, ValueResponse { value: 30 });
+
+        // the certificate does not match the host of the uri
+        let (cert_chain, key) = identity();
+        assert!(call(
+            crate::ClientConfig::builder()
+                .tls_root_certificates(roots.clone())
+                .tls_client_identity(cert_chain, key),
+        )
+        .await
+        .is_err());
+
+        // the server is not trusted by the default root certificates
+        let (cert_chain, key) = identity();
+        assert!(call(
+            crate::ClientConfig::builder()
+                .tls_client_identity(cert_chain, key)
+                .tls_server_name("testserver.com"),
+        )
+        .await
+        .is_err());
+
+        // the server requires a client certificate
+        assert!(call(
+            crate::ClientConfig::builder()
+                .tls_root_certificates(roots.clone())
+                .tls_server_name("testserver.com"),
+        )
+        .await
+        .is_err());
+
+        // any server certificate is accepted
+        let (cert_chain, key) = identity();
+        let resp = call(
+            crate::ClientConfig::builder()
+                .tls_client_identity(cert_chain, key)
+                .danger_accept_invalid_certs(true),
+        )
+        .await
+        .unwrap();
+        assert_eq!(resp, ValueResponse { value: 30 });
+
+        handle.abort();
+    }
 }
@@ -1,19 +1,20 @@
 use darling::{util::SpannedValue, FromMeta};
 use proc_macro2::{Ident, TokenStream};
-use quote::{format_ident, quote};
+use quote::{format_ident, quote, quote_spanned};
 use syn::{
-    ext::IdentExt, visit_mut::VisitMut, Error, Expr, FnArg, ImplItem, ImplItemFn, ItemImpl, Pat,
-    Path, ReturnType, Type,
+    ext::IdentExt, spanned::Spanned, visit_mut::VisitMut, Error, Expr, FnArg, GenericArgument,
+    ImplItem, ImplItemFn, ItemImpl, Pat, Path, PathArguments, ReturnType, Type, TypePath,
 };
 
 use crate::{
     common_args::{
         APIMethod, CodeSample, DefaultValue, ExampleValue, ExternalDocument, ExtraHeader,
+        ParamStyle,
     },
     error::GeneratorResult,
     utils::{
         convert_oai_path, get_crate_name, get_description, get_summary_and_description,
-        optional_literal, optional_literal_string, parse_oai_attrs, remove_description,
+        optional_literal, optional_literal_string, parse_oai_attrs, parse_size, remove_description,
         remove_oai_attrs, RemoveLifetime,
     },
     validators::Validators,
@@ -58,6 +59,8 @@ struct APIOperation {
     code_samples: Vec<CodeSample>,
     #[darling(default)]
     hidden: bool,
+    #[darling(default)]
+    max_request_size: Option<SpannedValue<String>>,
 }
 
 #[derive(FromMeta, Default)]
@@ -75,6 +78,8 @@ struct APIOperationParam {
     validator: Option<Validators>,
     #[darling(default)]
     explode: Option<bool>,
+    #[darling(default)]
+    style: Option<ParamStyle>,
 
     // for oauth
     #[darling(multiple, default, rename = "scope")]
@@ -181,6 +186,7 @@ fn generate_operation(
         actual_type,
         code_samples,
         hidden,
+        max_request_size,
     } = args;
     if methods.is_empty() {
         return Err(Error::new_spanned(
@@ -275,6 +281,13 @@ fn generate_operation(
 
         RemoveLifetime.visit_type_mut(&mut arg_ty);
 
+        // `Option<T>` is extracted with the extractor of `T`
+        let inner_ty = option_inner_type(&arg_ty).cloned();
+        let is_optional = inner_ty.is_some();
+        if let Some(inner_ty) = inner_ty {
+            *arg_ty = inner_ty;
+        }
+
         let pname = format_ident!("p{}", i);
         let param_name = operation_param
             .name
@@ -333,10 +346,15 @@ fn generate_operation(
 
         // validator
         let validator = operation_param.validator.clone().unwrap_or_default();
+        let param_raw_value = if is_optional {
+            quote!(::std::option::Option::and_then(::std::option::Option::as_ref(&#pname), #crate_name::ApiExtractor::param_raw_type))
+        } else {
+            quote!(#crate_name::ApiExtractor::param_raw_type(&#pname))
+        };
         let param_checker = validator.create_param_checker(crate_name, &res_ty, &param_name)?.map(|stream| {
             quote! {
                 if <#arg_ty as #crate_name::ApiExtractor>::TYPES.contains(&#crate_name::ApiExtractorType::Parameter) {
-                    if let ::std::option::Option::Some(value) = #crate_name::ApiExtractor::param_raw_type(&#pname) {
+                    if let ::std::option::Option::Some(value) = #param_raw_value {
                         #stream
                     }
                 }
@@ -345,17 +363,40 @@ fn generate_operation(
         let validators_update_meta = validator.create_update_meta(crate_name)?;
 
         // do extract
-        let explode = operation_param.explode.unwrap_or(true);
+        let explode = operation_param.explode.unwrap_or(matches!(
+            operation_param.style,
+            None | Some(ParamStyle::Form | ParamStyle::DeepObject)
+        ));
+        let style = match operation_param.style {
+            Some(style) => {
+                let style = style.to_token_stream(crate_name);
+                quote!(::std::option::Option::Some(#style))
+            }
+            None => quote!(::std::option::Option::None),
+        };
+        let assert_style = operation_param
+            .style
+            .filter(|style| *style == ParamStyle::DeepObject)
+            .map(|_| {
+                quote_spanned! {arg_ty.span()=>
+                    #crate_name::__private::assert_deep_object::<<#arg_ty as #crate_name::ApiExtractor>::ParamType>();
+                }
+            });
 
+        let extract = if is_optional {
+            quote!(#crate_name::__private::extract_optional::<#arg_ty>(&request, &mut body, param_opts))
+        } else {
+            quote!(<#arg_ty as #crate_name::ApiExtractor>::from_request(&request, &mut body, param_opts))
+        };
         parse_args.push(quote! {
-            let mut param_opts = #crate_name::ExtractParamOptions {
-                name: #param_name,
-                default_value: #default_value,
-                example_value: #example_value,
-                explode: #explode,
-            };
-
-            let #pname = match <#arg_ty as #crate_name::ApiExtractor>::from_request(&request, &mut body, param_opts).await {
+            let mut param_opts = #crate_name::ExtractParamOptions::new(#param_name);
+            param_opts.default_value = #default_value;
+            param_opts.example_value = #example_value;
+            param_opts.explode = #explode;
+            param_opts.style = #style;
+            #assert_style
+
+            let #pname = match #extract.await {
                 ::std::result::Result::Ok(value) => value,
                 ::std::result::Result::Err(err) if <#res_ty as #crate_name::ApiResponse>::BAD_REQUEST_HANDLER => {
                     let res = <#res_ty as #crate_name::ApiResponse>::from_parse_request_error(err);
@@ -382,15 +423,16 @@ fn generate_operation(
                     schema
                 };
 
-                let meta_param = #crate_name::registry::MetaOperationParam {
-                    name: ::std::string::ToString::to_string(#param_name),
-                    schema: original_schema.merge(patch_schema),
-                    in_type: <#arg_ty as #crate_name::ApiExtractor>::param_in().unwrap(),
-                    description: #param_desc,
-                    required: <#arg_ty as #crate_name::ApiExtractor>::PARAM_IS_REQUIRED && !#has_default,
-                    deprecated: #deprecated,
-                    explode: #explode,
-                };
+                let mut meta_param = #crate_name::registry::MetaOperationParam::new(
+                    #param_name,
+                    original_schema.merge(patch_schema),
+                    <#arg_ty as #crate_name::ApiExtractor>::param_in().unwrap(),
+                );
+                meta_param.description = #param_desc;
+                meta_param.required = <#arg_ty as #crate_name::ApiExtractor>::PARAM_IS_REQUIRED && !#has_default && !#is_optional;
+                meta_param.deprecated = #deprecated;
+                meta_param.explode = #explode;
+                meta_param.style = #style;
                 params.push(meta_param);
             }
         });
@@ -404,6 +446,9 @@ fn generate_operation(
                     if request.description.is_none() {
                         request.description = #param_desc;
                     }
+                    if #is_optional {
+                        request.required = false;
+                    }
                 }
             }
         });
@@ -436,6 +481,18 @@ fn generate_operation(
             let ep = #crate_name::__private::poem::EndpointExt::map_to_response(#transform(ep));
         }
     });
+    let max_request_size = match &max_request_size {
+        Some(size) => {
+            let size = parse_size(size).ok_or_else(|| {
+                Error::new(
+                    size.span(),
+                    "Invalid size, expect a number of bytes with an optional unit such as `KB`, `MB` or `MiB`.",
+                )
+            })?;
+            quote!(::std::option::Option::Some(#size))
+        }
+        None => quote!(::std::option::Option::None),
+    };
     let update_content_type = match &actual_type {
         Some(actual_type) => quote!(
             resp.headers_mut().insert(#crate_name::__private::poem::http::header::CONTENT_TYPE,
@@ -465,9 +522,10 @@ fn generate_operation(
                 .or_default()
                 .insert(#crate_name::__private::poem::http::Method::#http_method, {
                     let api_obj = ::std::clone::Clone::clone(&api_obj);
-                    let ep = #crate_name::__private::poem::endpoint::make(move |request| {
+                    let ep = #crate_name::__private::poem::endpoint::make(move |mut request| {
                         let api_obj = ::std::clone::Clone::clone(&api_obj);
                         async move {
+                            #crate_name::__private::limit_request_body(&mut request, #max_request_size);
                             let (request, mut body) = request.split();
                             #(#parse_args)*
                             let res = api_obj.#fn_ident(#(#use_args),*).await;
@@ -515,15 +573,15 @@ fn generate_operation(
         let deprecated = header.deprecated;
 
         update_extra_request_headers.push(quote! {
-            params.push(#crate_name::registry::MetaOperationParam {
-                name: ::std::string::ToString::to_string(#name),
-                schema: <#ty as #crate_name::types::Type>::schema_ref(),
-                in_type: #crate_name::registry::MetaParamIn::Header,
-                description: #description,
-                required: <#ty as #crate_name::types::Type>::IS_REQUIRED,
-                deprecated: #deprecated,
-                explode: true,
-            });
+            let mut meta_param = #crate_name::registry::MetaOperationParam::new(
+                #name,
+                <#ty as #crate_name::types::Type>::schema_ref(),
+                #crate_name::registry::MetaParamIn::Header,
+            );
+            meta_param.description = #description;
+            meta_param.required = <#ty as #crate_name::types::Type>::IS_REQUIRED;
+            meta_param.deprecated = #deprecated;
+            params.push(meta_param);
         });
     }
 
@@ -622,3 +680,21 @@ fn generate_operation(
 
     Ok(())
 }
+
+/// Returns `T` if the type is `Option<T>`.
+fn option_inner_type(ty: &Type) -> Option<&Type> {
+    let Type::Path(TypePath { qself: None, path }) = ty else {
+        return None;
+    };
+    let segment = path.segments.last()?;
+    if segment.ident != "Option" {
+        return None;
+    }
+    let PathArguments::AngleBracketed(args) = &segment.arguments else {
+        return None;
+    };
+    match args.args.first() {
+        Some(GenericArgument::Type(ty)) if args.args.len() == 1 => Some(ty),
+        _ => None,
+    }
+}
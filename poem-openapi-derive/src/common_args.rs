@@ -3,6 +3,8 @@ use proc_macro2::TokenStream;
 use quote::quote;
 use syn::{Lit, Path};
 
+use crate::utils::optional_literal;
+
 #[derive(Debug, Copy, Clone, FromMeta)]
 #[allow(clippy::enum_variant_names)]
 pub(crate) enum RenameRule {
@@ -136,6 +138,30 @@ pub(crate) enum ParamIn {
     Cookie,
 }
 
+#[derive(Debug, Copy, Clone, FromMeta, Eq, PartialEq)]
+#[darling(rename_all = "camelCase")]
+pub(crate) enum ParamStyle {
+    Form,
+    SpaceDelimited,
+    PipeDelimited,
+    DeepObject,
+}
+
+impl ParamStyle {
+    pub(crate) fn to_token_stream(self, crate_name: &TokenStream) -> TokenStream {
+        match self {
+            ParamStyle::Form => quote!(#crate_name::registry::MetaParamStyle::Form),
+            ParamStyle::SpaceDelimited => {
+                quote!(#crate_name::registry::MetaParamStyle::SpaceDelimited)
+            }
+            ParamStyle::PipeDelimited => {
+                quote!(#crate_name::registry::MetaParamStyle::PipeDelimited)
+            }
+            ParamStyle::DeepObject => quote!(#crate_name::registry::MetaParamStyle::DeepObject),
+        }
+    }
+}
+
 #[derive(Debug)]
 pub(crate) enum DefaultValue {
     Default,
@@ -212,6 +238,62 @@ impl ExternalDocument {
     }
 }
 
+#[derive(FromMeta, Default)]
+pub(crate) struct XmlArgs {
+    #[darling(default)]
+    pub(crate) name: Option<String>,
+    #[darling(default)]
+    pub(crate) namespace: Option<String>,
+    #[darling(default)]
+    pub(crate) prefix: Option<String>,
+    #[darling(default)]
+    pub(crate) attribute: SpannedValue<bool>,
+    #[darling(default)]
+    pub(crate) wrapped: SpannedValue<bool>,
+}
+
+impl XmlArgs {
+    pub(crate) fn to_token_stream(&self, crate_name: &TokenStream) -> TokenStream {
+        let name = optional_literal(&self.name);
+        let namespace = optional_literal(&self.namespace);
+        let prefix = optional_literal(&self.prefix);
+        let attribute = *self.attribute;
+        let wrapped = *self.wrapped;
+        quote! {
+            #crate_name::registry::MetaXml {
+                name: #name,
+                namespace: #namespace,
+                prefix: #prefix,
+                attribute: #attribute,
+                wrapped: #wrapped,
+            }
+        }
+    }
+
+    /// Returns the qualified name of the element, or of the attribute
+    /// prefixed with `@`, the same way as the keys of `quick-xml`.
+    pub(crate) fn key(&self, name: &str) -> String {
+        let name = self.name.as_deref().unwrap_or(name);
+        let name = match &self.prefix {
+            Some(prefix) => format!("{prefix}:{name}"),
+            None => name.to_string(),
+        };
+        if *self.attribute {
+            format!("@{name}")
+        } else {
+            name
+        }
+    }
+
+    /// Returns the key of the attribute declaring the namespace.
+    pub(crate) fn namespace_key(&self) -> String {
+        match &self.prefix {
+            Some(prefix) => format!("@xmlns:{prefix}"),
+            None => "@xmlns".to_string(),
+        }
+    }
+}
+
 #[derive(FromMeta)]
 pub(crate) struct ExtraHeader {
     pub(crate) name: String,
@@ -10,7 +10,7 @@ use syn::{ext::IdentExt, Attribute, DeriveInput, Error, Path};
 use crate::{
     common_args::{apply_rename_rule_variant, ExternalDocument, RenameRule},
     error::GeneratorResult,
-    utils::{get_crate_name, get_description, optional_literal},
+    utils::{get_crate_name, get_description, optional_literal, optional_literal_string},
 };
 
 #[derive(FromVariant)]
@@ -21,6 +21,8 @@ struct EnumItem {
 
     #[darling(default)]
     rename: Option<String>,
+    #[darling(default)]
+    other: bool,
 }
 
 #[derive(FromDeriveInput)]
@@ -42,6 +44,10 @@ struct EnumArgs {
     deprecated: bool,
     #[darling(default)]
     external_docs: Option<ExternalDocument>,
+    #[darling(default)]
+    title: Option<String>,
+    #[darling(default)]
+    description: Option<String>,
 }
 
 pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
@@ -49,7 +55,10 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
     let crate_name = get_crate_name(args.internal);
     let ident = &args.ident;
     let oai_typename = args.rename.clone().unwrap_or_else(|| ident.to_string());
-    let description = get_description(&args.attrs)?;
+    let description = match &args.description {
+        Some(description) => Some(description.clone()),
+        None => get_description(&args.attrs)?,
+    };
     let e = match &args.data {
         Data::Enum(e) => e,
         _ => return Err(Error::new_spanned(ident, "Enum can only be applied to an enum.").into()),
@@ -58,8 +67,29 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
     let mut enum_items = Vec::new();
     let mut ident_to_item = Vec::new();
     let mut item_to_ident = Vec::new();
+    let mut other_item = None;
 
     for variant in e {
+        let item_ident = &variant.ident;
+
+        if variant.other {
+            if variant.fields.len() != 1 || !variant.fields.is_tuple() {
+                return Err(Error::new_spanned(
+                    item_ident,
+                    "The `other` variant must contain a single unnamed `String` field.",
+                )
+                .into());
+            }
+            if other_item.is_some() {
+                return Err(
+                    Error::new_spanned(item_ident, "Only one variant can be `other`.").into(),
+                );
+            }
+            ident_to_item.push(quote!(#ident::#item_ident(value) => value.as_str()));
+            other_item = Some(item_ident);
+            continue;
+        }
+
         if !variant.fields.is_empty() {
             return Err(Error::new_spanned(
                 &variant.ident,
@@ -71,7 +101,6 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
             .into());
         }
 
-        let item_ident = &variant.ident;
         let oai_item_name = variant.rename.clone().unwrap_or_else(|| {
             apply_rename_rule_variant(args.rename_all, variant.ident.unraw().to_string())
         });
@@ -84,15 +113,29 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
 
     let remote_conversion = if let Some(remote_ty) = &args.remote {
         let local_to_remote_items = e.iter().map(|item| {
+            let is_other = item.other;
             let item = &item.ident;
-            quote! {
-                #ident::#item => #remote_ty::#item,
+            if is_other {
+                quote! {
+                    #ident::#item(value) => #remote_ty::#item(value),
+                }
+            } else {
+                quote! {
+                    #ident::#item => #remote_ty::#item,
+                }
             }
         });
         let remote_to_local_items = e.iter().map(|item| {
+            let is_other = item.other;
             let item = &item.ident;
-            quote! {
-                #remote_ty::#item => #ident::#item,
+            if is_other {
+                quote! {
+                    #remote_ty::#item(value) => #ident::#item(value),
+                }
+            } else {
+                quote! {
+                    #remote_ty::#item => #ident::#item,
+                }
             }
         });
 
@@ -116,7 +159,18 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
     } else {
         None
     };
+    let (unknown_json_item, unknown_parameter_item) = match other_item {
+        Some(item) => (
+            quote!(_ => ::std::result::Result::Ok(#ident::#item(::std::clone::Clone::clone(item)))),
+            quote!(_ => ::std::result::Result::Ok(#ident::#item(::std::string::ToString::to_string(value)))),
+        ),
+        None => (
+            quote!(_ => ::std::result::Result::Err(#crate_name::types::ParseError::expected_type(value))),
+            quote!(_ => ::std::result::Result::Err(#crate_name::types::ParseError::custom("Expect a valid enumeration value."))),
+        ),
+    };
     let description = optional_literal(&description);
+    let title = optional_literal_string(&args.title);
     let deprecated = args.deprecated;
     let external_docs = match &args.external_docs {
         Some(external_docs) => {
@@ -148,6 +202,7 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
 
             fn register(registry: &mut #crate_name::registry::Registry) {
                 registry.create_schema::<Self, _>(<Self as #crate_name::types::Type>::name().into_owned(), |registry| #crate_name::registry::MetaSchema {
+                    title: #title,
                     description: #description,
                     external_docs: #external_docs,
                     deprecated: #deprecated,
@@ -167,7 +222,7 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
                 match &value {
                     #crate_name::__private::serde_json::Value::String(item) => match item.as_str() {
                         #(#item_to_ident,)*
-                        _ => ::std::result::Result::Err(#crate_name::types::ParseError::expected_type(value)),
+                        #unknown_json_item,
                     }
                     _ => ::std::result::Result::Err(#crate_name::types::ParseError::expected_type(value)),
                 }
@@ -178,14 +233,14 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
             fn parse_from_parameter(value: &str) -> #crate_name::types::ParseResult<Self> {
                 match value {
                     #(#item_to_ident,)*
-                    _ => ::std::result::Result::Err(#crate_name::types::ParseError::custom("Expect a valid enumeration value.")),
+                    #unknown_parameter_item,
                 }
             }
         }
 
         impl #crate_name::types::ToJSON for #ident {
             fn to_json(&self) -> ::std::option::Option<#crate_name::__private::serde_json::Value> {
-                let name = match self {
+                let name: &str = match self {
                     #(#ident_to_item),*
                 };
                 ::std::option::Option::Some(#crate_name::__private::serde_json::Value::String(::std::string::ToString::to_string(name)))
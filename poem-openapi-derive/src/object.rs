@@ -8,9 +8,12 @@ use quote::quote;
 use syn::{ext::IdentExt, Attribute, DeriveInput, Error, Generics, Path, Type};
 
 use crate::{
-    common_args::{apply_rename_rule_field, DefaultValue, ExternalDocument, RenameRule},
+    common_args::{apply_rename_rule_field, DefaultValue, ExternalDocument, RenameRule, XmlArgs},
     error::GeneratorResult,
-    utils::{create_object_name, get_crate_name, get_description, optional_literal},
+    utils::{
+        create_object_name, get_crate_name, get_description, optional_literal,
+        optional_literal_string,
+    },
     validators::Validators,
 };
 
@@ -48,6 +51,10 @@ struct ObjectField {
     serialize_with: Option<Path>,
     #[darling(default)]
     deserialize_with: Option<Path>,
+    #[darling(default)]
+    const_value: Option<String>,
+    #[darling(default)]
+    xml: Option<XmlArgs>,
 }
 
 #[derive(FromDeriveInput)]
@@ -79,6 +86,10 @@ struct ObjectArgs {
     #[darling(default)]
     external_docs: Option<ExternalDocument>,
     #[darling(default)]
+    title: Option<String>,
+    #[darling(default)]
+    description: Option<String>,
+    #[darling(default)]
     remote: Option<Path>,
     #[darling(default)]
     skip_serializing_if_is_none: bool,
@@ -86,6 +97,8 @@ struct ObjectArgs {
     skip_serializing_if_is_empty: bool,
     #[darling(default)]
     default: Option<DefaultValue>,
+    #[darling(default)]
+    xml: Option<XmlArgs>,
 }
 
 pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
@@ -102,13 +115,24 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
         }
     };
     let oai_typename = args.rename.clone().unwrap_or_else(|| ident.to_string());
-    let description = get_description(&args.attrs)?;
+    let description = match &args.description {
+        Some(description) => Some(description.clone()),
+        None => get_description(&args.attrs)?,
+    };
     let mut deserialize_fields = Vec::new();
+    let mut deserialize_flatten_fields = Vec::new();
     let mut serialize_fields = Vec::new();
     let mut register_types = Vec::new();
     let mut fields = Vec::new();
     let mut meta_fields = Vec::new();
     let mut required_fields = Vec::new();
+    let mut update_flatten_meta = Vec::new();
+    let mut property_names = Vec::new();
+    let mut flatten_types = Vec::new();
+    let mut xml_deserialize_fields = Vec::new();
+    let mut xml_serialize_fields = Vec::new();
+    let mut validate_fields = Vec::new();
+    let mut validate_flatten_fields = Vec::new();
     let object_name = create_object_name(&crate_name, &oai_typename, &args.generics);
 
     for field in &s.fields {
@@ -127,8 +151,12 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
         let skip_serializing_if = &field.skip_serializing_if;
 
         if field.skip {
+            let default_value = match &field.default {
+                Some(DefaultValue::Function(func_name)) => quote!(#func_name()),
+                _ => quote!(::std::default::Default::default()),
+            };
             deserialize_fields.push(quote! {
-                let #field_ident: #field_ty = ::std::default::Default::default();
+                let #field_ident: #field_ty = #default_value;
             });
             fields.push(field_ident);
             continue;
@@ -145,8 +173,59 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
         let field_name = field.rename.clone().unwrap_or_else(|| {
             apply_rename_rule_field(args.rename_all, field_ident.unraw().to_string())
         });
+        if *field.flatten {
+            flatten_types.push(field_ty);
+        } else {
+            property_names.push(field_name.clone());
+        }
         let field_description = get_description(&field.attrs)?;
         let field_description = optional_literal(&field_description);
+        let field_xml = match &field.xml {
+            Some(xml) => {
+                if *field.flatten {
+                    return Err(Error::new_spanned(
+                        field_ident,
+                        "The `xml` and `flatten` attributes cannot be enabled both.",
+                    )
+                    .into());
+                }
+
+                let key = xml.key(&field_name);
+                if *xml.wrapped {
+                    xml_deserialize_fields.push(quote! {
+                        if let ::std::option::Option::Some(value) = obj.remove(#key) {
+                            let value = match value {
+                                #crate_name::__private::serde_json::Value::Object(mut wrapper) => wrapper.remove(#key).unwrap_or_default(),
+                                value => value,
+                            };
+                            obj.insert(::std::string::ToString::to_string(#field_name), value);
+                        }
+                    });
+                    xml_serialize_fields.push(quote! {
+                        if let ::std::option::Option::Some(value) = object.remove(#field_name) {
+                            let mut wrapper = #crate_name::__private::serde_json::Map::new();
+                            wrapper.insert(::std::string::ToString::to_string(#key), value);
+                            object.insert(::std::string::ToString::to_string(#key), #crate_name::__private::serde_json::Value::Object(wrapper));
+                        }
+                    });
+                } else if key != field_name {
+                    xml_deserialize_fields.push(quote! {
+                        if let ::std::option::Option::Some(value) = obj.remove(#key) {
+                            obj.insert(::std::string::ToString::to_string(#field_name), value);
+                        }
+                    });
+                    xml_serialize_fields.push(quote! {
+                        if let ::std::option::Option::Some(value) = object.remove(#field_name) {
+                            object.insert(::std::string::ToString::to_string(#key), value);
+                        }
+                    });
+                }
+
+                let xml = xml.to_token_stream(&crate_name);
+                quote!(::std::option::Option::Some(#xml))
+            }
+            None => quote!(::std::option::Option::None),
+        };
         let validators = field.validator.clone().unwrap_or_default();
         let validators_checker = validators.create_obj_field_checker(&crate_name, &field_name)?;
         let validators_update_meta = validators.create_update_meta(&crate_name)?;
@@ -173,7 +252,26 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
             _ => None,
         };
 
-        if read_only {
+        if let Some(const_value) = &field.const_value {
+            if *field.flatten {
+                return Err(Error::new_spanned(
+                    field_ident,
+                    "The `const_value` and `flatten` attributes cannot be enabled both.",
+                )
+                .into());
+            }
+            deserialize_fields.push(quote! {
+                #[allow(non_snake_case)]
+                let #field_ident: #field_ty = {
+                    let value = obj.remove(#field_name);
+                    if value.as_ref().and_then(#crate_name::__private::serde_json::Value::as_str) != ::std::option::Option::Some(#const_value) {
+                        return Err(#crate_name::types::ParseError::custom(format!("property `{}` must be `{}`.", #field_name, #const_value)));
+                    }
+                    __parse_field(value)
+                        .map_err(#crate_name::types::ParseError::propagate)?
+                };
+            });
+        } else if read_only {
             let create_default_value = create_default_value
                 .clone()
                 .unwrap_or_else(|| quote! { ::std::default::Default::default() });
@@ -195,7 +293,7 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
                             match obj.remove(#field_name) {
                                 ::std::option::Option::Some(#crate_name::__private::serde_json::Value::Null) | ::std::option::Option::None => #create_default_value,
                                 value => {
-                                    let value = #crate_name::types::ParseFromJSON::parse_from_json(value).map_err(#crate_name::types::ParseError::propagate)?;
+                                    let value = __parse_field(value).map_err(#crate_name::types::ParseError::propagate)?;
                                     #validators_checker
                                     value
                                 }
@@ -206,7 +304,7 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
                 None => {
                     let deserialize_function = match field.deserialize_with {
                         Some(ref function) => quote! { #function },
-                        None => quote! { #crate_name::types::ParseFromJSON::parse_from_json },
+                        None => quote! { __parse_field },
                     };
 
                     deserialize_fields.push(quote! {
@@ -228,15 +326,107 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
                 )
                 .into());
             }
-            deserialize_fields.push(quote! {
+            // flattened fields are parsed after the other fields, and the properties
+            // consumed by each of them are removed, so a map declared last only
+            // receives the unknown properties.
+            deserialize_flatten_fields.push(quote! {
                 #[allow(non_snake_case)]
                 let #field_ident: #field_ty = {
-                    #crate_name::types::ParseFromJSON::parse_from_json(::std::option::Option::Some(#crate_name::__private::serde_json::Value::Object(::std::clone::Clone::clone(&obj))))
-                        .map_err(#crate_name::types::ParseError::propagate)?
+                    let value: #field_ty = __parse_field(::std::option::Option::Some(#crate_name::__private::serde_json::Value::Object(::std::clone::Clone::clone(&obj))))
+                        .map_err(#crate_name::types::ParseError::propagate)?;
+                    <#field_ty as #crate_name::types::ParseFromJSON>::remove_flattened_properties(&mut obj);
+                    #validators_checker
+                    value
                 };
             });
         }
 
+        // the fields are validated one by one, so an error is collected for each
+        // of the invalid values without parsing the object
+        let check_validators = if field.validator.is_some() {
+            quote! {
+                let check = || -> ::std::result::Result<(), #crate_name::types::ParseError<Self>> {
+                    #validators_checker
+                    ::std::result::Result::Ok(())
+                };
+                if let ::std::result::Result::Err(err) = check() {
+                    errors.push(#crate_name::types::ValidationError::new(&*path, err.into_message()));
+                }
+            }
+        } else {
+            quote!()
+        };
+        let collect_errors = match &field.deserialize_with {
+            Some(function) if !*field.flatten => quote! {
+                match #function(value.cloned()) {
+                    ::std::result::Result::Ok(value) => {
+                        let value: #field_ty = value;
+                        #check_validators
+                    }
+                    ::std::result::Result::Err(err) => {
+                        errors.push(#crate_name::types::ValidationError::new(&*path, err.into_message()));
+                    }
+                }
+            },
+            _ if field.validator.is_some() => quote! {
+                let len = errors.len();
+                <#field_ty as #crate_name::types::ParseFromJSON>::collect_json_errors(value, &path, errors);
+                if errors.len() == len {
+                    if let ::std::result::Result::Ok(value) = __parse_field::<#field_ty>(value.cloned()) {
+                        #check_validators
+                    }
+                }
+            },
+            _ => quote! {
+                <#field_ty as #crate_name::types::ParseFromJSON>::collect_json_errors(value, &path, errors);
+            },
+        };
+        if *field.flatten {
+            validate_flatten_fields.push(quote! {
+                {
+                    let path = ::std::string::ToString::to_string(path);
+                    let value = ::std::option::Option::Some(&rest);
+                    #collect_errors
+                }
+                if let #crate_name::__private::serde_json::Value::Object(rest) = &mut rest {
+                    <#field_ty as #crate_name::types::ParseFromJSON>::remove_flattened_properties(rest);
+                }
+            });
+        } else {
+            let pointer = format!("/{}", field_name.replace('~', "~0").replace('/', "~1"));
+            let collect_errors = if let Some(const_value) = &field.const_value {
+                quote! {
+                    if ::std::option::Option::and_then(value, #crate_name::__private::serde_json::Value::as_str) != ::std::option::Option::Some(#const_value) {
+                        errors.push(#crate_name::types::ValidationError::new(path, #crate_name::types::ParseError::<Self>::custom(::std::format!("property `{}` must be `{}`.", #field_name, #const_value)).into_message()));
+                    }
+                }
+            } else if read_only {
+                quote! {
+                    if ::std::option::Option::is_some(&value) {
+                        errors.push(#crate_name::types::ValidationError::new(path, #crate_name::types::ParseError::<Self>::custom(::std::format!("properties `{}` is read only.", #field_name)).into_message()));
+                    }
+                }
+            } else if create_default_value.is_some() {
+                quote! {
+                    match value {
+                        ::std::option::Option::Some(#crate_name::__private::serde_json::Value::Null) | ::std::option::Option::None => {}
+                        value => {
+                            #collect_errors
+                        }
+                    }
+                }
+            } else {
+                collect_errors
+            };
+            validate_fields.push(quote! {
+                {
+                    let path = ::std::format!("{}{}", path, #pointer);
+                    let value = obj.get(#field_name);
+                    #collect_errors
+                }
+            });
+        }
+
         if !*field.flatten {
             if !write_only {
                 let check_is_none = if skip_serializing_if_is_none {
@@ -257,20 +447,29 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
 
                 let serialize_function = match field.serialize_with {
                     Some(ref function) => quote! { #function },
-                    None => quote! { #crate_name::types::ToJSON::to_json },
+                    None => quote! { __to_value },
                 };
 
-                serialize_fields.push(quote! {
+                if let Some(const_value) = &field.const_value {
+                    serialize_fields.push(quote! {
+                        object.insert(
+                            ::std::string::ToString::to_string(#field_name),
+                            #crate_name::__private::serde_json::Value::String(::std::string::ToString::to_string(#const_value)),
+                        );
+                    });
+                } else {
+                    serialize_fields.push(quote! {
                     if #check_is_none && #check_is_empty && #check_if {
                         if let ::std::option::Option::Some(value) = #serialize_function(&self.#field_ident) {
                             object.insert(::std::string::ToString::to_string(#field_name), value);
                         }
                     }
                 });
+                }
             }
         } else {
             serialize_fields.push(quote! {
-                if let ::std::option::Option::Some(#crate_name::__private::serde_json::Value::Object(obj)) = #crate_name::types::ToJSON::to_json(&self.#field_ident) {
+                if let ::std::option::Option::Some(#crate_name::__private::serde_json::Value::Object(obj)) = __to_value(&self.#field_ident) {
                     object.extend(obj);
                 }
             });
@@ -287,14 +486,25 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
             register_types
                 .push(quote!(<#field_ty as #crate_name::types::Type>::register(registry);));
 
+            let original_schema = match &field.const_value {
+                Some(const_value) => quote! {
+                    #crate_name::registry::MetaSchemaRef::Inline(::std::boxed::Box::new(#crate_name::registry::MetaSchema {
+                        enum_items: ::std::vec![#crate_name::__private::serde_json::Value::String(::std::string::ToString::to_string(#const_value))],
+                        ..#crate_name::registry::MetaSchema::new("string")
+                    }))
+                },
+                None => quote!(<#field_ty as #crate_name::types::Type>::schema_ref()),
+            };
+
             meta_fields.push(quote! {{
-                let original_schema = <#field_ty as #crate_name::types::Type>::schema_ref();
+                let original_schema = #original_schema;
                 let patch_schema = {
                     let mut schema = #crate_name::registry::MetaSchema::ANY;
                     schema.default = #field_meta_default;
                     schema.nullable = #nullable;
                     schema.read_only = #read_only;
                     schema.write_only = #write_only;
+                    schema.xml = #field_xml;
 
                     if let ::std::option::Option::Some(field_description) = #field_description {
                         schema.description = ::std::option::Option::Some(field_description);
@@ -306,23 +516,54 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
                 fields.push((#field_name, original_schema.merge(patch_schema)));
             }});
 
-            let has_default = field.default.is_some();
+            let has_default = create_default_value.is_some() && field.const_value.is_none();
+            let is_const = field.const_value.is_some();
             required_fields.push(quote! {
-                if <#field_ty>::IS_REQUIRED && !#has_default {
+                if (#is_const || <#field_ty>::IS_REQUIRED) && !#has_default {
                     fields.push(#field_name);
                 }
             });
         } else {
+            register_types
+                .push(quote!(<#field_ty as #crate_name::types::Type>::register(registry);));
             meta_fields.push(quote! {
                 fields.extend(registry.create_fake_schema::<#field_ty>().properties);
             });
             required_fields.push(quote! {
                 fields.extend(registry.create_fake_schema::<#field_ty>().required);
             });
+            // the validators of a flattened map apply to the unknown properties
+            let update_validators_meta = field.validator.is_some().then(|| {
+                quote! {
+                    let original_schema = #crate_name::registry::MetaSchemaRef::Inline(::std::boxed::Box::new(#crate_name::registry::MetaSchema::new("object")));
+                    let mut schema = #crate_name::registry::MetaSchema::ANY;
+                    #validators_update_meta
+                    if let ::std::option::Option::Some(#crate_name::registry::MetaSchemaRef::Inline(patch_schema)) = schema.additional_properties.map(|schema| *schema) {
+                        additional_properties = ::std::boxed::Box::new((*additional_properties).merge(*patch_schema));
+                    }
+                    if let ::std::option::Option::Some(min_properties) = schema.min_properties {
+                        meta.min_properties = ::std::option::Option::Some(meta.required.len() + min_properties);
+                    }
+                    if let ::std::option::Option::Some(max_properties) = schema.max_properties {
+                        meta.max_properties = ::std::option::Option::Some(meta.properties.len() + max_properties);
+                    }
+                }
+            });
+            let additional_properties = match &update_validators_meta {
+                Some(_) => quote!(mut additional_properties),
+                None => quote!(additional_properties),
+            };
+            update_flatten_meta.push(quote! {
+                if let ::std::option::Option::Some(#additional_properties) = registry.create_fake_schema::<#field_ty>().additional_properties {
+                    #update_validators_meta
+                    meta.additional_properties = ::std::option::Option::Some(additional_properties);
+                }
+            });
         }
     }
 
     let description = optional_literal(&description);
+    let title = optional_literal_string(&args.title);
     let deprecated = args.deprecated;
     let external_docs = match &args.external_docs {
         Some(external_docs) => {
@@ -331,8 +572,73 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
         }
         None => quote!(::std::option::Option::None),
     };
+    let (xml, xml_root_name) = match &args.xml {
+        Some(xml) => {
+            if *xml.attribute || *xml.wrapped {
+                let span = if *xml.attribute {
+                    xml.attribute.span()
+                } else {
+                    xml.wrapped.span()
+                };
+                return Err(Error::new(
+                    span,
+                    "The `attribute` and `wrapped` attributes can only be used on the fields.",
+                )
+                .into());
+            }
+
+            // the namespace is declared by each element of the object
+            if let Some(namespace) = &xml.namespace {
+                let namespace_key = xml.namespace_key();
+                xml_deserialize_fields.push(quote! {
+                    obj.remove(#namespace_key);
+                });
+                xml_serialize_fields.push(quote! {
+                    object.insert(
+                        ::std::string::ToString::to_string(#namespace_key),
+                        #crate_name::__private::serde_json::Value::String(::std::string::ToString::to_string(#namespace)),
+                    );
+                });
+            }
+
+            let root_name = xml.key(&oai_typename);
+            let xml = xml.to_token_stream(&crate_name);
+            (
+                quote!(::std::option::Option::Some(#xml)),
+                Some(quote! {
+                    fn xml_root_name() -> ::std::option::Option<&'static str> {
+                        ::std::option::Option::Some(#root_name)
+                    }
+                }),
+            )
+        }
+        None => (quote!(::std::option::Option::None), None),
+    };
+    // the fields of the nested types are converted with these helpers, so the
+    // XML mapping of the nested objects is applied in XML documents
+    let field_helper = |parse: TokenStream, to_value: TokenStream| {
+        (
+            quote! {
+                #[allow(dead_code)]
+                fn __parse_field<T: #crate_name::types::ParseFromJSON>(value: ::std::option::Option<#crate_name::__private::serde_json::Value>) -> ::std::result::Result<T, #crate_name::types::ParseError<T>> {
+                    T::#parse(value)
+                }
+            },
+            quote! {
+                #[allow(dead_code)]
+                fn __to_value<T: #crate_name::types::ToJSON>(value: &T) -> ::std::option::Option<#crate_name::__private::serde_json::Value> {
+                    value.#to_value()
+                }
+            },
+        )
+    };
+    let (parse_json_field, json_value) = field_helper(quote!(parse_from_json), quote!(to_json));
+    let (parse_xml_field, xml_value) =
+        field_helper(quote!(parse_from_xml_value), quote!(to_xml_value));
+
     let meta = quote! {
         #crate_name::registry::MetaSchema {
+            title: #title,
             description: #description,
             external_docs: #external_docs,
             required: {
@@ -347,13 +653,44 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
                 fields
             },
             deprecated: #deprecated,
+            xml: #xml,
             ..#crate_name::registry::MetaSchema::new("object")
         }
     };
     let deny_unknown_fields = if args.deny_unknown_fields {
         Some(quote! {
             if let ::std::option::Option::Some((field_name, _)) = std::iter::Iterator::next(&mut ::std::iter::IntoIterator::into_iter(obj)) {
-                return Err(#crate_name::types::ParseError::custom(format!("unknown field `{}`.", field_name)));
+                return Err(#crate_name::types::ParseError::custom(::std::format!("unknown field `{}`.", field_name)));
+            }
+        })
+    } else {
+        None
+    };
+
+    let validate_flatten_fields = if !validate_flatten_fields.is_empty() {
+        Some(quote! {
+            let names: &[&str] = &[#(#property_names),*];
+            let mut rest = #crate_name::__private::serde_json::Value::Object(
+                obj.iter()
+                    .filter(|(name, _)| !names.contains(&name.as_str()))
+                    .map(|(name, value)| (::std::clone::Clone::clone(name), ::std::clone::Clone::clone(value)))
+                    .collect(),
+            );
+            #(#validate_flatten_fields)*
+        })
+    } else {
+        None
+    };
+    let validate_unknown_fields = if args.deny_unknown_fields {
+        Some(quote! {
+            let names: &[&str] = &[#(#property_names),*];
+            for name in obj.keys() {
+                if !names.contains(&name.as_str()) {
+                    errors.push(#crate_name::types::ValidationError::new(
+                        ::std::format!("{}/{}", path, name.replace('~', "~0").replace('/', "~1")),
+                        #crate_name::types::ParseError::<Self>::custom(::std::format!("unknown field `{}`.", name)).into_message(),
+                    ));
+                }
             }
         })
     } else {
@@ -404,6 +741,7 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
                 registry.create_schema::<Self, _>(<Self as #crate_name::types::Type>::name().into_owned(), |registry| {
                     #(#register_types)*
                     let mut meta = #meta;
+                    #(#update_flatten_meta)*
                     meta.example = #example;
                     meta
                 })
@@ -422,32 +760,57 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
 
         impl #impl_generics #crate_name::types::ParseFromJSON for #ident #ty_generics #where_clause {
             fn parse_from_json(value: ::std::option::Option<#crate_name::__private::serde_json::Value>) -> ::std::result::Result<Self, #crate_name::types::ParseError<Self>> {
+                #parse_json_field
                 let value = value.unwrap_or_default();
                 match value {
                     #crate_name::__private::serde_json::Value::Object(mut obj) => {
                         #(#deserialize_fields)*
+                        #(#deserialize_flatten_fields)*
                         #deny_unknown_fields
                         ::std::result::Result::Ok(Self { #(#fields),* })
                     }
                     _ => ::std::result::Result::Err(#crate_name::types::ParseError::expected_type(value)),
                 }
             }
-        }
 
-        impl #impl_generics #crate_name::types::ToJSON for #ident #ty_generics #where_clause {
-            fn to_json(&self) -> ::std::option::Option<#crate_name::__private::serde_json::Value> {
-                let mut object = #crate_name::__private::serde_json::Map::new();
-                #(#serialize_fields)*
-                ::std::option::Option::Some(#crate_name::__private::serde_json::Value::Object(object))
+            fn remove_flattened_properties(obj: &mut #crate_name::__private::serde_json::Map<::std::string::String, #crate_name::__private::serde_json::Value>) {
+                let names: &[&str] = &[#(#property_names),*];
+                for name in names {
+                    obj.remove(*name);
+                }
+                #(<#flatten_types as #crate_name::types::ParseFromJSON>::remove_flattened_properties(obj);)*
             }
-        }
 
-        impl #impl_generics #crate_name::types::ParseFromXML for #ident #ty_generics #where_clause {
-            fn parse_from_xml(value: ::std::option::Option<#crate_name::__private::serde_json::Value>) -> ::std::result::Result<Self, #crate_name::types::ParseError<Self>> {
+            fn collect_json_errors(
+                value: ::std::option::Option<&#crate_name::__private::serde_json::Value>,
+                path: &str,
+                errors: &mut ::std::vec::Vec<#crate_name::types::ValidationError>,
+            ) {
+                #parse_json_field
+                #[allow(unused_variables)]
+                let obj = match value {
+                    ::std::option::Option::Some(#crate_name::__private::serde_json::Value::Object(obj)) => obj,
+                    _ => {
+                        errors.push(#crate_name::types::ValidationError::new(
+                            path,
+                            #crate_name::types::ParseError::<Self>::expected_type(value.cloned().unwrap_or_default()).into_message(),
+                        ));
+                        return;
+                    }
+                };
+                #(#validate_fields)*
+                #validate_flatten_fields
+                #validate_unknown_fields
+            }
+
+            fn parse_from_xml_value(value: ::std::option::Option<#crate_name::__private::serde_json::Value>) -> ::std::result::Result<Self, #crate_name::types::ParseError<Self>> {
+                #parse_xml_field
                 let value = value.unwrap_or_default();
                 match value {
                     #crate_name::__private::serde_json::Value::Object(mut obj) => {
+                        #(#xml_deserialize_fields)*
                         #(#deserialize_fields)*
+                        #(#deserialize_flatten_fields)*
                         #deny_unknown_fields
                         ::std::result::Result::Ok(Self { #(#fields),* })
                     }
@@ -456,20 +819,45 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
             }
         }
 
-        impl #impl_generics #crate_name::types::ToXML for #ident #ty_generics #where_clause {
-            fn to_xml(&self) -> ::std::option::Option<#crate_name::__private::serde_json::Value> {
+        impl #impl_generics #crate_name::types::ToJSON for #ident #ty_generics #where_clause {
+            fn to_json(&self) -> ::std::option::Option<#crate_name::__private::serde_json::Value> {
+                #json_value
+                let mut object = #crate_name::__private::serde_json::Map::new();
+                #(#serialize_fields)*
+                ::std::option::Option::Some(#crate_name::__private::serde_json::Value::Object(object))
+            }
+
+            fn to_xml_value(&self) -> ::std::option::Option<#crate_name::__private::serde_json::Value> {
+                #xml_value
                 let mut object = #crate_name::__private::serde_json::Map::new();
                 #(#serialize_fields)*
+                #(#xml_serialize_fields)*
                 ::std::option::Option::Some(#crate_name::__private::serde_json::Value::Object(object))
             }
         }
 
+        impl #impl_generics #crate_name::types::ParseFromXML for #ident #ty_generics #where_clause {
+            fn parse_from_xml(value: ::std::option::Option<#crate_name::__private::serde_json::Value>) -> ::std::result::Result<Self, #crate_name::types::ParseError<Self>> {
+                <Self as #crate_name::types::ParseFromJSON>::parse_from_xml_value(value)
+            }
+        }
+
+        impl #impl_generics #crate_name::types::ToXML for #ident #ty_generics #where_clause {
+            fn to_xml(&self) -> ::std::option::Option<#crate_name::__private::serde_json::Value> {
+                #crate_name::types::ToJSON::to_xml_value(self)
+            }
+
+            #xml_root_name
+        }
+
         impl #impl_generics #crate_name::types::ParseFromYAML for #ident #ty_generics #where_clause {
             fn parse_from_yaml(value: ::std::option::Option<#crate_name::__private::serde_json::Value>) -> ::std::result::Result<Self, #crate_name::types::ParseError<Self>> {
+                #parse_json_field
                 let value = value.unwrap_or_default();
                 match value {
                     #crate_name::__private::serde_json::Value::Object(mut obj) => {
                         #(#deserialize_fields)*
+                        #(#deserialize_flatten_fields)*
                         #deny_unknown_fields
                         ::std::result::Result::Ok(Self { #(#fields),* })
                     }
@@ -480,6 +868,7 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
 
         impl #impl_generics #crate_name::types::ToYAML for #ident #ty_generics #where_clause {
             fn to_yaml(&self) -> ::std::option::Option<#crate_name::__private::serde_json::Value> {
+                #json_value
                 let mut object = #crate_name::__private::serde_json::Map::new();
                 #(#serialize_fields)*
                 ::std::option::Option::Some(#crate_name::__private::serde_json::Value::Object(object))
@@ -72,19 +72,29 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
             1 => {
                 // Item(payload)
                 let payload_ty = &variant.fields.fields[0];
-                let content_type = match &variant.content_type {
+                // a comma separated list of content types, which can't appear in a
+                // single content type
+                let content_types = match &variant.content_type {
                     Some(content_type) => {
-                        let content_type = &**content_type;
-                        quote!(#content_type)
-                    }
-                    None => {
-                        quote!(<#payload_ty as #crate_name::payload::Payload>::CONTENT_TYPE)
+                        let content_types = content_type
+                            .split(',')
+                            .map(str::trim)
+                            .filter(|content_type| !content_type.is_empty())
+                            .collect::<Vec<_>>();
+                        if content_types.is_empty() {
+                            return Err(Error::new(
+                                content_type.span(),
+                                "Expect at least one content type.",
+                            )
+                            .into());
+                        }
+                        Some(content_types)
                     }
+                    None => None,
                 };
-                let check_content_type = match &variant.content_type {
-                    Some(content_type) => {
-                        let content_type = &**content_type;
-                        quote!(content_type == #content_type)
+                let check_content_type = match &content_types {
+                    Some(content_types) => {
+                        quote!(false #(|| #crate_name::__private::content_type_matches(#content_types, content_type))*)
                     }
                     None => {
                         quote!(<#payload_ty as #crate_name::payload::Payload>::check_content_type(content_type))
@@ -97,12 +107,24 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
                         ));
                     }
                 });
-                content.push(quote! {
-                    #crate_name::registry::MetaMediaType {
-                        content_type: #content_type,
-                        schema: <#payload_ty as #crate_name::payload::Payload>::schema_ref(),
+                match &content_types {
+                    Some(content_types) => {
+                        for content_type in content_types {
+                            content.push(quote! {
+                                #crate_name::registry::MetaMediaType {
+                                    content_type: #content_type,
+                                    schema: <#payload_ty as #crate_name::payload::Payload>::schema_ref(),
+                                }
+                            });
+                        }
                     }
-                });
+                    None => content.push(quote! {
+                        #crate_name::registry::MetaMediaType {
+                            content_type: <#payload_ty as #crate_name::payload::Payload>::CONTENT_TYPE,
+                            schema: <#payload_ty as #crate_name::payload::Payload>::schema_ref(),
+                        }
+                    }),
+                }
                 schemas.push(payload_ty);
             }
             _ => {
@@ -140,6 +162,8 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
                 ) -> #crate_name::__private::poem::Result<Self> {
                     use ::std::str::FromStr;
 
+                    #crate_name::__private::decompress_request_body(request, body).await?;
+
                     match request.content_type() {
                         ::std::option::Option::Some(content_type) => {
                             #(#from_requests)*
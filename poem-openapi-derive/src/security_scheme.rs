@@ -25,6 +25,8 @@ pub(crate) enum AuthType {
     OAuth2,
     #[darling(rename = "openid_connect")]
     OpenIdConnect,
+    #[darling(rename = "mutual_tls")]
+    MutualTls,
 }
 
 #[derive(FromMeta)]
@@ -224,6 +226,13 @@ struct SecuritySchemeArgs {
 impl SecuritySchemeArgs {
     fn auth_type(&self) -> GeneratorResult<AuthType> {
         match self.ty {
+            // the generated documents are OpenAPI 3.0, which has no `mutualTLS` type
+            Some(AuthType::MutualTls) => Err(Error::new_spanned(
+                &self.ident,
+                "The `mutual_tls` security scheme requires OpenAPI 3.1, but the generated \
+                 documents are OpenAPI 3.0. Extract `TlsConnection` in the operations instead.",
+            )
+            .into()),
             Some(ty) => Ok(ty),
             None => Err(Error::new_spanned(&self.ident, "Missing an auth type.").into()),
         }
@@ -397,6 +406,7 @@ impl SecuritySchemeArgs {
                     });
                 }
             }
+            AuthType::MutualTls => unreachable!(),
         };
         Ok(ts)
     }
@@ -426,6 +436,7 @@ impl SecuritySchemeArgs {
             AuthType::OpenIdConnect => Ok(
                 quote!(<#crate_name::auth::Bearer as #crate_name::auth::BearerAuthorization>::from_request(req)),
             ),
+            AuthType::MutualTls => unreachable!(),
         }
     }
 }
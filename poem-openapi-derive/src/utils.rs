@@ -210,3 +210,23 @@ pub(crate) fn preserve_str_literal(meta: &Meta) -> darling::Result<Option<Expr>>
         Meta::NameValue(nv) => Ok(Some(nv.value.clone())),
     }
 }
+
+/// Parses a size such as `512`, `64KB`, `10MB` or `1GiB` into bytes, the
+/// decimal units are powers of 1000 and the binary units powers of 1024.
+pub(crate) fn parse_size(s: &str) -> Option<u64> {
+    let s = s.trim();
+    let idx = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+    let (value, unit) = s.split_at(idx);
+    let value = value.parse::<u64>().ok()?;
+    let multiplier: u64 = match unit.trim().to_ascii_lowercase().as_str() {
+        "" | "b" => 1,
+        "kb" => 1000,
+        "mb" => 1000 * 1000,
+        "gb" => 1000 * 1000 * 1000,
+        "kib" => 1024,
+        "mib" => 1024 * 1024,
+        "gib" => 1024 * 1024 * 1024,
+        _ => return None,
+    };
+    value.checked_mul(multiplier)
+}
@@ -3,14 +3,14 @@ use std::collections::HashSet;
 use darling::{util::SpannedValue, FromMeta};
 use indexmap::IndexMap;
 use proc_macro2::TokenStream;
-use quote::quote;
+use quote::{quote, quote_spanned};
 use syn::{
-    ext::IdentExt, visit_mut::VisitMut, Error, FnArg, ItemTrait, Pat, Path, ReturnType, TraitItem,
-    TraitItemFn,
+    ext::IdentExt, spanned::Spanned, visit_mut::VisitMut, Error, FnArg, ItemTrait, Pat, Path,
+    ReturnType, TraitItem, TraitItemFn,
 };
 
 use crate::{
-    common_args::{APIMethod, DefaultValue, ExternalDocument},
+    common_args::{APIMethod, DefaultValue, ExternalDocument, ParamStyle},
     error::GeneratorResult,
     utils::{
         get_crate_name, get_description, get_summary_and_description, optional_literal,
@@ -55,6 +55,8 @@ struct WebHookOperationParam {
     validator: Option<Validators>,
     #[darling(default)]
     explode: Option<bool>,
+    #[darling(default)]
+    style: Option<ParamStyle>,
 }
 
 struct Context {
@@ -213,7 +215,25 @@ fn generate_operation(
             .unwrap_or_else(|| arg_ident.unraw().to_string());
         let param_desc = optional_literal_string(&param_description);
         let deprecated = operation_param.deprecated;
-        let explode = operation_param.explode.unwrap_or(true);
+        let explode = operation_param.explode.unwrap_or(matches!(
+            operation_param.style,
+            None | Some(ParamStyle::Form | ParamStyle::DeepObject)
+        ));
+        let style = match operation_param.style {
+            Some(style) => {
+                let style = style.to_token_stream(crate_name);
+                quote!(::std::option::Option::Some(#style))
+            }
+            None => quote!(::std::option::Option::None),
+        };
+        let assert_style = operation_param
+            .style
+            .filter(|style| *style == ParamStyle::DeepObject)
+            .map(|_| {
+                quote_spanned! {arg_ty.span()=>
+                    #crate_name::__private::assert_deep_object::<<#arg_ty as #crate_name::ApiExtractor>::ParamType>();
+                }
+            });
 
         params_meta.push(quote! {
             if <#arg_ty as #crate_name::ApiExtractor>::TYPES.contains(&#crate_name::ApiExtractorType::Parameter) {
@@ -226,15 +246,17 @@ fn generate_operation(
                     schema
                 };
 
-                let meta_param = #crate_name::registry::MetaOperationParam {
-                    name: ::std::string::ToString::to_string(#param_name),
-                    schema: original_schema.merge(patch_schema),
-                    in_type: <#arg_ty as #crate_name::ApiExtractor>::param_in().unwrap(),
-                    description: #param_desc,
-                    required: <#arg_ty as #crate_name::ApiExtractor>::PARAM_IS_REQUIRED,
-                    deprecated: #deprecated,
-                    explode: #explode,
-                };
+                let mut meta_param = #crate_name::registry::MetaOperationParam::new(
+                    #param_name,
+                    original_schema.merge(patch_schema),
+                    <#arg_ty as #crate_name::ApiExtractor>::param_in().unwrap(),
+                );
+                #assert_style
+                meta_param.description = #param_desc;
+                meta_param.required = <#arg_ty as #crate_name::ApiExtractor>::PARAM_IS_REQUIRED;
+                meta_param.deprecated = #deprecated;
+                meta_param.explode = #explode;
+                meta_param.style = #style;
                 params.push(meta_param);
             }
         });
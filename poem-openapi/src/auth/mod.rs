@@ -3,10 +3,11 @@
 mod api_key;
 mod basic;
 mod bearer;
+mod tls_connection;
 
 use poem::{Request, Result};
 
-pub use self::{api_key::ApiKey, basic::Basic, bearer::Bearer};
+pub use self::{api_key::ApiKey, basic::Basic, bearer::Bearer, tls_connection::TlsConnection};
 use crate::{base::UrlQuery, error::AuthorizationError, registry::MetaParamIn};
 
 /// Represents a basic authorization extractor.
@@ -0,0 +1,49 @@
+use poem::{web::TlsInfo, FromRequest, Request, RequestBody, Result};
+
+use crate::error::AuthorizationError;
+
+/// An extractor that requires a client certificate.
+///
+/// The certificate is verified by the TLS listener during the handshake, so
+/// the listener must be configured to request one, e.g. with
+/// `RustlsConfig::client_auth_required`. The requests without a client
+/// certificate, including the requests received over plain HTTP or forwarded
+/// by a reverse proxy that terminates TLS, are rejected with
+/// `401 Unauthorized`.
+///
+/// OpenAPI 3.0 cannot describe mutual TLS, so it is extracted as a regular
+/// argument of the operation rather than as a security scheme.
+///
+/// # Example
+///
+/// ```
+/// use poem_openapi::{auth::TlsConnection, payload::PlainText, OpenApi};
+///
+/// struct Api;
+///
+/// #[OpenApi]
+/// impl Api {
+///     #[oai(path = "/", method = "get")]
+///     async fn index(&self, tls: TlsConnection) -> PlainText<String> {
+///         PlainText(format!("{} bytes", tls.certificate().len()))
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct TlsConnection(pub TlsInfo);
+
+impl TlsConnection {
+    /// Returns the DER-encoded certificate of the client.
+    pub fn certificate(&self) -> &[u8] {
+        self.0.peer_certificate().unwrap_or_default()
+    }
+}
+
+impl<'a> FromRequest<'a> for TlsConnection {
+    async fn from_request(req: &'a Request, _body: &mut RequestBody) -> Result<Self> {
+        match req.data::<TlsInfo>() {
+            Some(tls) if tls.peer_certificate().is_some() => Ok(TlsConnection(tls.clone())),
+            _ => Err(AuthorizationError.into()),
+        }
+    }
+}
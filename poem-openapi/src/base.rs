@@ -5,15 +5,21 @@ use std::{
     ops::Deref,
 };
 
-use futures_util::FutureExt;
-use poem::{endpoint::BoxEndpoint, http::Method, Error, FromRequest, Request, RequestBody, Result};
+use futures_util::{FutureExt, StreamExt};
+use poem::{
+    endpoint::BoxEndpoint,
+    error::ReadBodyError,
+    http::{header, Method},
+    Body, Error, FromRequest, Request, RequestBody, Result,
+};
 
 use crate::{
     payload::Payload,
     registry::{
-        MetaApi, MetaMediaType, MetaOAuthScope, MetaParamIn, MetaRequest, MetaResponse,
-        MetaResponses, MetaSchemaRef, MetaWebhook, Registry,
+        MetaApi, MetaMediaType, MetaOAuthScope, MetaParamIn, MetaParamStyle, MetaRequest,
+        MetaResponse, MetaResponses, MetaSchemaRef, MetaWebhook, Registry,
     },
+    types::ParseFromDeepObject,
 };
 
 /// API extractor types.
@@ -59,8 +65,135 @@ impl UrlQuery {
     }
 }
 
+/// The default body size limit of the operations, see
+/// [`OpenApiService::max_request_size`](crate::OpenApiService::max_request_size).
+#[doc(hidden)]
+#[derive(Debug, Copy, Clone)]
+pub struct MaxRequestSize(pub u64);
+
+/// Limits the body of the request to the limit of the operation, or to the
+/// default limit of the service.
+#[doc(hidden)]
+pub fn limit_request_body(request: &mut Request, max_request_size: Option<u64>) {
+    let max_request_size =
+        max_request_size.or_else(|| request.data::<MaxRequestSize>().map(|size| size.0));
+    if let Some(max_request_size) = max_request_size {
+        let body = request.take_body().limit(max_request_size);
+        request.set_body(body);
+    }
+}
+
+/// Asserts that the type of a parameter with the `deepObject` style supports
+/// this style.
+#[doc(hidden)]
+pub fn assert_deep_object<T: ParseFromDeepObject>() {}
+
+/// Extracts an `Option<T>` argument of an operation.
+///
+/// The argument is `None` if it is absent from the request: an empty request
+/// body, a parameter without any value, or a security scheme without any
+/// credentials. Otherwise the errors of the extractor are returned. The poem
+/// extractors are `None` if the extraction fails, like `Option<T>` in poem.
+#[doc(hidden)]
+pub async fn extract_optional<'a, T: ApiExtractor<'a>>(
+    request: &'a Request,
+    body: &mut RequestBody,
+    param_opts: ExtractParamOptions<T::ParamType>,
+) -> Result<Option<T>> {
+    let present = if T::TYPES.contains(&ApiExtractorType::RequestObject) {
+        has_body(request, body).await?
+    } else if T::TYPES.contains(&ApiExtractorType::Parameter) {
+        param_opts.default_value.is_some() || has_param(request, T::param_in(), param_opts.name)
+    } else if T::TYPES.contains(&ApiExtractorType::SecurityScheme) {
+        has_credentials::<T>(request)
+    } else {
+        // FIXME: remove the unnecessary boxed
+        // https://github.com/rust-lang/rust/issues/100013
+        return Ok(T::from_request(request, body, param_opts)
+            .boxed()
+            .await
+            .ok());
+    };
+
+    if !present {
+        return Ok(None);
+    }
+    // FIXME: remove the unnecessary boxed
+    // https://github.com/rust-lang/rust/issues/100013
+    T::from_request(request, body, param_opts)
+        .boxed()
+        .await
+        .map(Some)
+}
+
+/// Returns `false` if the request body is empty, a body without
+/// `Content-Length` is checked by reading its first chunk, which is put back
+/// in front of the rest of the body.
+async fn has_body(request: &Request, body: &mut RequestBody) -> Result<bool> {
+    let content_length = request
+        .header(header::CONTENT_LENGTH)
+        .and_then(|value| value.parse::<u64>().ok());
+    if let Some(content_length) = content_length {
+        return Ok(content_length > 0);
+    }
+
+    let mut stream = body.take()?.into_bytes_stream();
+    loop {
+        match stream.next().await {
+            Some(Ok(data)) if data.is_empty() => continue,
+            Some(Ok(data)) => {
+                *body = RequestBody::new(Body::from_bytes_stream(
+                    futures_util::stream::once(async move { Ok(data) }).chain(stream),
+                ));
+                return Ok(true);
+            }
+            Some(Err(err)) => return Err(ReadBodyError::Io(err).into()),
+            None => return Ok(false),
+        }
+    }
+}
+
+fn has_param(request: &Request, param_in: Option<MetaParamIn>, name: &str) -> bool {
+    match param_in {
+        Some(MetaParamIn::Query) => request.extensions().get::<UrlQuery>().is_some_and(|query| {
+            query.iter().any(|(key, _)| {
+                // `name[key]` of the deep object style
+                key.strip_prefix(name)
+                    .is_some_and(|rest| rest.is_empty() || rest.starts_with('['))
+            })
+        }),
+        Some(MetaParamIn::Header) => request.headers().contains_key(name),
+        Some(MetaParamIn::Cookie | MetaParamIn::CookiePrivate | MetaParamIn::CookieSigned) => {
+            request.cookie().get(name).is_some()
+        }
+        Some(MetaParamIn::Path) | None => true,
+    }
+}
+
+fn has_credentials<'a, T: ApiExtractor<'a>>(request: &Request) -> bool {
+    let mut registry = Registry::new();
+    T::register(&mut registry);
+    T::security_schemes().into_iter().any(|name| {
+        let Some(scheme) = registry.security_schemes.get(name) else {
+            return true;
+        };
+        match (scheme.ty, scheme.key_in, scheme.name) {
+            ("apiKey", Some("query"), Some(key_name)) => {
+                has_param(request, Some(MetaParamIn::Query), key_name)
+            }
+            ("apiKey", Some("header"), Some(key_name)) => request.headers().contains_key(key_name),
+            ("apiKey", Some("cookie"), Some(key_name)) => request.cookie().get(key_name).is_some(),
+            ("http" | "oauth2" | "openIdConnect", _, _) => {
+                request.headers().contains_key(header::AUTHORIZATION)
+            }
+            _ => true,
+        }
+    })
+}
+
 /// Options for the parameter extractor.
 #[derive(Clone)]
+#[non_exhaustive]
 pub struct ExtractParamOptions<T> {
     /// The name of this parameter.
     pub name: &'static str,
@@ -75,6 +208,20 @@ pub struct ExtractParamOptions<T> {
     /// separate parameters for each value of the array or key-value pair of the
     /// map.
     pub explode: bool,
+
+    /// The style of this parameter, describes how the values of type array or
+    /// object are serialized, the `form` style is used when this is `None`.
+    pub style: Option<MetaParamStyle>,
+}
+
+impl<T> ExtractParamOptions<T> {
+    /// Create the options of the specified parameter.
+    pub fn new(name: &'static str) -> Self {
+        Self {
+            name,
+            ..Self::default()
+        }
+    }
 }
 
 impl<T> Default for ExtractParamOptions<T> {
@@ -84,6 +231,7 @@ impl<T> Default for ExtractParamOptions<T> {
             default_value: None,
             example_value: None,
             explode: true,
+            style: None,
         }
     }
 }
@@ -137,6 +285,10 @@ impl<T> Default for ExtractParamOptions<T> {
 ///    Extract the request body as utf8 string into
 ///    [`PlainText`](crate::payload::PlainText).
 ///
+/// - **Option&lt;T: Payload>**
+///
+///    An optional request body, it is `None` if the request body is empty.
+///
 /// - **Any type derived from the [`ApiRequest`](crate::ApiRequest) macro**
 ///
 ///    Extract the complex request body derived from the `ApiRequest` macro.
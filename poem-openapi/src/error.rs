@@ -1,8 +1,11 @@
 //! Some common error types.
 
-use poem::{error::ResponseError, http::StatusCode};
+use poem::{error::ResponseError, http::StatusCode, web::Json, IntoResponse, Response};
+use serde_json::json;
 use thiserror::Error;
 
+use crate::types::ValidationError;
+
 /// Parameter error.
 #[derive(Debug, Error)]
 #[error("failed to parse parameter `{name}`: {reason}")]
@@ -86,6 +89,36 @@ impl ResponseError for ContentTypeError {
     }
 }
 
+/// Content encoding error.
+#[cfg(feature = "compression")]
+#[cfg_attr(docsrs, doc(cfg(feature = "compression")))]
+#[derive(Debug, Error)]
+pub enum ContentEncodingError {
+    /// Not supported.
+    #[error("the `Content-Encoding` requested by the client is not supported: {content_encoding}")]
+    NotSupported {
+        /// The `Content-Encoding` header requested by the client.
+        content_encoding: String,
+    },
+
+    /// The decompressed payload is too large.
+    #[error("the decompressed request payload exceeds the limit of {max_size} bytes")]
+    TooLarge {
+        /// The maximum size of the decompressed payload.
+        max_size: usize,
+    },
+}
+
+#[cfg(feature = "compression")]
+impl ResponseError for ContentEncodingError {
+    fn status(&self) -> StatusCode {
+        match self {
+            ContentEncodingError::NotSupported { .. } => StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            ContentEncodingError::TooLarge { .. } => StatusCode::PAYLOAD_TOO_LARGE,
+        }
+    }
+}
+
 /// Authorization error.
 #[derive(Debug, Error)]
 #[error("authorization error")]
@@ -96,3 +129,51 @@ impl ResponseError for AuthorizationError {
         StatusCode::UNAUTHORIZED
     }
 }
+
+/// Validate JSON error, see
+/// [`JsonValidators`](crate::types::JsonValidators).
+#[derive(Debug, Error)]
+pub enum ValidateJsonError {
+    /// No type is registered with this name.
+    #[error("unknown type `{type_name}`")]
+    UnknownType {
+        /// The name of the type.
+        type_name: String,
+    },
+
+    /// The value is not valid for the type.
+    #[error("the value is not valid for the type `{type_name}`")]
+    Invalid {
+        /// The name of the type.
+        type_name: String,
+
+        /// The errors of the invalid values.
+        errors: Vec<ValidationError>,
+    },
+}
+
+impl ResponseError for ValidateJsonError {
+    fn status(&self) -> StatusCode {
+        match self {
+            ValidateJsonError::UnknownType { .. } => StatusCode::NOT_FOUND,
+            ValidateJsonError::Invalid { .. } => StatusCode::BAD_REQUEST,
+        }
+    }
+
+    fn as_response(&self) -> Response {
+        let mut body = match self {
+            ValidateJsonError::UnknownType { type_name } => json!({ "type": type_name }),
+            ValidateJsonError::Invalid { type_name, errors } => json!({
+                "type": type_name,
+                "errors": errors
+                    .iter()
+                    .map(|err| json!({ "path": err.path, "message": err.message }))
+                    .collect::<Vec<_>>(),
+            }),
+        };
+        body["message"] = self.to_string().into();
+        let mut resp = Json(body).into_response();
+        resp.set_status(self.status());
+        resp
+    }
+}
@@ -0,0 +1,178 @@
+use std::{error::Error as StdError, sync::Arc};
+
+use poem::{
+    error::ResponseError,
+    http::{header, StatusCode},
+    Error, Response,
+};
+
+use crate::{
+    registry::{MetaMediaType, MetaOperation, MetaResponse, MetaSchema, MetaSchemaRef},
+    types::Type,
+};
+
+#[derive(Clone)]
+struct ErrorMapping {
+    status: StatusCode,
+    code: &'static str,
+    matches: fn(&Error) -> bool,
+}
+
+/// A registry that maps the domain error types to a status code and an error
+/// code, attached to an API container with
+/// [`OpenApiService::error_mapper`](crate::OpenApiService::error_mapper).
+///
+/// When an operation returns an error of a registered type, the response has
+/// the registered status code and a JSON body with the `code` and `message`
+/// fields, where the message is the [`Display`](std::fmt::Display) of the
+/// error. The registered status codes are also added to the documented
+/// responses of each operation.
+///
+/// To return `Result<T, MyError>` from an operation, implement
+/// [`ApiResponse`](crate::ApiResponse) for the error type with
+/// [`impl_apiresponse_for_mapped_error!`](crate::impl_apiresponse_for_mapped_error),
+/// or return [`poem::Result`] and convert the error with the `?` operator.
+///
+/// # Example
+///
+/// ```
+/// use poem::{error::ResponseError, http::StatusCode, test::TestClient};
+/// use poem_openapi::{
+///     impl_apiresponse_for_mapped_error, param::Path, payload::PlainText, ErrorMapper, OpenApi,
+///     OpenApiService,
+/// };
+///
+/// #[derive(Debug, thiserror::Error)]
+/// #[error("user `{0}` not found")]
+/// struct UserNotFound(String);
+///
+/// impl ResponseError for UserNotFound {
+///     fn status(&self) -> StatusCode {
+///         StatusCode::NOT_FOUND
+///     }
+/// }
+///
+/// impl_apiresponse_for_mapped_error!(UserNotFound);
+///
+/// struct Api;
+///
+/// #[OpenApi]
+/// impl Api {
+///     #[oai(path = "/users/:name", method = "get")]
+///     async fn user(&self, name: Path<String>) -> Result<PlainText<String>, UserNotFound> {
+///         Err(UserNotFound(name.0))
+///     }
+/// }
+///
+/// let api = OpenApiService::new(Api, "test", "1.0")
+///     .error_mapper(ErrorMapper::new().error::<UserNotFound>(StatusCode::NOT_FOUND, "USER_NOT_FOUND"));
+///
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// let resp = TestClient::new(api).get("/users/alice").send().await;
+/// resp.assert_status(StatusCode::NOT_FOUND);
+/// resp.assert_json(serde_json::json!({
+///     "code": "USER_NOT_FOUND",
+///     "message": "user `alice` not found",
+/// }))
+/// .await;
+/// # });
+/// ```
+#[derive(Clone, Default)]
+pub struct ErrorMapper {
+    mappings: Arc<Vec<ErrorMapping>>,
+}
+
+impl ErrorMapper {
+    /// Create an empty `ErrorMapper`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Maps the errors of type `E` to the specified status code and error
+    /// code.
+    #[must_use]
+    pub fn error<E>(mut self, status: StatusCode, code: &'static str) -> Self
+    where
+        E: ResponseError + StdError + Send + Sync + 'static,
+    {
+        Arc::make_mut(&mut self.mappings).push(ErrorMapping {
+            status,
+            code,
+            matches: |err| err.downcast_ref::<E>().is_some(),
+        });
+        self
+    }
+
+    /// Renders the error with the first matching mapping, the other errors
+    /// are returned unchanged.
+    pub(crate) fn map_error(&self, err: Error) -> Error {
+        let Some(mapping) = self.mappings.iter().find(|mapping| (mapping.matches)(&err)) else {
+            return err;
+        };
+        let body = serde_json::json!({
+            "code": mapping.code,
+            "message": err.to_string(),
+        });
+        Error::from_response(
+            Response::builder()
+                .status(mapping.status)
+                .header(header::CONTENT_TYPE, "application/json; charset=utf-8")
+                .body(body.to_string()),
+        )
+    }
+
+    /// Adds the registered status codes that are not documented yet to the
+    /// responses of the operation.
+    pub(crate) fn update_operation(&self, operation: &mut MetaOperation) {
+        let mut statuses: Vec<StatusCode> = Vec::new();
+        for mapping in self.mappings.iter() {
+            if !statuses.contains(&mapping.status) {
+                statuses.push(mapping.status);
+            }
+        }
+
+        for status in statuses {
+            if operation
+                .responses
+                .responses
+                .iter()
+                .any(|resp| resp.status == Some(status.as_u16()))
+            {
+                continue;
+            }
+
+            let codes = self
+                .mappings
+                .iter()
+                .filter(|mapping| mapping.status == status)
+                .map(|mapping| mapping.code.into())
+                .collect();
+            operation.responses.responses.push(MetaResponse {
+                description: status.canonical_reason().unwrap_or_default(),
+                status: Some(status.as_u16()),
+                content: vec![MetaMediaType {
+                    content_type: "application/json; charset=utf-8",
+                    schema: error_body_schema(codes),
+                }],
+                headers: vec![],
+            });
+        }
+    }
+}
+
+fn error_body_schema(codes: Vec<serde_json::Value>) -> MetaSchemaRef {
+    MetaSchemaRef::Inline(Box::new(MetaSchema {
+        properties: vec![
+            (
+                "code",
+                MetaSchemaRef::Inline(Box::new(MetaSchema {
+                    enum_items: codes,
+                    ..MetaSchema::new("string")
+                })),
+            ),
+            ("message", String::schema_ref()),
+        ],
+        required: vec!["code", "message"],
+        ..MetaSchema::new("object")
+    }))
+}
@@ -112,7 +112,12 @@
 //! | prost-wkt-types  | Integrate with the [`prost-wkt-types` crate](https://crates.io/crates/prost-wkt-types) |
 //! | static-files     | Support for static file response                                                       |
 //! | websocket        | Support for websocket                                                                  |
+//! | compression      | Decompress the request payloads according to the `Content-Encoding` header             |
+//! | zstd             | Decompress the request payloads compressed with `zstd`                                 |
 //! |sonic-rs          | Uses [`sonic-rs`](https://github.com/cloudwego/sonic-rs) instead of `serde_json`. Pls, checkout `sonic-rs` requirements to properly enable `sonic-rs` capabilities |
+//! | json-preserve-order | Keeps the order of the keys of the JSON objects parsed to `serde_json::Value` |
+//! | json-raw-value   | Support for `Box<serde_json::value::RawValue>` with `poem::web::Json` |
+//! | upload-digest    | Compute the SHA-256 digest of [`Upload`](types::multipart::Upload) while receiving it |
 
 #![doc(html_favicon_url = "https://raw.githubusercontent.com/poem-web/poem/master/favicon.ico")]
 #![doc(html_logo_url = "https://raw.githubusercontent.com/poem-web/poem/master/logo.png")]
@@ -138,6 +143,8 @@ pub mod types;
 pub mod validation;
 
 mod base;
+mod error_mapper;
+mod mount;
 mod openapi;
 mod path_util;
 #[cfg(any(
@@ -152,6 +159,8 @@ pub use base::{
     ApiExtractor, ApiExtractorType, ApiResponse, ExtractParamOptions, OAuthScopes, OpenApi,
     OperationId, ResponseContent, Tags, Webhook,
 };
+pub use error_mapper::ErrorMapper;
+pub use mount::{Mount, MountPoint};
 pub use openapi::{
     ContactObject, ExternalDocumentObject, ExtraHeader, LicenseObject, OpenApiService, ServerObject,
 };
@@ -181,6 +190,14 @@ pub use poem_openapi_derive::Tags;
 pub use poem_openapi_derive::Union;
 #[doc = include_str!("docs/webhook.md")]
 pub use poem_openapi_derive::Webhook;
+pub use response::{NoContent, PaginatedResponse};
+#[cfg(any(
+    feature = "swagger-ui",
+    feature = "rapidoc",
+    feature = "redoc",
+    feature = "openapi-explorer"
+))]
+pub use ui::UiConfig;
 pub use validation::Validator;
 
 #[doc(hidden)]
@@ -191,5 +208,12 @@ pub mod __private {
     pub use serde;
     pub use serde_json;
 
-    pub use crate::{auth::CheckerReturn, base::UrlQuery, path_util::join_path};
+    pub use crate::{
+        auth::CheckerReturn,
+        base::{
+            assert_deep_object, extract_optional, limit_request_body, MaxRequestSize, UrlQuery,
+        },
+        path_util::join_path,
+        payload::{content_type_matches, decompress_request_body},
+    };
 }
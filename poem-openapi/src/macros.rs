@@ -36,6 +36,7 @@ macro_rules! impl_apirequest_for_payload {
                 match request.content_type() {
                     Some(content_type) => {
                         if <$ty>::check_content_type(content_type) {
+                            $crate::payload::decompress_request_body(request, body).await?;
                             <Self as $crate::payload::ParsePayload>::from_request(request, body).await
                         } else {
                             return Err($crate::error::ContentTypeError::NotSupported {
@@ -50,3 +51,24 @@ macro_rules! impl_apirequest_for_payload {
         }
     };
 }
+
+#[macro_export]
+/// This macro implements ApiResponse for an error type that is registered to
+/// an [`ErrorMapper`](crate::ErrorMapper), so that the operations can return
+/// `Result<T, YourError>`.
+///
+/// The error responses are documented by the `ErrorMapper`, so the
+/// implementation does not describe any response.
+macro_rules! impl_apiresponse_for_mapped_error {
+    ($ty:ty) => {
+        impl $crate::ApiResponse for $ty {
+            fn meta() -> $crate::registry::MetaResponses {
+                $crate::registry::MetaResponses {
+                    responses: ::std::vec::Vec::new(),
+                }
+            }
+
+            fn register(_registry: &mut $crate::registry::Registry) {}
+        }
+    };
+}
@@ -0,0 +1,268 @@
+use std::{
+    collections::{BTreeSet, HashMap, HashSet},
+    marker::PhantomData,
+    sync::Mutex,
+};
+
+use poem::{endpoint::BoxEndpoint, http::Method};
+
+use crate::{
+    path_util::join_path,
+    registry::{MetaApi, MetaMediaType, MetaSchema, MetaSchemaRef, MetaTag, Registry},
+    OpenApi,
+};
+
+/// The path prefix and the namespace of an API mounted with [`Mount`].
+pub trait MountPoint: Send + Sync + 'static {
+    /// The path prefix of all operations, such as `/v1`.
+    const PATH: &'static str;
+
+    /// The prefix added to the tags, operation ids and component names of the
+    /// mounted API, so that they do not collide with the other APIs.
+    ///
+    /// Default is an empty string, which does not change them.
+    const NAMESPACE: &'static str = "";
+}
+
+/// Mounts an API under the path prefix of a [`MountPoint`], so that multiple
+/// versions of an API can be served by a single
+/// [`OpenApiService`](crate::OpenApiService) and documented in one
+/// specification.
+///
+/// # Example
+///
+/// ```
+/// use poem_openapi::{payload::Json, Mount, MountPoint, OpenApi, OpenApiService};
+///
+/// mod v1 {
+///     #[derive(poem_openapi::Object)]
+///     pub struct User {
+///         pub name: String,
+///     }
+/// }
+///
+/// mod v2 {
+///     #[derive(poem_openapi::Object)]
+///     pub struct User {
+///         pub first_name: String,
+///         pub last_name: String,
+///     }
+/// }
+///
+/// struct ApiV1;
+///
+/// #[OpenApi]
+/// impl ApiV1 {
+///     #[oai(path = "/user", method = "get")]
+///     async fn user(&self) -> Json<v1::User> {
+///         todo!()
+///     }
+/// }
+///
+/// struct ApiV2;
+///
+/// #[OpenApi]
+/// impl ApiV2 {
+///     #[oai(path = "/user", method = "get")]
+///     async fn user(&self) -> Json<v2::User> {
+///         todo!()
+///     }
+/// }
+///
+/// struct V1;
+///
+/// impl MountPoint for V1 {
+///     const PATH: &'static str = "/v1";
+///     const NAMESPACE: &'static str = "V1_";
+/// }
+///
+/// struct V2;
+///
+/// impl MountPoint for V2 {
+///     const PATH: &'static str = "/v2";
+///     const NAMESPACE: &'static str = "V2_";
+/// }
+///
+/// let api_service = OpenApiService::new(
+///     (Mount::<V1, _>::new(ApiV1), Mount::<V2, _>::new(ApiV2)),
+///     "Users",
+///     "2.0",
+/// );
+/// let spec: serde_json::Value = serde_json::from_str(&api_service.spec()).unwrap();
+/// assert!(spec["paths"]["/v1/user"].is_object());
+/// assert!(spec["paths"]["/v2/user"].is_object());
+/// assert!(spec["components"]["schemas"]["V1_User"].is_object());
+/// assert!(spec["components"]["schemas"]["V2_User"].is_object());
+/// ```
+pub struct Mount<P, T> {
+    api: T,
+    _mark: PhantomData<P>,
+}
+
+impl<P, T> Mount<P, T> {
+    /// Mounts the API under the specified mount point.
+    pub fn new(api: T) -> Self {
+        Self {
+            api,
+            _mark: PhantomData,
+        }
+    }
+}
+
+/// Returns a `'static` string for the namespaced name, the names are leaked
+/// at most once since they are generated from a fixed set of identifiers.
+fn intern(name: String) -> &'static str {
+    static NAMES: Mutex<BTreeSet<&'static str>> = Mutex::new(BTreeSet::new());
+
+    let mut names = NAMES.lock().unwrap();
+    match names.get(name.as_str()) {
+        Some(name) => *name,
+        None => {
+            let name: &'static str = Box::leak(name.into_boxed_str());
+            names.insert(name);
+            name
+        }
+    }
+}
+
+struct Renamer<'a> {
+    namespace: &'static str,
+    schemas: &'a HashSet<String>,
+}
+
+impl Renamer<'_> {
+    fn rename(&self, name: &str) -> String {
+        format!("{}{}", self.namespace, name)
+    }
+
+    fn schema_ref(&self, schema_ref: &mut MetaSchemaRef) {
+        match schema_ref {
+            MetaSchemaRef::Reference(name) => {
+                if self.schemas.contains(name.as_str()) {
+                    *name = self.rename(name);
+                }
+            }
+            MetaSchemaRef::Inline(schema) => self.schema(schema),
+        }
+    }
+
+    fn schema(&self, schema: &mut MetaSchema) {
+        for (_, schema_ref) in &mut schema.properties {
+            self.schema_ref(schema_ref);
+        }
+        if let Some(schema_ref) = &mut schema.items {
+            self.schema_ref(schema_ref);
+        }
+        if let Some(schema_ref) = &mut schema.additional_properties {
+            self.schema_ref(schema_ref);
+        }
+        for schema_ref in schema
+            .any_of
+            .iter_mut()
+            .chain(&mut schema.one_of)
+            .chain(&mut schema.all_of)
+        {
+            self.schema_ref(schema_ref);
+        }
+        if let Some(discriminator) = &mut schema.discriminator {
+            for (_, target) in &mut discriminator.mapping {
+                if let Some(name) = target.strip_prefix("#/components/schemas/") {
+                    if self.schemas.contains(name) {
+                        *target = format!("#/components/schemas/{}", self.rename(name));
+                    }
+                }
+            }
+        }
+    }
+
+    fn media_types(&self, media_types: &mut [MetaMediaType]) {
+        for media_type in media_types {
+            self.schema_ref(&mut media_type.schema);
+        }
+    }
+}
+
+fn registered_schemas<T: OpenApi>() -> (Registry, HashSet<String>) {
+    let mut registry = Registry::new();
+    T::register(&mut registry);
+    let names = registry.schemas.keys().cloned().collect();
+    (registry, names)
+}
+
+impl<P: MountPoint, T: OpenApi> OpenApi for Mount<P, T> {
+    fn meta() -> Vec<MetaApi> {
+        let mut apis = T::meta();
+        let (_, schemas) = registered_schemas::<T>();
+        let renamer = Renamer {
+            namespace: P::NAMESPACE,
+            schemas: &schemas,
+        };
+
+        for path in apis.iter_mut().flat_map(|api| api.paths.iter_mut()) {
+            path.path = join_path(P::PATH, &path.path);
+
+            if P::NAMESPACE.is_empty() {
+                continue;
+            }
+
+            for operation in &mut path.operations {
+                for tag in &mut operation.tags {
+                    *tag = intern(renamer.rename(tag));
+                }
+                if let Some(operation_id) = &mut operation.operation_id {
+                    *operation_id = intern(renamer.rename(operation_id));
+                }
+                for param in &mut operation.params {
+                    renamer.schema_ref(&mut param.schema);
+                }
+                if let Some(request) = &mut operation.request {
+                    renamer.media_types(&mut request.content);
+                }
+                for response in &mut operation.responses.responses {
+                    renamer.media_types(&mut response.content);
+                    for header in &mut response.headers {
+                        renamer.schema_ref(&mut header.schema);
+                    }
+                }
+            }
+        }
+
+        apis
+    }
+
+    fn register(registry: &mut Registry) {
+        if P::NAMESPACE.is_empty() {
+            T::register(registry);
+            return;
+        }
+
+        let (inner, schemas) = registered_schemas::<T>();
+        let renamer = Renamer {
+            namespace: P::NAMESPACE,
+            schemas: &schemas,
+        };
+
+        for (name, mut schema) in inner.schemas {
+            renamer.schema(&mut schema);
+            registry.schemas.insert(renamer.rename(&name), schema);
+        }
+        for tag in inner.tags {
+            registry.tags.insert(MetaTag {
+                name: intern(renamer.rename(tag.name)),
+                ..tag
+            });
+        }
+        registry.security_schemes.extend(inner.security_schemes);
+    }
+
+    fn add_routes(self, route_table: &mut HashMap<String, HashMap<Method, BoxEndpoint<'static>>>) {
+        let mut inner = HashMap::new();
+        self.api.add_routes(&mut inner);
+        for (path, methods) in inner {
+            route_table
+                .entry(join_path(P::PATH, &path))
+                .or_default()
+                .extend(methods);
+        }
+    }
+}
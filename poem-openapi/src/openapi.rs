@@ -4,20 +4,22 @@ use std::{
 };
 
 use poem::{
-    endpoint::{make_sync, BoxEndpoint},
+    endpoint::{make, make_sync, BoxEndpoint},
+    http::{header, HeaderValue},
     middleware::CookieJarManager,
-    web::cookie::CookieKey,
-    Endpoint, EndpointExt, IntoEndpoint, Request, Response, Result, Route, RouteMethod,
+    web::{cookie::CookieKey, Accept},
+    Endpoint, EndpointExt, FromRequest, IntoEndpoint, Request, Response, Result, Route,
+    RouteMethod,
 };
 
 use crate::{
-    base::UrlQuery,
+    base::{MaxRequestSize, UrlQuery},
     registry::{
         Document, MetaContact, MetaExternalDocument, MetaHeader, MetaInfo, MetaLicense,
         MetaOperationParam, MetaParamIn, MetaSchemaRef, MetaServer, Registry,
     },
     types::Type,
-    OpenApi, Webhook,
+    ErrorMapper, OpenApi, Webhook,
 };
 
 /// An object representing a Server.
@@ -224,6 +226,15 @@ pub struct OpenApiService<T, W> {
     extra_response_headers: Vec<(ExtraHeader, MetaSchemaRef, bool)>,
     extra_request_headers: Vec<(ExtraHeader, MetaSchemaRef, bool)>,
     url_prefix: Option<String>,
+    error_mapper: Option<ErrorMapper>,
+    #[cfg(any(
+        feature = "swagger-ui",
+        feature = "rapidoc",
+        feature = "redoc",
+        feature = "openapi-explorer"
+    ))]
+    ui_config: crate::ui::UiConfig,
+    max_request_size: Option<u64>,
 }
 
 impl<T> OpenApiService<T, ()> {
@@ -248,6 +259,15 @@ impl<T> OpenApiService<T, ()> {
             extra_response_headers: vec![],
             extra_request_headers: vec![],
             url_prefix: None,
+            error_mapper: None,
+            #[cfg(any(
+                feature = "swagger-ui",
+                feature = "rapidoc",
+                feature = "redoc",
+                feature = "openapi-explorer"
+            ))]
+            ui_config: Default::default(),
+            max_request_size: None,
         }
     }
 }
@@ -265,6 +285,15 @@ impl<T, W> OpenApiService<T, W> {
             extra_response_headers: self.extra_response_headers,
             extra_request_headers: self.extra_request_headers,
             url_prefix: None,
+            error_mapper: self.error_mapper,
+            #[cfg(any(
+                feature = "swagger-ui",
+                feature = "rapidoc",
+                feature = "redoc",
+                feature = "openapi-explorer"
+            ))]
+            ui_config: self.ui_config,
+            max_request_size: self.max_request_size,
         }
     }
 
@@ -386,6 +415,42 @@ impl<T, W> OpenApiService<T, W> {
         }
     }
 
+    /// Sets the [`ErrorMapper`] that renders the registered error types and
+    /// documents them in the responses of each operation.
+    #[must_use]
+    pub fn error_mapper(self, error_mapper: ErrorMapper) -> Self {
+        Self {
+            error_mapper: Some(error_mapper),
+            ..self
+        }
+    }
+
+    /// Sets the default body size limit of the operations, in bytes.
+    ///
+    /// The operations with a `max_request_size` attribute use their own limit
+    /// instead, which can be larger. A request with a longer body fails with
+    /// `413 Payload Too Large`, as soon as the limit is exceeded.
+    #[must_use]
+    pub fn max_request_size(self, max_request_size: u64) -> Self {
+        Self {
+            max_request_size: Some(max_request_size),
+            ..self
+        }
+    }
+
+    /// Sets the configuration of the UIs, such as the title, the logo and the
+    /// `Content-Security-Policy`.
+    #[must_use]
+    #[cfg(any(
+        feature = "swagger-ui",
+        feature = "rapidoc",
+        feature = "redoc",
+        feature = "openapi-explorer"
+    ))]
+    pub fn ui_config(self, ui_config: crate::ui::UiConfig) -> Self {
+        Self { ui_config, ..self }
+    }
+
     /// Create the OpenAPI Explorer endpoint.
     #[must_use]
     #[cfg(feature = "openapi-explorer")]
@@ -394,7 +459,7 @@ impl<T, W> OpenApiService<T, W> {
         T: OpenApi,
         W: Webhook,
     {
-        crate::ui::openapi_explorer::create_endpoint(&self.spec())
+        crate::ui::openapi_explorer::create_endpoint(&self.spec(), &self.ui_config)
     }
 
     /// Create the OpenAPI Explorer HTML
@@ -404,7 +469,7 @@ impl<T, W> OpenApiService<T, W> {
         T: OpenApi,
         W: Webhook,
     {
-        crate::ui::openapi_explorer::create_html(&self.spec())
+        crate::ui::openapi_explorer::create_html(&self.spec(), &self.ui_config)
     }
 
     /// Create the Swagger UI endpoint.
@@ -415,7 +480,7 @@ impl<T, W> OpenApiService<T, W> {
         T: OpenApi,
         W: Webhook,
     {
-        crate::ui::swagger_ui::create_endpoint(&self.spec())
+        crate::ui::swagger_ui::create_endpoint(&self.spec(), &self.ui_config)
     }
 
     /// Create the Swagger UI HTML
@@ -425,7 +490,7 @@ impl<T, W> OpenApiService<T, W> {
         T: OpenApi,
         W: Webhook,
     {
-        crate::ui::swagger_ui::create_html(&self.spec())
+        crate::ui::swagger_ui::create_html(&self.spec(), &self.ui_config)
     }
 
     /// Create the Rapidoc endpoint.
@@ -436,7 +501,7 @@ impl<T, W> OpenApiService<T, W> {
         T: OpenApi,
         W: Webhook,
     {
-        crate::ui::rapidoc::create_endpoint(&self.spec())
+        crate::ui::rapidoc::create_endpoint(&self.spec(), &self.ui_config)
     }
 
     /// Create the Rapidoc HTML
@@ -446,7 +511,7 @@ impl<T, W> OpenApiService<T, W> {
         T: OpenApi,
         W: Webhook,
     {
-        crate::ui::rapidoc::create_html(&self.spec())
+        crate::ui::rapidoc::create_html(&self.spec(), &self.ui_config)
     }
 
     /// Create the Redoc endpoint.
@@ -457,7 +522,7 @@ impl<T, W> OpenApiService<T, W> {
         T: OpenApi,
         W: Webhook,
     {
-        crate::ui::redoc::create_endpoint(&self.spec())
+        crate::ui::redoc::create_endpoint(&self.spec(), &self.ui_config)
     }
 
     /// Create the Redoc HTML
@@ -468,7 +533,7 @@ impl<T, W> OpenApiService<T, W> {
         T: OpenApi,
         W: Webhook,
     {
-        crate::ui::redoc::create_html(&self.spec())
+        crate::ui::redoc::create_html(&self.spec(), &self.ui_config)
     }
 
     /// Create an endpoint to serve the open api specification as JSON.
@@ -500,6 +565,54 @@ impl<T, W> OpenApiService<T, W> {
         })
     }
 
+    /// Create an endpoint to serve the open api specification as JSON or
+    /// YAML, selected by the `Accept` header.
+    ///
+    /// The `format` query parameter (`json` or `yaml`) overrides the `Accept`
+    /// header, and JSON is served if neither of them selects a format.
+    pub fn spec_endpoint_negotiated(&self) -> impl Endpoint
+    where
+        T: OpenApi,
+        W: Webhook,
+    {
+        let spec_json = self.spec();
+        let spec_yaml = self.spec_yaml();
+        make(move |req| {
+            let spec_json = spec_json.clone();
+            let spec_yaml = spec_yaml.clone();
+            async move {
+                let is_yaml = match spec_format_param(&req) {
+                    Some(format) => format.eq_ignore_ascii_case("yaml"),
+                    None => Accept::from_request_without_body(&req)
+                        .await
+                        .ok()
+                        .and_then(|Accept(mimes)| {
+                            mimes.iter().find_map(|mime| match mime.subtype().as_str() {
+                                "json" => Some(false),
+                                "yaml" | "x-yaml" => Some(true),
+                                _ => None,
+                            })
+                        })
+                        .unwrap_or_default(),
+                };
+
+                let mut resp = if is_yaml {
+                    Response::builder()
+                        .content_type("application/x-yaml")
+                        .header("Content-Disposition", "inline; filename=\"spec.yaml\"")
+                        .body(spec_yaml)
+                } else {
+                    Response::builder()
+                        .content_type("application/json")
+                        .body(spec_json)
+                };
+                resp.headers_mut()
+                    .insert(header::VARY, HeaderValue::from_static("accept"));
+                resp
+            }
+        })
+    }
+
     fn document(&self) -> Document<'_>
     where
         T: OpenApi,
@@ -517,18 +630,12 @@ impl<T, W> OpenApiService<T, W> {
             for (idx, (header, schema_ref, is_required)) in
                 self.extra_request_headers.iter().enumerate()
             {
-                operation.params.insert(
-                    idx,
-                    MetaOperationParam {
-                        name: header.name.clone(),
-                        schema: schema_ref.clone(),
-                        in_type: MetaParamIn::Header,
-                        description: header.description.clone(),
-                        required: *is_required,
-                        deprecated: header.deprecated,
-                        explode: true,
-                    },
-                );
+                let mut param =
+                    MetaOperationParam::new(&header.name, schema_ref.clone(), MetaParamIn::Header);
+                param.description = header.description.clone();
+                param.required = *is_required;
+                param.deprecated = header.deprecated;
+                operation.params.insert(idx, param);
             }
         }
 
@@ -555,6 +662,17 @@ impl<T, W> OpenApiService<T, W> {
             }
         }
 
+        // add the responses of the mapped errors
+        if let Some(error_mapper) = &self.error_mapper {
+            for operation in apis
+                .iter_mut()
+                .flat_map(|meta_api| meta_api.paths.iter_mut())
+                .flat_map(|path| path.operations.iter_mut())
+            {
+                error_mapper.update_operation(operation);
+            }
+        }
+
         T::register(&mut registry);
         W::register(&mut registry);
 
@@ -595,6 +713,15 @@ impl<T, W> OpenApiService<T, W> {
     }
 }
 
+fn spec_format_param(req: &Request) -> Option<String> {
+    req.uri()
+        .query()?
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .find(|(name, _)| *name == "format")
+        .map(|(_, value)| value.to_string())
+}
+
 impl<T: OpenApi, W: Webhook> IntoEndpoint for OpenApiService<T, W> {
     type Endpoint = BoxEndpoint<'static>;
 
@@ -640,11 +767,25 @@ impl<T: OpenApi, W: Webhook> IntoEndpoint for OpenApiService<T, W> {
                 )
             });
 
-        route
+        let ep = route
             .with(cookie_jar_manager)
+            .data_opt(self.max_request_size.map(MaxRequestSize))
             .before(extract_query)
-            .map_to_response()
-            .boxed()
+            .map_to_response();
+
+        match self.error_mapper {
+            Some(error_mapper) => ep
+                .around(move |ep, req| {
+                    let error_mapper = error_mapper.clone();
+                    async move {
+                        ep.call(req)
+                            .await
+                            .map_err(|err| error_mapper.map_error(err))
+                    }
+                })
+                .boxed(),
+            None => ep.boxed(),
+        }
     }
 }
 
@@ -721,4 +862,92 @@ mod tests {
         assert!(params[2].deprecated);
         assert_eq!(params[2].schema, f32::schema_ref());
     }
+
+    #[test]
+    fn error_mapper_responses() {
+        #[derive(Debug, thiserror::Error)]
+        #[error("not found")]
+        struct NotFound;
+
+        impl poem::error::ResponseError for NotFound {
+            fn status(&self) -> poem::http::StatusCode {
+                poem::http::StatusCode::NOT_FOUND
+            }
+        }
+
+        #[derive(Debug, thiserror::Error)]
+        #[error("gone")]
+        struct Gone;
+
+        impl poem::error::ResponseError for Gone {
+            fn status(&self) -> poem::http::StatusCode {
+                poem::http::StatusCode::NOT_FOUND
+            }
+        }
+
+        struct Api;
+
+        #[OpenApi(internal)]
+        impl Api {
+            #[oai(path = "/", method = "get")]
+            async fn test(&self) {}
+        }
+
+        let api_service = OpenApiService::new(Api, "demo", "1.0").error_mapper(
+            ErrorMapper::new()
+                .error::<NotFound>(poem::http::StatusCode::NOT_FOUND, "NOT_FOUND")
+                .error::<Gone>(poem::http::StatusCode::NOT_FOUND, "GONE")
+                .error::<Gone>(poem::http::StatusCode::OK, "IGNORED"),
+        );
+        let doc = api_service.document();
+        let responses = &doc.apis[0].paths[0].operations[0].responses.responses;
+
+        assert_eq!(responses.len(), 2);
+        assert_eq!(responses[0].status, Some(200));
+        assert_eq!(responses[1].status, Some(404));
+        assert_eq!(responses[1].description, "Not Found");
+        let code = &responses[1].content[0].schema.unwrap_inline().properties[0].1;
+        assert_eq!(
+            code.unwrap_inline().enum_items,
+            vec![serde_json::json!("NOT_FOUND"), serde_json::json!("GONE")]
+        );
+    }
+
+    #[tokio::test]
+    async fn spec_endpoint_negotiated() {
+        struct Api;
+
+        #[OpenApi(internal)]
+        impl Api {
+            #[oai(path = "/", method = "get")]
+            async fn test(&self) {}
+        }
+
+        let api_service = OpenApiService::new(Api, "demo", "1.0");
+        let cli = poem::test::TestClient::new(api_service.spec_endpoint_negotiated());
+
+        let resp = cli.get("/").send().await;
+        resp.assert_content_type("application/json");
+        resp.assert_header("vary", "accept");
+        resp.assert_text(api_service.spec()).await;
+
+        let resp = cli
+            .get("/")
+            .header("accept", "text/html, application/yaml;q=0.9, */*;q=0.1")
+            .send()
+            .await;
+        resp.assert_content_type("application/x-yaml");
+        resp.assert_text(api_service.spec_yaml()).await;
+
+        let resp = cli
+            .get("/")
+            .header("accept", "application/yaml")
+            .query("format", &"json")
+            .send()
+            .await;
+        resp.assert_content_type("application/json");
+
+        let resp = cli.get("/").query("format", &"yaml").send().await;
+        resp.assert_content_type("application/x-yaml");
+    }
 }
@@ -1,10 +1,12 @@
 //! Parameter types for the API operation.
 mod cookie;
 mod header;
+mod pagination;
 mod path;
 mod query;
 
 pub use cookie::{Cookie, CookiePrivate, CookieSigned};
 pub use header::Header;
+pub use pagination::Pagination;
 pub use path::Path;
 pub use query::Query;
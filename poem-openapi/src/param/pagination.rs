@@ -0,0 +1,78 @@
+use std::ops::{Deref, DerefMut};
+
+use poem::{web::Page, FromRequest, Request, RequestBody, Result};
+
+use crate::{
+    registry::{MetaParamIn, MetaSchema, MetaSchemaRef},
+    types::Type,
+    ApiExtractor, ApiExtractorType, ExtractParamOptions,
+};
+
+/// Represents the pagination parameters passed by the query string, see
+/// [`poem::web::Page`].
+///
+/// The parameters are documented as an exploded object, so the `limit`,
+/// `per_page`, `cursor` and `page` query parameters are listed in the
+/// specification.
+///
+/// # Example
+///
+/// ```
+/// use poem_openapi::{param::Pagination, OpenApi, PaginatedResponse};
+///
+/// struct Api;
+///
+/// #[OpenApi]
+/// impl Api {
+///     #[oai(path = "/users", method = "get")]
+///     async fn users(&self, page: Pagination) -> PaginatedResponse<String> {
+///         PaginatedResponse::new(&page, vec!["alice".to_string()])
+///     }
+/// }
+/// ```
+pub struct Pagination(pub Page);
+
+impl Deref for Pagination {
+    type Target = Page;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl DerefMut for Pagination {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl<'a> ApiExtractor<'a> for Pagination {
+    const TYPES: &'static [ApiExtractorType] = &[ApiExtractorType::Parameter];
+
+    type ParamType = ();
+    type ParamRawType = ();
+
+    fn param_in() -> Option<MetaParamIn> {
+        Some(MetaParamIn::Query)
+    }
+
+    fn param_schema_ref() -> Option<MetaSchemaRef> {
+        Some(MetaSchemaRef::Inline(Box::new(MetaSchema {
+            properties: vec![
+                ("limit", u32::schema_ref()),
+                ("per_page", u32::schema_ref()),
+                ("cursor", String::schema_ref()),
+                ("page", u64::schema_ref()),
+            ],
+            ..MetaSchema::new("object")
+        })))
+    }
+
+    async fn from_request(
+        request: &'a Request,
+        _body: &mut RequestBody,
+        _param_opts: ExtractParamOptions<Self::ParamType>,
+    ) -> Result<Self> {
+        Page::from_request_without_body(request).await.map(Self)
+    }
+}
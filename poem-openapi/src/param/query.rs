@@ -5,8 +5,8 @@ use poem::{Request, RequestBody, Result};
 use crate::{
     base::UrlQuery,
     error::ParseParamError,
-    registry::{MetaParamIn, MetaSchemaRef, Registry},
-    types::ParseFromParameter,
+    registry::{MetaParamIn, MetaParamStyle, MetaSchemaRef, Registry},
+    types::{ParseError, ParseFromParameter},
     ApiExtractor, ApiExtractorType, ExtractParamOptions,
 };
 
@@ -55,12 +55,36 @@ impl<'a, T: ParseFromParameter> ApiExtractor<'a> for Query<T> {
         _body: &mut RequestBody,
         param_opts: ExtractParamOptions<Self::ParamType>,
     ) -> Result<Self> {
-        let mut values = request
-            .extensions()
-            .get::<UrlQuery>()
-            .unwrap()
-            .get_all(param_opts.name)
-            .peekable();
+        let query = request.extensions().get::<UrlQuery>().unwrap();
+        let to_param_error = |err: ParseError<T>| -> poem::Error {
+            ParseParamError {
+                name: param_opts.name,
+                reason: err.into_message(),
+            }
+            .into()
+        };
+
+        if param_opts.style == Some(MetaParamStyle::DeepObject) {
+            let mut pairs = query
+                .iter()
+                .filter_map(|(name, value)| {
+                    let key = name
+                        .strip_prefix(param_opts.name)?
+                        .strip_prefix('[')?
+                        .strip_suffix(']')?;
+                    Some((key, value))
+                })
+                .peekable();
+
+            return match &param_opts.default_value {
+                Some(default_value) if pairs.peek().is_none() => Ok(Self(default_value())),
+                _ => ParseFromParameter::parse_from_deep_object(pairs)
+                    .map(Self)
+                    .map_err(to_param_error),
+            };
+        }
+
+        let mut values = query.get_all(param_opts.name).peekable();
 
         match &param_opts.default_value {
             Some(default_value) if values.peek().is_none() => {
@@ -72,24 +96,21 @@ impl<'a, T: ParseFromParameter> ApiExtractor<'a> for Query<T> {
         if param_opts.explode {
             ParseFromParameter::parse_from_parameters(values)
                 .map(Self)
-                .map_err(|err| {
-                    ParseParamError {
-                        name: param_opts.name,
-                        reason: err.into_message(),
-                    }
-                    .into()
-                })
+                .map_err(to_param_error)
         } else {
-            let values = values.next().unwrap().split(',').map(|v| v.trim());
+            let delimiter = match param_opts.style {
+                Some(MetaParamStyle::SpaceDelimited) => ' ',
+                Some(MetaParamStyle::PipeDelimited) => '|',
+                _ => ',',
+            };
+            let values = values
+                .next()
+                .into_iter()
+                .flat_map(|value| value.split(delimiter))
+                .map(|v| v.trim());
             ParseFromParameter::parse_from_parameters(values)
                 .map(Self)
-                .map_err(|err| {
-                    ParseParamError {
-                        name: param_opts.name,
-                        reason: err.into_message(),
-                    }
-                    .into()
-                })
+                .map_err(to_param_error)
         }
     }
 }
@@ -0,0 +1,112 @@
+use bytes::Bytes;
+use futures_util::Stream;
+use poem::{Body, IntoResponse, Response};
+
+use crate::{
+    payload::Payload,
+    registry::{MetaMediaType, MetaResponse, MetaResponses, MetaSchema, MetaSchemaRef, Registry},
+    ApiResponse,
+};
+
+/// A binary response payload whose body is produced by a stream of chunks.
+///
+/// The chunks are sent as they are produced, without buffering the whole
+/// body in memory, so it is suitable for large generated files. Since the
+/// length of the body is unknown, HTTP/1.1 responses are sent with
+/// `Transfer-Encoding: chunked`.
+///
+/// The media type is documented as `application/octet-stream` by default, use
+/// the `content_type` attribute of an [`ApiResponse`](crate::ApiResponse)
+/// variant to document and send another one, such as `text/csv`.
+///
+/// Range requests are not supported, the whole body is always returned with
+/// status `200`. Use [`Binary`](crate::payload::Binary) with
+/// [`StaticFileRequest`](poem::web::StaticFileRequest) for files that should
+/// be resumable.
+///
+/// # Example
+///
+/// ```
+/// use futures_util::stream;
+/// use poem::test::TestClient;
+/// use poem_openapi::{payload::BinaryStream, ApiResponse, OpenApi, OpenApiService};
+///
+/// #[derive(ApiResponse)]
+/// enum ReportResponse {
+///     #[oai(status = 200, content_type = "text/csv")]
+///     Csv(BinaryStream<stream::Iter<std::vec::IntoIter<Result<String, std::io::Error>>>>),
+/// }
+///
+/// struct Api;
+///
+/// #[OpenApi]
+/// impl Api {
+///     #[oai(path = "/report", method = "get")]
+///     async fn report(&self) -> ReportResponse {
+///         let rows = (0..3).map(|i| Ok(format!("{i},row{i}\n"))).collect::<Vec<_>>();
+///         ReportResponse::Csv(BinaryStream(stream::iter(rows)))
+///     }
+/// }
+///
+/// let cli = TestClient::new(OpenApiService::new(Api, "test", "1.0"));
+///
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// let resp = cli.get("/report").send().await;
+/// resp.assert_status_is_ok();
+/// resp.assert_content_type("text/csv");
+/// resp.assert_text("0,row0\n1,row1\n2,row2\n").await;
+/// # });
+/// ```
+pub struct BinaryStream<S>(pub S);
+
+impl<S, O, E> Payload for BinaryStream<S>
+where
+    S: Stream<Item = Result<O, E>> + Send + 'static,
+    O: Into<Bytes> + 'static,
+    E: Into<std::io::Error> + 'static,
+{
+    const CONTENT_TYPE: &'static str = "application/octet-stream";
+
+    fn schema_ref() -> MetaSchemaRef {
+        MetaSchemaRef::Inline(Box::new(MetaSchema {
+            format: Some("binary"),
+            ..MetaSchema::new("string")
+        }))
+    }
+}
+
+impl<S, O, E> IntoResponse for BinaryStream<S>
+where
+    S: Stream<Item = Result<O, E>> + Send + 'static,
+    O: Into<Bytes> + 'static,
+    E: Into<std::io::Error> + 'static,
+{
+    fn into_response(self) -> Response {
+        Response::builder()
+            .content_type(Self::CONTENT_TYPE)
+            .body(Body::from_bytes_stream(self.0))
+    }
+}
+
+impl<S, O, E> ApiResponse for BinaryStream<S>
+where
+    S: Stream<Item = Result<O, E>> + Send + 'static,
+    O: Into<Bytes> + 'static,
+    E: Into<std::io::Error> + 'static,
+{
+    fn meta() -> MetaResponses {
+        MetaResponses {
+            responses: vec![MetaResponse {
+                description: "",
+                status: Some(200),
+                content: vec![MetaMediaType {
+                    content_type: Self::CONTENT_TYPE,
+                    schema: Self::schema_ref(),
+                }],
+                headers: vec![],
+            }],
+        }
+    }
+
+    fn register(_registry: &mut Registry) {}
+}
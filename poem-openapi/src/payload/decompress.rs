@@ -0,0 +1,97 @@
+use std::str::FromStr;
+
+use poem::{http::header, web::CompressionAlgo, Body, Request, RequestBody, Result};
+use tokio::io::AsyncReadExt;
+
+use crate::error::{ContentEncodingError, ParseRequestPayloadError};
+
+/// The settings for decompressing the request payloads.
+///
+/// When the `compression` feature is enabled, the request payloads are
+/// decompressed according to the `Content-Encoding` header (`gzip`, `deflate`,
+/// `br`, or `zstd` with the `zstd` feature) before parsing. The settings are read from the request
+/// data, so they can be attached to an endpoint with
+/// [`EndpointExt::data`](poem::EndpointExt::data).
+///
+/// An unsupported encoding is rejected with `415 Unsupported Media Type`, and
+/// a payload that cannot be decompressed with `400 Bad Request`.
+///
+/// # Example
+///
+/// ```
+/// use poem::EndpointExt;
+/// use poem_openapi::{payload::RequestDecompression, OpenApi, OpenApiService};
+///
+/// struct Api;
+///
+/// #[OpenApi]
+/// impl Api {}
+///
+/// let app = OpenApiService::new(Api, "demo", "1.0")
+///     .data(RequestDecompression::new().max_size(1024 * 1024));
+/// ```
+#[cfg_attr(docsrs, doc(cfg(feature = "compression")))]
+#[derive(Debug, Copy, Clone)]
+pub struct RequestDecompression {
+    max_size: usize,
+}
+
+impl Default for RequestDecompression {
+    fn default() -> Self {
+        Self {
+            max_size: 16 * 1024 * 1024,
+        }
+    }
+}
+
+impl RequestDecompression {
+    /// Create a new `RequestDecompression`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the maximum size in bytes of the decompressed payload.
+    ///
+    /// Default is `16MB`.
+    #[must_use]
+    pub fn max_size(self, max_size: usize) -> Self {
+        Self { max_size }
+    }
+}
+
+pub(crate) async fn decompress_request_body(
+    request: &Request,
+    body: &mut RequestBody,
+) -> Result<()> {
+    let Some(content_encoding) = request.header(header::CONTENT_ENCODING) else {
+        return Ok(());
+    };
+    if content_encoding.eq_ignore_ascii_case("identity") {
+        return Ok(());
+    }
+    let algo = CompressionAlgo::from_str(&content_encoding.to_ascii_lowercase()).map_err(|_| {
+        ContentEncodingError::NotSupported {
+            content_encoding: content_encoding.to_string(),
+        }
+    })?;
+    let max_size = request
+        .data::<RequestDecompression>()
+        .copied()
+        .unwrap_or_default()
+        .max_size;
+
+    let mut data = Vec::new();
+    algo.decompress_body(body.take()?)
+        .into_async_read()
+        .take(max_size as u64 + 1)
+        .read_to_end(&mut data)
+        .await
+        .map_err(|err| ParseRequestPayloadError {
+            reason: err.to_string(),
+        })?;
+    if data.len() > max_size {
+        return Err(ContentEncodingError::TooLarge { max_size }.into());
+    }
+    *body = RequestBody::new(Body::from_vec(data));
+    Ok(())
+}
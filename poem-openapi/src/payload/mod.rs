@@ -3,6 +3,9 @@
 mod attachment;
 mod base64_payload;
 mod binary;
+mod binary_stream;
+#[cfg(feature = "compression")]
+mod decompress;
 mod event_stream;
 mod form;
 mod html;
@@ -16,10 +19,13 @@ use std::future::Future;
 
 use poem::{Request, RequestBody, Result};
 
+#[cfg(feature = "compression")]
+pub use self::decompress::RequestDecompression;
 pub use self::{
     attachment::{Attachment, AttachmentType},
     base64_payload::Base64,
     binary::Binary,
+    binary_stream::BinaryStream,
     event_stream::EventStream,
     form::Form,
     html::Html,
@@ -31,6 +37,43 @@ pub use self::{
 };
 use crate::registry::{MetaSchemaRef, Registry};
 
+/// Decompresses the request body according to the `Content-Encoding` header
+/// before parsing the payload.
+#[doc(hidden)]
+pub async fn decompress_request_body(request: &Request, body: &mut RequestBody) -> Result<()> {
+    #[cfg(feature = "compression")]
+    {
+        decompress::decompress_request_body(request, body).await
+    }
+
+    #[cfg(not(feature = "compression"))]
+    {
+        let _ = (request, body);
+        Ok(())
+    }
+}
+
+/// Returns `true` if the content type matches the content type declared by an
+/// `ApiRequest` item, which can be a wildcard such as `image/*` or `*/*`, the
+/// parameters are ignored.
+#[doc(hidden)]
+pub fn content_type_matches(pattern: &str, content_type: &str) -> bool {
+    let (Ok(pattern), Ok(content_type)) = (
+        pattern.parse::<mime::Mime>(),
+        content_type.parse::<mime::Mime>(),
+    ) else {
+        return false;
+    };
+
+    if pattern.type_() == mime::STAR {
+        true
+    } else if pattern.subtype() == mime::STAR {
+        pattern.type_() == content_type.type_()
+    } else {
+        pattern.essence_str() == content_type.essence_str()
+    }
+}
+
 /// Represents a payload type.
 pub trait Payload: Send {
     /// The content type of this payload.
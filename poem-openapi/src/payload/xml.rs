@@ -1,6 +1,6 @@
 use std::ops::{Deref, DerefMut};
 
-use poem::{FromRequest, IntoResponse, Request, RequestBody, Response, Result};
+use poem::{http::StatusCode, FromRequest, IntoResponse, Request, RequestBody, Response, Result};
 use serde_json::Value;
 
 use crate::{
@@ -74,7 +74,17 @@ impl<T: ParseFromXML> ParsePayload for Xml<T> {
 
 impl<T: ToXML> IntoResponse for Xml<T> {
     fn into_response(self) -> Response {
-        poem::web::Xml(self.0.to_xml()).into_response()
+        match T::xml_root_name() {
+            Some(root) => match quick_xml::se::to_string_with_root(root, &self.0.to_xml()) {
+                Ok(data) => Response::builder()
+                    .content_type(Self::CONTENT_TYPE)
+                    .body(data),
+                Err(err) => Response::builder()
+                    .status(StatusCode::INTERNAL_SERVER_ERROR)
+                    .body(err.to_string()),
+            },
+            None => poem::web::Xml(self.0.to_xml()).into_response(),
+        }
     }
 }
 
@@ -118,6 +118,8 @@ pub struct MetaSchema {
     pub max_properties: Option<usize>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub min_properties: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub xml: Option<MetaXml>,
 }
 
 fn serialize_properties<S: Serializer>(
@@ -167,6 +169,7 @@ impl MetaSchema {
         unique_items: None,
         max_properties: None,
         min_properties: None,
+        xml: None,
     };
 
     pub fn new(ty: &'static str) -> Self {
@@ -212,6 +215,7 @@ impl MetaSchema {
             unique_items,
             max_properties,
             min_properties,
+            xml,
             ..
         }: MetaSchema,
     ) -> Self {
@@ -247,7 +251,8 @@ impl MetaSchema {
             min_items,
             unique_items,
             max_properties,
-            min_properties
+            min_properties,
+            xml
         );
 
         if let Some(items) = items {
@@ -297,6 +302,20 @@ impl MetaSchema {
     }
 }
 
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MetaXml {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<&'static str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub namespace: Option<&'static str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub prefix: Option<&'static str>,
+    #[serde(skip_serializing_if = "is_false")]
+    pub attribute: bool,
+    #[serde(skip_serializing_if = "is_false")]
+    pub wrapped: bool,
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum MetaSchemaRef {
     Inline(Box<MetaSchema>),
@@ -361,7 +380,17 @@ pub enum MetaParamIn {
     CookieSigned,
 }
 
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum MetaParamStyle {
+    Form,
+    SpaceDelimited,
+    PipeDelimited,
+    DeepObject,
+}
+
 #[derive(Debug, PartialEq, Serialize)]
+#[non_exhaustive]
 pub struct MetaOperationParam {
     pub name: String,
     pub schema: MetaSchemaRef,
@@ -372,6 +401,23 @@ pub struct MetaOperationParam {
     pub required: bool,
     pub deprecated: bool,
     pub explode: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub style: Option<MetaParamStyle>,
+}
+
+impl MetaOperationParam {
+    pub fn new(name: impl Into<String>, schema: MetaSchemaRef, in_type: MetaParamIn) -> Self {
+        Self {
+            name: name.into(),
+            schema,
+            in_type,
+            description: None,
+            required: false,
+            deprecated: false,
+            explode: true,
+            style: None,
+        }
+    }
 }
 
 #[derive(Debug, PartialEq, Serialize)]
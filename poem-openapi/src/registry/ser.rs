@@ -3,8 +3,9 @@ use std::collections::BTreeMap;
 use serde::{ser::SerializeMap, Serialize, Serializer};
 
 use crate::registry::{
-    MetaApi, MetaExternalDocument, MetaInfo, MetaPath, MetaResponses, MetaSchema, MetaSchemaRef,
-    MetaSecurityScheme, MetaServer, MetaWebhook, Registry,
+    MetaApi, MetaExternalDocument, MetaHeader, MetaInfo, MetaMediaType, MetaPath, MetaResponse,
+    MetaResponses, MetaSchema, MetaSchemaRef, MetaSecurityScheme, MetaServer, MetaWebhook,
+    Registry,
 };
 
 const OPENAPI_VERSION: &str = "3.0.0";
@@ -54,11 +55,102 @@ impl Serialize for MetaPath {
 impl Serialize for MetaResponses {
     fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
         let mut s = serializer.serialize_map(None)?;
-        for resp in &self.responses {
-            match resp.status {
-                Some(status) => s.serialize_entry(&format!("{status}"), resp)?,
-                None => s.serialize_entry("default", resp)?,
+        for (idx, resp) in self.responses.iter().enumerate() {
+            if self.responses[..idx]
+                .iter()
+                .any(|prev| prev.status == resp.status)
+            {
+                continue;
             }
+
+            // the responses with the same status, e.g. the variants of an
+            // `ApiResponse` with different media types, are merged into one
+            let same_status = self
+                .responses
+                .iter()
+                .filter(|other| other.status == resp.status)
+                .collect::<Vec<_>>();
+            let key = match resp.status {
+                Some(status) => format!("{status}"),
+                None => "default".to_string(),
+            };
+            if same_status.len() == 1 {
+                s.serialize_entry(&key, resp)?;
+            } else {
+                s.serialize_entry(&key, &MergedResponse(&same_status))?;
+            }
+        }
+        s.end()
+    }
+}
+
+struct Entries<'a, T>(Vec<(&'a str, &'a T)>);
+
+impl<T: Serialize> Serialize for Entries<'_, T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut s = serializer.serialize_map(None)?;
+        for (key, value) in &self.0 {
+            s.serialize_entry(key, value)?;
+        }
+        s.end()
+    }
+}
+
+struct MergedResponse<'a>(&'a [&'a MetaResponse]);
+
+impl Serialize for MergedResponse<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let description = self
+            .0
+            .iter()
+            .map(|resp| resp.description)
+            .find(|description| !description.is_empty())
+            .unwrap_or_default();
+
+        let mut content = Vec::new();
+        let mut headers = Vec::new();
+        for resp in self.0 {
+            for item in &resp.content {
+                if content
+                    .iter()
+                    .all(|prev: &&MetaMediaType| prev.content_type != item.content_type)
+                {
+                    content.push(item);
+                }
+            }
+            for header in &resp.headers {
+                if headers
+                    .iter()
+                    .all(|prev: &&MetaHeader| prev.name != header.name)
+                {
+                    headers.push(header);
+                }
+            }
+        }
+
+        let mut s = serializer.serialize_map(None)?;
+        s.serialize_entry("description", description)?;
+        if !content.is_empty() {
+            s.serialize_entry(
+                "content",
+                &Entries(
+                    content
+                        .iter()
+                        .map(|item| (item.content_type, *item))
+                        .collect(),
+                ),
+            )?;
+        }
+        if !headers.is_empty() {
+            s.serialize_entry(
+                "headers",
+                &Entries(
+                    headers
+                        .iter()
+                        .map(|header| (header.name.as_str(), *header))
+                        .collect(),
+                ),
+            )?;
         }
         s.end()
     }
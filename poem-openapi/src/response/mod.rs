@@ -1,4 +1,9 @@
 //! Commonly used response types.
 
+mod no_content;
+mod pagination;
 #[cfg(feature = "static-files")]
 mod static_file;
+
+pub use no_content::NoContent;
+pub use pagination::PaginatedResponse;
@@ -0,0 +1,52 @@
+use poem::{http::StatusCode, IntoResponse, Response};
+
+use crate::{
+    registry::{MetaResponse, MetaResponses, Registry},
+    ApiResponse,
+};
+
+/// A response with the `204 No Content` status, without a body or a
+/// `Content-Type` header.
+///
+/// Unlike `()`, which responds with `200 OK`, it is documented as a `204`
+/// response, which is the convention for the operations such as `DELETE` and
+/// `PUT` that return nothing.
+///
+/// # Example
+///
+/// ```
+/// use poem_openapi::{param::Path, NoContent, OpenApi};
+///
+/// struct Api;
+///
+/// #[OpenApi]
+/// impl Api {
+///     #[oai(path = "/users/:id", method = "delete")]
+///     async fn delete_user(&self, id: Path<i64>) -> NoContent {
+///         NoContent
+///     }
+/// }
+/// ```
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Default)]
+pub struct NoContent;
+
+impl IntoResponse for NoContent {
+    fn into_response(self) -> Response {
+        StatusCode::NO_CONTENT.into_response()
+    }
+}
+
+impl ApiResponse for NoContent {
+    fn meta() -> MetaResponses {
+        MetaResponses {
+            responses: vec![MetaResponse {
+                description: "No Content",
+                status: Some(204),
+                content: vec![],
+                headers: vec![],
+            }],
+        }
+    }
+
+    fn register(_registry: &mut Registry) {}
+}
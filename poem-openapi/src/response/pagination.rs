@@ -0,0 +1,105 @@
+use poem::{web::Page, IntoResponse, Response};
+use serde_json::{Map, Value};
+
+use crate::{
+    registry::{
+        MetaHeader, MetaMediaType, MetaResponse, MetaResponses, MetaSchema, MetaSchemaRef, Registry,
+    },
+    types::{ToJSON, Type},
+    ApiResponse,
+};
+
+const LINK_DESCRIPTION: &str = "The links to the first, previous, next and last pages (RFC 5988).";
+
+/// A page of items that is serialized as JSON with the `Link` header set, see
+/// [`poem::web::PaginatedResponse`].
+///
+/// Unlike [`poem::web::PaginatedResponse`], the items are serialized with
+/// [`ToJSON`], so they can be any type that is documented in the
+/// specification, such as an [`Object`](crate::Object).
+///
+/// # Example
+///
+/// ```
+/// use poem_openapi::{param::Pagination, OpenApi, PaginatedResponse};
+///
+/// struct Api;
+///
+/// #[OpenApi]
+/// impl Api {
+///     #[oai(path = "/users", method = "get")]
+///     async fn users(&self, page: Pagination) -> PaginatedResponse<String> {
+///         PaginatedResponse::new(&page, vec!["alice".to_string()])
+///     }
+/// }
+/// ```
+pub struct PaginatedResponse<T>(poem::web::PaginatedResponse<T>);
+
+impl<T> PaginatedResponse<T> {
+    /// Create a response with the items of the requested page.
+    pub fn new(page: &Page, items: Vec<T>) -> Self {
+        Self(poem::web::PaginatedResponse::new(page, items))
+    }
+
+    /// Sets the cursor of the next page.
+    #[must_use]
+    pub fn next_cursor(self, cursor: impl Into<String>) -> Self {
+        Self(self.0.next_cursor(cursor))
+    }
+
+    /// Sets the total number of items.
+    #[must_use]
+    pub fn total(self, total: u64) -> Self {
+        Self(self.0.total(total))
+    }
+}
+
+impl<T: ToJSON> IntoResponse for PaginatedResponse<T> {
+    fn into_response(self) -> Response {
+        self.0.into_response_with(|items, next_cursor, total| {
+            let mut body = Map::new();
+            body.insert("items".to_string(), items.to_json().unwrap_or_default());
+            if let Some(next_cursor) = next_cursor {
+                body.insert("next_cursor".to_string(), Value::String(next_cursor));
+            }
+            if let Some(total) = total {
+                body.insert("total".to_string(), total.into());
+            }
+            poem::web::Json(Value::Object(body)).into_response()
+        })
+    }
+}
+
+impl<T: ToJSON> ApiResponse for PaginatedResponse<T> {
+    fn meta() -> MetaResponses {
+        MetaResponses {
+            responses: vec![MetaResponse {
+                description: "",
+                status: Some(200),
+                content: vec![MetaMediaType {
+                    content_type: "application/json; charset=utf-8",
+                    schema: MetaSchemaRef::Inline(Box::new(MetaSchema {
+                        properties: vec![
+                            ("items", Vec::<T>::schema_ref()),
+                            ("next_cursor", String::schema_ref()),
+                            ("total", u64::schema_ref()),
+                        ],
+                        required: vec!["items"],
+                        ..MetaSchema::new("object")
+                    })),
+                }],
+                headers: vec![MetaHeader {
+                    name: "link".to_string(),
+                    description: Some(LINK_DESCRIPTION.to_string()),
+                    required: false,
+                    deprecated: false,
+                    schema: String::schema_ref(),
+                }],
+            }],
+        }
+    }
+
+    fn register(registry: &mut Registry) {
+        T::register(registry);
+    }
+}
@@ -79,3 +79,25 @@ impl<T: Type> ParseError<T> {
 
 /// An error parsing a value of type `T`.
 pub type ParseResult<T> = Result<T, ParseError<T>>;
+
+/// An invalid value found by
+/// [`ParseFromJSON::validate_json`](super::ParseFromJSON::validate_json).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationError {
+    /// The location of the invalid value as a JSON pointer, such as
+    /// `/pets/0/name`, or an empty string for the whole value.
+    pub path: String,
+
+    /// The reason for the error.
+    pub message: String,
+}
+
+impl ValidationError {
+    #[doc(hidden)]
+    pub fn new(path: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            path: path.into(),
+            message: message.into(),
+        }
+    }
+}
@@ -4,7 +4,10 @@ use serde_json::Value;
 
 use crate::{
     registry::{MetaSchema, MetaSchemaRef, Registry},
-    types::{ParseError, ParseFromJSON, ParseResult, ToJSON, Type},
+    types::{
+        ParseError, ParseFromDeepObject, ParseFromJSON, ParseFromParameter, ParseResult, ToJSON,
+        Type,
+    },
 };
 
 impl<K, V> Type for BTreeMap<K, V>
@@ -70,6 +73,60 @@ where
             Err(ParseError::expected_type(value))
         }
     }
+
+    fn remove_flattened_properties(obj: &mut serde_json::Map<String, Value>) {
+        obj.clear();
+    }
+}
+
+impl<K, V> ParseFromParameter for BTreeMap<K, V>
+where
+    K: ToString + FromStr + Ord + Sync + Send,
+    K::Err: Display,
+    V: ParseFromParameter,
+{
+    fn parse_from_parameter(value: &str) -> ParseResult<Self> {
+        Self::parse_from_parameters(value.split(','))
+    }
+
+    fn parse_from_parameters<I: IntoIterator<Item = A>, A: AsRef<str>>(
+        iter: I,
+    ) -> ParseResult<Self> {
+        // the keys and values are alternated, such as `R,100,G,200`
+        let mut iter = iter.into_iter();
+        let mut pairs = Vec::new();
+        while let Some(key) = iter.next() {
+            let value = iter.next().ok_or_else(|| {
+                ParseError::custom(format!("object key `{}` has no value.", key.as_ref()))
+            })?;
+            pairs.push((key, value));
+        }
+        Self::parse_from_deep_object(pairs)
+    }
+
+    fn parse_from_deep_object<I: IntoIterator<Item = (Q, A)>, Q: AsRef<str>, A: AsRef<str>>(
+        iter: I,
+    ) -> ParseResult<Self> {
+        let mut obj = BTreeMap::new();
+        for (key, value) in iter {
+            let key = key
+                .as_ref()
+                .parse()
+                .map_err(|err| ParseError::custom(format!("object key: {err}")))?;
+            let value = V::parse_from_parameters(std::iter::once(value.as_ref()))
+                .map_err(ParseError::propagate)?;
+            obj.insert(key, value);
+        }
+        Ok(obj)
+    }
+}
+
+impl<K, V> ParseFromDeepObject for BTreeMap<K, V>
+where
+    K: ToString + FromStr + Ord + Sync + Send,
+    K::Err: Display,
+    V: ParseFromParameter,
+{
 }
 
 impl<K, V> ToJSON for BTreeMap<K, V>
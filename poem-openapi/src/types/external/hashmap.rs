@@ -10,7 +10,10 @@ use serde_json::Value;
 
 use crate::{
     registry::{MetaSchema, MetaSchemaRef, Registry},
-    types::{ParseError, ParseFromJSON, ParseResult, ToJSON, Type},
+    types::{
+        ParseError, ParseFromDeepObject, ParseFromJSON, ParseFromParameter, ParseResult, ToJSON,
+        Type,
+    },
 };
 
 impl<K, V, R> Type for HashMap<K, V, R>
@@ -78,6 +81,62 @@ where
             Err(ParseError::expected_type(value))
         }
     }
+
+    fn remove_flattened_properties(obj: &mut serde_json::Map<String, Value>) {
+        obj.clear();
+    }
+}
+
+impl<K, V, R> ParseFromParameter for HashMap<K, V, R>
+where
+    K: ToString + FromStr + Eq + Hash + Sync + Send,
+    K::Err: Display,
+    V: ParseFromParameter,
+    R: Sync + Send + Default + BuildHasher,
+{
+    fn parse_from_parameter(value: &str) -> ParseResult<Self> {
+        Self::parse_from_parameters(value.split(','))
+    }
+
+    fn parse_from_parameters<I: IntoIterator<Item = A>, A: AsRef<str>>(
+        iter: I,
+    ) -> ParseResult<Self> {
+        // the keys and values are alternated, such as `R,100,G,200`
+        let mut iter = iter.into_iter();
+        let mut pairs = Vec::new();
+        while let Some(key) = iter.next() {
+            let value = iter.next().ok_or_else(|| {
+                ParseError::custom(format!("object key `{}` has no value.", key.as_ref()))
+            })?;
+            pairs.push((key, value));
+        }
+        Self::parse_from_deep_object(pairs)
+    }
+
+    fn parse_from_deep_object<I: IntoIterator<Item = (Q, A)>, Q: AsRef<str>, A: AsRef<str>>(
+        iter: I,
+    ) -> ParseResult<Self> {
+        let mut obj = HashMap::with_hasher(R::default());
+        for (key, value) in iter {
+            let key = key
+                .as_ref()
+                .parse()
+                .map_err(|err| ParseError::custom(format!("object key: {err}")))?;
+            let value = V::parse_from_parameters(std::iter::once(value.as_ref()))
+                .map_err(ParseError::propagate)?;
+            obj.insert(key, value);
+        }
+        Ok(obj)
+    }
+}
+
+impl<K, V, R> ParseFromDeepObject for HashMap<K, V, R>
+where
+    K: ToString + FromStr + Eq + Hash + Sync + Send,
+    K::Err: Display,
+    V: ParseFromParameter,
+    R: Sync + Send + Default + BuildHasher,
+{
 }
 
 impl<K, V, R> ToJSON for HashMap<K, V, R>
@@ -113,4 +172,20 @@ mod tests {
             }
         );
     }
+
+    #[test]
+    fn parse_from_parameters() {
+        type MyObj = HashMap<String, i32>;
+
+        let obj = MyObj::parse_from_parameter("R,100,G,200").unwrap();
+        assert_eq!(
+            obj,
+            HashMap::from([("R".to_string(), 100), ("G".to_string(), 200)])
+        );
+        assert!(MyObj::parse_from_parameter("R,100,G").is_err());
+
+        let obj = MyObj::parse_from_deep_object([("R", "100")]).unwrap();
+        assert_eq!(obj, HashMap::from([("R".to_string(), 100)]));
+        assert!(MyObj::parse_from_deep_object([("R", "abc")]).is_err());
+    }
 }
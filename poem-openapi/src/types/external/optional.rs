@@ -6,8 +6,8 @@ use serde_json::Value;
 use crate::{
     registry::{MetaSchemaRef, Registry},
     types::{
-        ParseError, ParseFromJSON, ParseFromMultipartField, ParseFromParameter, ParseResult,
-        ToHeader, ToJSON, Type,
+        ParseError, ParseFromDeepObject, ParseFromJSON, ParseFromMultipartField,
+        ParseFromParameter, ParseResult, ToHeader, ToJSON, Type, ValidationError,
     },
 };
 
@@ -61,6 +61,22 @@ impl<T: ParseFromJSON> ParseFromJSON for Option<T> {
             )),
         }
     }
+
+    fn parse_from_xml_value(value: Option<Value>) -> ParseResult<Self> {
+        match value.unwrap_or_default() {
+            Value::Null => Ok(None),
+            value => Ok(Some(
+                T::parse_from_xml_value(Some(value)).map_err(ParseError::propagate)?,
+            )),
+        }
+    }
+
+    fn collect_json_errors(value: Option<&Value>, path: &str, errors: &mut Vec<ValidationError>) {
+        match value {
+            None | Some(Value::Null) => {}
+            value => T::collect_json_errors(value, path, errors),
+        }
+    }
 }
 
 impl<T: ParseFromParameter> ParseFromParameter for Option<T> {
@@ -81,8 +97,24 @@ impl<T: ParseFromParameter> ParseFromParameter for Option<T> {
             .map_err(ParseError::propagate)
             .map(Some)
     }
+
+    fn parse_from_deep_object<I: IntoIterator<Item = (K, A)>, K: AsRef<str>, A: AsRef<str>>(
+        iter: I,
+    ) -> ParseResult<Self> {
+        let mut iter = iter.into_iter().peekable();
+
+        if iter.peek().is_none() {
+            return Ok(None);
+        }
+
+        T::parse_from_deep_object(iter)
+            .map_err(ParseError::propagate)
+            .map(Some)
+    }
 }
 
+impl<T: ParseFromDeepObject> ParseFromDeepObject for Option<T> {}
+
 impl<T: ParseFromMultipartField> ParseFromMultipartField for Option<T> {
     async fn parse_from_multipart(value: Option<PoemField>) -> ParseResult<Self> {
         match value {
@@ -102,6 +134,13 @@ impl<T: ToJSON> ToJSON for Option<T> {
             None => Some(Value::Null),
         }
     }
+
+    fn to_xml_value(&self) -> Option<Value> {
+        match self {
+            Some(value) => value.to_xml_value(),
+            None => Some(Value::Null),
+        }
+    }
 }
 
 impl<T: ToHeader> ToHeader for Option<T> {
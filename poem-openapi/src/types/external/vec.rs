@@ -7,7 +7,7 @@ use crate::{
     registry::{MetaSchema, MetaSchemaRef, Registry},
     types::{
         ParseError, ParseFromJSON, ParseFromMultipartField, ParseFromParameter, ParseResult,
-        ToJSON, Type,
+        ToJSON, Type, ValidationError,
     },
 };
 
@@ -62,6 +62,35 @@ impl<T: ParseFromJSON> ParseFromJSON for Vec<T> {
             _ => Err(ParseError::expected_type(value)),
         }
     }
+
+    fn parse_from_xml_value(value: Option<Value>) -> ParseResult<Self> {
+        let value = value.unwrap_or_default();
+        match value {
+            Value::Array(values) => {
+                let mut res = Vec::with_capacity(values.len());
+                for value in values {
+                    res.push(T::parse_from_xml_value(Some(value)).map_err(ParseError::propagate)?);
+                }
+                Ok(res)
+            }
+            _ => Err(ParseError::expected_type(value)),
+        }
+    }
+
+    fn collect_json_errors(value: Option<&Value>, path: &str, errors: &mut Vec<ValidationError>) {
+        match value {
+            Some(Value::Array(values)) => {
+                for (idx, value) in values.iter().enumerate() {
+                    T::collect_json_errors(Some(value), &format!("{path}/{idx}"), errors);
+                }
+            }
+            _ => errors.push(ValidationError::new(
+                path,
+                ParseError::<Self>::expected_type(value.cloned().unwrap_or_default())
+                    .into_message(),
+            )),
+        }
+    }
 }
 
 impl<T: ParseFromParameter> ParseFromParameter for Vec<T> {
@@ -115,6 +144,16 @@ impl<T: ToJSON> ToJSON for Vec<T> {
         }
         Some(Value::Array(values))
     }
+
+    fn to_xml_value(&self) -> Option<Value> {
+        let mut values = Vec::with_capacity(self.len());
+        for item in self {
+            if let Some(value) = item.to_xml_value() {
+                values.push(value);
+            }
+        }
+        Some(Value::Array(values))
+    }
 }
 
 #[cfg(test)]
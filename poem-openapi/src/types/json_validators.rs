@@ -0,0 +1,115 @@
+use std::collections::HashMap;
+
+use poem::{http::StatusCode, Endpoint, IntoResponse, Request, Response, Result};
+use serde_json::Value;
+
+use crate::{
+    error::ValidateJsonError,
+    types::{ParseFromJSON, ValidationError},
+};
+
+type ValidateFn = fn(&Value) -> Result<(), Vec<ValidationError>>;
+
+/// A set of types, registered by name, which can validate the JSON values
+/// without a handler, for example to implement a dry-run validation
+/// endpoint.
+///
+/// The values are validated with [`ParseFromJSON::validate_json`], with the
+/// same rules as the request payloads of these types.
+///
+/// It is also an endpoint, which validates the JSON body of the requests
+/// with the type named by the remaining path, and responds with
+/// `204 No Content` if the body is valid. Otherwise it responds with the
+/// [`ValidateJsonError`] as a JSON object, with the errors of the invalid
+/// values, such as
+/// `{"type": "Pet", "message": "...", "errors": [{"path": "/name", "message": "..."}]}`,
+/// with `400 Bad Request`, or `404 Not Found` if the type is unknown.
+///
+/// # Example
+///
+/// ```
+/// use poem::{http::StatusCode, test::TestClient, Route};
+/// use poem_openapi::{types::JsonValidators, Object};
+/// use serde_json::json;
+///
+/// #[derive(Object)]
+/// struct Pet {
+///     #[oai(validator(max_length = 8))]
+///     name: String,
+/// }
+///
+/// let app = Route::new().nest("/validate", JsonValidators::new().register::<Pet>());
+/// let cli = TestClient::new(app);
+///
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// cli.post("/validate/Pet")
+///     .body_json(&json!({ "name": "Tom" }))
+///     .send()
+///     .await
+///     .assert_status(StatusCode::NO_CONTENT);
+/// cli.post("/validate/Pet")
+///     .body_json(&json!({ "name": "Mr. Whiskers" }))
+///     .send()
+///     .await
+///     .assert_status(StatusCode::BAD_REQUEST);
+/// # });
+/// ```
+#[derive(Default, Clone)]
+pub struct JsonValidators {
+    types: HashMap<String, ValidateFn>,
+}
+
+impl JsonValidators {
+    /// Create an empty `JsonValidators`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers a type with its name, as returned by
+    /// [`Type::name`](crate::types::Type::name).
+    #[must_use]
+    pub fn register<T: ParseFromJSON>(self) -> Self {
+        let name = T::name().into_owned();
+        self.register_as::<T>(name)
+    }
+
+    /// Registers a type with the specified name.
+    #[must_use]
+    pub fn register_as<T: ParseFromJSON>(mut self, name: impl Into<String>) -> Self {
+        self.types.insert(name.into(), T::validate_json);
+        self
+    }
+
+    /// Returns the names of the registered types.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.types.keys().map(String::as_str)
+    }
+
+    /// Validates a JSON value with the type registered as `name`.
+    pub fn validate(&self, name: &str, value: &Value) -> Result<(), ValidateJsonError> {
+        let validate = self
+            .types
+            .get(name)
+            .ok_or_else(|| ValidateJsonError::UnknownType {
+                type_name: name.to_string(),
+            })?;
+        validate(value).map_err(|errors| ValidateJsonError::Invalid {
+            type_name: name.to_string(),
+            errors,
+        })
+    }
+}
+
+impl Endpoint for JsonValidators {
+    type Output = Response;
+
+    async fn call(&self, mut req: Request) -> Result<Self::Output> {
+        let name = req.uri().path().trim_matches('/').to_string();
+        if !self.types.contains_key(&name) {
+            return Err(ValidateJsonError::UnknownType { type_name: name }.into());
+        }
+        let value = req.take_body().into_json::<Value>().await?;
+        self.validate(&name, &value)?;
+        Ok(StatusCode::NO_CONTENT.into_response())
+    }
+}
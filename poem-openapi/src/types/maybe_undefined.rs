@@ -8,7 +8,7 @@ use crate::{
     registry::{MetaSchemaRef, Registry},
     types::{
         ParseError, ParseFromJSON, ParseFromMultipartField, ParseFromParameter, ParseResult,
-        ToHeader, ToJSON, Type,
+        ToHeader, ToJSON, Type, ValidationError,
     },
 };
 
@@ -362,6 +362,13 @@ impl<T: ParseFromJSON> ParseFromJSON for MaybeUndefined<T> {
             None => Ok(MaybeUndefined::Undefined),
         }
     }
+
+    fn collect_json_errors(value: Option<&Value>, path: &str, errors: &mut Vec<ValidationError>) {
+        match value {
+            None | Some(Value::Null) => {}
+            value => T::collect_json_errors(value, path, errors),
+        }
+    }
 }
 
 impl<T: ParseFromParameter> ParseFromParameter for MaybeUndefined<T> {
@@ -5,6 +5,7 @@ mod base64_type;
 mod binary;
 mod error;
 mod external;
+mod json_validators;
 mod maybe_undefined;
 mod string_types;
 
@@ -15,7 +16,8 @@ use std::{borrow::Cow, future::Future, sync::Arc};
 pub use any::Any;
 pub use base64_type::Base64;
 pub use binary::Binary;
-pub use error::{ParseError, ParseResult};
+pub use error::{ParseError, ParseResult, ValidationError};
+pub use json_validators::JsonValidators;
 pub use maybe_undefined::MaybeUndefined;
 use poem::{http::HeaderValue, web::Field as PoemField};
 use serde_json::Value;
@@ -95,6 +97,43 @@ pub trait ParseFromJSON: Sized + Type {
         let value = serde_json::from_str(s).map_err(|err| ParseError::custom(err.to_string()))?;
         Self::parse_from_json(value)
     }
+
+    /// Checks that a [`serde_json::Value`] is valid for this type, with the
+    /// same rules as a request payload, without keeping the parsed value.
+    ///
+    /// The properties of the objects and the items of the arrays are checked
+    /// one by one, and an error is returned for each of the invalid values.
+    fn validate_json(value: &Value) -> Result<(), Vec<ValidationError>> {
+        let mut errors = Vec::new();
+        Self::collect_json_errors(Some(value), "", &mut errors);
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// Appends the errors of the value located at `path` to `errors`, see
+    /// [`ParseFromJSON::validate_json`].
+    #[doc(hidden)]
+    fn collect_json_errors(value: Option<&Value>, path: &str, errors: &mut Vec<ValidationError>) {
+        if let Err(err) = Self::parse_from_json(value.cloned()) {
+            errors.push(ValidationError::new(path, err.into_message()));
+        }
+    }
+
+    /// Removes the properties that are parsed by this type when it is
+    /// flattened into an object, so that the following flattened fields only
+    /// receive the remaining properties.
+    #[doc(hidden)]
+    fn remove_flattened_properties(_obj: &mut serde_json::Map<String, Value>) {}
+
+    /// Parse from the value of an XML document, applying the XML mapping of
+    /// the objects nested in this type.
+    #[doc(hidden)]
+    fn parse_from_xml_value(value: Option<Value>) -> ParseResult<Self> {
+        Self::parse_from_json(value)
+    }
 }
 
 /// Represents a type that can parsing from XML.
@@ -138,8 +177,29 @@ pub trait ParseFromParameter: Sized + Type {
             None => Err(ParseError::expected_input()),
         }
     }
+
+    /// Parse from the parameters of the `deepObject` style, such as
+    /// `filter[name]=value`, the items are the names in the brackets and the
+    /// values.
+    ///
+    /// Only the types that implement [`ParseFromDeepObject`] support this
+    /// style.
+    fn parse_from_deep_object<I: IntoIterator<Item = (K, A)>, K: AsRef<str>, A: AsRef<str>>(
+        _iter: I,
+    ) -> ParseResult<Self> {
+        Err(ParseError::custom(
+            "the `deepObject` style is not supported by this type.",
+        ))
+    }
 }
 
+/// Represents a type that can parsing from the parameters of the `deepObject`
+/// style.
+///
+/// The `style = "deepObject"` parameter option requires the parameter type to
+/// implement this trait.
+pub trait ParseFromDeepObject: ParseFromParameter {}
+
 /// Represents a type that can parsing from multipart.
 pub trait ParseFromMultipartField: Sized + Type {
     /// Parse from multipart field.
@@ -165,6 +225,13 @@ pub trait ToJSON: Type {
     fn to_json_string(&self) -> String {
         serde_json::to_string(&self.to_json()).unwrap_or_default()
     }
+
+    /// Convert this value to the value of an XML document, applying the XML
+    /// mapping of the objects nested in this type.
+    #[doc(hidden)]
+    fn to_xml_value(&self) -> Option<Value> {
+        self.to_json()
+    }
 }
 
 /// Represents a type that can converted to XML value.
@@ -172,9 +239,20 @@ pub trait ToXML: Type {
     /// Convert this value to [`Value`].
     fn to_xml(&self) -> Option<Value>;
 
+    /// The name of the root element when this value is the root of an XML
+    /// document, such as the name specified with `#[oai(xml(name = "..."))]`
+    /// on an object.
+    fn xml_root_name() -> Option<&'static str> {
+        None
+    }
+
     /// Convert this value to XML string.
     fn to_xml_string(&self) -> String {
-        quick_xml::se::to_string(&self.to_xml()).unwrap_or_default()
+        match Self::xml_root_name() {
+            Some(root) => quick_xml::se::to_string_with_root(root, &self.to_xml()),
+            None => quick_xml::se::to_string(&self.to_xml()),
+        }
+        .unwrap_or_default()
     }
 }
 
@@ -229,12 +307,20 @@ impl<T: ToJSON> ToJSON for &T {
     fn to_json(&self) -> Option<Value> {
         T::to_json(self)
     }
+
+    fn to_xml_value(&self) -> Option<Value> {
+        T::to_xml_value(self)
+    }
 }
 
 impl<T: ToXML> ToXML for &T {
     fn to_xml(&self) -> Option<Value> {
         T::to_xml(self)
     }
+
+    fn xml_root_name() -> Option<&'static str> {
+        T::xml_root_name()
+    }
 }
 
 impl<T: ToYAML> ToYAML for &T {
@@ -285,6 +371,16 @@ impl<T: ParseFromJSON> ParseFromJSON for Arc<T> {
             .map_err(ParseError::propagate)
             .map(Arc::new)
     }
+
+    fn parse_from_xml_value(value: Option<Value>) -> ParseResult<Self> {
+        T::parse_from_xml_value(value)
+            .map_err(ParseError::propagate)
+            .map(Arc::new)
+    }
+
+    fn collect_json_errors(value: Option<&Value>, path: &str, errors: &mut Vec<ValidationError>) {
+        T::collect_json_errors(value, path, errors)
+    }
 }
 
 impl<T: ParseFromXML> ParseFromXML for Arc<T> {
@@ -313,12 +409,20 @@ impl<T: ToJSON> ToJSON for Arc<T> {
     fn to_json(&self) -> Option<Value> {
         self.as_ref().to_json()
     }
+
+    fn to_xml_value(&self) -> Option<Value> {
+        self.as_ref().to_xml_value()
+    }
 }
 
 impl<T: ToXML> ToXML for Arc<T> {
     fn to_xml(&self) -> Option<Value> {
         self.as_ref().to_xml()
     }
+
+    fn xml_root_name() -> Option<&'static str> {
+        T::xml_root_name()
+    }
 }
 
 impl<T: ToHeader> ToHeader for Arc<T> {
@@ -363,6 +467,16 @@ impl<T: ParseFromJSON> ParseFromJSON for Box<T> {
             .map_err(ParseError::propagate)
             .map(Box::new)
     }
+
+    fn parse_from_xml_value(value: Option<Value>) -> ParseResult<Self> {
+        T::parse_from_xml_value(value)
+            .map_err(ParseError::propagate)
+            .map(Box::new)
+    }
+
+    fn collect_json_errors(value: Option<&Value>, path: &str, errors: &mut Vec<ValidationError>) {
+        T::collect_json_errors(value, path, errors)
+    }
 }
 
 impl<T: ParseFromXML> ParseFromXML for Box<T> {
@@ -407,12 +521,20 @@ impl<T: ToJSON> ToJSON for Box<T> {
     fn to_json(&self) -> Option<Value> {
         self.as_ref().to_json()
     }
+
+    fn to_xml_value(&self) -> Option<Value> {
+        self.as_ref().to_xml_value()
+    }
 }
 
 impl<T: ToXML> ToXML for Box<T> {
     fn to_xml(&self) -> Option<Value> {
         self.as_ref().to_xml()
     }
+
+    fn xml_root_name() -> Option<&'static str> {
+        T::xml_root_name()
+    }
 }
 
 impl<T: ToYAML> ToYAML for Box<T> {
@@ -1,12 +1,15 @@
 use std::{
     borrow::Cow,
     fmt::{self, Debug, Formatter},
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicUsize, Ordering},
 };
 
 use poem::web::Field as PoemField;
+use sha2::{Digest, Sha256};
 use tokio::{
-    fs::File,
-    io::{AsyncRead, AsyncReadExt, AsyncSeek, Error as IoError, ErrorKind},
+    fs::{File, OpenOptions},
+    io::{AsyncRead, AsyncReadExt, AsyncSeek, AsyncWriteExt, Error as IoError, ErrorKind},
 };
 
 use crate::{
@@ -20,6 +23,8 @@ pub struct Upload {
     content_type: Option<String>,
     file: File,
     size: usize,
+    #[cfg(feature = "upload-digest")]
+    sha256: [u8; 32],
 }
 
 impl Debug for Upload {
@@ -54,6 +59,15 @@ impl Upload {
         self.size
     }
 
+    /// Returns the SHA-256 digest of the file, it is computed while the file
+    /// is received.
+    #[cfg(feature = "upload-digest")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "upload-digest")))]
+    #[inline]
+    pub fn sha256(&self) -> [u8; 32] {
+        self.sha256
+    }
+
     /// Consumes this body object to return a [`Vec<u8>`] that contains all
     /// data.
     pub async fn into_vec(self) -> Result<Vec<u8>, IoError> {
@@ -82,6 +96,76 @@ impl Upload {
     pub fn into_file(self) -> File {
         self.file
     }
+
+    /// Consumes this body object to write the data to the file at `path`, and
+    /// returns the path and the SHA-256 digest of the data.
+    ///
+    /// The data is streamed in chunks to a temporary file in the same
+    /// directory and hashed as it is written, the temporary file is renamed
+    /// to `path` when complete, so an existing file at `path` is left
+    /// untouched if an error occurs.
+    pub async fn write_to(
+        mut self,
+        path: impl AsRef<Path>,
+    ) -> Result<(PathBuf, [u8; 32]), IoError> {
+        static COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+        let path = path.as_ref();
+        let file_name = path
+            .file_name()
+            .ok_or_else(|| IoError::new(ErrorKind::InvalidInput, "invalid file path"))?;
+        let tmp_path = path.with_file_name(format!(
+            ".{}.{}-{}.tmp",
+            file_name.to_string_lossy(),
+            std::process::id(),
+            COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+
+        let mut tmp_file = OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&tmp_path)
+            .await?;
+        let res = async {
+            let digest = copy_with_digest(&mut self.file, &mut tmp_file).await?;
+            tmp_file.sync_all().await?;
+            drop(tmp_file);
+            tokio::fs::rename(&tmp_path, path).await?;
+            Ok((path.to_path_buf(), digest))
+        }
+        .await;
+        if res.is_err() {
+            let _ = tokio::fs::remove_file(&tmp_path).await;
+        }
+        res
+    }
+}
+
+/// Copies the data from `reader` to `writer` in chunks, and returns the
+/// SHA-256 digest of the data.
+async fn copy_with_digest(reader: impl AsyncRead, writer: &mut File) -> Result<[u8; 32], IoError> {
+    let mut reader = std::pin::pin!(reader);
+    let mut hasher = Sha256::new();
+    let mut buf = vec![0; 64 * 1024];
+    loop {
+        let n = reader.read(&mut buf).await?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buf[..n]);
+        writer.write_all(&buf[..n]).await?;
+    }
+    Ok(hasher.finalize().into())
+}
+
+#[cfg(feature = "upload-digest")]
+async fn spool_with_digest(field: PoemField) -> Result<(File, [u8; 32]), IoError> {
+    use tokio::io::AsyncSeekExt;
+
+    let mut file = File::from_std(tempfile::tempfile()?);
+    let digest = copy_with_digest(field.into_async_read(), &mut file).await?;
+    file.seek(std::io::SeekFrom::Start(0)).await?;
+    Ok((file, digest))
 }
 
 impl Type for Upload {
@@ -116,13 +200,18 @@ impl ParseFromMultipartField for Upload {
             Some(field) => {
                 let content_type = field.content_type().map(ToString::to_string);
                 let file_name = field.file_name().map(ToString::to_string);
+                #[cfg(not(feature = "upload-digest"))]
                 let file = field.tempfile().await.map_err(ParseError::custom)?;
+                #[cfg(feature = "upload-digest")]
+                let (file, sha256) = spool_with_digest(field).await.map_err(ParseError::custom)?;
                 let size = file.metadata().await.map_err(ParseError::custom)?.len() as usize;
                 Ok(Self {
                     content_type,
                     file_name,
                     file,
                     size,
+                    #[cfg(feature = "upload-digest")]
+                    sha256,
                 })
             }
             None => Err(ParseError::expected_input()),
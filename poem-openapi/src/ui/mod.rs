@@ -6,3 +6,157 @@ pub(crate) mod rapidoc;
 pub(crate) mod redoc;
 #[cfg(feature = "swagger-ui")]
 pub(crate) mod swagger_ui;
+
+/// A strict `Content-Security-Policy` for the UIs, the `{nonce}` placeholder is
+/// replaced with the nonce of each response.
+const STRICT_CSP: &str = "default-src 'none'; script-src 'nonce-{nonce}'; \
+                          style-src 'self' 'unsafe-inline'; img-src 'self' data:; \
+                          font-src 'self' data:; connect-src 'self'; worker-src blob:; \
+                          base-uri 'none'; form-action 'self'; frame-ancestors 'none'";
+
+/// The configuration of the OpenAPI UIs, see
+/// [`OpenApiService::ui_config`](crate::OpenApiService::ui_config).
+///
+/// All the JavaScript and CSS assets of the UIs are embedded in the binary,
+/// the UIs do not load anything from a CDN, so they work in air-gapped
+/// deployments.
+#[derive(Debug, Clone, Default)]
+pub struct UiConfig {
+    title: Option<String>,
+    logo: Option<String>,
+    csp: Option<String>,
+}
+
+impl UiConfig {
+    /// Create a `UiConfig` with the default settings.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the title of the UI pages.
+    #[must_use]
+    pub fn title(self, title: impl Into<String>) -> Self {
+        Self {
+            title: Some(title.into()),
+            ..self
+        }
+    }
+
+    /// Sets the URL of a logo displayed above the UI, it should be served by
+    /// the same origin or as a `data:` URL to be allowed by the strict
+    /// `Content-Security-Policy`.
+    #[must_use]
+    pub fn logo(self, url: impl Into<String>) -> Self {
+        Self {
+            logo: Some(url.into()),
+            ..self
+        }
+    }
+
+    /// Sends a `Content-Security-Policy` header with the UI pages.
+    ///
+    /// A new nonce is generated for each response and added to the inline
+    /// scripts of the page, the `{nonce}` placeholder in the policy is
+    /// replaced with it, e.g. `script-src 'nonce-{nonce}'`.
+    #[must_use]
+    pub fn content_security_policy(self, policy: impl Into<String>) -> Self {
+        Self {
+            csp: Some(policy.into()),
+            ..self
+        }
+    }
+
+    /// Sends a strict nonce-based `Content-Security-Policy` header with the UI
+    /// pages, which only allows the scripts of the page and the requests to
+    /// the same origin.
+    #[must_use]
+    pub fn strict_content_security_policy(self) -> Self {
+        self.content_security_policy(STRICT_CSP)
+    }
+}
+
+#[cfg(any(
+    feature = "openapi-explorer",
+    feature = "rapidoc",
+    feature = "redoc",
+    feature = "swagger-ui"
+))]
+mod html {
+    use base64::engine::{general_purpose::STANDARD, Engine};
+    use poem::{
+        endpoint::make_sync,
+        http::{header, HeaderValue},
+        web::Html,
+        Endpoint, IntoResponse, Response,
+    };
+
+    use super::UiConfig;
+
+    impl UiConfig {
+        fn apply(&self, template: &str, default_title: &str) -> String {
+            let title = self.title.as_deref().unwrap_or(default_title);
+            let logo = match &self.logo {
+                Some(url) => format!(
+                    r#"<img src="{}" alt="logo" style="display: block; height: 48px; margin: 8px 16px">"#,
+                    escape_html(url)
+                ),
+                None => String::new(),
+            };
+            template
+                .replace("{:title}", &escape_html(title))
+                .replace("{:logo}", &logo)
+        }
+
+        /// Fills the placeholders of the template, the `{:nonce}`
+        /// placeholders are removed.
+        pub(crate) fn create_html(
+            &self,
+            template: &str,
+            default_title: &str,
+            replace: &dyn Fn(&str) -> String,
+        ) -> String {
+            replace(&self.apply(template, default_title).replace("{:nonce}", ""))
+        }
+
+        /// Creates an endpoint serving the page, the `{:nonce}` placeholders
+        /// are replaced with a new nonce for each response if a
+        /// `Content-Security-Policy` is set.
+        pub(crate) fn create_endpoint(
+            &self,
+            template: &str,
+            default_title: &str,
+            replace: &dyn Fn(&str) -> String,
+        ) -> impl Endpoint<Output = Response> + 'static {
+            // the template is split before the assets are inserted, so the assets
+            // are never scanned for the placeholders
+            let parts = self
+                .apply(template, default_title)
+                .split("{:nonce}")
+                .map(replace)
+                .collect::<Vec<_>>();
+            let csp = self.csp.clone();
+
+            make_sync(move |_| match &csp {
+                Some(csp) => {
+                    let nonce = STANDARD.encode(rand::random::<[u8; 16]>());
+                    let mut resp =
+                        Html(parts.join(&format!(r#" nonce="{nonce}""#))).into_response();
+                    if let Ok(value) = HeaderValue::try_from(csp.replace("{nonce}", &nonce)) {
+                        resp.headers_mut()
+                            .insert(header::CONTENT_SECURITY_POLICY, value);
+                    }
+                    resp
+                }
+                None => Html(parts.concat()).into_response(),
+            })
+        }
+    }
+
+    fn escape_html(s: &str) -> String {
+        s.replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;")
+            .replace('"', "&quot;")
+            .replace('\'', "&#39;")
+    }
+}
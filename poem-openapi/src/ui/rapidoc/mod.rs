@@ -1,4 +1,6 @@
-use poem::{endpoint::make_sync, web::Html, Endpoint};
+use poem::Endpoint;
+
+use crate::ui::UiConfig;
 
 const RAPIDOC_JS: &str = include_str!("rapidoc-min.js");
 const OAUTH_RECEIVER_HTML: &str = include_str!("oauth-receiver.html");
@@ -8,13 +10,12 @@ const RAPIDOC_TEMPLATE: &str = r#"
 <head>
     <meta http-equiv="Content-Type" content="text/html;charset=utf-8">
     <meta name="viewport" content="width=device-width, minimum-scale=1, initial-scale=1, user-scalable=yes">
-    <link href="https://fonts.googleapis.com/css2?family=Open+Sans:wght@300;600&family=Roboto+Mono&display=swap" rel="stylesheet">
-    <title>RapiDoc</title>
-    <script charset="UTF-8">{:script}</script>
+    <title>{:title}</title>
+    <script{:nonce} charset="UTF-8">{:script}</script>
 </head>
 </html>
 <body>
-
+    {:logo}
     <rapi-doc
         id="thedoc"
         theme="light"
@@ -29,7 +30,7 @@ const RAPIDOC_TEMPLATE: &str = r#"
         schema-description-expanded = "true"	
     >
     </rapi-doc>
-    <script>
+    <script{:nonce}>
     document.addEventListener('DOMContentLoaded', (event) => {
         let docEl = document.getElementById("thedoc");
         docEl.loadSpec({:spec});
@@ -39,20 +40,28 @@ const RAPIDOC_TEMPLATE: &str = r#"
 </body>
 "#;
 
-pub(crate) fn create_html(document: &str) -> String {
-    RAPIDOC_TEMPLATE
-        .replace("{:script}", RAPIDOC_JS)
-        .replace("{:spec}", document)
+const TITLE: &str = "RapiDoc";
+
+fn replace(document: &str) -> impl Fn(&str) -> String + '_ {
+    move |template: &str| {
+        template
+            .replace("{:script}", RAPIDOC_JS)
+            .replace("{:spec}", document)
+    }
 }
 
-pub(crate) fn create_endpoint(document: &str) -> impl Endpoint {
-    let ui_html = create_html(document);
-    let oauth_receiver_html = OAUTH_RECEIVER_HTML.replace("{:script}", RAPIDOC_JS);
+pub(crate) fn create_html(document: &str, config: &UiConfig) -> String {
+    config.create_html(RAPIDOC_TEMPLATE, TITLE, &replace(document))
+}
 
+pub(crate) fn create_endpoint(document: &str, config: &UiConfig) -> impl Endpoint {
     poem::Route::new()
-        .at("/", make_sync(move |_| Html(ui_html.clone())))
+        .at(
+            "/",
+            config.create_endpoint(RAPIDOC_TEMPLATE, TITLE, &replace(document)),
+        )
         .at(
             "/oauth-receiver.html",
-            make_sync(move |_| Html(oauth_receiver_html.clone())),
+            config.create_endpoint(OAUTH_RECEIVER_HTML, TITLE, &replace(document)),
         )
 }
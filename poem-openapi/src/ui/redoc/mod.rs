@@ -1,4 +1,6 @@
-use poem::{endpoint::make_sync, web::Html, Endpoint};
+use poem::Endpoint;
+
+use crate::ui::UiConfig;
 
 const REDOC_JS: &str = include_str!("redoc.standalone.js");
 
@@ -6,11 +8,10 @@ const REDOC_TEMPLATE: &str = r#"
 <!DOCTYPE html>
 <html>
   <head>
-    <title>Redoc</title>
+    <title>{:title}</title>
     <!-- needed for adaptive design -->
     <meta charset="utf-8"/>
     <meta name="viewport" content="width=device-width, initial-scale=1">
-    <link href="https://fonts.googleapis.com/css?family=Montserrat:300,400,700|Roboto:300,400,700" rel="stylesheet">
 
     <!--
     Redoc doesn't change outer page styles
@@ -21,12 +22,13 @@ const REDOC_TEMPLATE: &str = r#"
         padding: 0;
       }
     </style>
-    <script charset="UTF-8">{:script}</script>
+    <script{:nonce} charset="UTF-8">{:script}</script>
   </head>
   <body>
+    {:logo}
     <div id="redoc-container"></div>
     
-    <script>
+    <script{:nonce}>
         let spec = {:spec};
         Redoc.init(spec, {
           scrollYOffset: 50
@@ -36,13 +38,23 @@ const REDOC_TEMPLATE: &str = r#"
 </html>
 "#;
 
-pub(crate) fn create_html(document: &str) -> String {
-    REDOC_TEMPLATE
-        .replace("{:script}", REDOC_JS)
-        .replace("{:spec}", document)
+const TITLE: &str = "Redoc";
+
+fn replace(document: &str) -> impl Fn(&str) -> String + '_ {
+    move |template: &str| {
+        template
+            .replace("{:script}", REDOC_JS)
+            .replace("{:spec}", document)
+    }
+}
+
+pub(crate) fn create_html(document: &str, config: &UiConfig) -> String {
+    config.create_html(REDOC_TEMPLATE, TITLE, &replace(document))
 }
 
-pub(crate) fn create_endpoint(document: &str) -> impl Endpoint {
-    let ui_html = create_html(document);
-    poem::Route::new().at("/", make_sync(move |_| Html(ui_html.clone())))
+pub(crate) fn create_endpoint(document: &str, config: &UiConfig) -> impl Endpoint {
+    poem::Route::new().at(
+        "/",
+        config.create_endpoint(REDOC_TEMPLATE, TITLE, &replace(document)),
+    )
 }
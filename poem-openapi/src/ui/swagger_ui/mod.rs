@@ -1,4 +1,6 @@
-use poem::{endpoint::make_sync, web::Html, Endpoint};
+use poem::Endpoint;
+
+use crate::ui::UiConfig;
 
 const SWAGGER_UI_JS: &str = include_str!("swagger-ui-bundle.js");
 const SWAGGER_UI_CSS: &str = include_str!("swagger-ui.css");
@@ -8,14 +10,15 @@ const SWAGGER_UI_TEMPLATE: &str = r#"
 <html charset="UTF-8">
 <head>
     <meta http-equiv="Content-Type" content="text/html;charset=utf-8">
-    <title>Swagger UI</title>
+    <title>{:title}</title>
     <style charset="UTF-8">{:style}</style>
-    <script charset="UTF-8">{:script}</script>
+    <script{:nonce} charset="UTF-8">{:script}</script>
 </head>
 <body>
 
+{:logo}
 <div id="ui"></div>
-<script>
+<script{:nonce}>
     let spec = {:spec};
     let oauth2RedirectUrl;
 
@@ -43,19 +46,29 @@ const SWAGGER_UI_TEMPLATE: &str = r#"
 </html>
 "#;
 
-pub(crate) fn create_html(document: &str) -> String {
-    SWAGGER_UI_TEMPLATE
-        .replace("{:style}", SWAGGER_UI_CSS)
-        .replace("{:script}", SWAGGER_UI_JS)
-        .replace("{:spec}", document)
+const TITLE: &str = "Swagger UI";
+
+fn replace(document: &str) -> impl Fn(&str) -> String + '_ {
+    move |template: &str| {
+        template
+            .replace("{:style}", SWAGGER_UI_CSS)
+            .replace("{:script}", SWAGGER_UI_JS)
+            .replace("{:spec}", document)
+    }
 }
 
-pub(crate) fn create_endpoint(document: &str) -> impl Endpoint {
-    let ui_html = create_html(document);
+pub(crate) fn create_html(document: &str, config: &UiConfig) -> String {
+    config.create_html(SWAGGER_UI_TEMPLATE, TITLE, &replace(document))
+}
+
+pub(crate) fn create_endpoint(document: &str, config: &UiConfig) -> impl Endpoint {
     poem::Route::new()
-        .at("/", make_sync(move |_| Html(ui_html.clone())))
+        .at(
+            "/",
+            config.create_endpoint(SWAGGER_UI_TEMPLATE, TITLE, &replace(document)),
+        )
         .at(
             "/oauth-receiver.html",
-            make_sync(move |_| Html(OAUTH_RECEIVER_HTML.to_string())),
+            config.create_endpoint(OAUTH_RECEIVER_HTML, TITLE, &str::to_string),
         )
 }
@@ -1,15 +1,21 @@
+use bytes::Bytes;
 use poem::{
     http::{Method, StatusCode},
     test::TestClient,
     web::Data,
-    Endpoint, EndpointExt, Error,
+    Body, Endpoint, EndpointExt, Error,
 };
 use poem_openapi::{
+    auth::Bearer,
     param::{Path, Query},
     payload::{Binary, Json, Payload, PlainText},
-    registry::{MetaApi, MetaExternalDocument, MetaOperation, MetaParamIn, MetaSchema, Registry},
+    registry::{
+        MetaApi, MetaExternalDocument, MetaOperation, MetaParamIn, MetaSchema, MetaSchemaRef,
+        Registry,
+    },
     types::Type,
-    ApiRequest, ApiResponse, Object, OpenApi, OpenApiService, Tags,
+    ApiRequest, ApiResponse, Mount, MountPoint, Object, OpenApi, OpenApiService, SecurityScheme,
+    Tags,
 };
 
 #[tokio::test]
@@ -220,6 +226,116 @@ async fn prefix_path() {
         .assert_status_is_ok();
 }
 
+#[tokio::test]
+async fn mount() {
+    #[derive(Tags)]
+    enum MyTags {
+        Users,
+    }
+
+    mod v1 {
+        #[derive(poem_openapi::Object)]
+        pub struct User {
+            pub name: String,
+        }
+    }
+
+    mod v2 {
+        #[derive(poem_openapi::Object)]
+        pub struct User {
+            pub first_name: String,
+        }
+
+        #[derive(poem_openapi::Object)]
+        pub struct Users {
+            pub items: Vec<User>,
+        }
+    }
+
+    struct ApiV1;
+
+    #[OpenApi(tag = "MyTags::Users")]
+    impl ApiV1 {
+        #[oai(path = "/users/:id", method = "get", operation_id = "getUser")]
+        async fn user(&self, id: Path<String>) -> Json<v1::User> {
+            Json(v1::User { name: id.0 })
+        }
+    }
+
+    struct ApiV2;
+
+    #[OpenApi(tag = "MyTags::Users")]
+    impl ApiV2 {
+        #[oai(path = "/users", method = "get", operation_id = "getUsers")]
+        async fn users(&self) -> Json<v2::Users> {
+            Json(v2::Users {
+                items: vec![v2::User {
+                    first_name: "sunli".to_string(),
+                }],
+            })
+        }
+    }
+
+    struct V1;
+
+    impl MountPoint for V1 {
+        const PATH: &'static str = "/v1";
+        const NAMESPACE: &'static str = "V1";
+    }
+
+    struct V2;
+
+    impl MountPoint for V2 {
+        const PATH: &'static str = "/v2";
+        const NAMESPACE: &'static str = "V2";
+    }
+
+    type Apis = (Mount<V1, ApiV1>, Mount<V2, ApiV2>);
+
+    let meta = Apis::meta();
+    assert_eq!(meta[0].paths[0].path, "/v1/users/{id}");
+    assert_eq!(meta[0].paths[0].operations[0].tags, vec!["V1Users"]);
+    assert_eq!(
+        meta[0].paths[0].operations[0].operation_id,
+        Some("V1getUser")
+    );
+    assert_eq!(meta[1].paths[0].path, "/v2/users");
+
+    let mut registry = Registry::new();
+    Apis::register(&mut registry);
+    assert_eq!(
+        registry.schemas.keys().collect::<Vec<_>>(),
+        vec!["V1User", "V2User", "V2Users"]
+    );
+    assert_eq!(
+        registry.schemas["V2Users"].properties[0]
+            .1
+            .unwrap_inline()
+            .items,
+        Some(Box::new(MetaSchemaRef::Reference("V2User".to_string())))
+    );
+    assert_eq!(
+        registry.tags.iter().map(|tag| tag.name).collect::<Vec<_>>(),
+        vec!["V1Users", "V2Users"]
+    );
+
+    let ep = OpenApiService::new(
+        (Mount::<V1, _>::new(ApiV1), Mount::<V2, _>::new(ApiV2)),
+        "test",
+        "1.0",
+    );
+    let cli = TestClient::new(ep);
+    let resp = cli.get("/v1/users/sunli").send().await;
+    resp.assert_status_is_ok();
+    resp.assert_json(&serde_json::json!({ "name": "sunli" }))
+        .await;
+    cli.get("/v2/users").send().await.assert_status_is_ok();
+    cli.get("/users")
+        .send()
+        .await
+        .assert_status(StatusCode::NOT_FOUND);
+}
+
 #[tokio::test]
 async fn request() {
     /// Test request
@@ -351,6 +467,163 @@ async fn payload_request() {
         .assert_status(StatusCode::UNSUPPORTED_MEDIA_TYPE);
 }
 
+#[tokio::test]
+async fn optional_payload_request() {
+    struct Api;
+
+    #[OpenApi]
+    impl Api {
+        #[oai(path = "/", method = "post")]
+        async fn test(&self, req: Option<Json<i32>>) -> PlainText<String> {
+            PlainText(format!("{:?}", req.map(|req| req.0)))
+        }
+
+        #[oai(path = "/empty", method = "post")]
+        async fn empty(&self) {}
+    }
+
+    let meta: MetaApi = Api::meta().remove(0);
+    let meta_request = meta.paths[0].operations[0].request.as_ref().unwrap();
+    assert!(!meta_request.required);
+    assert_eq!(
+        meta_request.content[0].content_type,
+        "application/json; charset=utf-8"
+    );
+    assert_eq!(meta_request.content[0].schema, i32::schema_ref());
+    let empty = meta
+        .paths
+        .iter()
+        .find(|path| path.path == "/empty")
+        .unwrap();
+    assert!(empty.operations[0].request.is_none());
+
+    let ep = OpenApiService::new(Api, "test", "1.0");
+    let cli = TestClient::new(ep);
+
+    let resp = cli
+        .post("/")
+        .content_type("application/json")
+        .body("100")
+        .send()
+        .await;
+    resp.assert_status_is_ok();
+    resp.assert_text("Some(100)").await;
+
+    let resp = cli.post("/").send().await;
+    resp.assert_status_is_ok();
+    resp.assert_text("None").await;
+
+    let resp = cli
+        .post("/")
+        .content_type("application/json")
+        .header("content-length", "0")
+        .send()
+        .await;
+    resp.assert_status_is_ok();
+    resp.assert_text("None").await;
+
+    cli.post("/")
+        .content_type("text/plain")
+        .body("100")
+        .send()
+        .await
+        .assert_status(StatusCode::UNSUPPORTED_MEDIA_TYPE);
+}
+
+#[tokio::test]
+async fn optional_payload_without_content_length() {
+    struct Api;
+
+    #[OpenApi]
+    impl Api {
+        #[oai(path = "/", method = "post")]
+        async fn test(&self, req: Option<Json<i32>>) -> PlainText<String> {
+            PlainText(format!("{:?}", req.map(|req| req.0)))
+        }
+    }
+
+    let cli = TestClient::new(OpenApiService::new(Api, "test", "1.0"));
+    let stream = |chunks: &'static [&'static str]| {
+        Body::from_bytes_stream(futures_util::stream::iter(
+            chunks
+                .iter()
+                .map(|chunk| Ok::<_, std::io::Error>(Bytes::from_static(chunk.as_bytes()))),
+        ))
+    };
+
+    let resp = cli
+        .post("/")
+        .content_type("application/json")
+        .body(stream(&["", "10", "0"]))
+        .send()
+        .await;
+    resp.assert_status_is_ok();
+    resp.assert_text("Some(100)").await;
+
+    let resp = cli
+        .post("/")
+        .content_type("application/json")
+        .body(stream(&["", ""]))
+        .send()
+        .await;
+    resp.assert_status_is_ok();
+    resp.assert_text("None").await;
+}
+
+#[tokio::test]
+async fn optional_extractors() {
+    #[derive(SecurityScheme)]
+    #[oai(ty = "bearer", checker = "check_token")]
+    struct MyAuth(Bearer);
+
+    async fn check_token(_req: &poem::Request, bearer: Bearer) -> Option<Bearer> {
+        (bearer.token == "abc").then_some(bearer)
+    }
+
+    struct Api;
+
+    #[OpenApi]
+    impl Api {
+        #[oai(path = "/query", method = "get")]
+        async fn query(&self, n: Option<Query<u32>>) -> PlainText<String> {
+            PlainText(format!("{:?}", n.map(|n| n.0)))
+        }
+
+        #[oai(path = "/auth", method = "get")]
+        async fn auth(&self, auth: Option<MyAuth>) -> PlainText<String> {
+            PlainText(format!("{:?}", auth.map(|auth| auth.0.token)))
+        }
+    }
+
+    let cli = TestClient::new(OpenApiService::new(Api, "test", "1.0"));
+
+    cli.get("/query")
+        .query("n", &1)
+        .send()
+        .await
+        .assert_text("Some(1)")
+        .await;
+    cli.get("/query").send().await.assert_text("None").await;
+    cli.get("/query")
+        .query("n", &"a")
+        .send()
+        .await
+        .assert_status(StatusCode::BAD_REQUEST);
+
+    cli.get("/auth")
+        .header("authorization", "Bearer abc")
+        .send()
+        .await
+        .assert_text("Some(\"abc\")")
+        .await;
+    cli.get("/auth").send().await.assert_text("None").await;
+    cli.get("/auth")
+        .header("authorization", "Bearer def")
+        .send()
+        .await
+        .assert_status(StatusCode::UNAUTHORIZED);
+}
+
 #[tokio::test]
 async fn response() {
     const ALREADY_EXISTS_CODE: u16 = 409;
@@ -1001,3 +1274,197 @@ async fn issue_489() {
         .await
         .assert_status(StatusCode::METHOD_NOT_ALLOWED);
 }
+
+#[cfg(feature = "compression")]
+#[tokio::test]
+async fn decompress_request_payload() {
+    use poem::{
+        web::{Compress, CompressionAlgo},
+        IntoResponse,
+    };
+    use poem_openapi::payload::RequestDecompression;
+
+    struct Api;
+
+    #[OpenApi]
+    impl Api {
+        #[oai(path = "/", method = "post")]
+        async fn test(&self, req: Json<Vec<i32>>) -> PlainText<String> {
+            PlainText(format!("{:?}", req.0))
+        }
+    }
+
+    let data = Compress::new("[1, 2, 3]", CompressionAlgo::GZIP)
+        .into_response()
+        .into_body()
+        .into_vec()
+        .await
+        .unwrap();
+
+    let cli = TestClient::new(OpenApiService::new(Api, "test", "1.0"));
+    let resp = cli
+        .post("/")
+        .content_type("application/json")
+        .header("content-encoding", "gzip")
+        .body(data.clone())
+        .send()
+        .await;
+    resp.assert_status_is_ok();
+    resp.assert_text("[1, 2, 3]").await;
+
+    #[cfg(feature = "zstd")]
+    {
+        let zstd_data = Compress::new("[1, 2, 3]", CompressionAlgo::ZSTD)
+            .into_response()
+            .into_body()
+            .into_vec()
+            .await
+            .unwrap();
+        let resp = cli
+            .post("/")
+            .content_type("application/json")
+            .header("content-encoding", "zstd")
+            .body(zstd_data)
+            .send()
+            .await;
+        resp.assert_status_is_ok();
+        resp.assert_text("[1, 2, 3]").await;
+
+        // gzip data labelled as zstd cannot be decompressed
+        let resp = cli
+            .post("/")
+            .content_type("application/json")
+            .header("content-encoding", "zstd")
+            .body(data.clone())
+            .send()
+            .await;
+        resp.assert_status(StatusCode::BAD_REQUEST);
+    }
+
+    let resp = cli
+        .post("/")
+        .content_type("application/json")
+        .header("content-encoding", "compress")
+        .body(data.clone())
+        .send()
+        .await;
+    resp.assert_status(StatusCode::UNSUPPORTED_MEDIA_TYPE);
+
+    let cli = TestClient::new(
+        OpenApiService::new(Api, "test", "1.0").data(RequestDecompression::new().max_size(4)),
+    );
+    let resp = cli
+        .post("/")
+        .content_type("application/json")
+        .header("content-encoding", "gzip")
+        .body(data)
+        .send()
+        .await;
+    resp.assert_status(StatusCode::PAYLOAD_TOO_LARGE);
+}
+
+#[tokio::test]
+async fn max_request_size() {
+    struct Api;
+
+    #[OpenApi]
+    impl Api {
+        #[oai(path = "/small", method = "post")]
+        async fn small(&self, data: Binary<Vec<u8>>) -> PlainText<String> {
+            PlainText(data.len().to_string())
+        }
+
+        #[oai(path = "/large", method = "post", max_request_size = "1KB")]
+        async fn large(&self, data: Binary<Vec<u8>>) -> PlainText<String> {
+            PlainText(data.len().to_string())
+        }
+    }
+
+    let cli = TestClient::new(OpenApiService::new(Api, "test", "1.0").max_request_size(10));
+
+    let resp = cli
+        .post("/small")
+        .content_type("application/octet-stream")
+        .body(vec![0; 10])
+        .send()
+        .await;
+    resp.assert_status_is_ok();
+    resp.assert_text("10").await;
+    cli.post("/small")
+        .content_type("application/octet-stream")
+        .body(vec![0; 11])
+        .send()
+        .await
+        .assert_status(StatusCode::PAYLOAD_TOO_LARGE);
+
+    // the limit of the operation overrides the default limit
+    let resp = cli
+        .post("/large")
+        .content_type("application/octet-stream")
+        .body(vec![0; 1000])
+        .send()
+        .await;
+    resp.assert_status_is_ok();
+    resp.assert_text("1000").await;
+    cli.post("/large")
+        .content_type("application/octet-stream")
+        .body(vec![0; 1001])
+        .send()
+        .await
+        .assert_status(StatusCode::PAYLOAD_TOO_LARGE);
+}
+
+#[cfg(feature = "swagger-ui")]
+#[tokio::test]
+async fn ui_config() {
+    use poem_openapi::UiConfig;
+
+    struct Api;
+
+    #[OpenApi]
+    impl Api {
+        #[oai(path = "/", method = "get")]
+        async fn test(&self) {}
+    }
+
+    let service = OpenApiService::new(Api, "test", "1.0").ui_config(
+        UiConfig::new()
+            .title("My <API>")
+            .logo("/logo.png")
+            .strict_content_security_policy(),
+    );
+    let html = service.swagger_ui_html();
+    assert!(html.contains("<title>My &lt;API&gt;</title>"));
+    assert!(html.contains(r#"<img src="/logo.png""#));
+    assert!(!html.contains("{:nonce}"));
+
+    let cli = TestClient::new(service.swagger_ui());
+    let resp = cli.get("/").send().await;
+    resp.assert_status_is_ok();
+    let csp = resp
+        .0
+        .headers()
+        .get("content-security-policy")
+        .unwrap()
+        .to_str()
+        .unwrap()
+        .to_string();
+    let nonce = csp
+        .split("'nonce-")
+        .nth(1)
+        .and_then(|s| s.split('\'').next())
+        .unwrap()
+        .to_string();
+    let html = resp.0.into_body().into_string().await.unwrap();
+    assert_eq!(
+        html.matches(&format!(r#"<script nonce="{nonce}""#)).count(),
+        2
+    );
+
+    // a new nonce for each response
+    let resp = cli.get("/").send().await;
+    assert_ne!(
+        resp.0.headers().get("content-security-policy").unwrap(),
+        csp.as_str()
+    );
+}
@@ -1,6 +1,6 @@
 use poem_openapi::{
     registry::{MetaExternalDocument, MetaSchemaRef, Registry},
-    types::{ParseFromJSON, ToJSON, Type},
+    types::{ParseFromJSON, ParseFromParameter, ToJSON, Type},
     Enum,
 };
 use serde_json::{json, Value};
@@ -158,6 +158,46 @@ fn remote() {
     assert_eq!(a, EnumA::C);
 }
 
+#[test]
+fn other() {
+    #[derive(Enum, Debug, Eq, PartialEq)]
+    #[oai(rename_all = "snake_case")]
+    enum MyEnum {
+        CreateUser,
+        DeleteUser,
+        #[oai(other)]
+        Unknown(String),
+    }
+
+    let mut registry = Registry::new();
+    MyEnum::register(&mut registry);
+    let meta = registry.schemas.remove("MyEnum").unwrap();
+    assert_eq!(
+        meta.enum_items,
+        vec![json!("create_user"), json!("delete_user")]
+    );
+
+    assert_eq!(
+        MyEnum::parse_from_json(Some(json!("create_user"))).unwrap(),
+        MyEnum::CreateUser
+    );
+    assert_eq!(
+        MyEnum::parse_from_json(Some(json!("archive_user"))).unwrap(),
+        MyEnum::Unknown("archive_user".to_string())
+    );
+    assert_eq!(
+        MyEnum::parse_from_parameter("archive_user").unwrap(),
+        MyEnum::Unknown("archive_user".to_string())
+    );
+    assert!(MyEnum::parse_from_json(Some(json!(1))).is_err());
+
+    assert_eq!(
+        MyEnum::Unknown("archive_user".to_string()).to_json(),
+        Some(json!("archive_user"))
+    );
+    assert_eq!(MyEnum::DeleteUser.to_json(), Some(json!("delete_user")));
+}
+
 #[test]
 fn description() {
     /// A
@@ -175,6 +215,22 @@ fn description() {
     assert_eq!(meta.description, Some("A\n\nAB\nCDE"));
 }
 
+#[test]
+fn title_and_description() {
+    /// A
+    #[derive(Enum)]
+    #[oai(title = "Status", description = "The status of a pet")]
+    enum MyEnum {
+        A,
+    }
+
+    let mut registry = Registry::new();
+    MyEnum::register(&mut registry);
+    let meta = registry.schemas.remove("MyEnum").unwrap();
+    assert_eq!(meta.title.as_deref(), Some("Status"));
+    assert_eq!(meta.description, Some("The status of a pet"));
+}
+
 #[test]
 fn deprecated() {
     #[derive(Enum)]
@@ -549,3 +549,50 @@ async fn deny_unknown_fields() {
     .unwrap_err();
     assert_eq!(err.to_string(), "parse multipart error: unknown field `c`");
 }
+
+#[tokio::test]
+async fn upload_write_to() {
+    #[derive(Multipart, Debug)]
+    struct A {
+        file: Upload,
+    }
+
+    let data = create_multipart_payload(&[("file", Some("1.txt"), b"hello")]);
+    let a = A::from_request(
+        &Request::builder()
+            .header("content-type", "multipart/form-data; boundary=X-BOUNDARY")
+            .finish(),
+        &mut RequestBody::new(data.into()),
+    )
+    .await
+    .unwrap();
+
+    let dir = std::env::temp_dir().join(format!("poem-openapi-upload-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("1.txt");
+    std::fs::write(&path, b"old content").unwrap();
+
+    #[cfg(feature = "upload-digest")]
+    assert_eq!(
+        a.file
+            .sha256()
+            .iter()
+            .map(|b| format!("{b:02x}"))
+            .collect::<String>(),
+        // sha256("hello")
+        "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824"
+    );
+    let (file_path, digest) = a.file.write_to(&path).await.unwrap();
+    assert_eq!(file_path, path);
+    assert_eq!(
+        digest
+            .iter()
+            .map(|b| format!("{b:02x}"))
+            .collect::<String>(),
+        // sha256("hello")
+        "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824"
+    );
+    assert_eq!(std::fs::read(&path).unwrap(), b"hello");
+    assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 1);
+    std::fs::remove_dir_all(&dir).unwrap();
+}
@@ -1,6 +1,8 @@
+use std::collections::BTreeMap;
+
 use poem_openapi::{
-    registry::{MetaExternalDocument, MetaSchema, MetaSchemaRef, Registry},
-    types::{Example, ParseFromJSON, ToJSON, Type},
+    registry::{MetaExternalDocument, MetaSchema, MetaSchemaRef, MetaXml, Registry},
+    types::{Example, ParseFromJSON, ParseFromXML, ToJSON, ToXML, Type},
     Enum, NewType, Object, OpenApi,
 };
 use serde_json::json;
@@ -176,6 +178,49 @@ fn field_skip() {
     );
 }
 
+#[test]
+fn field_skip_with_default() {
+    #[derive(Debug, Eq, PartialEq)]
+    struct Internal(i32);
+
+    fn default_internal() -> Internal {
+        Internal(100)
+    }
+
+    #[derive(Object, Debug, Eq, PartialEq)]
+    struct Obj {
+        a: i32,
+        #[oai(skip, default = "default_internal")]
+        b: Internal,
+    }
+
+    let meta = get_meta::<Obj>();
+    assert_eq!(meta.properties.len(), 1);
+
+    assert_eq!(
+        Obj::parse_from_json(Some(json!({
+            "a": 10,
+            "b": 20,
+        })))
+        .unwrap(),
+        Obj {
+            a: 10,
+            b: Internal(100)
+        }
+    );
+
+    assert_eq!(
+        Obj {
+            a: 10,
+            b: Internal(1)
+        }
+        .to_json(),
+        Some(json!({
+            "a": 10,
+        }))
+    );
+}
+
 #[test]
 fn field_rename() {
     #[derive(Object)]
@@ -232,6 +277,141 @@ fn description() {
     assert_eq!(meta.description, Some("A\n\nAB\nCDE"));
 }
 
+#[test]
+fn title_and_description() {
+    /// A
+    #[derive(Object)]
+    #[oai(title = "UserDto", description = "A user of the system")]
+    struct Obj {
+        a: i32,
+    }
+
+    let meta = get_meta::<Obj>();
+    assert_eq!(meta.title.as_deref(), Some("UserDto"));
+    assert_eq!(meta.description, Some("A user of the system"));
+
+    /// A
+    #[derive(Object)]
+    #[oai(title = "UserDto")]
+    struct ObjTitle {
+        a: i32,
+    }
+
+    let meta = get_meta::<ObjTitle>();
+    assert_eq!(meta.title.as_deref(), Some("UserDto"));
+    assert_eq!(meta.description, Some("A"));
+}
+
+#[test]
+fn xml() {
+    #[derive(Debug, Object, PartialEq)]
+    #[oai(xml(name = "pet", namespace = "https://example.com/schema", prefix = "ex"))]
+    struct Pet {
+        #[oai(xml(attribute))]
+        id: i64,
+        #[oai(xml(name = "pet-name"))]
+        name: String,
+        #[oai(xml(wrapped))]
+        tags: Vec<String>,
+    }
+
+    let meta = get_meta::<Pet>();
+    assert_eq!(
+        meta.xml,
+        Some(MetaXml {
+            name: Some("pet"),
+            namespace: Some("https://example.com/schema"),
+            prefix: Some("ex"),
+            attribute: false,
+            wrapped: false,
+        })
+    );
+    assert_eq!(
+        meta.properties[0].1.unwrap_inline().xml,
+        Some(MetaXml {
+            name: None,
+            namespace: None,
+            prefix: None,
+            attribute: true,
+            wrapped: false,
+        })
+    );
+    assert_eq!(
+        meta.properties[1]
+            .1
+            .unwrap_inline()
+            .xml
+            .as_ref()
+            .and_then(|xml| xml.name),
+        Some("pet-name")
+    );
+
+    let pet = Pet {
+        id: 1,
+        name: "Tom".to_string(),
+        tags: vec!["a".to_string(), "b".to_string()],
+    };
+    let value = json!({
+        "@id": 1,
+        "@xmlns:ex": "https://example.com/schema",
+        "pet-name": "Tom",
+        "tags": { "tags": ["a", "b"] },
+    });
+    assert_eq!(pet.to_xml(), Some(value.clone()));
+    assert_eq!(Pet::parse_from_xml(Some(value)).unwrap(), pet);
+    assert_eq!(Pet::xml_root_name(), Some("ex:pet"));
+    assert!(pet.to_xml_string().starts_with("<ex:pet "));
+
+    // the mapping does not apply to JSON
+    assert_eq!(
+        pet.to_json(),
+        Some(json!({ "id": 1, "name": "Tom", "tags": ["a", "b"] }))
+    );
+}
+
+#[test]
+fn xml_nested() {
+    #[derive(Debug, Object, PartialEq)]
+    #[oai(xml(name = "tag"))]
+    struct Tag {
+        #[oai(xml(attribute))]
+        id: i64,
+        #[oai(xml(name = "tag-name"))]
+        name: String,
+    }
+
+    #[derive(Debug, Object, PartialEq)]
+    #[oai(xml(name = "pet"))]
+    struct Pet {
+        owner: Option<Box<Tag>>,
+        tags: Vec<Tag>,
+    }
+
+    let pet = Pet {
+        owner: Some(Box::new(Tag {
+            id: 1,
+            name: "a".to_string(),
+        })),
+        tags: vec![Tag {
+            id: 2,
+            name: "b".to_string(),
+        }],
+    };
+    let value = json!({
+        "owner": { "@id": 1, "tag-name": "a" },
+        "tags": [{ "@id": 2, "tag-name": "b" }],
+    });
+    assert_eq!(pet.to_xml(), Some(value.clone()));
+    assert_eq!(Pet::parse_from_xml(Some(value)).unwrap(), pet);
+    assert_eq!(
+        pet.to_json(),
+        Some(json!({
+            "owner": { "id": 1, "name": "a" },
+            "tags": [{ "id": 2, "name": "b" }],
+        }))
+    );
+}
+
 #[test]
 fn field_description() {
     #[derive(Object)]
@@ -278,6 +458,7 @@ fn field_default() {
 
     let field_meta = meta.properties[2].1.unwrap_inline();
     assert_eq!(field_meta.default, Some(json!(200)));
+    assert!(meta.required.is_empty());
 
     assert_eq!(
         Obj::parse_from_json(Some(json!({
@@ -656,6 +837,117 @@ fn flatten_field() {
     );
 }
 
+#[test]
+fn flatten_map() {
+    #[derive(Object, Debug, Eq, PartialEq)]
+    struct Obj1 {
+        a: i32,
+    }
+
+    #[derive(Object, Debug, Eq, PartialEq)]
+    struct Obj {
+        #[oai(flatten)]
+        obj1: Obj1,
+        b: i32,
+        #[oai(flatten)]
+        extra: BTreeMap<String, serde_json::Value>,
+    }
+
+    let meta = get_meta::<Obj>();
+    assert_eq!(meta.required, vec!["a", "b"]);
+    assert_eq!(meta.properties.len(), 2);
+    assert!(meta.additional_properties.is_some());
+
+    let obj = Obj {
+        obj1: Obj1 { a: 100 },
+        b: 200,
+        extra: BTreeMap::from([
+            ("c".to_string(), json!(300)),
+            ("d".to_string(), json!({"e": true})),
+        ]),
+    };
+
+    let value = json!({"a": 100, "b": 200, "c": 300, "d": {"e": true}});
+    assert_eq!(Obj::parse_from_json(Some(value.clone())).unwrap(), obj);
+    assert_eq!(obj.to_json(), Some(value));
+}
+
+#[test]
+fn flatten_map_validator() {
+    #[derive(Object, Debug, Eq, PartialEq)]
+    struct Obj {
+        a: i32,
+        b: Option<i32>,
+        #[oai(
+            flatten,
+            validator(min_properties = 1, max_properties = 2, maximum(value = "10"))
+        )]
+        extra: BTreeMap<String, i32>,
+    }
+
+    let meta = get_meta::<Obj>();
+    assert_eq!(meta.min_properties, Some(2));
+    assert_eq!(meta.max_properties, Some(4));
+    assert_eq!(
+        meta.additional_properties
+            .as_deref()
+            .unwrap()
+            .unwrap_inline()
+            .maximum,
+        Some(10.0)
+    );
+
+    assert_eq!(
+        Obj::parse_from_json(Some(json!({"a": 1, "c": 2}))).unwrap(),
+        Obj {
+            a: 1,
+            b: None,
+            extra: BTreeMap::from([("c".to_string(), 2)]),
+        }
+    );
+    assert_eq!(
+        Obj::parse_from_json(Some(json!({"a": 1})))
+            .unwrap_err()
+            .into_message(),
+        "failed to parse \"Obj\": field `extra` verification failed. minProperties(1)"
+    );
+    assert_eq!(
+        Obj::parse_from_json(Some(json!({"a": 1, "c": 2, "d": 3, "e": 4})))
+            .unwrap_err()
+            .into_message(),
+        "failed to parse \"Obj\": field `extra` verification failed. maxProperties(2)"
+    );
+    assert!(Obj::parse_from_json(Some(json!({"a": 1, "c": 11}))).is_err());
+}
+
+#[test]
+fn flatten_map_write_only() {
+    #[derive(Object, Debug, Eq, PartialEq)]
+    struct Credentials {
+        #[oai(write_only)]
+        password: String,
+    }
+
+    #[derive(Object, Debug, Eq, PartialEq)]
+    struct Obj {
+        #[oai(flatten)]
+        credentials: Credentials,
+        #[oai(flatten)]
+        extra: BTreeMap<String, serde_json::Value>,
+    }
+
+    let value = json!({"password": "abc", "c": 300});
+    assert_eq!(
+        Obj::parse_from_json(Some(value)).unwrap(),
+        Obj {
+            credentials: Credentials {
+                password: "abc".to_string(),
+            },
+            extra: BTreeMap::from([("c".to_string(), json!(300))]),
+        }
+    );
+}
+
 #[test]
 fn remote() {
     mod remote_types {
@@ -863,6 +1155,7 @@ fn object_default() {
 
     let field_meta = meta.properties[1].1.unwrap_inline();
     assert_eq!(field_meta.default, Some(json!("abc")));
+    assert!(meta.required.is_empty());
 
     assert_eq!(
         Obj::parse_from_json(Some(json!({}))).unwrap(),
@@ -919,6 +1212,7 @@ fn object_default_by_function() {
 
     let field_meta = meta.properties[1].1.unwrap_inline();
     assert_eq!(field_meta.default, Some(json!("abc")));
+    assert!(meta.required.is_empty());
 
     assert_eq!(
         Obj::parse_from_json(Some(json!({}))).unwrap(),
@@ -1077,3 +1371,153 @@ fn deserialize_with() {
         Obj { a: 7 }
     );
 }
+
+#[test]
+fn const_field() {
+    #[derive(Debug, PartialEq, Object)]
+    struct Obj {
+        #[oai(rename = "type", const_value = "user")]
+        ty: String,
+        name: String,
+    }
+
+    let meta = get_meta::<Obj>();
+    assert_eq!(meta.required, vec!["type", "name"]);
+    assert_eq!(
+        meta.properties[0].1.unwrap_inline().enum_items,
+        vec![json!("user")]
+    );
+
+    assert_eq!(
+        Obj {
+            ty: String::new(),
+            name: "sunli".to_string(),
+        }
+        .to_json(),
+        Some(json!({"type": "user", "name": "sunli"}))
+    );
+    assert_eq!(
+        Obj::parse_from_json(Some(json!({"type": "user", "name": "sunli"}))).unwrap(),
+        Obj {
+            ty: "user".to_string(),
+            name: "sunli".to_string(),
+        }
+    );
+    assert_eq!(
+        Obj::parse_from_json(Some(json!({"type": "admin", "name": "sunli"})))
+            .unwrap_err()
+            .into_message(),
+        "failed to parse \"Obj\": property `type` must be `user`."
+    );
+    assert!(Obj::parse_from_json(Some(json!({"name": "sunli"}))).is_err());
+}
+
+#[tokio::test]
+async fn validate_json() {
+    use poem::{http::StatusCode, test::TestClient, Route};
+    use poem_openapi::{
+        error::ValidateJsonError,
+        types::{JsonValidators, ValidationError},
+    };
+
+    #[derive(Object)]
+    struct Tag {
+        #[oai(validator(min_length = 1))]
+        name: String,
+    }
+
+    #[derive(Object)]
+    struct Pet {
+        #[oai(validator(max_length = 8))]
+        name: String,
+        age: Option<u8>,
+        #[oai(default)]
+        tags: Vec<Tag>,
+    }
+
+    assert!(Pet::validate_json(&json!({ "name": "Tom", "age": 3 })).is_ok());
+    assert!(Pet::validate_json(&json!({ "name": "Mr. Whiskers" })).is_err());
+    assert!(Pet::validate_json(&json!({ "age": 3 })).is_err());
+
+    let errors = Pet::validate_json(&json!({
+        "name": "Mr. Whiskers",
+        "age": -1,
+        "tags": [{ "name": "a" }, { "name": "" }, 1],
+    }))
+    .unwrap_err();
+    assert_eq!(
+        errors
+            .iter()
+            .map(|err| err.path.as_str())
+            .collect::<Vec<_>>(),
+        ["/name", "/age", "/tags/1/name", "/tags/2"]
+    );
+    assert_eq!(
+        errors[0],
+        ValidationError {
+            path: "/name".to_string(),
+            message: "failed to parse \"Pet\": field `name` verification failed. maxLength(8)"
+                .to_string(),
+        }
+    );
+    assert_eq!(Pet::validate_json(&json!([])).unwrap_err()[0].path, "");
+
+    #[derive(Object)]
+    #[oai(deny_unknown_fields)]
+    struct Strict {
+        a: i32,
+    }
+
+    #[derive(Object)]
+    struct Flatten {
+        a: i32,
+        #[oai(flatten)]
+        extra: std::collections::HashMap<String, i32>,
+    }
+
+    let errors = Strict::validate_json(&json!({ "a": 1, "b/c": 2 })).unwrap_err();
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].path, "/b~1c");
+    assert!(Flatten::validate_json(&json!({ "a": 1, "b": 2 })).is_ok());
+    let errors = Flatten::validate_json(&json!({ "a": 1, "b": "x" })).unwrap_err();
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].path, "");
+
+    let validators = JsonValidators::new()
+        .register::<Pet>()
+        .register_as::<i32>("number");
+    assert!(validators.validate("number", &json!(1)).is_ok());
+    assert!(matches!(
+        validators.validate("Pet", &json!({ "name": "Tom", "age": -1 })),
+        Err(ValidateJsonError::Invalid { type_name, .. }) if type_name == "Pet"
+    ));
+    assert!(matches!(
+        validators.validate("Dog", &json!({})),
+        Err(ValidateJsonError::UnknownType { .. })
+    ));
+
+    let cli = TestClient::new(Route::new().nest("/validate", validators));
+    cli.post("/validate/Pet")
+        .body_json(&json!({ "name": "Tom" }))
+        .send()
+        .await
+        .assert_status(StatusCode::NO_CONTENT);
+
+    let resp = cli
+        .post("/validate/Pet")
+        .body_json(&json!({ "name": "Mr. Whiskers" }))
+        .send()
+        .await;
+    resp.assert_status(StatusCode::BAD_REQUEST);
+    let value = resp.json().await;
+    value.value().object().get("type").assert_string("Pet");
+    let errors = value.value().object().get("errors").array();
+    errors.assert_len(1);
+    errors.get(0).object().get("path").assert_string("/name");
+
+    cli.post("/validate/Dog")
+        .body_json(&json!({}))
+        .send()
+        .await
+        .assert_status(StatusCode::NOT_FOUND);
+}
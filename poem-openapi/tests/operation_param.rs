@@ -1,13 +1,15 @@
+use std::collections::BTreeMap;
+
 use poem::{
-    http::header,
+    http::{header, StatusCode},
     test::TestClient,
     web::cookie::{Cookie, CookieJar, CookieKey},
 };
 use poem_openapi::{
-    param::{Cookie as ParamCookie, CookiePrivate, CookieSigned, Header, Path, Query},
-    registry::{MetaApi, MetaParamIn, MetaSchema, MetaSchemaRef},
+    param::{Cookie as ParamCookie, CookiePrivate, CookieSigned, Header, Pagination, Path, Query},
+    registry::{MetaApi, MetaParamIn, MetaParamStyle, MetaSchema, MetaSchemaRef},
     types::Type,
-    OpenApi, OpenApiService,
+    Object, OpenApi, OpenApiService, PaginatedResponse,
 };
 use serde_json::json;
 
@@ -124,6 +126,61 @@ async fn query_multiple_values_no_explode() {
         .assert_status_is_ok();
 }
 
+#[tokio::test]
+async fn query_style() {
+    struct Api;
+
+    #[OpenApi]
+    impl Api {
+        #[oai(path = "/", method = "get")]
+        async fn test(
+            &self,
+            #[oai(style = "spaceDelimited")] a: Query<Vec<i32>>,
+            #[oai(style = "pipeDelimited")] b: Query<Vec<i32>>,
+            #[oai(style = "deepObject")] filter: Query<BTreeMap<String, String>>,
+            #[oai(style = "deepObject")] c: Query<Option<BTreeMap<String, i32>>>,
+        ) {
+            assert_eq!(a.0, vec![1, 2]);
+            assert_eq!(b.0, vec![3, 4]);
+            assert_eq!(
+                filter.0,
+                BTreeMap::from([
+                    ("name".to_string(), "poem".to_string()),
+                    ("lang".to_string(), "rust".to_string()),
+                ])
+            );
+            assert!(c.0.is_none());
+        }
+    }
+
+    let meta: MetaApi = Api::meta().remove(0);
+    let params = &meta.paths[0].operations[0].params;
+    assert_eq!(params[0].style, Some(MetaParamStyle::SpaceDelimited));
+    assert!(!params[0].explode);
+    assert_eq!(params[1].style, Some(MetaParamStyle::PipeDelimited));
+    assert_eq!(params[2].style, Some(MetaParamStyle::DeepObject));
+    assert_eq!(params[2].schema.unwrap_inline().ty, "object");
+
+    let api = OpenApiService::new(Api, "test", "1.0");
+    let cli = TestClient::new(api);
+    cli.get("/")
+        .query("a", &"1 2")
+        .query("b", &"3|4")
+        .query("filter[name]", &"poem")
+        .query("filter[lang]", &"rust")
+        .send()
+        .await
+        .assert_status_is_ok();
+    cli.get("/")
+        .query("a", &"1 2")
+        .query("b", &"3|4")
+        .query("filter[name]", &"poem")
+        .query("c[x]", &"abc")
+        .send()
+        .await
+        .assert_status(StatusCode::BAD_REQUEST);
+}
+
 #[tokio::test]
 async fn query_default() {
     struct Api;
@@ -606,3 +663,76 @@ async fn cookie_rename() {
         .await
         .assert_status_is_ok();
 }
+
+#[tokio::test]
+async fn pagination() {
+    struct Api;
+
+    #[OpenApi]
+    impl Api {
+        #[oai(path = "/abc", method = "get")]
+        async fn query(&self, page: Pagination) -> PaginatedResponse<i32> {
+            PaginatedResponse::new(&page, vec![1, 2]).next_cursor("next")
+        }
+    }
+
+    let meta: MetaApi = Api::meta().remove(0);
+    let operation = &meta.paths[0].operations[0];
+    assert_eq!(operation.params[0].in_type, MetaParamIn::Query);
+    assert!(operation.params[0].explode);
+    assert_eq!(
+        operation.params[0]
+            .schema
+            .unwrap_inline()
+            .properties
+            .iter()
+            .map(|(name, _)| *name)
+            .collect::<Vec<_>>(),
+        vec!["limit", "per_page", "cursor", "page"]
+    );
+    let response = &operation.responses.responses[0];
+    assert_eq!(response.status, Some(200));
+    assert_eq!(response.headers[0].name, "link");
+
+    let ep = OpenApiService::new(Api, "test", "1.0");
+    let resp = TestClient::new(ep)
+        .get("/abc")
+        .query("limit", &2)
+        .send()
+        .await;
+    resp.assert_status_is_ok();
+    resp.assert_header("link", r#"</abc?limit=2&cursor=next>; rel="next""#);
+    resp.assert_json(json!({ "items": [1, 2], "next_cursor": "next" }))
+        .await;
+}
+
+#[tokio::test]
+async fn pagination_object() {
+    #[derive(Object)]
+    struct User {
+        name: String,
+    }
+
+    struct Api;
+
+    #[OpenApi]
+    impl Api {
+        #[oai(path = "/users", method = "get")]
+        async fn users(&self, page: Pagination) -> PaginatedResponse<User> {
+            let users = vec![User {
+                name: "alice".to_string(),
+            }];
+            PaginatedResponse::new(&page, users).total(1)
+        }
+    }
+
+    let ep = OpenApiService::new(Api, "test", "1.0");
+    let resp = TestClient::new(ep)
+        .get("/users")
+        .query("page", &1)
+        .send()
+        .await;
+    resp.assert_status_is_ok();
+    resp.assert_json(json!({ "items": [{ "name": "alice" }], "total": 1 }))
+        .await;
+}
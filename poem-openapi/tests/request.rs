@@ -1,5 +1,5 @@
 use poem_openapi::{
-    payload::{Json, PlainText},
+    payload::{Binary, Json, PlainText},
     registry::{MetaMediaType, MetaRequest, MetaSchema, MetaSchemaRef},
     types::ParseFromJSON,
     ApiExtractor, ApiRequest, Object,
@@ -160,3 +160,51 @@ async fn item_content_type() {
         Req::Create(Json(100))
     );
 }
+
+#[tokio::test]
+async fn item_multiple_content_types() {
+    #[derive(Debug, ApiRequest, Eq, PartialEq)]
+    enum Req {
+        #[oai(content_type = "application/json, application/vnd.api+json")]
+        Create(Json<i32>),
+        #[oai(content_type = "*/*")]
+        Other(Binary<Vec<u8>>),
+    }
+
+    let meta = Req::request_meta().unwrap();
+    assert_eq!(
+        meta.content
+            .iter()
+            .map(|item| item.content_type)
+            .collect::<Vec<_>>(),
+        vec!["application/json", "application/vnd.api+json", "*/*"]
+    );
+    assert_eq!(meta.content[0].schema, meta.content[1].schema);
+
+    for content_type in [
+        "application/json",
+        "application/vnd.api+json; charset=utf-8",
+    ] {
+        let request = poem::Request::builder()
+            .content_type(content_type)
+            .body("100".to_string());
+        let (request, mut body) = request.split();
+        assert_eq!(
+            Req::from_request(&request, &mut body, Default::default())
+                .await
+                .unwrap(),
+            Req::Create(Json(100))
+        );
+    }
+
+    let request = poem::Request::builder()
+        .content_type("image/png")
+        .body(vec![1, 2, 3]);
+    let (request, mut body) = request.split();
+    assert_eq!(
+        Req::from_request(&request, &mut body, Default::default())
+            .await
+            .unwrap(),
+        Req::Other(Binary(vec![1, 2, 3]))
+    );
+}
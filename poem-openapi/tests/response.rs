@@ -8,12 +8,12 @@ use poem::{
     Error, IntoResponse,
 };
 use poem_openapi::{
-    payload::{Binary, Json, Payload, PlainText, Yaml},
+    payload::{Binary, BinaryStream, Json, Payload, PlainText, Yaml},
     registry::{
         MetaApi, MetaMediaType, MetaResponse, MetaResponses, MetaSchema, MetaSchemaRef, Registry,
     },
     types::{ToJSON, Type},
-    ApiResponse, Object, OpenApi, OpenApiService,
+    ApiResponse, NoContent, Object, OpenApi, OpenApiService,
 };
 use serde::Deserialize;
 use serde_json::Value;
@@ -345,6 +345,47 @@ async fn item_content_type() {
     );
 }
 
+#[tokio::test]
+async fn same_status_content_types() {
+    #[derive(ApiResponse)]
+    pub enum Resp {
+        /// Ok
+        #[oai(status = 200)]
+        Json(Json<i32>),
+        #[oai(status = 200)]
+        Text(PlainText<String>),
+    }
+
+    struct Api;
+
+    #[OpenApi]
+    impl Api {
+        #[oai(path = "/", method = "get")]
+        async fn get(&self) -> Resp {
+            Resp::Json(Json(1))
+        }
+    }
+
+    let spec: Value =
+        serde_json::from_str(&OpenApiService::new(Api, "test", "1.0").spec()).unwrap();
+    assert_eq!(
+        spec["paths"]["/"]["get"]["responses"],
+        serde_json::json!({
+            "200": {
+                "description": "Ok",
+                "content": {
+                    "application/json; charset=utf-8": {
+                        "schema": { "type": "integer", "format": "int32" }
+                    },
+                    "text/plain; charset=utf-8": {
+                        "schema": { "type": "string" }
+                    }
+                }
+            }
+        })
+    );
+}
+
 #[tokio::test]
 async fn header_deprecated() {
     #[derive(ApiResponse, Debug, Eq, PartialEq)]
@@ -546,3 +587,65 @@ async fn actual_type() {
     let type_name: Vec<&String> = registry.schemas.keys().collect();
     assert_eq!(&type_name, &["MyObj"]);
 }
+
+#[tokio::test]
+async fn binary_stream() {
+    type Rows = futures_util::stream::Iter<std::vec::IntoIter<Result<String, std::io::Error>>>;
+
+    #[derive(ApiResponse)]
+    enum MyResponse {
+        #[oai(status = 200, content_type = "text/csv")]
+        Ok(BinaryStream<Rows>),
+    }
+
+    struct Api;
+
+    #[OpenApi]
+    impl Api {
+        #[oai(path = "/", method = "get")]
+        async fn test(&self) -> MyResponse {
+            MyResponse::Ok(BinaryStream(futures_util::stream::iter(vec![
+                Ok("a,b\n".to_string()),
+                Ok("1,2\n".to_string()),
+            ])))
+        }
+    }
+
+    let meta: MetaApi = Api::meta().remove(0);
+    let media = &meta.paths[0].operations[0].responses.responses[0].content[0];
+    assert_eq!(media.content_type, "text/csv");
+    assert_eq!(media.schema, <BinaryStream<Rows>>::schema_ref());
+
+    let ep = OpenApiService::new(Api, "test", "1.0");
+    let cli = TestClient::new(ep);
+    let resp = cli.get("/").send().await;
+    resp.assert_status_is_ok();
+    resp.assert_content_type("text/csv");
+    resp.assert_text("a,b\n1,2\n").await;
+}
+
+#[tokio::test]
+async fn no_content() {
+    struct Api;
+
+    #[OpenApi]
+    impl Api {
+        #[oai(path = "/", method = "delete")]
+        async fn delete(&self) -> poem::Result<NoContent> {
+            Ok(NoContent)
+        }
+    }
+
+    let meta: MetaApi = Api::meta().remove(0);
+    let responses = &meta.paths[0].operations[0].responses.responses;
+    assert_eq!(responses.len(), 1);
+    assert_eq!(responses[0].status, Some(204));
+    assert!(responses[0].content.is_empty());
+
+    let ep = OpenApiService::new(Api, "test", "1.0");
+    let cli = TestClient::new(ep);
+    let resp = cli.delete("/").send().await;
+    resp.assert_status(StatusCode::NO_CONTENT);
+    resp.assert_header_is_not_exist("content-type");
+    resp.assert_text("").await;
+}
@@ -2,11 +2,11 @@ use poem::{
     error::ResponseError,
     http::{header, StatusCode},
     test::TestClient,
-    web::{cookie::Cookie, headers},
+    web::{cookie::Cookie, headers, TlsInfo},
     Request,
 };
 use poem_openapi::{
-    auth::{ApiKey, Basic, Bearer},
+    auth::{ApiKey, Basic, Bearer, TlsConnection},
     payload::PlainText,
     registry::{MetaOAuthFlow, MetaOAuthFlows, MetaOAuthScope, MetaSecurityScheme, Registry},
     ApiExtractor, OAuthScopes, OpenApi, OpenApiService, SecurityScheme,
@@ -439,6 +439,90 @@ async fn oauth2_auth() {
     );
 }
 
+#[tokio::test]
+async fn openid_connect_auth() {
+    #[derive(SecurityScheme)]
+    #[oai(
+        ty = "openid_connect",
+        openid_connect_url = "https://example.com/.well-known/openid-configuration"
+    )]
+    struct MySecurityScheme(Bearer);
+
+    let mut registry = Registry::new();
+    MySecurityScheme::register(&mut registry);
+    assert_eq!(
+        registry.security_schemes.get("MySecurityScheme").unwrap(),
+        &MetaSecurityScheme {
+            ty: "openIdConnect",
+            description: None,
+            name: None,
+            key_in: None,
+            scheme: None,
+            bearer_format: None,
+            flows: None,
+            openid_connect_url: Some("https://example.com/.well-known/openid-configuration")
+        }
+    );
+
+    struct MyApi;
+
+    #[OpenApi]
+    impl MyApi {
+        #[oai(path = "/test", method = "get")]
+        async fn test(&self, auth: MySecurityScheme) -> PlainText<String> {
+            PlainText(auth.0.token)
+        }
+    }
+
+    let service = OpenApiService::new(MyApi, "test", "1.0");
+    let resp = TestClient::new(service)
+        .get("/test")
+        .typed_header(Authorization::bearer("abcdef").unwrap())
+        .send()
+        .await;
+    resp.assert_status_is_ok();
+    resp.assert_text("abcdef").await;
+}
+
+#[tokio::test]
+async fn tls_connection() {
+    struct MyApi;
+
+    #[OpenApi]
+    impl MyApi {
+        #[oai(path = "/test", method = "get")]
+        async fn test(&self, tls: TlsConnection) -> PlainText<String> {
+            PlainText(String::from_utf8_lossy(tls.certificate()).into_owned())
+        }
+    }
+
+    let cli = TestClient::new(OpenApiService::new(MyApi, "test", "1.0"));
+
+    // the requests received over plain HTTP are rejected
+    cli.get("/test")
+        .send()
+        .await
+        .assert_status(StatusCode::UNAUTHORIZED);
+
+    // the TLS connections without a client certificate are rejected
+    cli.get("/test")
+        .data(TlsInfo::default())
+        .send()
+        .await
+        .assert_status(StatusCode::UNAUTHORIZED);
+
+    let resp = cli
+        .get("/test")
+        .data(TlsInfo {
+            peer_certificates: vec![b"cert".to_vec()],
+            ..TlsInfo::default()
+        })
+        .send()
+        .await;
+    resp.assert_status_is_ok();
+    resp.assert_text("cert").await;
+}
+
 #[tokio::test]
 async fn checker_result() {
     #[derive(SecurityScheme)]
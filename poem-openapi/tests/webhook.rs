@@ -118,23 +118,16 @@ async fn parameters() {
     assert_eq!(
         <&dyn MyWebhooks>::meta()[0].operation.params,
         vec![
-            MetaOperationParam {
-                name: "a".to_string(),
-                schema: i32::schema_ref(),
-                in_type: MetaParamIn::Query,
-                description: None,
-                required: true,
-                deprecated: false,
-                explode: true,
+            {
+                let mut param = MetaOperationParam::new("a", i32::schema_ref(), MetaParamIn::Query);
+                param.required = true;
+                param
             },
-            MetaOperationParam {
-                name: "b".to_string(),
-                schema: String::schema_ref(),
-                in_type: MetaParamIn::Path,
-                description: None,
-                required: true,
-                deprecated: false,
-                explode: true,
+            {
+                let mut param =
+                    MetaOperationParam::new("b", String::schema_ref(), MetaParamIn::Path);
+                param.required = true;
+                param
             }
         ]
     );
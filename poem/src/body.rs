@@ -2,23 +2,31 @@ use std::{
     fmt::{Debug, Formatter},
     io::{Error as IoError, ErrorKind},
     pin::Pin,
-    task::Poll,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    task::{Context, Poll},
 };
 
 use bytes::{Bytes, BytesMut};
 use futures_util::{Stream, TryStreamExt};
 use http_body_util::BodyExt;
-use hyper::body::{Body as _, Frame};
+use hyper::body::{Body as _, Frame, SizeHint};
 use serde::{de::DeserializeOwned, Serialize};
-use sync_wrapper::SyncStream;
-use tokio::io::{AsyncRead, AsyncReadExt};
+use sync_wrapper::{SyncStream, SyncWrapper};
+use tokio::{
+    io::{AsyncRead, AsyncReadExt},
+    sync::mpsc,
+};
 
 use crate::{
-    error::{ParseJsonError, ReadBodyError},
+    error::{ParseJsonError, PayloadTooLargeError, ReadBodyError},
     Result,
 };
 
 pub(crate) type BoxBody = http_body_util::combinators::BoxBody<Bytes, IoError>;
+type FinishCallback = SyncWrapper<Box<dyn FnOnce(BodyOutcome) + Send>>;
 
 /// A body object for requests and responses.
 #[derive(Default)]
@@ -141,6 +149,44 @@ impl Body {
         )))
     }
 
+    /// Create a streaming body and a [`BodySender`] that pushes the chunks of
+    /// the body, e.g. from another task.
+    ///
+    /// The body ends when the sender is dropped. The channel buffers a single
+    /// chunk, so [`BodySender::send_data`] waits until the previous chunk has
+    /// been read, which slows down the producer when the client is slow.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use poem::{handler, test::TestClient, Body};
+    ///
+    /// #[handler]
+    /// fn index() -> Body {
+    ///     let (mut tx, body) = Body::channel();
+    ///     tokio::spawn(async move {
+    ///         for i in 0..3 {
+    ///             if tx.send_data(format!("{i}\n")).await.is_err() {
+    ///                 // the client has disconnected
+    ///                 break;
+    ///             }
+    ///         }
+    ///     });
+    ///     body
+    /// }
+    ///
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let cli = TestClient::new(index);
+    /// cli.get("/").send().await.assert_text("0\n1\n2\n").await;
+    /// # });
+    /// ```
+    pub fn channel() -> (BodySender, Body) {
+        let (tx, mut rx) = mpsc::channel(1);
+        let body =
+            Self::from_bytes_stream(futures_util::stream::poll_fn(move |cx| rx.poll_recv(cx)));
+        (BodySender(tx), body)
+    }
+
     /// Create a body object from JSON.
     #[cfg(not(feature = "sonic-rs"))]
     pub fn from_json(body: impl Serialize) -> serde_json::Result<Self> {
@@ -175,7 +221,7 @@ impl Body {
             .0
             .collect()
             .await
-            .map_err(|err| ReadBodyError::Io(IoError::new(ErrorKind::Other, err)))?
+            .map_err(ReadBodyError::Io)?
             .to_bytes())
     }
 
@@ -268,6 +314,138 @@ impl Body {
         tokio_util::io::StreamReader::new(self.into_bytes_stream())
     }
 
+    /// Calls `f` with the total number of bytes produced so far each time a
+    /// chunk of data is read from this body.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::sync::{
+    ///     atomic::{AtomicU64, Ordering},
+    ///     Arc,
+    /// };
+    ///
+    /// use poem::Body;
+    ///
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let sent = Arc::new(AtomicU64::new(0));
+    /// let body = Body::from("hello").on_progress({
+    ///     let sent = sent.clone();
+    ///     move |bytes| sent.store(bytes, Ordering::Relaxed)
+    /// });
+    /// body.into_bytes().await.unwrap();
+    /// assert_eq!(sent.load(Ordering::Relaxed), 5);
+    /// # });
+    /// ```
+    #[must_use]
+    pub fn on_progress(self, f: impl FnMut(u64) + Send + 'static) -> Self {
+        Self(BoxBody::new(ProgressBody {
+            inner: self.0,
+            bytes: 0,
+            f: SyncWrapper::new(Box::new(f)),
+        }))
+    }
+
+    /// Calls `f` once when this body is dropped, with the number of bytes
+    /// produced and whether the body was read to the end.
+    ///
+    /// The server drops the response body as soon as it stops sending it,
+    /// which happens when the client disconnects in the middle of a download,
+    /// so `f` can be used to stop an expensive producer, such as cancelling
+    /// the upstream request. The stream that produces the body is dropped at
+    /// the same time.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use poem::{Body, BodyOutcome};
+    ///
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let (tx, rx) = tokio::sync::oneshot::channel();
+    /// let body = Body::from("hello").on_finish(move |outcome| {
+    ///     let _ = tx.send(outcome);
+    /// });
+    /// drop(body);
+    /// assert_eq!(
+    ///     rx.await.unwrap(),
+    ///     BodyOutcome {
+    ///         bytes: 0,
+    ///         completed: false
+    ///     }
+    /// );
+    /// # });
+    /// ```
+    #[must_use]
+    pub fn on_finish(self, f: impl FnOnce(BodyOutcome) + Send + 'static) -> Self {
+        Self(BoxBody::new(FinishBody {
+            inner: self.0,
+            bytes: 0,
+            completed: false,
+            f: Some(SyncWrapper::new(Box::new(f))),
+        }))
+    }
+
+    /// Limits the length of this body to `limit` bytes, without buffering it.
+    ///
+    /// The body fails with an error as soon as more than `limit` bytes have
+    /// been read, so an upload can be processed while it is streamed and is
+    /// aborted in the middle if it is too long. The body fails before reading
+    /// anything if the `Content-Length` already exceeds the limit, but a
+    /// shorter `Content-Length` is not trusted.
+    ///
+    /// The error is an [`std::io::Error`] wrapping a [`PayloadTooLargeError`],
+    /// which responds with `413 Payload Too Large` when it is returned from an
+    /// endpoint, directly or as a [`ReadBodyError`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use futures_util::TryStreamExt;
+    /// use poem::{handler, http::StatusCode, test::TestClient, Body, Result};
+    ///
+    /// #[handler]
+    /// async fn upload(body: Body) -> Result<String> {
+    ///     let mut stream = body.limit(5).into_bytes_stream();
+    ///     let mut len = 0;
+    ///     while let Some(data) = stream.try_next().await? {
+    ///         len += data.len();
+    ///     }
+    ///     Ok(format!("{len} bytes"))
+    /// }
+    ///
+    /// let cli = TestClient::new(upload);
+    ///
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// cli.post("/")
+    ///     .body("12345")
+    ///     .send()
+    ///     .await
+    ///     .assert_text("5 bytes")
+    ///     .await;
+    ///
+    /// cli.post("/")
+    ///     .body("123456")
+    ///     .send()
+    ///     .await
+    ///     .assert_status(StatusCode::PAYLOAD_TOO_LARGE);
+    /// # });
+    /// ```
+    #[must_use]
+    pub fn limit(self, limit: u64) -> Self {
+        self.shared_limit(Arc::new(AtomicU64::new(limit)))
+    }
+
+    /// Limits the length of this body, the limit is read each time the body
+    /// is polled, so it can be changed after the body is wrapped.
+    pub(crate) fn shared_limit(self, limit: Arc<AtomicU64>) -> Self {
+        Self(BoxBody::new(LimitBody {
+            inner: self.0,
+            bytes: 0,
+            limit,
+            exceeded: false,
+        }))
+    }
+
     /// Consumes this body object to return a bytes stream.
     pub fn into_bytes_stream(self) -> impl Stream<Item = Result<Bytes, IoError>> + Send + 'static {
         let mut body = self.0;
@@ -285,10 +463,207 @@ impl Body {
     }
 }
 
+/// The sending half of a body created by [`Body::channel`].
+#[derive(Debug)]
+pub struct BodySender(mpsc::Sender<Result<Bytes, IoError>>);
+
+impl BodySender {
+    /// Appends a chunk to the body, waiting while the previous chunk has not
+    /// been read.
+    ///
+    /// Returns an error if the body has been dropped, for example because the
+    /// client has disconnected.
+    pub async fn send_data(&mut self, data: impl Into<Bytes>) -> Result<(), IoError> {
+        self.0
+            .send(Ok(data.into()))
+            .await
+            .map_err(|_| IoError::new(ErrorKind::BrokenPipe, "the body has been dropped"))
+    }
+
+    /// Ends the body with an error, the response is aborted instead of being
+    /// completed.
+    pub async fn abort(self, err: IoError) {
+        _ = self.0.send(Err(err)).await;
+    }
+
+    /// Returns `true` if the body has been dropped.
+    pub fn is_closed(&self) -> bool {
+        self.0.is_closed()
+    }
+}
+
+/// The state of a body when it is dropped, passed to the callback of
+/// [`Body::on_finish`].
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct BodyOutcome {
+    /// The number of bytes produced by the body.
+    pub bytes: u64,
+    /// Returns `true` if the body was read to the end, `false` if it was
+    /// dropped early, for example because the client disconnected, or if it
+    /// returned an error.
+    pub completed: bool,
+}
+
+fn data_len(frame: &Poll<Option<Result<Frame<Bytes>, IoError>>>) -> u64 {
+    match frame {
+        Poll::Ready(Some(Ok(frame))) => frame.data_ref().map(|data| data.len() as u64).unwrap_or(0),
+        _ => 0,
+    }
+}
+
+struct ProgressBody {
+    inner: BoxBody,
+    bytes: u64,
+    f: SyncWrapper<Box<dyn FnMut(u64) + Send>>,
+}
+
+impl hyper::body::Body for ProgressBody {
+    type Data = Bytes;
+    type Error = IoError;
+
+    fn poll_frame(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Frame<Self::Data>, Self::Error>>> {
+        let this = self.get_mut();
+        let frame = Pin::new(&mut this.inner).poll_frame(cx);
+        let len = data_len(&frame);
+        if len > 0 {
+            this.bytes += len;
+            (this.f.get_mut())(this.bytes);
+        }
+        frame
+    }
+
+    fn is_end_stream(&self) -> bool {
+        self.inner.is_end_stream()
+    }
+
+    fn size_hint(&self) -> SizeHint {
+        self.inner.size_hint()
+    }
+}
+
+struct LimitBody {
+    inner: BoxBody,
+    bytes: u64,
+    limit: Arc<AtomicU64>,
+    exceeded: bool,
+}
+
+impl hyper::body::Body for LimitBody {
+    type Data = Bytes;
+    type Error = IoError;
+
+    fn poll_frame(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Frame<Self::Data>, Self::Error>>> {
+        let this = self.get_mut();
+        if this.exceeded {
+            return Poll::Ready(None);
+        }
+
+        // the remaining length is known in advance if the body has a
+        // `Content-Length`
+        let limit = this.limit.load(Ordering::Relaxed);
+        if this.bytes.saturating_add(this.inner.size_hint().lower()) <= limit {
+            let frame = Pin::new(&mut this.inner).poll_frame(cx);
+            this.bytes += data_len(&frame);
+            if this.bytes <= limit {
+                return frame;
+            }
+        }
+
+        this.exceeded = true;
+        Poll::Ready(Some(Err(IoError::new(
+            ErrorKind::InvalidData,
+            PayloadTooLargeError,
+        ))))
+    }
+
+    fn is_end_stream(&self) -> bool {
+        self.exceeded || self.inner.is_end_stream()
+    }
+
+    fn size_hint(&self) -> SizeHint {
+        self.inner.size_hint()
+    }
+}
+
+struct FinishBody {
+    inner: BoxBody,
+    bytes: u64,
+    completed: bool,
+    f: Option<FinishCallback>,
+}
+
+impl hyper::body::Body for FinishBody {
+    type Data = Bytes;
+    type Error = IoError;
+
+    fn poll_frame(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Frame<Self::Data>, Self::Error>>> {
+        let this = self.get_mut();
+        let frame = Pin::new(&mut this.inner).poll_frame(cx);
+        this.bytes += data_len(&frame);
+        match &frame {
+            // the server stops polling once the body reports the end of stream
+            Poll::Ready(Some(Ok(_))) if this.inner.is_end_stream() => this.completed = true,
+            Poll::Ready(None) => this.completed = true,
+            _ => {}
+        }
+        frame
+    }
+
+    fn is_end_stream(&self) -> bool {
+        self.inner.is_end_stream()
+    }
+
+    fn size_hint(&self) -> SizeHint {
+        self.inner.size_hint()
+    }
+}
+
+impl Drop for FinishBody {
+    fn drop(&mut self) {
+        if let Some(f) = self.f.take() {
+            // the server does not poll a body that is known to be empty
+            let completed = self.completed || (self.bytes == 0 && self.inner.is_end_stream());
+            (f.into_inner())(BodyOutcome {
+                bytes: self.bytes,
+                completed,
+            });
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[tokio::test]
+    async fn channel() {
+        let (mut tx, body) = Body::channel();
+        let producer = tokio::spawn(async move {
+            tx.send_data("abc").await.unwrap();
+            tx.send_data(Bytes::from_static(b"def")).await.unwrap();
+        });
+        assert_eq!(body.into_string().await.unwrap(), "abcdef");
+        producer.await.unwrap();
+
+        let (tx, body) = Body::channel();
+        tokio::spawn(tx.abort(IoError::new(ErrorKind::Other, "failed")));
+        assert!(body.into_bytes().await.is_err());
+
+        let (mut tx, body) = Body::channel();
+        drop(body);
+        assert!(tx.is_closed());
+        assert!(tx.send_data("abc").await.is_err());
+    }
+
     #[tokio::test]
     async fn create() {
         let body = Body::from(b"abc".as_ref());
@@ -331,4 +706,85 @@ mod tests {
         let body = Body::from_json("abc").unwrap();
         assert_eq!(body.into_json::<String>().await.unwrap(), "abc");
     }
+
+    #[tokio::test]
+    async fn on_progress() {
+        let progress = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let body = Body::from_bytes_stream(futures_util::stream::iter(
+            ["abc", "", "defg"].map(Ok::<_, std::io::Error>),
+        ))
+        .on_progress({
+            let progress = progress.clone();
+            move |bytes| progress.lock().unwrap().push(bytes)
+        });
+        assert_eq!(body.into_string().await.unwrap(), "abcdefg");
+        assert_eq!(*progress.lock().unwrap(), vec![3, 7]);
+    }
+
+    #[tokio::test]
+    async fn on_finish() {
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        let body = Body::from("abc").on_finish(|outcome| tx.send(outcome).unwrap());
+        assert_eq!(body.into_string().await.unwrap(), "abc");
+        assert_eq!(
+            rx.await.unwrap(),
+            BodyOutcome {
+                bytes: 3,
+                completed: true
+            }
+        );
+
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        let mut stream = Body::from_bytes_stream(futures_util::stream::iter(
+            ["abc", "def"].map(Ok::<_, std::io::Error>),
+        ))
+        .on_finish(|outcome| tx.send(outcome).unwrap())
+        .into_bytes_stream();
+        futures_util::StreamExt::next(&mut stream).await;
+        drop(stream);
+        assert_eq!(
+            rx.await.unwrap(),
+            BodyOutcome {
+                bytes: 3,
+                completed: false
+            }
+        );
+
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        drop(Body::empty().on_finish(|outcome| tx.send(outcome).unwrap()));
+        assert!(rx.await.unwrap().completed);
+    }
+
+    #[tokio::test]
+    async fn limit() {
+        let body = Body::from_bytes_stream(futures_util::stream::iter(
+            ["abc", "def"].map(Ok::<_, std::io::Error>),
+        ));
+        assert_eq!(body.limit(6).into_string().await.unwrap(), "abcdef");
+
+        // the chunks before the limit is exceeded are produced
+        let mut stream = Body::from_bytes_stream(futures_util::stream::iter(
+            ["abc", "def", "ghi"].map(Ok::<_, std::io::Error>),
+        ))
+        .limit(5)
+        .into_bytes_stream();
+        assert_eq!(stream.try_next().await.unwrap().unwrap(), "abc");
+        let err = stream.try_next().await.unwrap_err();
+        assert!(err.get_ref().unwrap().is::<PayloadTooLargeError>());
+        assert!(stream.try_next().await.unwrap().is_none());
+
+        // the known length is checked before reading
+        let mut stream = Body::from("abcdef").limit(5).into_bytes_stream();
+        assert!(stream.try_next().await.is_err());
+
+        let err = Body::from("abcdef")
+            .limit(5)
+            .into_bytes()
+            .await
+            .unwrap_err();
+        assert_eq!(
+            crate::error::ResponseError::status(&err),
+            http::StatusCode::PAYLOAD_TOO_LARGE
+        );
+    }
 }
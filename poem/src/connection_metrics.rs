@@ -0,0 +1,154 @@
+#[cfg(feature = "opentelemetry")]
+use std::sync::{
+    atomic::{AtomicU64, Ordering},
+    Arc,
+};
+
+#[cfg(feature = "opentelemetry")]
+use libopentelemetry::{
+    global,
+    metrics::{Counter, Histogram, UpDownCounter},
+    KeyValue,
+};
+
+use crate::web::LocalAddr;
+
+/// Records the metrics of the connections accepted by the server with the
+/// global OpenTelemetry meter, it is no-op if the `opentelemetry` feature is
+/// disabled.
+#[derive(Clone)]
+pub(crate) struct ConnectionMetrics {
+    #[cfg(feature = "opentelemetry")]
+    instruments: Arc<Instruments>,
+}
+
+#[cfg(feature = "opentelemetry")]
+struct Instruments {
+    accepted: Counter<u64>,
+    open: UpDownCounter<i64>,
+    requests: Histogram<u64>,
+}
+
+impl ConnectionMetrics {
+    pub(crate) fn new() -> Self {
+        #[cfg(feature = "opentelemetry")]
+        {
+            let meter = global::meter("poem");
+            Self {
+                instruments: Arc::new(Instruments {
+                    accepted: meter
+                        .u64_counter("poem_connections_accepted")
+                        .with_description("accepted connection count (since start of service)")
+                        .build(),
+                    open: meter
+                        .i64_up_down_counter("poem_connections_open")
+                        .with_description("currently open connection count")
+                        .build(),
+                    requests: meter
+                        .u64_histogram("poem_connection_requests")
+                        .with_description("request count per connection (keep-alive reuse)")
+                        .build(),
+                }),
+            }
+        }
+
+        #[cfg(not(feature = "opentelemetry"))]
+        Self {}
+    }
+
+    /// Records an accepted connection, the returned guard records the
+    /// requests served by the connection and when it is closed.
+    pub(crate) fn accepted(&self, local_addr: &LocalAddr) -> ConnectionGuard {
+        #[cfg(feature = "opentelemetry")]
+        {
+            let labels = vec![listener_label(local_addr)];
+            self.instruments.accepted.add(1, &labels);
+            self.instruments.open.add(1, &labels);
+            ConnectionGuard {
+                requests: AtomicU64::new(0),
+                instruments: self.instruments.clone(),
+                labels,
+            }
+        }
+
+        #[cfg(not(feature = "opentelemetry"))]
+        {
+            let _ = local_addr;
+            ConnectionGuard {}
+        }
+    }
+}
+
+pub(crate) struct ConnectionGuard {
+    #[cfg(feature = "opentelemetry")]
+    requests: AtomicU64,
+    #[cfg(feature = "opentelemetry")]
+    instruments: Arc<Instruments>,
+    #[cfg(feature = "opentelemetry")]
+    labels: Vec<KeyValue>,
+}
+
+impl ConnectionGuard {
+    #[inline]
+    pub(crate) fn request_received(&self) {
+        #[cfg(feature = "opentelemetry")]
+        self.requests.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+#[cfg(feature = "opentelemetry")]
+impl Drop for ConnectionGuard {
+    fn drop(&mut self) {
+        self.instruments.open.add(-1, &self.labels);
+        self.instruments
+            .requests
+            .record(self.requests.load(Ordering::Relaxed), &self.labels);
+    }
+}
+
+#[cfg(feature = "opentelemetry")]
+fn listener_label(local_addr: &LocalAddr) -> KeyValue {
+    KeyValue::new("listener", local_addr.to_string())
+}
+
+/// Wraps a TLS handshake to count the completed and failed handshakes.
+#[cfg(any(feature = "native-tls", feature = "rustls", feature = "openssl-tls"))]
+pub(crate) fn record_tls_handshake<F, S>(
+    local_addr: &LocalAddr,
+    handshake: F,
+) -> impl std::future::Future<Output = std::io::Result<S>> + Send + 'static
+where
+    F: std::future::Future<Output = std::io::Result<S>> + Send + 'static,
+    S: Send + 'static,
+{
+    #[cfg(feature = "opentelemetry")]
+    {
+        static TLS_HANDSHAKES: std::sync::OnceLock<Counter<u64>> = std::sync::OnceLock::new();
+
+        let listener = listener_label(local_addr);
+        async move {
+            let res = handshake.await;
+            TLS_HANDSHAKES
+                .get_or_init(|| {
+                    global::meter("poem")
+                        .u64_counter("poem_tls_handshakes")
+                        .with_description("TLS handshake count (since start of service)")
+                        .build()
+                })
+                .add(
+                    1,
+                    &[
+                        listener,
+                        KeyValue::new("result", if res.is_ok() { "success" } else { "failure" }),
+                    ],
+                );
+            res
+        }
+    }
+
+    #[cfg(not(feature = "opentelemetry"))]
+    {
+        let _ = local_addr;
+        handshake
+    }
+}
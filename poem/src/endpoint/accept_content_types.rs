@@ -0,0 +1,154 @@
+use mime::Mime;
+
+use crate::{error::UnsupportedContentTypeError, http::header, Endpoint, Request, Result};
+
+/// Endpoint for the
+/// [`accept_content_types`](super::EndpointExt::accept_content_types) method.
+pub struct AcceptContentTypes<E> {
+    inner: E,
+    types: Vec<Mime>,
+    accept: String,
+}
+
+impl<E> AcceptContentTypes<E> {
+    pub(crate) fn new(inner: E, types: impl IntoIterator<Item = impl AsRef<str>>) -> Self {
+        let types = types
+            .into_iter()
+            .map(|ty| {
+                ty.as_ref()
+                    .parse::<Mime>()
+                    .unwrap_or_else(|_| panic!("invalid content type `{}`", ty.as_ref()))
+            })
+            .collect::<Vec<_>>();
+        let accept = types
+            .iter()
+            .map(|ty| ty.as_ref())
+            .collect::<Vec<_>>()
+            .join(", ");
+        Self {
+            inner,
+            types,
+            accept,
+        }
+    }
+}
+
+/// Returns `true` if the content type matches the pattern, the type and
+/// subtype of the pattern can be `*`, and the parameters of the pattern, such
+/// as `charset`, must also be present in the content type.
+fn matches(pattern: &Mime, content_type: &Mime) -> bool {
+    (pattern.type_() == mime::STAR || pattern.type_() == content_type.type_())
+        && (pattern.subtype() == mime::STAR || pattern.subtype() == content_type.subtype())
+        && pattern.params().all(|(name, value)| {
+            content_type
+                .get_param(name)
+                .is_some_and(|v| v.as_str().eq_ignore_ascii_case(value.as_str()))
+        })
+}
+
+impl<E: Endpoint> Endpoint for AcceptContentTypes<E> {
+    type Output = E::Output;
+
+    async fn call(&self, mut req: Request) -> Result<Self::Output> {
+        let body = req.take_body();
+        let has_body = !body.is_empty();
+        req.set_body(body);
+
+        match req.headers().get(header::CONTENT_TYPE) {
+            Some(content_type) => {
+                let accepted = content_type
+                    .to_str()
+                    .ok()
+                    .and_then(|value| value.parse::<Mime>().ok())
+                    .is_some_and(|content_type| {
+                        self.types.iter().any(|ty| matches(ty, &content_type))
+                    });
+                if !accepted {
+                    return Err(UnsupportedContentTypeError::Unsupported {
+                        content_type: String::from_utf8_lossy(content_type.as_bytes()).into_owned(),
+                        accept: self.accept.clone(),
+                    }
+                    .into());
+                }
+            }
+            None if has_body => {
+                return Err(UnsupportedContentTypeError::ContentTypeRequired {
+                    accept: self.accept.clone(),
+                }
+                .into());
+            }
+            None => {}
+        }
+
+        self.inner.call(req).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        endpoint::make_sync,
+        http::{HeaderValue, StatusCode},
+        test::TestClient,
+        EndpointExt,
+    };
+
+    #[test]
+    fn test_matches() {
+        let m = |pattern: &str, content_type: &str| {
+            matches(&pattern.parse().unwrap(), &content_type.parse().unwrap())
+        };
+        assert!(m("application/json", "application/json"));
+        assert!(m("application/json", "application/json; charset=utf-8"));
+        assert!(m("application/json", "Application/JSON"));
+        assert!(m("application/*", "application/xml"));
+        assert!(m("*/*", "text/plain"));
+        assert!(m("text/plain; charset=utf-8", "text/plain; charset=UTF-8"));
+        assert!(!m("text/plain; charset=utf-8", "text/plain"));
+        assert!(!m("application/json", "text/json"));
+        assert!(!m("application/*", "text/plain"));
+    }
+
+    #[tokio::test]
+    async fn test_accept_content_types() {
+        let cli = TestClient::new(
+            make_sync(|_| "ok").accept_content_types(["application/json", "text/*"]),
+        );
+
+        cli.post("/")
+            .content_type("application/json; charset=utf-8")
+            .body("{}")
+            .send()
+            .await
+            .assert_status_is_ok();
+        cli.post("/")
+            .content_type("text/csv")
+            .body("a,b")
+            .send()
+            .await
+            .assert_status_is_ok();
+        cli.get("/").send().await.assert_status_is_ok();
+
+        let resp = cli
+            .post("/")
+            .content_type("application/xml")
+            .body("<a/>")
+            .send()
+            .await;
+        resp.assert_status(StatusCode::UNSUPPORTED_MEDIA_TYPE);
+        resp.assert_header("accept-post", "application/json, text/*");
+
+        cli.post("/")
+            .body("{}")
+            .send()
+            .await
+            .assert_status(StatusCode::UNSUPPORTED_MEDIA_TYPE);
+        cli.post("/")
+            .header(header::CONTENT_TYPE, HeaderValue::from_static("json"))
+            .body("{}")
+            .send()
+            .await
+            .assert_status(StatusCode::UNSUPPORTED_MEDIA_TYPE);
+    }
+}
@@ -1,16 +1,87 @@
-use std::marker::PhantomData;
+use std::{
+    marker::PhantomData,
+    sync::Arc,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
 
+use headers::{ETag, HeaderMapExt, IfModifiedSince, IfNoneMatch};
+use httpdate::HttpDate;
 use rust_embed::RustEmbed;
 
 use crate::{
-    http::{header, Method, StatusCode},
+    http::{header, HeaderValue, Method, StatusCode},
     Endpoint, Error, Request, Response,
 };
 
+const IMMUTABLE_CACHE_CONTROL: &str = "public, max-age=31536000, immutable";
+
+type FingerprintedFn = Arc<dyn Fn(&str) -> bool + Send + Sync>;
+
+/// Serves the file at `path` of the bundle `E`, with a strong `ETag` derived
+/// from the hash of the embedded content, and a `Last-Modified` header if the
+/// modification time was embedded.
+fn serve<E: RustEmbed>(
+    req: &Request,
+    path: &str,
+    cache_control: Option<&HeaderValue>,
+) -> Result<Response, Error> {
+    if req.method() != Method::GET && req.method() != Method::HEAD {
+        return Err(StatusCode::METHOD_NOT_ALLOWED.into());
+    }
+
+    let content = E::get(path).ok_or(StatusCode::NOT_FOUND)?;
+    let hash = format!("\"{}\"", hex::encode(content.metadata.sha256_hash()));
+    let etag = hash.parse::<ETag>().expect("valid etag");
+    let last_modified = content
+        .metadata
+        .last_modified()
+        .map(|secs| UNIX_EPOCH + Duration::from_secs(secs));
+
+    let mut builder = Response::builder().header(header::ETAG, &hash);
+    if let Some(last_modified) = last_modified {
+        builder = builder.header(
+            header::LAST_MODIFIED,
+            HttpDate::from(last_modified).to_string(),
+        );
+    }
+    if let Some(cache_control) = cache_control {
+        builder = builder.header(header::CACHE_CONTROL, cache_control.clone());
+    }
+
+    if is_not_modified(req, &etag, last_modified) {
+        return Ok(builder.status(StatusCode::NOT_MODIFIED).finish());
+    }
+
+    let body: Vec<u8> = content.data.into();
+    let mime = mime_guess::from_path(path).first_or_octet_stream();
+    Ok(builder
+        .header(header::CONTENT_TYPE, mime.as_ref())
+        .body(body))
+}
+
+/// Returns `true` if the client has the current version of the file,
+/// `If-Modified-Since` is only checked when there is no `If-None-Match`.
+fn is_not_modified(req: &Request, etag: &ETag, last_modified: Option<SystemTime>) -> bool {
+    if let Some(if_none_match) = req.headers().typed_get::<IfNoneMatch>() {
+        return !if_none_match.precondition_passes(etag);
+    }
+    match (req.headers().typed_get::<IfModifiedSince>(), last_modified) {
+        (Some(if_modified_since), Some(last_modified)) => {
+            !if_modified_since.is_modified(last_modified)
+        }
+        _ => false,
+    }
+}
+
 /// An endpoint that wraps a single file from a `rust-embed` bundle.
+///
+/// The file is served with a strong `ETag` derived from the hash of its
+/// embedded content, and the conditional requests with `If-None-Match` or
+/// `If-Modified-Since` are answered with `304 Not Modified`.
 pub struct EmbeddedFileEndpoint<E: RustEmbed + Send + Sync> {
     _embed: PhantomData<E>,
     path: String,
+    cache_control: Option<HeaderValue>,
 }
 
 impl<E: RustEmbed + Send + Sync> EmbeddedFileEndpoint<E> {
@@ -21,6 +92,22 @@ impl<E: RustEmbed + Send + Sync> EmbeddedFileEndpoint<E> {
         EmbeddedFileEndpoint {
             _embed: PhantomData,
             path: path.to_owned(),
+            cache_control: None,
+        }
+    }
+
+    /// Sets the `Cache-Control` header of the file.
+    ///
+    /// By default no `Cache-Control` header is set.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the value is not a valid header value.
+    #[must_use]
+    pub fn cache_control(self, value: impl AsRef<str>) -> Self {
+        Self {
+            cache_control: Some(HeaderValue::from_str(value.as_ref()).unwrap()),
+            ..self
         }
     }
 }
@@ -29,38 +116,38 @@ impl<E: RustEmbed + Send + Sync> Endpoint for EmbeddedFileEndpoint<E> {
     type Output = Response;
 
     async fn call(&self, req: Request) -> Result<Self::Output, Error> {
-        if req.method() != Method::GET {
-            return Err(StatusCode::METHOD_NOT_ALLOWED.into());
-        }
-
-        match E::get(&self.path) {
-            Some(content) => {
-                let hash = hex::encode(content.metadata.sha256_hash());
-                if req
-                    .headers()
-                    .get(header::IF_NONE_MATCH)
-                    .map(|etag| etag.to_str().unwrap_or("000000").eq(&hash))
-                    .unwrap_or(false)
-                {
-                    return Err(StatusCode::NOT_MODIFIED.into());
-                }
-
-                // otherwise, return 200 with etag hash
-                let body: Vec<u8> = content.data.into();
-                let mime = mime_guess::from_path(&self.path).first_or_octet_stream();
-                Ok(Response::builder()
-                    .header(header::CONTENT_TYPE, mime.as_ref())
-                    .header(header::ETAG, hash)
-                    .body(body))
-            }
-            None => Err(StatusCode::NOT_FOUND.into()),
-        }
+        serve::<E>(&req, &self.path, self.cache_control.as_ref())
     }
 }
 
 /// An endpoint that wraps a `rust-embed` bundle.
+///
+/// The files are served like [`EmbeddedFileEndpoint`], and a request for a
+/// directory is served with its index file.
+///
+/// # Example
+///
+/// ```ignore
+/// use poem::{endpoint::EmbeddedFilesEndpoint, Route};
+/// use rust_embed::RustEmbed;
+///
+/// #[derive(RustEmbed)]
+/// #[folder = "dist"]
+/// struct Assets;
+///
+/// let app = Route::new().nest(
+///     "/",
+///     EmbeddedFilesEndpoint::<Assets>::new()
+///         .cache_control("no-cache")
+///         // the bundler adds a content hash to the names of the assets
+///         .fingerprinted(|path| path.starts_with("assets/")),
+/// );
+/// ```
 pub struct EmbeddedFilesEndpoint<E: RustEmbed + Send + Sync> {
     _embed: PhantomData<E>,
+    index_file: Option<String>,
+    cache_control: Option<HeaderValue>,
+    fingerprinted: Option<FingerprintedFn>,
 }
 
 impl<E: RustEmbed + Sync + Send> Default for EmbeddedFilesEndpoint<E> {
@@ -75,8 +162,69 @@ impl<E: RustEmbed + Send + Sync> EmbeddedFilesEndpoint<E> {
     pub fn new() -> Self {
         EmbeddedFilesEndpoint {
             _embed: PhantomData,
+            index_file: Some("index.html".to_string()),
+            cache_control: None,
+            fingerprinted: None,
+        }
+    }
+
+    /// Sets the file that is served for the requests of a directory.
+    ///
+    /// Default is `index.html`.
+    #[must_use]
+    pub fn index_file(self, index: impl Into<String>) -> Self {
+        Self {
+            index_file: Some(index.into()),
+            ..self
+        }
+    }
+
+    /// Disables the index file, the requests of a directory return `404 Not
+    /// Found`.
+    #[must_use]
+    pub fn disable_index_file(self) -> Self {
+        Self {
+            index_file: None,
+            ..self
         }
     }
+
+    /// Sets the `Cache-Control` header of the files that are not
+    /// fingerprinted.
+    ///
+    /// By default no `Cache-Control` header is set.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the value is not a valid header value.
+    #[must_use]
+    pub fn cache_control(self, value: impl AsRef<str>) -> Self {
+        Self {
+            cache_control: Some(HeaderValue::from_str(value.as_ref()).unwrap()),
+            ..self
+        }
+    }
+
+    /// Sets a function that returns `true` for the paths of the files whose
+    /// names contain a hash of their content, such files never change, so
+    /// they are served with `Cache-Control: public, max-age=31536000,
+    /// immutable`.
+    #[must_use]
+    pub fn fingerprinted(self, f: impl Fn(&str) -> bool + Send + Sync + 'static) -> Self {
+        Self {
+            fingerprinted: Some(Arc::new(f)),
+            ..self
+        }
+    }
+
+    fn serve(&self, req: &Request, path: &str) -> Result<Response, Error> {
+        let immutable = HeaderValue::from_static(IMMUTABLE_CACHE_CONTROL);
+        let cache_control = match &self.fingerprinted {
+            Some(f) if f(path) => Some(&immutable),
+            _ => self.cache_control.as_ref(),
+        };
+        serve::<E>(req, path, cache_control)
+    }
 }
 
 impl<E: RustEmbed + Send + Sync> Endpoint for EmbeddedFilesEndpoint<E> {
@@ -89,23 +237,98 @@ impl<E: RustEmbed + Send + Sync> Endpoint for EmbeddedFilesEndpoint<E> {
 
         use header::LOCATION;
 
+        let Some(index_file) = &self.index_file else {
+            return self.serve(&req, path);
+        };
+
         if path.is_empty() && !original_end_with_slash {
             Ok(Response::builder()
                 .status(StatusCode::FOUND)
                 .header(LOCATION, format!("{}/", original_path))
                 .finish())
         } else if original_end_with_slash {
-            let path = format!("{}index.html", path);
-            EmbeddedFileEndpoint::<E>::new(&path).call(req).await
+            let path = format!("{}{}", path, index_file);
+            self.serve(&req, &path)
         } else if E::get(path).is_some() {
-            EmbeddedFileEndpoint::<E>::new(path).call(req).await
-        } else if E::get(&format!("{}/index.html", path)).is_some() {
+            self.serve(&req, path)
+        } else if E::get(&format!("{}/{}", path, index_file)).is_some() {
             Ok(Response::builder()
                 .status(StatusCode::FOUND)
                 .header(LOCATION, format!("{}/", original_path))
                 .finish())
         } else {
-            EmbeddedFileEndpoint::<E>::new(path).call(req).await
+            self.serve(&req, path)
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{test::TestClient, Route};
+
+    #[derive(RustEmbed)]
+    #[folder = "src/endpoint/testdata/embed"]
+    struct Assets;
+
+    #[tokio::test]
+    async fn embedded_files() {
+        let cli = TestClient::new(
+            Route::new().nest(
+                "/",
+                EmbeddedFilesEndpoint::<Assets>::new()
+                    .cache_control("no-cache")
+                    .fingerprinted(|path| path.starts_with("assets/")),
+            ),
+        );
+
+        let resp = cli.get("/").send().await;
+        resp.assert_status_is_ok();
+        resp.assert_content_type("text/html");
+        resp.assert_header(header::CACHE_CONTROL, "no-cache");
+        let etag = resp.0.headers().get(header::ETAG).unwrap().clone();
+        assert!(etag.to_str().unwrap().starts_with('"'));
+        resp.assert_text("<h1>hello</h1>\n").await;
+
+        let resp = cli
+            .get("/")
+            .header(header::IF_NONE_MATCH, etag)
+            .send()
+            .await;
+        resp.assert_status(StatusCode::NOT_MODIFIED);
+        resp.assert_header(header::CACHE_CONTROL, "no-cache");
+        resp.assert_text("").await;
+
+        cli.get("/")
+            .header(header::IF_NONE_MATCH, "\"other\"")
+            .send()
+            .await
+            .assert_status_is_ok();
+
+        let resp = cli.get("/assets/app.0123abcd.js").send().await;
+        resp.assert_status_is_ok();
+        resp.assert_header(header::CACHE_CONTROL, IMMUTABLE_CACHE_CONTROL);
+
+        cli.get("/missing.txt")
+            .send()
+            .await
+            .assert_status(StatusCode::NOT_FOUND);
+        cli.post("/")
+            .send()
+            .await
+            .assert_status(StatusCode::METHOD_NOT_ALLOWED);
+    }
+
+    #[tokio::test]
+    async fn disable_index_file() {
+        let cli = TestClient::new(Route::new().nest(
+            "/",
+            EmbeddedFilesEndpoint::<Assets>::new().disable_index_file(),
+        ));
+        cli.get("/")
+            .send()
+            .await
+            .assert_status(StatusCode::NOT_FOUND);
+        cli.get("/index.html").send().await.assert_status_is_ok();
+    }
+}
@@ -3,12 +3,14 @@ use std::{future::Future, marker::PhantomData, sync::Arc};
 use futures_util::{future::BoxFuture, FutureExt};
 
 use super::{
-    After, AndThen, Around, Before, CatchAllError, CatchError, InspectAllError, InspectError, Map,
+    AcceptContentTypes, After, AndThen, Around, Before, CatchAllError, CatchError,
+    InferContentType, InspectAllError, InspectError, Map, MapResponse, MapResponseAsync,
     MapToResponse, ToResponse,
 };
 use crate::{
     error::IntoResult,
-    middleware::{AddData, AddDataEndpoint},
+    http::StatusCode,
+    middleware::{AddData, AddDataEndpoint, CatchPanic, CatchPanicEndpoint, PanicHandler},
     Error, IntoResponse, Middleware, Request, Response, Result,
 };
 
@@ -178,6 +180,22 @@ where
     }
 }
 
+/// Runs the body of a handler declared with `#[handler(blocking)]` on the
+/// blocking thread pool.
+#[doc(hidden)]
+pub async fn run_blocking<F, R>(f: F) -> Result<R>
+where
+    F: FnOnce() -> R + Send + 'static,
+    R: Send + 'static,
+{
+    match tokio::task::spawn_blocking(f).await {
+        Ok(res) => Ok(res),
+        // resume the panic so that it can be caught by `CatchPanic`
+        Err(err) if err.is_panic() => std::panic::resume_unwind(err.into_panic()),
+        Err(err) => Err(Error::new(err, StatusCode::INTERNAL_SERVER_ERROR)),
+    }
+}
+
 impl<T: Endpoint + ?Sized> Endpoint for &T {
     type Output = T::Output;
 
@@ -376,6 +394,58 @@ pub trait EndpointExt: IntoEndpoint {
         }
     }
 
+    /// Catches panics raised by this endpoint and converts them into `500
+    /// INTERNAL SERVER ERROR` responses, similar to `with(CatchPanic::new())`.
+    ///
+    /// Unlike applying [`CatchPanic`] to the whole application, this only
+    /// isolates panics of this endpoint, so routes that are not wrapped will
+    /// still propagate their panics.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use poem::{handler, http::StatusCode, test::TestClient, EndpointExt, Route};
+    ///
+    /// #[handler]
+    /// async fn isolated() {
+    ///     panic!()
+    /// }
+    ///
+    /// #[handler]
+    /// async fn index() -> &'static str {
+    ///     "hello"
+    /// }
+    ///
+    /// let app = Route::new()
+    ///     .at("/", index)
+    ///     .at("/isolated", isolated.catch_panic());
+    ///
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let cli = TestClient::new(app);
+    /// cli.get("/isolated")
+    ///     .send()
+    ///     .await
+    ///     .assert_status(StatusCode::INTERNAL_SERVER_ERROR);
+    /// # });
+    /// ```
+    fn catch_panic(self) -> CatchPanicEndpoint<Self::Endpoint, ()>
+    where
+        Self: Sized,
+    {
+        self.with(CatchPanic::new())
+    }
+
+    /// Catches panics raised by this endpoint and uses the specified
+    /// [`PanicHandler`] to create the response, similar to
+    /// `with(CatchPanic::new().with_handler(handler))`.
+    fn catch_panic_with<H>(self, handler: H) -> CatchPanicEndpoint<Self::Endpoint, H>
+    where
+        H: PanicHandler,
+        Self: Sized,
+    {
+        self.with(CatchPanic::new().with_handler(handler))
+    }
+
     /// Maps the request of this endpoint.
     ///
     /// # Example
@@ -554,6 +624,98 @@ pub trait EndpointExt: IntoEndpoint {
         ToResponse::new(self.into_endpoint())
     }
 
+    /// Sets the `Content-Type` of the responses that do not specify one by
+    /// sniffing the leading bytes of the body.
+    ///
+    /// PNG, JPEG, GIF, PDF, HTML and JSON contents are recognized, other
+    /// contents are sent as `application/octet-stream`. The bodies returned
+    /// without a type, such as a [`Body`](crate::Body), and the bodies of
+    /// `Vec<u8>`, `Bytes` and `&[u8]`, whose default type is
+    /// `application/octet-stream`, are sniffed, while an explicit
+    /// `Content-Type` is never overridden. Empty bodies, `204 No Content` and
+    /// `304 Not Modified` responses and streaming bodies are left untouched.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use poem::{handler, test::TestClient, EndpointExt};
+    ///
+    /// #[handler]
+    /// fn index() -> Vec<u8> {
+    ///     b"\x89PNG\r\n\x1a\n".to_vec()
+    /// }
+    ///
+    /// let cli = TestClient::new(index.infer_content_type());
+    ///
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let resp = cli.get("/").send().await;
+    /// resp.assert_status_is_ok();
+    /// resp.assert_content_type("image/png");
+    /// # });
+    /// ```
+    fn infer_content_type(self) -> InferContentType<Self::Endpoint>
+    where
+        Self: Sized,
+    {
+        InferContentType::new(self.into_endpoint())
+    }
+
+    /// Rejects the requests whose `Content-Type` is not one of the specified
+    /// types with `415 Unsupported Media Type`, before calling this endpoint.
+    ///
+    /// The types can be wildcards such as `application/*`, and the parameters
+    /// of the request content type, such as `charset`, are ignored unless they
+    /// are specified. The requests without a body are always accepted. The
+    /// rejected responses contain an `Accept-Post` header that lists the
+    /// accepted types.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a type is not a valid media type.
+    ///
+    /// # Errors
+    ///
+    /// - [`UnsupportedContentTypeError`](crate::error::UnsupportedContentTypeError)
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use poem::{handler, http::StatusCode, test::TestClient, EndpointExt};
+    ///
+    /// #[handler]
+    /// fn webhook(body: String) -> String {
+    ///     body
+    /// }
+    ///
+    /// let cli = TestClient::new(webhook.accept_content_types(["application/json"]));
+    ///
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// cli.post("/")
+    ///     .content_type("application/json; charset=utf-8")
+    ///     .body("{}")
+    ///     .send()
+    ///     .await
+    ///     .assert_status_is_ok();
+    ///
+    /// let resp = cli
+    ///     .post("/")
+    ///     .content_type("text/plain")
+    ///     .body("hello")
+    ///     .send()
+    ///     .await;
+    /// resp.assert_status(StatusCode::UNSUPPORTED_MEDIA_TYPE);
+    /// resp.assert_header("accept-post", "application/json");
+    /// # });
+    /// ```
+    fn accept_content_types<I, T>(self, types: I) -> AcceptContentTypes<Self::Endpoint>
+    where
+        I: IntoIterator<Item = T>,
+        T: AsRef<str>,
+        Self: Sized,
+    {
+        AcceptContentTypes::new(self.into_endpoint(), types)
+    }
+
     /// Maps the response of this endpoint.
     ///
     /// # Example
@@ -582,6 +744,84 @@ pub trait EndpointExt: IntoEndpoint {
         Map::new(self.into_endpoint(), f)
     }
 
+    /// Maps every response of this endpoint, after the inner endpoint
+    /// resolves.
+    ///
+    /// # Errors
+    ///
+    /// The errors of the inner endpoint are converted into responses before
+    /// `f` is called, so `f` also receives the error responses and the
+    /// returned endpoint never fails. The middlewares and the error handlers
+    /// such as [`EndpointExt::catch_error`] applied outside of it only see the
+    /// mapped responses, so apply them to the inner endpoint to handle the
+    /// errors by type.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use poem::{
+    ///     endpoint::make_sync, http::HeaderValue, test::TestClient, EndpointExt, Response,
+    /// };
+    ///
+    /// let ep = make_sync(|_| "hello").map_response(|mut resp: Response| {
+    ///     resp.headers_mut()
+    ///         .insert("x-version", HeaderValue::from_static("1"));
+    ///     resp
+    /// });
+    ///
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let resp = TestClient::new(ep).get("/").send().await;
+    /// resp.assert_header("x-version", "1");
+    /// resp.assert_text("hello").await;
+    /// # });
+    /// ```
+    fn map_response<F, R>(self, f: F) -> MapResponse<Self::Endpoint, F>
+    where
+        F: Fn(Response) -> R + Send + Sync,
+        R: IntoResponse,
+        Self: Sized,
+    {
+        MapResponse::new(self.into_endpoint(), f)
+    }
+
+    /// Maps every response of this endpoint with an async function, after
+    /// the inner endpoint resolves.
+    ///
+    /// # Errors
+    ///
+    /// The errors of the inner endpoint are converted into responses before
+    /// `f` is called, the same as [`EndpointExt::map_response`], so the
+    /// middlewares and the error handlers applied outside of the returned
+    /// endpoint never see them.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use poem::{endpoint::make_sync, test::TestClient, EndpointExt, IntoResponse, Response};
+    /// use serde_json::json;
+    ///
+    /// let ep = make_sync(|_| "hello").map_response_async(|resp: Response| async move {
+    ///     let status = resp.status();
+    ///     let data = resp.into_body().into_string().await.unwrap_or_default();
+    ///     poem::web::Json(json!({ "code": status.as_u16(), "data": data })).with_status(status)
+    /// });
+    ///
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let resp = TestClient::new(ep).get("/").send().await;
+    /// resp.assert_status_is_ok();
+    /// resp.assert_json(json!({ "code": 200, "data": "hello" })).await;
+    /// # });
+    /// ```
+    fn map_response_async<F, Fut, R>(self, f: F) -> MapResponseAsync<Self::Endpoint, F>
+    where
+        F: Fn(Response) -> Fut + Send + Sync,
+        Fut: Future<Output = R> + Send,
+        R: IntoResponse,
+        Self: Sized,
+    {
+        MapResponseAsync::new(self.into_endpoint(), f)
+    }
+
     /// Calls `f` if the result is `Ok`, otherwise returns the `Err` value of
     /// self.
     ///
@@ -981,4 +1221,47 @@ mod test {
         resp.assert_status_is_ok();
         resp.assert_text("none").await;
     }
+
+    #[tokio::test]
+    async fn test_catch_panic() {
+        #[handler(internal)]
+        async fn index() {
+            panic!()
+        }
+
+        let cli = TestClient::new(index.catch_panic());
+        cli.get("/")
+            .send()
+            .await
+            .assert_status(StatusCode::INTERNAL_SERVER_ERROR);
+
+        let cli = TestClient::new(index.catch_panic_with(|_| "custom"));
+        let resp = cli.get("/").send().await;
+        resp.assert_status_is_ok();
+        resp.assert_text("custom").await;
+    }
+
+    #[tokio::test]
+    async fn test_blocking_handler() {
+        #[handler(internal, blocking)]
+        fn index(method: Method, body: String) -> String {
+            std::thread::sleep(std::time::Duration::from_millis(10));
+            format!("{method} {body}")
+        }
+
+        #[handler(internal, blocking)]
+        fn panicked() {
+            panic!()
+        }
+
+        let resp = TestClient::new(index).post("/").body("abc").send().await;
+        resp.assert_status_is_ok();
+        resp.assert_text("POST abc").await;
+
+        TestClient::new(panicked.catch_panic())
+            .get("/")
+            .send()
+            .await
+            .assert_status(StatusCode::INTERNAL_SERVER_ERROR);
+    }
 }
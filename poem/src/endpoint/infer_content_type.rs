@@ -0,0 +1,182 @@
+use crate::{
+    http::{header, StatusCode},
+    Body, Endpoint, IntoResponse, Request, Response, Result,
+};
+
+/// Marks the `application/octet-stream` type that is set by default for the
+/// binary responses, so it can be replaced by the sniffed type.
+#[derive(Clone)]
+pub(crate) struct DefaultContentType;
+
+/// Endpoint for the
+/// [`infer_content_type`](super::EndpointExt::infer_content_type) method.
+pub struct InferContentType<E> {
+    inner: E,
+}
+
+impl<E> InferContentType<E> {
+    #[inline]
+    pub(crate) fn new(inner: E) -> InferContentType<E> {
+        Self { inner }
+    }
+}
+
+/// Guesses the media type of the data from its leading bytes.
+fn sniff(data: &[u8]) -> &'static str {
+    const SIGNATURES: &[(&[u8], &str)] = &[
+        (b"\x89PNG\r\n\x1a\n", "image/png"),
+        (b"\xff\xd8\xff", "image/jpeg"),
+        (b"GIF87a", "image/gif"),
+        (b"GIF89a", "image/gif"),
+        (b"%PDF-", "application/pdf"),
+    ];
+
+    if let Some((_, mime)) = SIGNATURES
+        .iter()
+        .find(|(signature, _)| data.starts_with(signature))
+    {
+        return mime;
+    }
+
+    let text = data.trim_ascii_start();
+    let starts_with_ignore_case = |prefix: &[u8]| {
+        text.get(..prefix.len())
+            .is_some_and(|head| head.eq_ignore_ascii_case(prefix))
+    };
+    if starts_with_ignore_case(b"<!doctype html") || starts_with_ignore_case(b"<html") {
+        return "text/html; charset=utf-8";
+    }
+    if matches!(text.first(), Some(b'{' | b'['))
+        && serde_json::from_slice::<serde::de::IgnoredAny>(text).is_ok()
+    {
+        return "application/json";
+    }
+
+    "application/octet-stream"
+}
+
+impl<E: Endpoint> Endpoint for InferContentType<E> {
+    type Output = Response;
+
+    async fn call(&self, req: Request) -> Result<Self::Output> {
+        let mut resp = self.inner.call(req).await?.into_response();
+        if matches!(
+            resp.status(),
+            StatusCode::NO_CONTENT | StatusCode::NOT_MODIFIED
+        ) {
+            return Ok(resp);
+        }
+        if let Some(content_type) = resp.headers().get(header::CONTENT_TYPE) {
+            let is_default = content_type == "application/octet-stream"
+                && resp.extensions().get::<DefaultContentType>().is_some();
+            if !is_default {
+                return Ok(resp);
+            }
+        }
+
+        // only buffered bodies have an exact size, streaming bodies are left
+        // untouched to avoid reading them into memory.
+        let size_hint = hyper::body::Body::size_hint(&resp.body().0);
+        if size_hint.upper() != Some(size_hint.lower()) || size_hint.lower() == 0 {
+            return Ok(resp);
+        }
+
+        let data = resp.take_body().into_bytes().await?;
+        resp.headers_mut().insert(
+            header::CONTENT_TYPE,
+            header::HeaderValue::from_static(sniff(&data)),
+        );
+        resp.set_body(Body::from_bytes(data));
+        Ok(resp)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures_util::stream;
+
+    use super::*;
+    use crate::{endpoint::make_sync, test::TestClient, EndpointExt};
+
+    #[test]
+    fn test_sniff() {
+        assert_eq!(sniff(b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR"), "image/png");
+        assert_eq!(sniff(b"\xff\xd8\xff\xe0\0\x10JFIF"), "image/jpeg");
+        assert_eq!(sniff(b"GIF89a\x01\0"), "image/gif");
+        assert_eq!(sniff(b"%PDF-1.7\n"), "application/pdf");
+        assert_eq!(
+            sniff(b"\n  <!DOCTYPE html><html></html>"),
+            "text/html; charset=utf-8"
+        );
+        assert_eq!(sniff(b"<HTML><body></body>"), "text/html; charset=utf-8");
+        assert_eq!(sniff(br#" {"a": [1, 2]}"#), "application/json");
+        assert_eq!(sniff(b"{not json"), "application/octet-stream");
+        assert_eq!(sniff(b"\0\x01\x02"), "application/octet-stream");
+    }
+
+    #[tokio::test]
+    async fn test_infer_content_type() {
+        let cli = TestClient::new(
+            make_sync(|_| Body::from_vec(b"%PDF-1.4".to_vec())).infer_content_type(),
+        );
+        let resp = cli.get("/").send().await;
+        resp.assert_content_type("application/pdf");
+        resp.assert_bytes("%PDF-1.4").await;
+
+        let cli = TestClient::new(make_sync(|_| Body::empty()).infer_content_type());
+        let resp = cli.get("/").send().await;
+        resp.assert_header_is_not_exist(header::CONTENT_TYPE);
+        resp.assert_bytes("").await;
+
+        let cli = TestClient::new(
+            make_sync(|_| StatusCode::NOT_MODIFIED.with_body("%PDF-1.4")).infer_content_type(),
+        );
+        cli.get("/")
+            .send()
+            .await
+            .assert_header_is_not_exist(header::CONTENT_TYPE);
+
+        // the default type of the binary responses is replaced
+        let cli = TestClient::new(
+            make_sync(|_| b"<html><script></script></html>".to_vec()).infer_content_type(),
+        );
+        cli.get("/")
+            .send()
+            .await
+            .assert_content_type("text/html; charset=utf-8");
+
+        // an explicit `application/octet-stream` is never upgraded
+        let cli = TestClient::new(
+            make_sync(|_| {
+                Response::builder()
+                    .content_type("application/octet-stream")
+                    .body("%PDF-1.4")
+            })
+            .infer_content_type(),
+        );
+        cli.get("/")
+            .send()
+            .await
+            .assert_content_type("application/octet-stream");
+
+        let cli = TestClient::new(
+            make_sync(|_| {
+                Response::builder()
+                    .content_type("text/plain")
+                    .body(r#"{"a":1}"#)
+            })
+            .infer_content_type(),
+        );
+        cli.get("/").send().await.assert_content_type("text/plain");
+
+        let cli = TestClient::new(
+            make_sync(|_| {
+                Body::from_bytes_stream(stream::iter([Ok::<_, std::io::Error>("%PDF-1.4")]))
+            })
+            .infer_content_type(),
+        );
+        let resp = cli.get("/").send().await;
+        resp.assert_header_is_not_exist(header::CONTENT_TYPE);
+        resp.assert_bytes("%PDF-1.4").await;
+    }
+}
@@ -0,0 +1,116 @@
+use std::future::Future;
+
+use crate::{Endpoint, IntoResponse, Request, Response, Result};
+
+/// Endpoint for the [`map_response`](super::EndpointExt::map_response)
+/// method.
+///
+/// It never returns an error, the errors of the inner endpoint are converted
+/// into responses and mapped.
+pub struct MapResponse<E, F> {
+    inner: E,
+    f: F,
+}
+
+impl<E, F> MapResponse<E, F> {
+    #[inline]
+    pub(crate) fn new(inner: E, f: F) -> MapResponse<E, F> {
+        Self { inner, f }
+    }
+}
+
+impl<E, F, R> Endpoint for MapResponse<E, F>
+where
+    E: Endpoint,
+    F: Fn(Response) -> R + Send + Sync,
+    R: IntoResponse,
+{
+    type Output = Response;
+
+    async fn call(&self, req: Request) -> Result<Self::Output> {
+        let resp = self.inner.get_response(req).await;
+        Ok((self.f)(resp).into_response())
+    }
+}
+
+/// Endpoint for the
+/// [`map_response_async`](super::EndpointExt::map_response_async) method.
+///
+/// It never returns an error, the errors of the inner endpoint are converted
+/// into responses and mapped.
+pub struct MapResponseAsync<E, F> {
+    inner: E,
+    f: F,
+}
+
+impl<E, F> MapResponseAsync<E, F> {
+    #[inline]
+    pub(crate) fn new(inner: E, f: F) -> MapResponseAsync<E, F> {
+        Self { inner, f }
+    }
+}
+
+impl<E, F, Fut, R> Endpoint for MapResponseAsync<E, F>
+where
+    E: Endpoint,
+    F: Fn(Response) -> Fut + Send + Sync,
+    Fut: Future<Output = R> + Send,
+    R: IntoResponse,
+{
+    type Output = Response;
+
+    async fn call(&self, req: Request) -> Result<Self::Output> {
+        let resp = self.inner.get_response(req).await;
+        Ok((self.f)(resp).await.into_response())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use http::{HeaderValue, StatusCode};
+
+    use crate::{
+        endpoint::make_sync, error::NotFoundError, test::TestClient, EndpointExt, Error,
+        IntoResponse, Response,
+    };
+
+    #[tokio::test]
+    async fn map_response() {
+        let cli = TestClient::new(make_sync(|_| "hello").map_response(|mut resp: Response| {
+            resp.headers_mut()
+                .insert("x-mapped", HeaderValue::from_static("1"));
+            resp
+        }));
+        let resp = cli.get("/").send().await;
+        resp.assert_status_is_ok();
+        resp.assert_header("x-mapped", "1");
+        resp.assert_text("hello").await;
+
+        // the errors are mapped as responses
+        let cli = TestClient::new(
+            make_sync(|_| Err::<(), _>(Error::from_status(StatusCode::BAD_REQUEST)))
+                .map_response(|resp: Response| format!("status: {}", resp.status().as_u16())),
+        );
+        let resp = cli.get("/").send().await;
+        resp.assert_status_is_ok();
+        resp.assert_text("status: 400").await;
+    }
+
+    #[tokio::test]
+    async fn map_response_async() {
+        let cli = TestClient::new(
+            make_sync(|_| Err::<(), _>(NotFoundError))
+                .catch_error(|_: NotFoundError| async {
+                    "custom not found".with_status(StatusCode::NOT_FOUND)
+                })
+                .map_response_async(|resp: Response| async move {
+                    let status = resp.status();
+                    let body = resp.into_body().into_string().await.unwrap();
+                    format!("{}: {}", status.as_u16(), body).with_status(status)
+                }),
+        );
+        let resp = cli.get("/").send().await;
+        resp.assert_status(StatusCode::NOT_FOUND);
+        resp.assert_text("404: custom not found").await;
+    }
+}
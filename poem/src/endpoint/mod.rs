@@ -1,5 +1,6 @@
 //! Endpoint related types.
 
+mod accept_content_types;
 mod after;
 mod and_then;
 mod around;
@@ -10,18 +11,22 @@ mod catch_error;
 mod embed;
 #[allow(clippy::module_inception)]
 mod endpoint;
+mod infer_content_type;
 mod inspect_all_err;
 mod inspect_err;
 mod map;
+mod map_response;
 mod map_to_response;
 #[cfg(feature = "prometheus")]
 mod prometheus_exporter;
+mod split;
 #[cfg(feature = "static-files")]
 mod static_files;
 mod to_response;
 #[cfg(feature = "tower-compat")]
 mod tower_compat;
 
+pub use accept_content_types::AcceptContentTypes;
 pub use after::After;
 pub use and_then::AndThen;
 pub use around::Around;
@@ -31,17 +36,21 @@ pub use catch_error::CatchError;
 #[cfg(feature = "embed")]
 pub use embed::{EmbeddedFileEndpoint, EmbeddedFilesEndpoint};
 pub use endpoint::{
-    make, make_sync, BoxEndpoint, DynEndpoint, EitherEndpoint, Endpoint, EndpointExt, IntoEndpoint,
-    ToDynEndpoint,
+    make, make_sync, run_blocking, BoxEndpoint, DynEndpoint, EitherEndpoint, Endpoint, EndpointExt,
+    IntoEndpoint, ToDynEndpoint,
 };
+pub(crate) use infer_content_type::DefaultContentType;
+pub use infer_content_type::InferContentType;
 pub use inspect_all_err::InspectAllError;
 pub use inspect_err::InspectError;
 pub use map::Map;
+pub use map_response::{MapResponse, MapResponseAsync};
 pub use map_to_response::MapToResponse;
 #[cfg(feature = "prometheus")]
 pub use prometheus_exporter::PrometheusExporter;
+pub use split::{split, Split, SplitVariant};
 #[cfg(feature = "static-files")]
-pub use static_files::{StaticFileEndpoint, StaticFilesEndpoint};
+pub use static_files::{SpaEndpoint, StaticFileEndpoint, StaticFilesEndpoint};
 pub use to_response::ToResponse;
 #[cfg(feature = "tower-compat")]
 pub use tower_compat::TowerCompatExt;
@@ -0,0 +1,279 @@
+use std::sync::{
+    atomic::{AtomicU64, Ordering},
+    Arc,
+};
+
+use crate::{
+    http::{header::HeaderName, HeaderValue},
+    Endpoint, IntoEndpoint, Request, Response, Result,
+};
+
+type KeyFn = Arc<dyn Fn(&Request) -> Option<String> + Send + Sync>;
+type PredicateFn = Arc<dyn Fn(&Request) -> bool + Send + Sync>;
+
+/// The variant chosen by the [`split`] endpoint, it is added to the
+/// extensions of the request.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum SplitVariant {
+    /// The request is sent to the first endpoint.
+    A,
+    /// The request is sent to the second endpoint.
+    B,
+}
+
+impl SplitVariant {
+    /// Returns the name of the variant, `a` or `b`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SplitVariant::A => "a",
+            SplitVariant::B => "b",
+        }
+    }
+}
+
+/// Create an endpoint that sends a fraction of the requests to the endpoint
+/// `b`, and the other requests to the endpoint `a`, e.g. to roll out a new
+/// version gradually or to run an A/B experiment.
+///
+/// The variant is chosen by hashing the key returned by [`Split::key`], such
+/// as a user id, so the requests with the same key always get the same
+/// variant, and the requests without a key are spread evenly. The requests
+/// matching [`Split::when`] are always sent to `b`.
+///
+/// The chosen [`SplitVariant`] is added to the extensions of the request, and
+/// its name is sent in the `x-variant` response header. The errors of the
+/// endpoints are converted into responses, so the header is also sent with
+/// the error responses.
+///
+/// # Example
+///
+/// ```
+/// use poem::{endpoint::split, handler, test::TestClient};
+///
+/// #[handler]
+/// fn old_checkout() -> &'static str {
+///     "old"
+/// }
+///
+/// #[handler]
+/// fn new_checkout() -> &'static str {
+///     "new"
+/// }
+///
+/// let ep = split(old_checkout, new_checkout)
+///     .fraction(0.1)
+///     .key(|req| req.header("x-user-id").map(ToString::to_string))
+///     .when(|req| req.header("x-beta").is_some());
+/// let cli = TestClient::new(ep);
+///
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// let resp = cli.get("/").header("x-beta", "1").send().await;
+/// resp.assert_header("x-variant", "b");
+/// resp.assert_text("new").await;
+/// # });
+/// ```
+pub fn split<A, B>(a: A, b: B) -> Split<A::Endpoint, B::Endpoint>
+where
+    A: IntoEndpoint,
+    B: IntoEndpoint,
+{
+    Split {
+        a: a.into_endpoint(),
+        b: b.into_endpoint(),
+        fraction: 0.5,
+        key: None,
+        when: None,
+        header: Some(HeaderName::from_static("x-variant")),
+        counter: AtomicU64::new(0),
+    }
+}
+
+/// Endpoint for the [`split`] function.
+pub struct Split<A, B> {
+    a: A,
+    b: B,
+    fraction: f64,
+    key: Option<KeyFn>,
+    when: Option<PredicateFn>,
+    header: Option<HeaderName>,
+    counter: AtomicU64,
+}
+
+impl<A, B> Split<A, B> {
+    /// Sets the fraction of the requests that are sent to the endpoint `b`,
+    /// between `0.0` and `1.0`.
+    ///
+    /// Default is `0.5`.
+    #[must_use]
+    pub fn fraction(self, fraction: f64) -> Self {
+        Self {
+            fraction: fraction.clamp(0.0, 1.0),
+            ..self
+        }
+    }
+
+    /// Sets a function that returns a stable key of the request, such as a
+    /// user id from a cookie, the requests with the same key get the same
+    /// variant.
+    #[must_use]
+    pub fn key(self, f: impl Fn(&Request) -> Option<String> + Send + Sync + 'static) -> Self {
+        Self {
+            key: Some(Arc::new(f)),
+            ..self
+        }
+    }
+
+    /// Sets a predicate, the requests matching it are always sent to the
+    /// endpoint `b`, e.g. the requests with a header or a cookie opting in.
+    #[must_use]
+    pub fn when(self, f: impl Fn(&Request) -> bool + Send + Sync + 'static) -> Self {
+        Self {
+            when: Some(Arc::new(f)),
+            ..self
+        }
+    }
+
+    /// Sets the name of the response header containing the chosen variant,
+    /// `None` to not send it.
+    ///
+    /// Default is `x-variant`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the name is not a valid header name.
+    #[must_use]
+    pub fn variant_header(self, name: Option<&str>) -> Self {
+        Self {
+            header: name.map(|name| HeaderName::try_from(name).unwrap()),
+            ..self
+        }
+    }
+
+    fn choose(&self, req: &Request) -> SplitVariant {
+        if self.when.as_ref().is_some_and(|when| when(req)) {
+            return SplitVariant::B;
+        }
+
+        let hash = match self.key.as_ref().and_then(|key| key(req)) {
+            Some(key) => hash(key.as_bytes()),
+            None => hash(&self.counter.fetch_add(1, Ordering::Relaxed).to_le_bytes()),
+        };
+        // the top 53 bits of the hash as a number in `[0, 1)`
+        let point = (hash >> 11) as f64 / (1u64 << 53) as f64;
+        if point < self.fraction {
+            SplitVariant::B
+        } else {
+            SplitVariant::A
+        }
+    }
+}
+
+/// FNV-1a, which is stable across processes and versions unlike the default
+/// hasher of the standard library, followed by a finalizer that spreads the
+/// bits of the short keys.
+fn hash(data: &[u8]) -> u64 {
+    let mut hash = 0xcbf2_9ce4_8422_2325u64;
+    for byte in data {
+        hash ^= u64::from(*byte);
+        hash = hash.wrapping_mul(0x0100_0000_01b3);
+    }
+    hash ^= hash >> 33;
+    hash = hash.wrapping_mul(0xff51_afd7_ed55_8ccd);
+    hash ^= hash >> 33;
+    hash
+}
+
+impl<A: Endpoint, B: Endpoint> Endpoint for Split<A, B> {
+    type Output = Response;
+
+    async fn call(&self, mut req: Request) -> Result<Self::Output> {
+        let variant = self.choose(&req);
+        req.extensions_mut().insert(variant);
+
+        let mut resp = match variant {
+            SplitVariant::A => self.a.get_response(req).await,
+            SplitVariant::B => self.b.get_response(req).await,
+        };
+        if let Some(header) = &self.header {
+            resp.headers_mut()
+                .insert(header.clone(), HeaderValue::from_static(variant.as_str()));
+        }
+        Ok(resp)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{endpoint::make_sync, handler, http::StatusCode, test::TestClient, web::Data};
+
+    fn variant_ep() -> impl Endpoint<Output = String> {
+        make_sync(|req| {
+            req.extensions()
+                .get::<SplitVariant>()
+                .unwrap()
+                .as_str()
+                .to_string()
+        })
+    }
+
+    #[tokio::test]
+    async fn stable_key() {
+        let cli = TestClient::new(
+            split(variant_ep(), variant_ep())
+                .fraction(0.3)
+                .key(|req| req.header("x-user-id").map(ToString::to_string)),
+        );
+
+        let mut count_b = 0;
+        for id in 0..1000 {
+            let resp = cli.get("/").header("x-user-id", id).send().await;
+            let variant = resp.0.headers().get("x-variant").unwrap().clone();
+            if variant == "b" {
+                count_b += 1;
+            }
+            resp.assert_text(variant.to_str().unwrap()).await;
+
+            // the same key always gets the same variant
+            cli.get("/")
+                .header("x-user-id", id)
+                .send()
+                .await
+                .assert_header("x-variant", variant);
+        }
+        assert!((250..350).contains(&count_b), "{count_b}");
+    }
+
+    #[tokio::test]
+    async fn fraction_and_predicate() {
+        let cli = TestClient::new(
+            split(variant_ep(), variant_ep())
+                .fraction(0.0)
+                .when(|req| req.header("x-beta").is_some())
+                .variant_header(None),
+        );
+        for _ in 0..10 {
+            cli.get("/").send().await.assert_text("a").await;
+        }
+        let resp = cli.get("/").header("x-beta", "1").send().await;
+        resp.assert_header_is_not_exist("x-variant");
+        resp.assert_text("b").await;
+
+        let cli = TestClient::new(split(variant_ep(), variant_ep()).fraction(1.0));
+        for _ in 0..10 {
+            cli.get("/").send().await.assert_text("b").await;
+        }
+    }
+
+    #[tokio::test]
+    async fn error_response() {
+        // fails because the data is missing
+        #[handler(internal)]
+        fn missing_data(_: Data<&i32>) {}
+
+        let cli = TestClient::new(split(variant_ep(), missing_data).fraction(1.0));
+        let resp = cli.get("/").send().await;
+        resp.assert_status(StatusCode::INTERNAL_SERVER_ERROR);
+        resp.assert_header("x-variant", "b");
+    }
+}
@@ -4,7 +4,7 @@ use std::{
     path::{Path, PathBuf},
 };
 
-use http::header::LOCATION;
+use http::{header::LOCATION, HeaderValue};
 
 use crate::{
     error::StaticFileError,
@@ -66,6 +66,9 @@ struct FileRef {
 
 /// Static files handling service.
 ///
+/// The `Range` and conditional requests are supported, see
+/// [`StaticFileRequest`].
+///
 /// # Errors
 ///
 /// - [`StaticFileError`]
@@ -161,6 +164,37 @@ impl StaticFilesEndpoint {
     }
 }
 
+/// Decodes the request path and resolves it relative to the base directory.
+fn resolve_path(base: &Path, req: &Request) -> Result<(String, PathBuf), StaticFileError> {
+    let path = req
+        .uri()
+        .path()
+        .trim_start_matches('/')
+        .trim_end_matches('/');
+
+    let path = percent_encoding::percent_decode_str(path)
+        .decode_utf8()
+        .map_err(|_| StaticFileError::InvalidPath)?
+        .into_owned();
+
+    let mut file_path = base.to_path_buf();
+    for p in Path::new(&path) {
+        if p == OsStr::new(".") {
+            continue;
+        } else if p == OsStr::new("..") {
+            file_path.pop();
+        } else {
+            file_path.push(p);
+        }
+    }
+
+    if !file_path.starts_with(base) {
+        return Err(StaticFileError::Forbidden(file_path.display().to_string()));
+    }
+
+    Ok((path, file_path))
+}
+
 impl Endpoint for StaticFilesEndpoint {
     type Output = Response;
 
@@ -169,30 +203,7 @@ impl Endpoint for StaticFilesEndpoint {
             return Err(StaticFileError::MethodNotAllowed(req.method().clone()).into());
         }
 
-        let path = req
-            .uri()
-            .path()
-            .trim_start_matches('/')
-            .trim_end_matches('/');
-
-        let path = percent_encoding::percent_decode_str(path)
-            .decode_utf8()
-            .map_err(|_| StaticFileError::InvalidPath)?;
-
-        let mut file_path = self.path.clone();
-        for p in Path::new(&*path) {
-            if p == OsStr::new(".") {
-                continue;
-            } else if p == OsStr::new("..") {
-                file_path.pop();
-            } else {
-                file_path.push(p);
-            }
-        }
-
-        if !file_path.starts_with(&self.path) {
-            return Err(StaticFileError::Forbidden(file_path.display().to_string()).into());
-        }
+        let (path, file_path) = resolve_path(&self.path, &req)?;
 
         if !file_path.exists() {
             if self.fallback_to_index {
@@ -276,6 +287,9 @@ impl Endpoint for StaticFilesEndpoint {
 
 /// Single static file handling service.
 ///
+/// The `Range` and conditional requests are supported, see
+/// [`StaticFileRequest`].
+///
 /// # Errors
 ///
 /// - [`StaticFileError`]
@@ -324,3 +338,201 @@ impl Endpoint for StaticFileEndpoint {
             .into_response())
     }
 }
+
+/// Single-page application handling service.
+///
+/// Serves the static files in the base directory, and falls back to the index
+/// file for the paths that do not match a file, so that the client-side
+/// router can handle them. Missing files whose last path segment has an
+/// extension (such as `/assets/app.js`) are still reported as not found.
+///
+/// The index file is served with `Cache-Control: no-cache` so that the
+/// browser always revalidates it, the other files can be served with a
+/// separate `Cache-Control` value set by [`SpaEndpoint::assets_cache_control`].
+///
+/// # Errors
+///
+/// - [`StaticFileError`]
+#[cfg_attr(docsrs, doc(cfg(feature = "static-files")))]
+pub struct SpaEndpoint {
+    path: PathBuf,
+    index_file: String,
+    index_cache_control: Option<HeaderValue>,
+    assets_cache_control: Option<HeaderValue>,
+    prefer_utf8: bool,
+}
+
+impl SpaEndpoint {
+    /// Create new single-page application service for a specified base
+    /// directory.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use poem::{endpoint::SpaEndpoint, Route};
+    ///
+    /// let app = Route::new().nest(
+    ///     "/",
+    ///     SpaEndpoint::new("./dist")
+    ///         .assets_cache_control("public, max-age=31536000, immutable"),
+    /// );
+    /// ```
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            index_file: "index.html".to_string(),
+            index_cache_control: Some(HeaderValue::from_static("no-cache")),
+            assets_cache_control: None,
+            prefer_utf8: true,
+        }
+    }
+
+    /// Sets the index file that is served for the paths handled by the
+    /// client-side router.
+    ///
+    /// Default is `index.html`.
+    #[must_use]
+    pub fn index_file(self, index: impl Into<String>) -> Self {
+        Self {
+            index_file: index.into(),
+            ..self
+        }
+    }
+
+    /// Sets the `Cache-Control` header of the index file.
+    ///
+    /// Default is `no-cache`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the value is not a valid header value.
+    #[must_use]
+    pub fn index_cache_control(self, value: impl AsRef<str>) -> Self {
+        Self {
+            index_cache_control: Some(HeaderValue::from_str(value.as_ref()).unwrap()),
+            ..self
+        }
+    }
+
+    /// Sets the `Cache-Control` header of the files other than the index
+    /// file.
+    ///
+    /// Most bundlers add a content hash to the names of the generated assets,
+    /// so they can be cached forever with `public, max-age=31536000,
+    /// immutable`.
+    ///
+    /// By default no `Cache-Control` header is set.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the value is not a valid header value.
+    #[must_use]
+    pub fn assets_cache_control(self, value: impl AsRef<str>) -> Self {
+        Self {
+            assets_cache_control: Some(HeaderValue::from_str(value.as_ref()).unwrap()),
+            ..self
+        }
+    }
+
+    /// Specifies whether text responses should signal a UTF-8 encoding.
+    ///
+    /// Default is `true`.
+    #[must_use]
+    pub fn prefer_utf8(self, value: bool) -> Self {
+        Self {
+            prefer_utf8: value,
+            ..self
+        }
+    }
+
+    async fn serve(
+        &self,
+        req: &Request,
+        path: &Path,
+        cache_control: &Option<HeaderValue>,
+    ) -> Result<Response> {
+        let mut resp = StaticFileRequest::from_request_without_body(req)
+            .await?
+            .create_response(path, self.prefer_utf8)?
+            .into_response();
+        if let Some(value) = cache_control {
+            resp.headers_mut()
+                .insert(header::CACHE_CONTROL, value.clone());
+        }
+        Ok(resp)
+    }
+}
+
+impl Endpoint for SpaEndpoint {
+    type Output = Response;
+
+    async fn call(&self, req: Request) -> Result<Self::Output> {
+        if req.method() != Method::GET && req.method() != Method::HEAD {
+            return Err(StaticFileError::MethodNotAllowed(req.method().clone()).into());
+        }
+
+        let (path, file_path) = resolve_path(&self.path, &req)?;
+        let index_path = self.path.join(&self.index_file);
+
+        if file_path.is_file() && file_path != index_path {
+            return self
+                .serve(&req, &file_path, &self.assets_cache_control)
+                .await;
+        }
+
+        let is_asset = path
+            .rsplit('/')
+            .next()
+            .is_some_and(|segment| segment.contains('.'));
+        if is_asset && !file_path.is_file() {
+            return Err(StaticFileError::NotFound.into());
+        }
+
+        if !index_path.is_file() {
+            return Err(StaticFileError::NotFound.into());
+        }
+        self.serve(&req, &index_path, &self.index_cache_control)
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test::TestClient;
+
+    #[tokio::test]
+    async fn test_spa_endpoint() {
+        let dir = std::env::temp_dir().join(format!("poem-spa-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("assets")).unwrap();
+        std::fs::write(dir.join("index.html"), "index").unwrap();
+        std::fs::write(dir.join("assets/app.1234.js"), "app").unwrap();
+
+        let cli = TestClient::new(
+            SpaEndpoint::new(&dir).assets_cache_control("public, max-age=31536000, immutable"),
+        );
+
+        for path in ["/", "/users/1", "/assets", "/index.html"] {
+            let resp = cli.get(path).send().await;
+            resp.assert_status_is_ok();
+            resp.assert_header(header::CACHE_CONTROL, "no-cache");
+            resp.assert_text("index").await;
+        }
+
+        let resp = cli.get("/assets/app.1234.js").send().await;
+        resp.assert_status_is_ok();
+        resp.assert_header(header::CACHE_CONTROL, "public, max-age=31536000, immutable");
+        resp.assert_text("app").await;
+
+        cli.get("/assets/missing.js")
+            .send()
+            .await
+            .assert_status(StatusCode::NOT_FOUND);
+        cli.post("/")
+            .send()
+            .await
+            .assert_status(StatusCode::METHOD_NOT_ALLOWED);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -90,6 +90,10 @@ impl AsResponse {
     }
 }
 
+#[derive(Debug, thiserror::Error)]
+#[error("{0}")]
+struct StringError(String);
+
 /// General response error.
 ///
 /// # Create from any error types
@@ -365,10 +369,6 @@ impl Error {
 
     /// Create a new error object from a string with a status code.
     pub fn from_string(msg: impl Into<String>, status: StatusCode) -> Self {
-        #[derive(Debug, thiserror::Error)]
-        #[error("{0}")]
-        struct StringError(String);
-
         Self::new(StringError(msg.into()), status)
     }
 
@@ -504,6 +504,15 @@ impl Error {
     pub fn set_error_message(&mut self, msg: impl Into<String>) {
         self.msg = Some(msg.into());
     }
+
+    /// Returns `true` if the response body of this error is the message of an
+    /// error that does not implement [`ResponseError`], such as the errors
+    /// created by [`Error::new`] or converted from `anyhow::Error`.
+    pub(crate) fn is_opaque(&self) -> bool {
+        matches!(self.as_response, AsResponse::Status(_))
+            && self.msg.is_none()
+            && !self.is::<StringError>()
+    }
 }
 
 define_http_error!(
@@ -654,6 +663,27 @@ define_simple_errors!(
 
     /// Error occurred in the router.
     (MethodNotAllowedError, METHOD_NOT_ALLOWED, "method not allowed");
+
+    /// The host of the request cannot be determined.
+    (MissingHostError, BAD_REQUEST, "missing host");
+
+    /// The request was not matched by a [`Route`](crate::Route), see [`MatchedPath`](crate::web::MatchedPath).
+    (MissingMatchedPathError, INTERNAL_SERVER_ERROR, "the request was not matched by a route");
+
+    /// An upstream call did not complete in time, see [`with_upstream_timeout`](crate::web::with_upstream_timeout).
+    (GatewayTimeoutError, GATEWAY_TIMEOUT, "gateway timeout");
+
+    /// The circuit breaker is open, see [`CircuitBreaker`](crate::middleware::CircuitBreaker).
+    (CircuitOpenError, SERVICE_UNAVAILABLE, "circuit breaker is open");
+
+    /// The body is longer than the limit, see [`Body::limit`](crate::Body::limit).
+    (PayloadTooLargeError, PAYLOAD_TOO_LARGE, "payload too large");
+
+    /// The queue of the endpoint is full, see [`Queue`](crate::middleware::Queue).
+    (QueueFullError, SERVICE_UNAVAILABLE, "the queue is full");
+
+    /// The request waited in the queue for too long, see [`Queue`](crate::middleware::Queue).
+    (QueueTimeoutError, SERVICE_UNAVAILABLE, "timed out waiting in the queue");
 );
 
 /// A possible error value when reading the body.
@@ -681,6 +711,7 @@ impl ResponseError for ReadBodyError {
         match self {
             ReadBodyError::BodyHasBeenTaken => StatusCode::INTERNAL_SERVER_ERROR,
             ReadBodyError::Utf8(_) => StatusCode::BAD_REQUEST,
+            ReadBodyError::Io(err) if is_payload_too_large(err) => StatusCode::PAYLOAD_TOO_LARGE,
             ReadBodyError::Io(_) => StatusCode::BAD_REQUEST,
             ReadBodyError::PayloadTooLarge => StatusCode::PAYLOAD_TOO_LARGE,
         }
@@ -843,6 +874,123 @@ impl ResponseError for ParseYamlError {
     }
 }
 
+/// A possible error value when parsing CSV.
+#[cfg(feature = "csv")]
+#[derive(Debug, thiserror::Error)]
+pub enum ParseCsvError {
+    /// Invalid content type.
+    #[error("invalid content type `{0}`, expect: `text/csv`")]
+    InvalidContentType(String),
+
+    /// `Content-Type` header is required.
+    #[error("expect content type `text/csv`")]
+    ContentTypeRequired,
+
+    /// Csv parse error.
+    #[error("parse error: {0}")]
+    Parse(#[from] csv::Error),
+}
+
+#[cfg(feature = "csv")]
+impl ResponseError for ParseCsvError {
+    fn status(&self) -> StatusCode {
+        match self {
+            ParseCsvError::InvalidContentType(_) => StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            ParseCsvError::ContentTypeRequired => StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            ParseCsvError::Parse(_) => StatusCode::BAD_REQUEST,
+        }
+    }
+}
+
+/// A possible error value when validating a JWT.
+#[cfg(feature = "jwt")]
+#[cfg_attr(docsrs, doc(cfg(feature = "jwt")))]
+#[derive(Debug, thiserror::Error)]
+pub enum JwtError {
+    /// The `JwtConfig` is not attached to the endpoint.
+    #[error("missing jwt config")]
+    ConfigRequired,
+
+    /// The `Authorization` header does not contain a bearer token.
+    #[error("missing bearer token")]
+    MissingToken,
+
+    /// The token has expired.
+    #[error("token has expired")]
+    Expired,
+
+    /// The token is not valid yet.
+    #[error("token is not valid yet")]
+    NotYetValid,
+
+    /// The signature of the token is invalid.
+    #[error("invalid token signature")]
+    InvalidSignature,
+
+    /// The algorithm of the token is not accepted.
+    #[error("invalid token algorithm")]
+    InvalidAlgorithm,
+
+    /// The audience of the token is not expected.
+    #[error("invalid token audience")]
+    InvalidAudience,
+
+    /// The issuer of the token is not expected.
+    #[error("invalid token issuer")]
+    InvalidIssuer,
+
+    /// No verification key matches the key id of the token.
+    #[error("unknown token key id")]
+    UnknownKey,
+
+    /// The token is malformed.
+    #[error("invalid token: {0}")]
+    Invalid(String),
+}
+
+#[cfg(feature = "jwt")]
+impl ResponseError for JwtError {
+    fn status(&self) -> StatusCode {
+        match self {
+            JwtError::ConfigRequired => StatusCode::INTERNAL_SERVER_ERROR,
+            _ => StatusCode::UNAUTHORIZED,
+        }
+    }
+}
+
+/// A possible error value occurred in the `VerifyDigest` middleware.
+#[cfg(feature = "digest")]
+#[cfg_attr(docsrs, doc(cfg(feature = "digest")))]
+#[derive(Debug, thiserror::Error)]
+pub enum DigestError {
+    /// The request does not contain a digest of a supported algorithm.
+    #[error("missing digest header")]
+    MissingDigest,
+
+    /// The digest header is malformed.
+    #[error("invalid digest header: {0}")]
+    InvalidDigest(String),
+
+    /// The digest of the request body does not match the header.
+    #[error("digest mismatch")]
+    Mismatch,
+
+    /// Failed to read the request body.
+    #[error(transparent)]
+    ReadBody(#[from] ReadBodyError),
+}
+
+#[cfg(feature = "digest")]
+impl ResponseError for DigestError {
+    fn status(&self) -> StatusCode {
+        match self {
+            DigestError::MissingDigest | DigestError::InvalidDigest(_) => StatusCode::BAD_REQUEST,
+            DigestError::Mismatch => StatusCode::UNPROCESSABLE_ENTITY,
+            DigestError::ReadBody(err) => err.status(),
+        }
+    }
+}
+
 /// A possible error value when parsing query.
 #[derive(Debug, thiserror::Error)]
 #[error(transparent)]
@@ -854,6 +1002,19 @@ impl ResponseError for ParseQueryError {
     }
 }
 
+/// A possible error value when none of the extractors of
+/// [`AnyOf`](crate::web::AnyOf) succeeds, it contains the error of each
+/// extractor.
+#[derive(Debug, thiserror::Error)]
+#[error("none of the extractors succeeded: {}", .0.iter().map(ToString::to_string).collect::<Vec<_>>().join("; "))]
+pub struct AnyOfError(pub Vec<Error>);
+
+impl ResponseError for AnyOfError {
+    fn status(&self) -> StatusCode {
+        StatusCode::UNAUTHORIZED
+    }
+}
+
 /// A possible error value when parsing multipart.
 #[cfg(feature = "multipart")]
 #[cfg_attr(docsrs, doc(cfg(feature = "multipart")))]
@@ -1036,6 +1197,47 @@ impl ResponseError for SizedLimitError {
     }
 }
 
+/// A possible error value occurred in the
+/// [`AcceptContentTypes`](crate::endpoint::AcceptContentTypes) endpoint.
+#[derive(Debug, thiserror::Error, Eq, PartialEq)]
+pub enum UnsupportedContentTypeError {
+    /// The `Content-Type` of the request is not accepted.
+    #[error("unsupported content type `{content_type}`, expect: `{accept}`")]
+    Unsupported {
+        /// The `Content-Type` of the request.
+        content_type: String,
+        /// The accepted content types, separated by commas.
+        accept: String,
+    },
+
+    /// `Content-Type` header is required.
+    #[error("expect content type `{accept}`")]
+    ContentTypeRequired {
+        /// The accepted content types, separated by commas.
+        accept: String,
+    },
+}
+
+impl ResponseError for UnsupportedContentTypeError {
+    fn status(&self) -> StatusCode {
+        StatusCode::UNSUPPORTED_MEDIA_TYPE
+    }
+
+    fn as_response(&self) -> Response {
+        let accept = match self {
+            UnsupportedContentTypeError::Unsupported { accept, .. }
+            | UnsupportedContentTypeError::ContentTypeRequired { accept } => accept,
+        };
+        let mut resp = self.to_string().into_response();
+        resp.set_status(self.status());
+        if let Ok(value) = http::HeaderValue::from_str(accept) {
+            resp.headers_mut()
+                .insert(http::HeaderName::from_static("accept-post"), value);
+        }
+        resp
+    }
+}
+
 /// A possible error value occurred when adding a route.
 #[derive(Debug, thiserror::Error, Eq, PartialEq)]
 pub enum RouteError {
@@ -1047,6 +1249,10 @@ pub enum RouteError {
     #[error("duplicate path: {0}")]
     Duplicate(String),
 
+    /// Duplicate route name
+    #[error("duplicate route name: {0}")]
+    DuplicateName(String),
+
     /// Invalid regex in path
     #[error("invalid regex in path: {path}")]
     InvalidRegex {
@@ -1064,6 +1270,28 @@ impl ResponseError for RouteError {
     }
 }
 
+/// A possible error value occurred when building the URL of a named route.
+#[derive(Debug, thiserror::Error, Eq, PartialEq)]
+pub enum UrlForError {
+    /// The route name does not exist.
+    #[error("unknown route name: {0}")]
+    UnknownRoute(String),
+
+    /// A parameter of the path pattern is not supplied.
+    #[error("missing parameter: {0}")]
+    MissingParam(String),
+
+    /// The path pattern contains a parameter without a name.
+    #[error("unnamed parameter in path: {0}")]
+    UnnamedParam(String),
+}
+
+impl ResponseError for UrlForError {
+    fn status(&self) -> StatusCode {
+        StatusCode::INTERNAL_SERVER_ERROR
+    }
+}
+
 /// A possible error value occurred in the `Cors` middleware.
 #[derive(Debug, thiserror::Error, Eq, PartialEq)]
 pub enum CorsError {
@@ -1141,6 +1369,45 @@ impl ResponseError for RedisSessionError {
     }
 }
 
+/// The status code is chosen by the kind of the error, and the message,
+/// which can contain file paths or addresses, is not sent to the client.
+///
+/// | Kind                                       | Status                      |
+/// |--------------------------------------------|-----------------------------|
+/// | `NotFound`                                 | `404 Not Found`             |
+/// | `PermissionDenied`                         | `403 Forbidden`             |
+/// | `TimedOut`                                 | `504 Gateway Timeout`       |
+/// | `ConnectionRefused`, `ConnectionReset`     | `502 Bad Gateway`           |
+/// | others                                     | `500 Internal Server Error` |
+///
+/// The errors produced by a body exceeding [`Body::limit`](crate::Body::limit)
+/// are mapped to `413 Payload Too Large`.
+impl ResponseError for std::io::Error {
+    fn status(&self) -> StatusCode {
+        if is_payload_too_large(self) {
+            return StatusCode::PAYLOAD_TOO_LARGE;
+        }
+        match self.kind() {
+            std::io::ErrorKind::NotFound => StatusCode::NOT_FOUND,
+            std::io::ErrorKind::PermissionDenied => StatusCode::FORBIDDEN,
+            std::io::ErrorKind::TimedOut => StatusCode::GATEWAY_TIMEOUT,
+            std::io::ErrorKind::ConnectionRefused | std::io::ErrorKind::ConnectionReset => {
+                StatusCode::BAD_GATEWAY
+            }
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    fn as_response(&self) -> Response {
+        self.status().into_response()
+    }
+}
+
+fn is_payload_too_large(err: &std::io::Error) -> bool {
+    err.get_ref()
+        .is_some_and(|err| err.is::<PayloadTooLargeError>())
+}
+
 #[cfg(test)]
 mod tests {
     use std::io::{Error as IoError, ErrorKind};
@@ -1214,6 +1481,24 @@ mod tests {
         assert_eq!(err.into_response().status(), StatusCode::BAD_GATEWAY);
     }
 
+    #[test]
+    fn test_io_error() {
+        for (kind, status) in [
+            (ErrorKind::NotFound, StatusCode::NOT_FOUND),
+            (ErrorKind::PermissionDenied, StatusCode::FORBIDDEN),
+            (ErrorKind::TimedOut, StatusCode::GATEWAY_TIMEOUT),
+            (ErrorKind::ConnectionRefused, StatusCode::BAD_GATEWAY),
+            (ErrorKind::ConnectionReset, StatusCode::BAD_GATEWAY),
+            (ErrorKind::AlreadyExists, StatusCode::INTERNAL_SERVER_ERROR),
+        ] {
+            let err: Error = IoError::new(kind, "/secret/path").into();
+            assert_eq!(err.status(), status);
+            assert!(err.is::<IoError>());
+            assert_eq!(err.to_string(), "/secret/path");
+            assert_eq!(err.into_response().status(), status);
+        }
+    }
+
     #[tokio::test]
     async fn test_custom_as_response() {
         #[derive(Debug, thiserror::Error)]
@@ -64,6 +64,33 @@
 //! # });
 //! ```
 //!
+//! Handlers that do CPU-bound or blocking work can be declared with
+//! `#[handler(blocking)]`, then the extractors run on the async runtime and
+//! the function body runs on the blocking thread pool with
+//! [`tokio::task::spawn_blocking`]. The function must not be `async`, and its
+//! arguments must be `Send + 'static`, so use extractors such as
+//! `Data<Arc<T>>` rather than `Data<&T>`.
+//!
+//! ```
+//! use poem::{handler, test::TestClient};
+//!
+//! #[handler(blocking)]
+//! fn checksum(body: Vec<u8>) -> String {
+//!     body.iter().map(|b| *b as u32).sum::<u32>().to_string()
+//! }
+//!
+//! # tokio::runtime::Runtime::new().unwrap().block_on(async {
+//! let resp = TestClient::new(checksum).post("/").body("abc").send().await;
+//! resp.assert_status_is_ok();
+//! resp.assert_text("294").await;
+//! # });
+//! ```
+//!
+//! Checks that only need the request head, such as authorization, can be
+//! declared with `#[handler(passthrough)]`, which generates a middleware that
+//! passes the untouched request to the inner endpoint, see
+//! [`middleware::Passthrough`].
+//!
 //! # Extractors
 //!
 //! The extractor is used to extract something from the HTTP request.
@@ -232,6 +259,7 @@
 //! |------------------|--------------------------------|
 //! | server | Server and listener APIs(enable by default) |
 //! |compression  | Support decompress request body and compress response body |
+//! |zstd              | Support for the `zstd` algorithm of the compression, requires the C toolchain to build |
 //! |cookie            | Support for Cookie             |
 //! |csrf | Support for Cross-Site Request Forgery (CSRF) protection |
 //! |multipart         | Support for Multipart          |
@@ -256,7 +284,14 @@
 //! | embed  | Integrate with [`rust-embed`](https://crates.io/crates/rust-embed) crate. |
 //! | xml | Integrate with [`quick-xml`](https://crates.io/crates/quick-xml) crate. |
 //! | yaml | Integrate with [`serde-yaml`](https://crates.io/crates/serde-yaml) crate.                   |
+//! | csv | Integrate with [`csv`](https://crates.io/crates/csv) crate. |
+//! | jwt | Support for validating JWTs with [`jsonwebtoken`](https://crates.io/crates/jsonwebtoken) crate. |
+//! | listenfd | Support for systemd socket activation with [`listenfd`](https://crates.io/crates/listenfd) crate. |
+//! | http3 | Support for HTTP/3 over QUIC with [`quinn`](https://crates.io/crates/quinn) and [`h3`](https://crates.io/crates/h3) crates. |
+//! | digest | Support for verifying the request body digest. |
 //! |sonic-rs          | Uses [`sonic-rs`](https://github.com/cloudwego/sonic-rs) instead of `serde_json`. Pls, checkout `sonic-rs` requirements to properly enable `sonic-rs` capabilities |
+//! | json-preserve-order | Keeps the order of the keys of the JSON objects deserialized to `serde_json::Value`, see [`Json`](web::Json#precision-and-key-order). |
+//! | json-raw-value | Support for `Box<serde_json::value::RawValue>`, see [`Json`](web::Json#precision-and-key-order). |
 
 #![doc(html_favicon_url = "https://raw.githubusercontent.com/poem-web/poem/master/favicon.ico")]
 #![doc(html_logo_url = "https://raw.githubusercontent.com/poem-web/poem/master/logo.png")]
@@ -288,6 +323,8 @@ pub use http;
 
 mod addr;
 mod body;
+#[cfg(feature = "server")]
+mod connection_metrics;
 mod request;
 mod response;
 mod route;
@@ -295,17 +332,17 @@ mod route;
 mod server;
 
 pub use addr::Addr;
-pub use body::Body;
+pub use body::{Body, BodyOutcome, BodySender};
 pub use endpoint::{Endpoint, EndpointExt, IntoEndpoint};
 pub use error::{Error, Result};
 pub use middleware::Middleware;
 pub use poem_derive::handler;
-pub use request::{OnUpgrade, Request, RequestBuilder, RequestParts, Upgraded};
+pub use request::{OnUpgrade, Request, RequestBuilder, RequestParts, RequestTemplate, Upgraded};
 pub use response::{Response, ResponseBuilder, ResponseParts};
 pub use route::{
     connect, delete, get, head, options, patch, post, put, trace, PathPattern, Route, RouteDomain,
-    RouteMethod, RouteScheme,
+    RouteGroup, RouteMethod, RouteNames, RouteScheme,
 };
 #[cfg(feature = "server")]
-pub use server::Server;
+pub use server::{ConnectionInfo, Server};
 pub use web::{FromRequest, IntoResponse, RequestBody};
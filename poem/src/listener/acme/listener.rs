@@ -223,7 +223,7 @@ impl<T: Acceptor> Acceptor for AutoCertAcceptor<T> {
 
     async fn accept(&mut self) -> IoResult<(Self::Io, LocalAddr, RemoteAddr, Scheme)> {
         let (stream, local_addr, remote_addr, _) = self.inner.accept().await?;
-        let stream = HandshakeStream::new(self.acceptor.accept(stream));
+        let stream = HandshakeStream::new(&local_addr, self.acceptor.accept(stream));
         Ok((stream, local_addr, remote_addr, Scheme::HTTPS))
     }
 }
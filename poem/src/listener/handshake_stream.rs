@@ -5,11 +5,23 @@ use std::{
     task::{Context, Poll},
 };
 
+use bytes::Bytes;
 use futures_util::{future::BoxFuture, FutureExt};
 use tokio::io::{AsyncRead, AsyncWrite, ReadBuf, Result};
 
+use crate::{
+    connection_metrics::record_tls_handshake,
+    server::CONNECTION_TLS,
+    web::{LocalAddr, TlsInfo},
+};
+
+/// A TLS stream that describes its session once the handshake is complete.
+pub(crate) trait TlsSession {
+    fn tls_info(&self) -> TlsInfo;
+}
+
 enum State<S> {
-    Handshaking(BoxFuture<'static, Result<S>>),
+    Handshaking(BoxFuture<'static, Result<(S, Bytes)>>),
     Ready(S),
     Error,
 }
@@ -17,15 +29,38 @@ enum State<S> {
 /// A handshake stream for tls.
 pub struct HandshakeStream<S> {
     state: State<S>,
+    early_data: Bytes,
 }
 
 impl<S> HandshakeStream<S> {
-    pub(crate) fn new<F>(handshake: F) -> Self
+    #[cfg(any(feature = "acme-base", feature = "native-tls", feature = "openssl-tls"))]
+    pub(crate) fn new<F>(local_addr: &LocalAddr, handshake: F) -> Self
     where
         F: Future<Output = Result<S>> + Send + 'static,
+        S: TlsSession + Send + 'static,
     {
+        Self::with_early_data(
+            local_addr,
+            handshake.map(|res| res.map(|stream| (stream, Bytes::new()))),
+        )
+    }
+
+    /// The data returned with the stream is read before the data received
+    /// after the handshake, such as the TLS 1.3 early data.
+    pub(crate) fn with_early_data<F>(local_addr: &LocalAddr, handshake: F) -> Self
+    where
+        F: Future<Output = Result<(S, Bytes)>> + Send + 'static,
+        S: TlsSession + Send + 'static,
+    {
+        // the handshake is polled by the task serving the connection
+        let handshake = handshake.inspect(|res| {
+            if let Ok((stream, _)) = res {
+                let _ = CONNECTION_TLS.try_with(|tls| tls.set(stream.tls_info()));
+            }
+        });
         Self {
-            state: State::Handshaking(handshake.boxed()),
+            state: State::Handshaking(record_tls_handshake(local_addr, handshake).boxed()),
+            early_data: Bytes::new(),
         }
     }
 }
@@ -44,13 +79,21 @@ where
         loop {
             match &mut this.state {
                 State::Handshaking(fut) => match fut.poll_unpin(cx) {
-                    Poll::Ready(Ok(s)) => this.state = State::Ready(s),
+                    Poll::Ready(Ok((s, early_data))) => {
+                        this.state = State::Ready(s);
+                        this.early_data = early_data;
+                    }
                     Poll::Ready(Err(err)) => {
                         this.state = State::Error;
                         return Poll::Ready(Err(err));
                     }
                     Poll::Pending => return Poll::Pending,
                 },
+                State::Ready(_) if !this.early_data.is_empty() => {
+                    let len = this.early_data.len().min(buf.remaining());
+                    buf.put_slice(&this.early_data.split_to(len));
+                    return Poll::Ready(Ok(()));
+                }
                 State::Ready(stream) => return Pin::new(stream).poll_read(cx, buf),
                 State::Error => return Poll::Ready(Err(invalid_data_error())),
             }
@@ -72,7 +115,10 @@ where
         loop {
             match &mut this.state {
                 State::Handshaking(fut) => match fut.poll_unpin(cx) {
-                    Poll::Ready(Ok(s)) => this.state = State::Ready(s),
+                    Poll::Ready(Ok((s, early_data))) => {
+                        this.state = State::Ready(s);
+                        this.early_data = early_data;
+                    }
                     Poll::Ready(Err(err)) => {
                         this.state = State::Error;
                         return Poll::Ready(Err(err));
@@ -94,7 +140,10 @@ where
         loop {
             match &mut this.state {
                 State::Handshaking(fut) => match fut.poll_unpin(cx) {
-                    Poll::Ready(Ok(s)) => this.state = State::Ready(s),
+                    Poll::Ready(Ok((s, early_data))) => {
+                        this.state = State::Ready(s);
+                        this.early_data = early_data;
+                    }
                     Poll::Ready(Err(err)) => {
                         this.state = State::Error;
                         return Poll::Ready(Err(err));
@@ -116,7 +165,10 @@ where
         loop {
             match &mut this.state {
                 State::Handshaking(fut) => match fut.poll_unpin(cx) {
-                    Poll::Ready(Ok(s)) => this.state = State::Ready(s),
+                    Poll::Ready(Ok((s, early_data))) => {
+                        this.state = State::Ready(s);
+                        this.early_data = early_data;
+                    }
                     Poll::Ready(Err(err)) => {
                         this.state = State::Error;
                         return Poll::Ready(Err(err));
@@ -0,0 +1,345 @@
+use std::{error::Error as StdError, future::poll_fn, sync::Arc};
+
+use bytes::{Buf, Bytes};
+use h2::client::{ResponseFuture, SendRequest};
+use h3::{error::Code, server::RequestStream};
+use h3_quinn::{BidiStream, RecvStream, SendStream};
+use http::uri::Scheme;
+use quinn::{crypto::rustls::QuicServerConfig, rustls, Endpoint, Incoming};
+use tokio::{
+    io::{duplex, DuplexStream, Error as IoError, ErrorKind, Result as IoResult},
+    net::{lookup_host, ToSocketAddrs},
+};
+
+use crate::{
+    listener::{Acceptor, Listener, RustlsCertificate},
+    web::{LocalAddr, RemoteAddr},
+};
+
+type BoxError = Box<dyn StdError + Send + Sync>;
+
+/// The buffer size of the stream between a QUIC connection and the server.
+const BUFFER_SIZE: usize = 64 * 1024;
+
+/// A listener that serves HTTP/3 over QUIC.
+///
+/// Each QUIC connection is passed to the [`Server`](crate::Server) as an
+/// HTTP/2 connection, and each of its requests is forwarded to an HTTP/2
+/// stream, so the requests go through the same endpoints and server options
+/// as the other listeners, and are seen with the HTTP/2 version. The bodies
+/// and trailers are streamed in both directions. The extended `CONNECT`
+/// method (such as WebSocket over HTTP/3), server push and WebTransport are
+/// not supported.
+///
+/// The clients only use HTTP/3 after it is advertised with the `Alt-Svc`
+/// header of a response sent over HTTP/1 or HTTP/2, so this listener is
+/// usually combined with a TLS listener bound to the same port.
+///
+/// # Example
+///
+/// ```no_run
+/// use poem::{
+///     get, handler,
+///     http::header,
+///     listener::{Http3Listener, Listener, RustlsCertificate, RustlsConfig, TcpListener},
+///     middleware::SetHeader,
+///     EndpointExt, Route, Server,
+/// };
+///
+/// #[handler]
+/// fn index() -> &'static str {
+///     "hello"
+/// }
+///
+/// # async fn run() -> std::io::Result<()> {
+/// let cert = std::fs::read("cert.pem")?;
+/// let key = std::fs::read("key.pem")?;
+///
+/// let listener = TcpListener::bind("0.0.0.0:443")
+///     .rustls(RustlsConfig::new().fallback(
+///         RustlsCertificate::new().cert(cert.clone()).key(key.clone()),
+///     ))
+///     .combine(Http3Listener::bind(
+///         "0.0.0.0:443",
+///         RustlsCertificate::new().cert(cert).key(key),
+///     ));
+/// let app = Route::new()
+///     .at("/", get(index))
+///     .with(SetHeader::new().overriding(header::ALT_SVC, "h3=\":443\"; ma=86400"));
+/// Server::new(listener).run(app).await
+/// # }
+/// ```
+#[cfg_attr(docsrs, doc(cfg(feature = "http3")))]
+pub struct Http3Listener<T> {
+    addr: T,
+    certificate: RustlsCertificate,
+}
+
+impl<T> Http3Listener<T> {
+    /// Binds to the provided UDP address, and returns a [`Http3Listener<T>`]
+    /// that uses the specified certificate.
+    pub fn bind(addr: T, certificate: RustlsCertificate) -> Self {
+        Self { addr, certificate }
+    }
+}
+
+impl<T: ToSocketAddrs + Send> Listener for Http3Listener<T> {
+    type Acceptor = Http3Acceptor;
+
+    async fn into_acceptor(self) -> IoResult<Self::Acceptor> {
+        let (cert, key) = self.certificate.read_certificate_key()?;
+        let mut tls_config = rustls::ServerConfig::builder_with_provider(Arc::new(
+            rustls::crypto::ring::default_provider(),
+        ))
+        .with_protocol_versions(&[&rustls::version::TLS13])
+        .map_err(IoError::other)?
+        .with_no_client_auth()
+        .with_single_cert(cert, key)
+        .map_err(IoError::other)?;
+        tls_config.alpn_protocols = vec![b"h3".to_vec()];
+        let crypto = QuicServerConfig::try_from(tls_config).map_err(IoError::other)?;
+        let config = quinn::ServerConfig::with_crypto(Arc::new(crypto));
+
+        let mut last_err = None;
+        for addr in lookup_host(self.addr).await? {
+            match Endpoint::server(config.clone(), addr) {
+                Ok(endpoint) => {
+                    let local_addr = LocalAddr(endpoint.local_addr()?.into());
+                    return Ok(Http3Acceptor {
+                        endpoint,
+                        local_addr,
+                    });
+                }
+                Err(err) => last_err = Some(err),
+            }
+        }
+
+        Err(last_err.unwrap_or_else(|| {
+            IoError::new(ErrorKind::InvalidInput, "could not resolve to any address")
+        }))
+    }
+}
+
+/// A acceptor that accepts QUIC connections and serves HTTP/3 on them, see
+/// [`Http3Listener`].
+#[cfg_attr(docsrs, doc(cfg(feature = "http3")))]
+pub struct Http3Acceptor {
+    endpoint: Endpoint,
+    local_addr: LocalAddr,
+}
+
+impl Acceptor for Http3Acceptor {
+    type Io = DuplexStream;
+
+    fn local_addr(&self) -> Vec<LocalAddr> {
+        vec![self.local_addr.clone()]
+    }
+
+    async fn accept(&mut self) -> IoResult<(Self::Io, LocalAddr, RemoteAddr, Scheme)> {
+        let incoming = self
+            .endpoint
+            .accept()
+            .await
+            .ok_or_else(|| IoError::other("the endpoint is closed"))?;
+        let remote_addr = RemoteAddr(incoming.remote_address().into());
+        let (io, bridge_io) = duplex(BUFFER_SIZE);
+
+        // the handshake runs in the task, so it does not delay the other
+        // connections
+        tokio::spawn(async move {
+            if let Err(err) = bridge(incoming, bridge_io).await {
+                tracing::debug!(error = %err, "http3 connection error");
+            }
+        });
+
+        Ok((io, self.local_addr.clone(), remote_addr, Scheme::HTTPS))
+    }
+}
+
+impl Drop for Http3Acceptor {
+    fn drop(&mut self) {
+        // refuse the new connections, the accepted ones are kept open until
+        // they are closed by the server
+        self.endpoint.set_server_config(None);
+    }
+}
+
+/// Forwards the requests of a QUIC connection to the streams of the HTTP/2
+/// connection served on `io`.
+async fn bridge(incoming: Incoming, io: DuplexStream) -> Result<(), BoxError> {
+    let conn = incoming.await?;
+    let mut h3_conn =
+        h3::server::Connection::<_, Bytes>::new(h3_quinn::Connection::new(conn)).await?;
+    let (send_request, h2_conn) = h2::client::handshake(io).await?;
+    tokio::pin!(h2_conn);
+
+    loop {
+        tokio::select! {
+            res = &mut h2_conn => {
+                // the connection is closed by the server, such as on graceful
+                // shutdown or when it is idle
+                res?;
+                h3_conn.shutdown(0).await?;
+                return Ok(());
+            }
+            res = h3_conn.accept() => match res? {
+                Some(resolver) => {
+                    let send_request = send_request.clone();
+                    tokio::spawn(async move {
+                        let res = async {
+                            let (req, stream) = resolver.resolve_request().await?;
+                            forward_request(send_request, req, stream).await
+                        };
+                        if let Err(err) = res.await {
+                            tracing::debug!(error = %err, "http3 request error");
+                        }
+                    });
+                }
+                None => break,
+            }
+        }
+    }
+
+    // the pending requests are completed before the HTTP/2 connection is closed
+    drop(send_request);
+    h2_conn.await?;
+    Ok(())
+}
+
+async fn forward_request(
+    send_request: SendRequest<Bytes>,
+    req: http::Request<()>,
+    stream: RequestStream<BidiStream<Bytes>, Bytes>,
+) -> Result<(), BoxError> {
+    let (mut send_stream, recv_stream) = stream.split();
+    let (resp, body_tx) = send_request.ready().await?.send_request(req, false)?;
+
+    // the response can be sent before the request body is read, so the body is
+    // forwarded concurrently
+    let request_body = tokio::spawn(forward_request_body(recv_stream, body_tx));
+    let res = forward_response(resp, &mut send_stream).await;
+    request_body.abort();
+    if res.is_err() {
+        send_stream.stop_stream(Code::H3_INTERNAL_ERROR);
+    }
+    res
+}
+
+async fn forward_request_body(
+    mut stream: RequestStream<RecvStream, Bytes>,
+    mut body_tx: h2::SendStream<Bytes>,
+) -> Result<(), BoxError> {
+    while let Some(mut data) = stream.recv_data().await? {
+        let mut data = data.copy_to_bytes(data.remaining());
+        while !data.is_empty() {
+            body_tx.reserve_capacity(data.len());
+            let capacity = poll_fn(|cx| body_tx.poll_capacity(cx))
+                .await
+                .ok_or("the stream is closed")??;
+            body_tx.send_data(data.split_to(capacity.min(data.len())), false)?;
+        }
+    }
+
+    match stream.recv_trailers().await? {
+        Some(trailers) => body_tx.send_trailers(trailers)?,
+        None => body_tx.send_data(Bytes::new(), true)?,
+    }
+    Ok(())
+}
+
+async fn forward_response(
+    resp: ResponseFuture,
+    stream: &mut RequestStream<SendStream<Bytes>, Bytes>,
+) -> Result<(), BoxError> {
+    let (parts, mut body) = resp.await?.into_parts();
+    stream
+        .send_response(http::Response::from_parts(parts, ()))
+        .await?;
+
+    while let Some(data) = body.data().await {
+        let data = data?;
+        body.flow_control().release_capacity(data.len())?;
+        stream.send_data(data).await?;
+    }
+    if let Some(trailers) = body.trailers().await? {
+        stream.send_trailers(trailers).await?;
+    }
+    stream.finish().await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use quinn::{crypto::rustls::QuicClientConfig, ClientConfig};
+
+    use super::*;
+    use crate::{handler, post, Route, Server};
+
+    #[handler(internal)]
+    fn echo(body: Vec<u8>) -> Vec<u8> {
+        body
+    }
+
+    #[tokio::test]
+    async fn request() {
+        let acceptor = Http3Listener::bind(
+            "127.0.0.1:0",
+            RustlsCertificate::new()
+                .cert(include_bytes!("certs/cert1.pem").as_ref())
+                .key(include_bytes!("certs/key1.pem").as_ref()),
+        )
+        .into_acceptor()
+        .await
+        .unwrap();
+        let addr = *acceptor.local_addr()[0].as_socket_addr().unwrap();
+        tokio::spawn(async move {
+            Server::new_with_acceptor(acceptor)
+                .run(Route::new().at("/echo", post(echo)))
+                .await
+        });
+
+        let mut roots = rustls::RootCertStore::empty();
+        for cert in rustls_pemfile::certs(&mut include_bytes!("certs/chain1.pem").as_ref()) {
+            roots.add(cert.unwrap()).unwrap();
+        }
+        let mut tls_config = rustls::ClientConfig::builder_with_provider(Arc::new(
+            rustls::crypto::ring::default_provider(),
+        ))
+        .with_protocol_versions(&[&rustls::version::TLS13])
+        .unwrap()
+        .with_root_certificates(roots)
+        .with_no_client_auth();
+        tls_config.alpn_protocols = vec![b"h3".to_vec()];
+        let mut endpoint = Endpoint::client("127.0.0.1:0".parse().unwrap()).unwrap();
+        endpoint.set_default_client_config(ClientConfig::new(Arc::new(
+            QuicClientConfig::try_from(tls_config).unwrap(),
+        )));
+        let conn = endpoint
+            .connect(addr, "testserver.com")
+            .unwrap()
+            .await
+            .unwrap();
+
+        let (mut driver, mut send_request) = h3::client::new(h3_quinn::Connection::new(conn))
+            .await
+            .unwrap();
+        tokio::spawn(async move { poll_fn(|cx| driver.poll_close(cx)).await });
+
+        let req = http::Request::post("https://testserver.com/echo")
+            .body(())
+            .unwrap();
+        // larger than the initial window of HTTP/2
+        let data = vec![b'a'; 256 * 1024];
+        let mut stream = send_request.send_request(req).await.unwrap();
+        stream.send_data(Bytes::from(data.clone())).await.unwrap();
+        stream.finish().await.unwrap();
+
+        let resp = stream.recv_response().await.unwrap();
+        assert_eq!(resp.status(), http::StatusCode::OK);
+        let mut body = Vec::new();
+        while let Some(mut data) = stream.recv_data().await.unwrap() {
+            body.extend_from_slice(&data.copy_to_bytes(data.remaining()));
+        }
+        assert_eq!(body, data);
+    }
+}
@@ -6,12 +6,16 @@ pub mod acme;
 mod combined;
 #[cfg(any(feature = "native-tls", feature = "rustls", feature = "openssl-tls"))]
 mod handshake_stream;
+#[cfg(feature = "http3")]
+mod http3;
 #[cfg(feature = "native-tls")]
 mod native_tls;
 #[cfg(feature = "openssl-tls")]
 mod openssl_tls;
 #[cfg(feature = "rustls")]
 mod rustls;
+#[cfg(all(unix, feature = "listenfd"))]
+mod systemd;
 mod tcp;
 #[cfg(any(feature = "rustls", feature = "native-tls", feature = "openssl-tls"))]
 mod tls;
@@ -33,12 +37,18 @@ use tokio::io::{AsyncRead, AsyncWrite, ReadBuf, Result as IoResult};
 use self::acme::{AutoCert, AutoCertListener};
 #[cfg(any(feature = "native-tls", feature = "rustls", feature = "openssl-tls"))]
 pub use self::handshake_stream::HandshakeStream;
+#[cfg(any(feature = "native-tls", feature = "rustls", feature = "openssl-tls"))]
+pub(crate) use self::handshake_stream::TlsSession;
+#[cfg(feature = "http3")]
+pub use self::http3::{Http3Acceptor, Http3Listener};
 #[cfg(feature = "native-tls")]
 pub use self::native_tls::{NativeTlsAcceptor, NativeTlsConfig, NativeTlsListener};
 #[cfg(feature = "openssl-tls")]
 pub use self::openssl_tls::{OpensslTlsAcceptor, OpensslTlsConfig, OpensslTlsListener};
 #[cfg(feature = "rustls")]
 pub use self::rustls::{RustlsAcceptor, RustlsCertificate, RustlsConfig, RustlsListener};
+#[cfg(all(unix, feature = "listenfd"))]
+pub use self::systemd::SystemdListener;
 #[cfg(any(feature = "rustls", feature = "native-tls", feature = "openssl-tls"))]
 pub use self::tls::IntoTlsConfigStream;
 #[cfg(unix)]
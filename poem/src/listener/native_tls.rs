@@ -3,12 +3,12 @@ use futures_util::{
     Stream, StreamExt, TryFutureExt,
 };
 use http::uri::Scheme;
-use tokio::io::{Error as IoError, ErrorKind, Result as IoResult};
+use tokio::io::{AsyncRead, AsyncWrite, Error as IoError, ErrorKind, Result as IoResult};
 use tokio_native_tls::{native_tls::Identity, TlsStream};
 
 use crate::{
-    listener::{Acceptor, HandshakeStream, IntoTlsConfigStream, Listener},
-    web::{LocalAddr, RemoteAddr},
+    listener::{Acceptor, HandshakeStream, IntoTlsConfigStream, Listener, TlsSession},
+    web::{LocalAddr, RemoteAddr, TlsInfo},
 };
 
 /// Native TLS Config.
@@ -173,7 +173,7 @@ where
                         None => return Err(IoError::new(ErrorKind::Other, "no valid tls config.")),
                     };
                     let fut = async move { tls_acceptor.accept(stream).map_err(|err| IoError::new(ErrorKind::Other, err.to_string())).await };
-                    let stream = HandshakeStream::new(fut);
+                    let stream = HandshakeStream::new(&local_addr, fut);
                     return Ok((stream, local_addr, remote_addr, Scheme::HTTPS));
                 }
             }
@@ -181,6 +181,22 @@ where
     }
 }
 
+impl<S: AsyncRead + AsyncWrite + Unpin> TlsSession for TlsStream<S> {
+    fn tls_info(&self) -> TlsInfo {
+        // only the certificate of the client itself is available
+        let peer_certificate = self
+            .get_ref()
+            .peer_certificate()
+            .ok()
+            .flatten()
+            .and_then(|cert| cert.to_der().ok());
+        TlsInfo {
+            peer_certificates: peer_certificate.into_iter().collect(),
+            ..TlsInfo::default()
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use tokio::{
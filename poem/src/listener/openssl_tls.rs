@@ -18,8 +18,8 @@ use tokio_openssl::SslStream;
 use tokio_util::either::Either;
 
 use crate::{
-    listener::{Acceptor, HandshakeStream, IntoTlsConfigStream, Listener},
-    web::{LocalAddr, RemoteAddr},
+    listener::{Acceptor, HandshakeStream, IntoTlsConfigStream, Listener, TlsSession},
+    web::{LocalAddr, RemoteAddr, TlsInfo},
 };
 
 /// Openssl configuration contains certificate's chain and private key.
@@ -226,7 +226,7 @@ where
                         Pin::new(&mut tls_stream).accept().await.map_err(|err|
                             IoError::new(ErrorKind::Other, err.to_string()))?;
                         Ok(tls_stream) };
-                    let stream = HandshakeStream::new(fut);
+                    let stream = HandshakeStream::new(&local_addr, fut);
                     return Ok((stream, local_addr, remote_addr, Scheme::HTTPS));
                 }
             }
@@ -234,6 +234,26 @@ where
     }
 }
 
+impl<S> TlsSession for SslStream<S> {
+    fn tls_info(&self) -> TlsInfo {
+        let ssl = self.ssl();
+        // the chain of the peer does not contain its certificate on the server
+        let mut peer_certificates: Vec<_> = ssl
+            .peer_certificate()
+            .and_then(|cert| cert.to_der().ok())
+            .into_iter()
+            .collect();
+        if let Some(chain) = ssl.peer_cert_chain() {
+            peer_certificates.extend(chain.iter().filter_map(|cert| cert.to_der().ok()));
+        }
+        TlsInfo {
+            alpn_protocol: ssl.selected_alpn_protocol().map(<[u8]>::to_vec),
+            protocol_version: Some(ssl.version_str().to_string()),
+            peer_certificates,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use openssl::ssl::SslConnector;
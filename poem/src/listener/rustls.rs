@@ -1,25 +1,32 @@
-use std::{collections::HashMap, sync::Arc};
+use std::{collections::HashMap, io::Read, sync::Arc};
 
+use bytes::Bytes;
 use futures_util::{
     stream::{BoxStream, Chain, Pending},
     Stream, StreamExt,
 };
 use http::uri::Scheme;
 use rustls_pemfile::Item;
-use tokio::io::{Error as IoError, ErrorKind, Result as IoResult};
+use tokio::io::{
+    AsyncRead, AsyncWrite, AsyncWriteExt, Error as IoError, ErrorKind, Result as IoResult,
+};
 use tokio_rustls::{
     rustls::{
-        crypto::ring::sign::any_supported_type,
-        server::{ClientHello, ResolvesServerCert, WebPkiClientVerifier},
+        crypto::ring::{sign::any_supported_type, Ticketer},
+        pki_types::{CertificateDer, PrivateKeyDer},
+        server::{
+            ClientHello, NoServerSessionStorage, ResolvesServerCert, ServerSessionMemoryCache,
+            WebPkiClientVerifier,
+        },
         sign::CertifiedKey,
-        RootCertStore, ServerConfig,
+        ProtocolVersion, RootCertStore, ServerConfig,
     },
     server::TlsStream,
 };
 
 use crate::{
-    listener::{Acceptor, HandshakeStream, IntoTlsConfigStream, Listener},
-    web::{LocalAddr, RemoteAddr},
+    listener::{Acceptor, HandshakeStream, IntoTlsConfigStream, Listener, TlsSession},
+    web::{LocalAddr, RemoteAddr, TlsInfo},
 };
 
 #[cfg_attr(docsrs, doc(cfg(feature = "rustls")))]
@@ -68,7 +75,9 @@ impl RustlsCertificate {
 }
 
 impl RustlsCertificate {
-    fn create_certificate_key(&self) -> IoResult<CertifiedKey> {
+    pub(crate) fn read_certificate_key(
+        &self,
+    ) -> IoResult<(Vec<CertificateDer<'static>>, PrivateKeyDer<'static>)> {
         let cert = rustls_pemfile::certs(&mut self.cert.as_slice())
             .collect::<Result<_, _>>()
             .map_err(|_| IoError::new(ErrorKind::Other, "failed to parse tls certificates"))?;
@@ -87,7 +96,11 @@ impl RustlsCertificate {
                 _ => continue,
             }
         };
+        Ok((cert, priv_key))
+    }
 
+    fn create_certificate_key(&self) -> IoResult<CertifiedKey> {
+        let (cert, priv_key) = self.read_certificate_key()?;
         let key = any_supported_type(&priv_key)
             .map_err(|_| IoError::new(ErrorKind::Other, "invalid private key"))?;
 
@@ -109,6 +122,10 @@ pub struct RustlsConfig {
     certificates: HashMap<String, RustlsCertificate>,
     fallback: Option<RustlsCertificate>,
     client_auth: TlsClientAuth,
+    session_tickets: bool,
+    session_cache_size: usize,
+    max_early_data_size: u32,
+    alpn_protocols: Option<Vec<Vec<u8>>>,
 }
 
 impl Default for RustlsConfig {
@@ -124,6 +141,10 @@ impl RustlsConfig {
             certificates: HashMap::new(),
             fallback: Default::default(),
             client_auth: TlsClientAuth::Off,
+            session_tickets: false,
+            session_cache_size: 256,
+            max_early_data_size: 0,
+            alpn_protocols: None,
         }
     }
 
@@ -215,6 +236,80 @@ impl RustlsConfig {
         self
     }
 
+    /// Enables the stateless session resumption with session tickets that are
+    /// encrypted with a key rotated every 6 hours.
+    ///
+    /// By default session tickets are disabled.
+    #[must_use]
+    pub fn session_tickets(mut self, enable: bool) -> Self {
+        self.session_tickets = enable;
+        self
+    }
+
+    /// Sets the number of sessions cached in memory for the stateful session
+    /// resumption, `0` disables it.
+    ///
+    /// Default is `256`.
+    #[must_use]
+    pub fn session_cache_size(mut self, size: usize) -> Self {
+        self.session_cache_size = size;
+        self
+    }
+
+    /// Sets the maximum amount of TLS 1.3 early data (0-RTT) in bytes accepted
+    /// from a resumed session, `0` disables early data.
+    ///
+    /// Early data can be replayed by an attacker, so it is only accepted if
+    /// it contains HTTP/1.1 requests with the `GET`, `HEAD` or `OPTIONS`
+    /// method and without a body. Otherwise the request is answered with
+    /// `425 Too Early` ([RFC 8470](https://www.rfc-editor.org/rfc/rfc8470)),
+    /// which makes the client retry after the handshake, and HTTP/2
+    /// connections with early data are closed.
+    ///
+    /// Default is `0`.
+    #[must_use]
+    pub fn max_early_data_size(mut self, size: u32) -> Self {
+        self.max_early_data_size = size;
+        self
+    }
+
+    /// Sets the protocols advertised with ALPN, in order of preference, e.g.
+    /// `["h2"]` to only accept HTTP/2 or `["http/1.1"]` to only accept
+    /// HTTP/1.1.
+    ///
+    /// The handshake fails if the client offers none of these protocols, and
+    /// the connection is closed if the client does not use ALPN at all. An
+    /// empty list disables ALPN, and the protocol is detected from the data
+    /// sent by the client.
+    ///
+    /// Default is `["h2", "http/1.1"]`, and the clients without ALPN are
+    /// accepted.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use poem::listener::RustlsConfig;
+    ///
+    /// let config = RustlsConfig::new().alpn_protocols(["h2"]);
+    /// ```
+    #[must_use]
+    pub fn alpn_protocols<I, P>(mut self, protocols: I) -> Self
+    where
+        I: IntoIterator<Item = P>,
+        P: Into<Vec<u8>>,
+    {
+        self.alpn_protocols = Some(protocols.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Returns `true` if the connections must negotiate one of the ALPN
+    /// protocols.
+    fn requires_alpn(&self) -> bool {
+        self.alpn_protocols
+            .as_ref()
+            .is_some_and(|protocols| !protocols.is_empty())
+    }
+
     fn create_server_config(&self) -> IoResult<ServerConfig> {
         let fallback = self
             .fallback
@@ -255,7 +350,19 @@ impl RustlsConfig {
             certificate_keys,
             fallback,
         }));
-        server_config.alpn_protocols = vec!["h2".into(), "http/1.1".into()];
+        server_config.alpn_protocols = match &self.alpn_protocols {
+            Some(protocols) => protocols.clone(),
+            None => vec!["h2".into(), "http/1.1".into()],
+        };
+        if self.session_tickets {
+            server_config.ticketer = Ticketer::new().map_err(IoError::other)?;
+        }
+        if self.session_cache_size > 0 {
+            server_config.session_storage = ServerSessionMemoryCache::new(self.session_cache_size);
+        } else {
+            server_config.session_storage = Arc::new(NoServerSessionStorage {});
+        }
+        server_config.max_early_data_size = self.max_early_data_size;
 
         Ok(server_config)
     }
@@ -336,7 +443,7 @@ impl<T: Listener, S: IntoTlsConfigStream<RustlsConfig>> Listener for RustlsListe
 pub struct RustlsAcceptor<T, S> {
     inner: T,
     config_stream: Chain<S, Pending<RustlsConfig>>,
-    current_tls_acceptor: Option<tokio_rustls::TlsAcceptor>,
+    current_tls_acceptor: Option<(tokio_rustls::TlsAcceptor, bool)>,
 }
 
 impl<T, S> RustlsAcceptor<T, S>
@@ -375,7 +482,10 @@ where
                                 } else {
                                     tracing::info!("tls config loaded.");
                                 }
-                                self.current_tls_acceptor = Some(tokio_rustls::TlsAcceptor::from(Arc::new(server_config)));
+                                self.current_tls_acceptor = Some((
+                                    tokio_rustls::TlsAcceptor::from(Arc::new(server_config)),
+                                    tls_config.requires_alpn(),
+                                ));
 
                             },
                             Err(err) => tracing::error!(error = %err, "invalid tls config."),
@@ -386,12 +496,15 @@ where
                 }
                 res = self.inner.accept() => {
                     let (stream, local_addr, remote_addr, _) = res?;
-                    let tls_acceptor = match &self.current_tls_acceptor {
-                        Some(tls_acceptor) => tls_acceptor,
+                    let (tls_acceptor, requires_alpn) = match &self.current_tls_acceptor {
+                        Some((tls_acceptor, requires_alpn)) => (tls_acceptor, *requires_alpn),
                         None => return Err(IoError::new(ErrorKind::Other, "no valid tls config.")),
                     };
 
-                    let stream = HandshakeStream::new(tls_acceptor.accept(stream));
+                    let stream = HandshakeStream::with_early_data(
+                        &local_addr,
+                        accept_with_early_data(tls_acceptor.accept(stream), requires_alpn),
+                    );
                     return Ok((stream, local_addr, remote_addr, Scheme::HTTPS));
                 }
             }
@@ -399,6 +512,94 @@ where
     }
 }
 
+impl<IO> TlsSession for TlsStream<IO> {
+    fn tls_info(&self) -> TlsInfo {
+        let conn = self.get_ref().1;
+        TlsInfo {
+            alpn_protocol: conn.alpn_protocol().map(<[u8]>::to_vec),
+            protocol_version: conn.protocol_version().map(|version| match version {
+                ProtocolVersion::TLSv1_2 => "TLSv1.2".to_string(),
+                ProtocolVersion::TLSv1_3 => "TLSv1.3".to_string(),
+                version => format!("{version:?}"),
+            }),
+            peer_certificates: conn
+                .peer_certificates()
+                .unwrap_or_default()
+                .iter()
+                .map(|cert| cert.to_vec())
+                .collect(),
+        }
+    }
+}
+
+const TOO_EARLY_RESPONSE: &[u8] =
+    b"HTTP/1.1 425 Too Early\r\ncontent-length: 0\r\nconnection: close\r\n\r\n";
+
+async fn accept_with_early_data<IO>(
+    accept: tokio_rustls::Accept<IO>,
+    requires_alpn: bool,
+) -> IoResult<(TlsStream<IO>, Bytes)>
+where
+    IO: AsyncRead + AsyncWrite + Unpin,
+{
+    let mut stream = accept.await?;
+    if requires_alpn && stream.get_ref().1.alpn_protocol().is_none() {
+        return Err(IoError::new(
+            ErrorKind::InvalidData,
+            "no application protocol negotiated",
+        ));
+    }
+
+    let mut early_data = Vec::new();
+    if let Some(mut reader) = stream.get_mut().1.early_data() {
+        reader.read_to_end(&mut early_data)?;
+    }
+
+    if !early_data.is_empty() {
+        let is_h2 = stream.get_ref().1.alpn_protocol() == Some(&b"h2"[..]);
+        if is_h2 || !is_safe_early_data(&early_data) {
+            if !is_h2 {
+                stream.write_all(TOO_EARLY_RESPONSE).await?;
+                stream.shutdown().await?;
+            }
+            return Err(IoError::new(ErrorKind::InvalidData, "early data rejected"));
+        }
+    }
+
+    Ok((stream, early_data.into()))
+}
+
+/// Returns `true` if the early data only contains complete HTTP/1.1 requests
+/// with a safe method and without a body, which are harmless if replayed.
+fn is_safe_early_data(mut data: &[u8]) -> bool {
+    while !data.is_empty() {
+        let Some(end) = data.windows(4).position(|w| w == b"\r\n\r\n") else {
+            return false;
+        };
+        let Ok(head) = std::str::from_utf8(&data[..end]) else {
+            return false;
+        };
+        let mut lines = head.split("\r\n");
+        let method = lines
+            .next()
+            .and_then(|line| line.split(' ').next())
+            .unwrap_or_default();
+        if !matches!(method, "GET" | "HEAD" | "OPTIONS") {
+            return false;
+        }
+        for line in lines {
+            let name = line.split(':').next().unwrap_or_default().trim();
+            if name.eq_ignore_ascii_case("content-length")
+                || name.eq_ignore_ascii_case("transfer-encoding")
+            {
+                return false;
+            }
+        }
+        data = &data[end + 4..];
+    }
+    true
+}
+
 #[derive(Debug)]
 struct ResolveServerCert {
     certificate_keys: HashMap<String, Arc<CertifiedKey>>,
@@ -425,6 +626,20 @@ mod tests {
     use super::*;
     use crate::listener::TcpListener;
 
+    #[test]
+    fn safe_early_data() {
+        assert!(is_safe_early_data(
+            b"GET / HTTP/1.1\r\nhost: a\r\n\r\nHEAD /b HTTP/1.1\r\n\r\n"
+        ));
+        assert!(!is_safe_early_data(b"GET / HTTP/1.1\r\nhost: a\r\n"));
+        assert!(!is_safe_early_data(
+            b"GET / HTTP/1.1\r\n\r\nPOST / HTTP/1.1\r\ncontent-length: 0\r\n\r\n"
+        ));
+        assert!(!is_safe_early_data(
+            b"GET / HTTP/1.1\r\nContent-Length: 3\r\n\r\nabc"
+        ));
+    }
+
     #[tokio::test]
     async fn tls_listener() {
         let listener = TcpListener::bind("127.0.0.1:0").rustls(
@@ -456,4 +671,195 @@ mod tests {
         let (mut stream, _, _, _) = acceptor.accept().await.unwrap();
         assert_eq!(stream.read_i32().await.unwrap(), 10);
     }
+
+    #[tokio::test]
+    async fn alpn_protocols() {
+        let listener = TcpListener::bind("127.0.0.1:0").rustls(
+            RustlsConfig::new()
+                .fallback(
+                    RustlsCertificate::new()
+                        .cert(include_bytes!("certs/cert1.pem").as_ref())
+                        .key(include_bytes!("certs/key1.pem").as_ref()),
+                )
+                .alpn_protocols(["h2"]),
+        );
+        let mut acceptor = listener.into_acceptor().await.unwrap();
+        let local_addr = *acceptor
+            .local_addr()
+            .pop()
+            .unwrap()
+            .as_socket_addr()
+            .unwrap();
+
+        let connect = move |alpn_protocols: Vec<Vec<u8>>| async move {
+            let mut config = ClientConfig::builder()
+                .with_root_certificates(
+                    read_trust_anchor(include_bytes!("certs/chain1.pem")).unwrap(),
+                )
+                .with_no_client_auth();
+            config.alpn_protocols = alpn_protocols;
+
+            let connector = tokio_rustls::TlsConnector::from(Arc::new(config));
+            let domain = ServerName::try_from("testserver.com").unwrap();
+            let stream = TcpStream::connect(local_addr).await.unwrap();
+            let mut stream = connector.connect(domain, stream).await?;
+            stream.write_i32(10).await?;
+            Ok::<_, IoError>(stream)
+        };
+
+        tokio::spawn(async move {
+            let stream = connect(vec![b"h2".to_vec()]).await.unwrap();
+            assert_eq!(stream.get_ref().1.alpn_protocol(), Some(&b"h2"[..]));
+        });
+        let (mut stream, _, _, _) = acceptor.accept().await.unwrap();
+        assert_eq!(stream.read_i32().await.unwrap(), 10);
+
+        // the client does not use ALPN
+        tokio::spawn(async move {
+            _ = connect(vec![]).await;
+        });
+        let (mut stream, _, _, _) = acceptor.accept().await.unwrap();
+        assert!(stream.read_i32().await.is_err());
+
+        // the client offers none of the protocols
+        tokio::spawn(async move {
+            assert!(connect(vec![b"http/1.1".to_vec()]).await.is_err());
+        });
+        let (mut stream, _, _, _) = acceptor.accept().await.unwrap();
+        assert!(stream.read_i32().await.is_err());
+    }
+
+    #[tokio::test]
+    async fn tls_info() {
+        let acceptor = TcpListener::bind("127.0.0.1:0")
+            .rustls(
+                RustlsConfig::new()
+                    .fallback(
+                        RustlsCertificate::new()
+                            .cert(include_bytes!("certs/cert1.pem").as_ref())
+                            .key(include_bytes!("certs/key1.pem").as_ref()),
+                    )
+                    .alpn_protocols(["http/1.1"]),
+            )
+            .into_acceptor()
+            .await
+            .unwrap();
+        let local_addr = *acceptor
+            .local_addr()
+            .pop()
+            .unwrap()
+            .as_socket_addr()
+            .unwrap();
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        let tx = std::sync::Mutex::new(Some(tx));
+        let handle = tokio::spawn(async move {
+            let _ = crate::Server::new_with_acceptor(acceptor)
+                .on_disconnect(move |info| {
+                    if let Some(tx) = tx.lock().unwrap().take() {
+                        _ = tx.send(info.tls().cloned());
+                    }
+                })
+                .run(crate::endpoint::make(|req| async move {
+                    let tls = req.data::<TlsInfo>().unwrap();
+                    format!(
+                        "{} {}",
+                        tls.protocol_version.as_deref().unwrap_or_default(),
+                        String::from_utf8_lossy(tls.alpn_protocol.as_deref().unwrap_or_default()),
+                    )
+                }))
+                .await;
+        });
+
+        let mut config = ClientConfig::builder()
+            .with_root_certificates(read_trust_anchor(include_bytes!("certs/chain1.pem")).unwrap())
+            .with_no_client_auth();
+        config.alpn_protocols = vec![b"http/1.1".to_vec()];
+        let connector = tokio_rustls::TlsConnector::from(Arc::new(config));
+        let stream = TcpStream::connect(local_addr).await.unwrap();
+        let domain = ServerName::try_from("testserver.com").unwrap();
+        let mut stream = connector.connect(domain, stream).await.unwrap();
+        stream
+            .write_all(b"GET / HTTP/1.1\r\nhost: a\r\nconnection: close\r\n\r\n")
+            .await
+            .unwrap();
+        let mut resp = String::new();
+        let _ = stream.read_to_string(&mut resp).await;
+        assert!(resp.ends_with("TLSv1.3 http/1.1"), "{resp}");
+
+        let tls = rx.await.unwrap().unwrap();
+        assert_eq!(tls.protocol_version.as_deref(), Some("TLSv1.3"));
+        assert!(tls.peer_certificate().is_none());
+
+        handle.abort();
+    }
+
+    #[tokio::test]
+    async fn early_data() {
+        let acceptor = TcpListener::bind("127.0.0.1:0")
+            .rustls(
+                RustlsConfig::new()
+                    .fallback(
+                        RustlsCertificate::new()
+                            .cert(include_bytes!("certs/cert1.pem").as_ref())
+                            .key(include_bytes!("certs/key1.pem").as_ref()),
+                    )
+                    .max_early_data_size(1024),
+            )
+            .into_acceptor()
+            .await
+            .unwrap();
+        let local_addr = *acceptor
+            .local_addr()
+            .pop()
+            .unwrap()
+            .as_socket_addr()
+            .unwrap();
+        let handle = tokio::spawn(async move {
+            let _ = crate::Server::new_with_acceptor(acceptor)
+                .run(crate::endpoint::make(|req| async move {
+                    req.method().to_string()
+                }))
+                .await;
+        });
+
+        let mut config = ClientConfig::builder()
+            .with_root_certificates(read_trust_anchor(include_bytes!("certs/chain1.pem")).unwrap())
+            .with_no_client_auth();
+        config.alpn_protocols = vec![b"http/1.1".to_vec()];
+        config.enable_early_data = true;
+        let connector = tokio_rustls::TlsConnector::from(Arc::new(config)).early_data(true);
+
+        let request = |method: &'static str| {
+            let connector = connector.clone();
+            async move {
+                let stream = TcpStream::connect(local_addr).await.unwrap();
+                let domain = ServerName::try_from("testserver.com").unwrap();
+                let mut stream = connector.connect(domain, stream).await.unwrap();
+                let req = format!("{method} / HTTP/1.1\r\nhost: a\r\nconnection: close\r\n\r\n");
+                stream.write_all(req.as_bytes()).await.unwrap();
+                stream.flush().await.unwrap();
+                let mut resp = String::new();
+                let _ = stream.read_to_string(&mut resp).await;
+                (stream.get_ref().1.is_early_data_accepted(), resp)
+            }
+        };
+
+        // the first connection receives the session ticket
+        let (accepted, resp) = request("GET").await;
+        assert!(!accepted);
+        assert!(resp.starts_with("HTTP/1.1 200 OK"), "{resp}");
+
+        // the safe request sent as early data is handled
+        let (accepted, resp) = request("GET").await;
+        assert!(accepted);
+        assert!(resp.starts_with("HTTP/1.1 200 OK"), "{resp}");
+        assert!(resp.ends_with("GET"), "{resp}");
+
+        // the unsafe request sent as early data is rejected
+        let (accepted, resp) = request("DELETE").await;
+        assert!(accepted);
+        assert!(resp.starts_with("HTTP/1.1 425 Too Early"), "{resp}");
+
+        handle.abort();
+    }
 }
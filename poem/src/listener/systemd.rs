@@ -0,0 +1,188 @@
+use std::{
+    ffi::OsStr,
+    io::{Error, ErrorKind, Result},
+    os::unix::net::UnixDatagram,
+    sync::{LazyLock, Mutex},
+};
+
+use http::uri::Scheme;
+use listenfd::ListenFd;
+use tokio::io::Result as IoResult;
+
+use crate::{
+    listener::{Acceptor, AcceptorExt, BoxAcceptor, BoxIo, Listener, TcpAcceptor, UnixAcceptor},
+    web::{LocalAddr, RemoteAddr},
+};
+
+/// The sockets passed by the process manager, each socket can only be taken
+/// once.
+static LISTEN_FD: LazyLock<Mutex<ListenFd>> = LazyLock::new(|| Mutex::new(ListenFd::from_env()));
+
+fn not_passed(index: usize) -> Error {
+    Error::new(
+        ErrorKind::NotFound,
+        format!("socket {index} is not passed to this process or has already been taken"),
+    )
+}
+
+/// Takes the TCP socket passed with the systemd socket activation protocol at
+/// the specified position.
+pub(crate) fn take_tcp_listener(index: usize) -> Result<std::net::TcpListener> {
+    let listener = LISTEN_FD
+        .lock()
+        .unwrap()
+        .take_tcp_listener(index)?
+        .ok_or_else(|| not_passed(index))?;
+    listener.set_nonblocking(true)?;
+    Ok(listener)
+}
+
+/// Takes the Unix domain socket passed with the systemd socket activation
+/// protocol at the specified position.
+pub(crate) fn take_unix_listener(index: usize) -> Result<std::os::unix::net::UnixListener> {
+    let listener = LISTEN_FD
+        .lock()
+        .unwrap()
+        .take_unix_listener(index)?
+        .ok_or_else(|| not_passed(index))?;
+    listener.set_nonblocking(true)?;
+    Ok(listener)
+}
+
+/// Sends the state to the service manager with the `sd_notify` protocol, it
+/// does nothing if the `NOTIFY_SOCKET` environment variable is not set.
+fn sd_notify(state: &str) {
+    if let Some(socket) = std::env::var_os("NOTIFY_SOCKET") {
+        if let Err(err) = notify_socket(&socket, state) {
+            tracing::warn!(error = %err, state, "failed to notify the service manager");
+        }
+    }
+}
+
+fn notify_socket(socket: &OsStr, state: &str) -> Result<()> {
+    let datagram = UnixDatagram::unbound()?;
+
+    // a name starting with `@` is in the abstract namespace
+    #[cfg(target_os = "linux")]
+    if let Some(name) = std::os::unix::ffi::OsStrExt::as_bytes(socket).strip_prefix(b"@") {
+        use std::os::linux::net::SocketAddrExt;
+
+        let addr = std::os::unix::net::SocketAddr::from_abstract_name(name)?;
+        datagram.send_to_addr(state.as_bytes(), &addr)?;
+        return Ok(());
+    }
+
+    datagram.send_to(state.as_bytes(), socket)?;
+    Ok(())
+}
+
+/// A listener that adopts a socket passed by systemd socket activation,
+/// instead of binding a new one.
+///
+/// Both TCP and Unix domain sockets are supported. The sockets are configured
+/// in a `.socket` unit, so the service can listen on a privileged port
+/// without running as root, and the socket keeps accepting connections while
+/// the service restarts.
+///
+/// For a service with `Type=notify`, the readiness is reported with the
+/// `sd_notify` protocol: `READY=1` is sent once the socket is adopted, and
+/// `STOPPING=1` when the server stops accepting connections to shut down.
+///
+/// # Example
+///
+/// ```no_run
+/// use poem::{listener::SystemdListener, Route, Server};
+///
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// Server::new(SystemdListener::new(0))
+///     .run(Route::new())
+///     .await
+/// # });
+/// ```
+#[cfg_attr(docsrs, doc(cfg(all(unix, feature = "listenfd"))))]
+pub struct SystemdListener {
+    index: usize,
+}
+
+impl SystemdListener {
+    /// Adopts the passed socket at the specified position, starting at `0`.
+    pub fn new(index: usize) -> Self {
+        Self { index }
+    }
+}
+
+impl Listener for SystemdListener {
+    type Acceptor = BoxAcceptor;
+
+    async fn into_acceptor(self) -> IoResult<Self::Acceptor> {
+        // a socket of the other type is rejected without being taken
+        let inner = match take_tcp_listener(self.index) {
+            Ok(listener) => TcpAcceptor::from_std(listener)?.boxed(),
+            Err(err) if err.kind() == ErrorKind::NotFound => return Err(err),
+            Err(_) => UnixAcceptor::from_std(take_unix_listener(self.index)?)?.boxed(),
+        };
+        sd_notify("READY=1");
+        Ok(SystemdAcceptor { inner }.boxed())
+    }
+}
+
+/// Reports the shutdown to the service manager when it is dropped.
+struct SystemdAcceptor {
+    inner: BoxAcceptor,
+}
+
+impl Drop for SystemdAcceptor {
+    fn drop(&mut self) {
+        sd_notify("STOPPING=1");
+    }
+}
+
+impl Acceptor for SystemdAcceptor {
+    type Io = BoxIo;
+
+    fn local_addr(&self) -> Vec<LocalAddr> {
+        self.inner.local_addr()
+    }
+
+    async fn accept(&mut self) -> IoResult<(Self::Io, LocalAddr, RemoteAddr, Scheme)> {
+        self.inner.accept().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn not_passed() {
+        let err = SystemdListener::new(0).into_acceptor().await.err().unwrap();
+        assert_eq!(err.kind(), ErrorKind::NotFound);
+        assert!(TcpAcceptor::from_listen_fds(0).is_err());
+        assert!(UnixAcceptor::from_listen_fds(0).is_err());
+    }
+
+    #[test]
+    fn notify() {
+        let path = std::env::temp_dir().join(format!("poem-notify-{}.sock", std::process::id()));
+        let receiver = UnixDatagram::bind(&path).unwrap();
+
+        notify_socket(path.as_os_str(), "READY=1").unwrap();
+        let mut buf = [0; 64];
+        let n = receiver.recv(&mut buf).unwrap();
+        assert_eq!(&buf[..n], b"READY=1");
+        std::fs::remove_file(&path).unwrap();
+
+        #[cfg(target_os = "linux")]
+        {
+            use std::os::{linux::net::SocketAddrExt, unix::net::SocketAddr};
+
+            let name = format!("poem-notify-{}", std::process::id());
+            let addr = SocketAddr::from_abstract_name(name.as_bytes()).unwrap();
+            let receiver = UnixDatagram::bind_addr(&addr).unwrap();
+
+            notify_socket(OsStr::new(&format!("@{name}")), "STOPPING=1").unwrap();
+            let n = receiver.recv(&mut buf).unwrap();
+            assert_eq!(&buf[..n], b"STOPPING=1");
+        }
+    }
+}
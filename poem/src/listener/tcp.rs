@@ -1,9 +1,9 @@
-use std::io::Result;
+use std::{io::Result, net::SocketAddr};
 
 use http::uri::Scheme;
 use tokio::{
     io::Result as IoResult,
-    net::{TcpListener as TokioTcpListener, TcpStream, ToSocketAddrs},
+    net::{lookup_host, TcpListener as TokioTcpListener, TcpSocket, TcpStream, ToSocketAddrs},
 };
 
 use crate::{
@@ -14,20 +14,99 @@ use crate::{
 /// A TCP listener.
 pub struct TcpListener<T> {
     addr: T,
+    reuse_address: bool,
+    reuse_port: bool,
 }
 
 impl<T> TcpListener<T> {
     /// Binds to the provided address, and returns a [`TcpListener<T>`].
     pub fn bind(addr: T) -> Self {
-        Self { addr }
+        Self {
+            addr,
+            reuse_address: false,
+            reuse_port: false,
+        }
+    }
+
+    /// Sets the `SO_REUSEADDR` option on the socket before binding.
+    #[must_use]
+    pub fn reuse_address(self, enable: bool) -> Self {
+        Self {
+            reuse_address: enable,
+            ..self
+        }
+    }
+
+    /// Sets the `SO_REUSEPORT` option on the socket before binding.
+    ///
+    /// This allows a new process to bind the same address while the old one
+    /// is still draining its connections, for zero-downtime restarts. The
+    /// kernel distributes the incoming connections between all the sockets
+    /// bound with this option.
+    #[cfg(all(unix, not(target_os = "solaris"), not(target_os = "illumos")))]
+    #[cfg_attr(
+        docsrs,
+        doc(cfg(all(unix, not(target_os = "solaris"), not(target_os = "illumos"))))
+    )]
+    #[must_use]
+    pub fn reuse_port(self, enable: bool) -> Self {
+        Self {
+            reuse_port: enable,
+            ..self
+        }
     }
 }
 
+impl<T: ToSocketAddrs + Send> TcpListener<T> {
+    async fn bind_with_options(self) -> IoResult<TokioTcpListener> {
+        let mut last_err = None;
+
+        for addr in lookup_host(self.addr).await? {
+            match bind_socket(addr, self.reuse_address, self.reuse_port) {
+                Ok(listener) => return Ok(listener),
+                Err(err) => last_err = Some(err),
+            }
+        }
+
+        Err(last_err.unwrap_or_else(|| {
+            std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "could not resolve to any address",
+            )
+        }))
+    }
+}
+
+#[allow(unused_variables)]
+fn bind_socket(
+    addr: SocketAddr,
+    reuse_address: bool,
+    reuse_port: bool,
+) -> IoResult<TokioTcpListener> {
+    let socket = match addr {
+        SocketAddr::V4(_) => TcpSocket::new_v4()?,
+        SocketAddr::V6(_) => TcpSocket::new_v6()?,
+    };
+    if reuse_address {
+        socket.set_reuseaddr(true)?;
+    }
+    #[cfg(all(unix, not(target_os = "solaris"), not(target_os = "illumos")))]
+    if reuse_port {
+        socket.set_reuseport(true)?;
+    }
+    socket.bind(addr)?;
+    socket.listen(1024)
+}
+
 impl<T: ToSocketAddrs + Send> Listener for TcpListener<T> {
     type Acceptor = TcpAcceptor;
 
     async fn into_acceptor(self) -> IoResult<Self::Acceptor> {
-        let listener = TokioTcpListener::bind(self.addr).await?;
+        let listener = if self.reuse_address || self.reuse_port {
+            self.bind_with_options().await?
+        } else {
+            TokioTcpListener::bind(self.addr).await?
+        };
         let local_addr = listener.local_addr().map(|addr| LocalAddr(addr.into()))?;
         Ok(TcpAcceptor {
             local_addr,
@@ -52,6 +131,40 @@ impl TcpAcceptor {
         })
     }
 
+    /// Creates new `TcpAcceptor` from a socket passed by the process manager
+    /// with the systemd socket activation protocol.
+    ///
+    /// The `index` is the position of the socket in the passed file
+    /// descriptors, starting at `0`. Returns an error if the `LISTEN_PID` and
+    /// `LISTEN_FDS` environment variables do not pass a socket at this
+    /// position to the current process, if it is not a TCP socket, or if it has
+    /// already been taken.
+    ///
+    /// Use [`SystemdListener`](crate::listener::SystemdListener) if the socket
+    /// type is not known in advance.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use poem::{
+    ///     listener::{Listener, TcpAcceptor, TcpListener},
+    ///     Route, Server,
+    /// };
+    ///
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let app = Route::new();
+    /// match TcpAcceptor::from_listen_fds(0) {
+    ///     Ok(acceptor) => Server::new_with_acceptor(acceptor).run(app).await,
+    ///     Err(_) => Server::new(TcpListener::bind("0.0.0.0:3000")).run(app).await,
+    /// }
+    /// # });
+    /// ```
+    #[cfg(all(unix, feature = "listenfd"))]
+    #[cfg_attr(docsrs, doc(cfg(all(unix, feature = "listenfd"))))]
+    pub fn from_listen_fds(index: usize) -> Result<Self> {
+        Self::from_std(super::systemd::take_tcp_listener(index)?)
+    }
+
     /// Creates new `TcpAcceptor` from a `tokio::net::TcpListener`.
     pub fn from_tokio(listener: tokio::net::TcpListener) -> Result<Self> {
         let local_addr = listener.local_addr().map(|addr| LocalAddr(addr.into()))?;
@@ -105,4 +218,24 @@ mod tests {
         let (mut stream, _, _, _) = acceptor.accept().await.unwrap();
         assert_eq!(stream.read_i32().await.unwrap(), 10);
     }
+
+    #[cfg(all(unix, not(target_os = "solaris"), not(target_os = "illumos")))]
+    #[tokio::test]
+    async fn reuse_port() {
+        let a = TcpListener::bind("127.0.0.1:0")
+            .reuse_port(true)
+            .into_acceptor()
+            .await
+            .unwrap();
+        let addr = *a.local_addr()[0].as_socket_addr().unwrap();
+
+        assert!(TcpListener::bind(addr).into_acceptor().await.is_err());
+        let b = TcpListener::bind(addr)
+            .reuse_address(true)
+            .reuse_port(true)
+            .into_acceptor()
+            .await
+            .unwrap();
+        assert_eq!(b.local_addr()[0].as_socket_addr(), Some(&addr));
+    }
 }
@@ -101,6 +101,16 @@ impl UnixAcceptor {
             listener,
         })
     }
+
+    /// Creates new `UnixAcceptor` from a socket passed by the process manager
+    /// with the systemd socket activation protocol.
+    ///
+    /// See [`TcpAcceptor::from_listen_fds`](crate::listener::TcpAcceptor::from_listen_fds).
+    #[cfg(feature = "listenfd")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "listenfd")))]
+    pub fn from_listen_fds(index: usize) -> Result<Self> {
+        Self::from_std(super::systemd::take_unix_listener(index)?)
+    }
 }
 
 impl Acceptor for UnixAcceptor {
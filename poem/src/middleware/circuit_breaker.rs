@@ -0,0 +1,455 @@
+use std::{
+    collections::VecDeque,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use parking_lot::Mutex;
+
+use crate::{
+    error::CircuitOpenError, Endpoint, IntoResponse, Middleware, Request, Response, Result,
+};
+
+/// The state of a [`CircuitBreaker`].
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum CircuitState {
+    /// Requests are passed to the inner endpoint.
+    Closed,
+    /// Requests are rejected without calling the inner endpoint.
+    Open,
+    /// A single request is passed to the inner endpoint to test whether it
+    /// has recovered.
+    HalfOpen,
+}
+
+type StateChangeCallback = Arc<dyn Fn(CircuitState) + Send + Sync>;
+
+/// Middleware that stops calling an endpoint when it keeps failing.
+///
+/// A request fails if the inner endpoint returns an error or a response with a
+/// `5xx` status code. When the failure rate over the rolling window reaches the
+/// threshold, the circuit trips to [`CircuitState::Open`] and requests are
+/// rejected with `503 SERVICE UNAVAILABLE`. After the cooldown a single request
+/// is let through to test the endpoint, the circuit closes if it succeeds and
+/// opens again otherwise.
+///
+/// Each endpoint transformed by this middleware has its own state, so it can
+/// be applied to each route separately.
+///
+/// # Errors
+///
+/// - [`CircuitOpenError`]
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+///
+/// use poem::{get, handler, middleware::CircuitBreaker, EndpointExt, Route};
+///
+/// #[handler]
+/// fn index() -> &'static str {
+///     "hello"
+/// }
+///
+/// let app = Route::new().at(
+///     "/",
+///     get(index).with(
+///         CircuitBreaker::new()
+///             .failure_threshold(0.5)
+///             .minimum_requests(20)
+///             .window(Duration::from_secs(30))
+///             .cooldown(Duration::from_secs(10))
+///             .on_state_change(|state| println!("circuit state: {state:?}")),
+///     ),
+/// );
+/// ```
+#[derive(Clone)]
+pub struct CircuitBreaker {
+    failure_threshold: f64,
+    minimum_requests: usize,
+    window: Duration,
+    cooldown: Duration,
+    on_state_change: Option<StateChangeCallback>,
+}
+
+impl Default for CircuitBreaker {
+    fn default() -> Self {
+        Self {
+            failure_threshold: 0.5,
+            minimum_requests: 10,
+            window: Duration::from_secs(60),
+            cooldown: Duration::from_secs(30),
+            on_state_change: None,
+        }
+    }
+}
+
+impl CircuitBreaker {
+    /// Create new `CircuitBreaker` middleware.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the failure rate (between `0.0` and `1.0`) that trips the circuit.
+    ///
+    /// Default is `0.5`.
+    #[must_use]
+    pub fn failure_threshold(self, threshold: f64) -> Self {
+        Self {
+            failure_threshold: threshold.clamp(0.0, 1.0),
+            ..self
+        }
+    }
+
+    /// Sets the minimum number of requests in the window before the circuit
+    /// can trip.
+    ///
+    /// Default is `10`.
+    #[must_use]
+    pub fn minimum_requests(self, count: usize) -> Self {
+        Self {
+            minimum_requests: count.max(1),
+            ..self
+        }
+    }
+
+    /// Sets the length of the rolling window used to compute the failure
+    /// rate.
+    ///
+    /// Default is `60s`.
+    #[must_use]
+    pub fn window(self, window: Duration) -> Self {
+        Self { window, ..self }
+    }
+
+    /// Sets how long the circuit stays open before a request is let through
+    /// to test the endpoint.
+    ///
+    /// Default is `30s`.
+    #[must_use]
+    pub fn cooldown(self, cooldown: Duration) -> Self {
+        Self { cooldown, ..self }
+    }
+
+    /// Sets a callback that is called with the new state whenever the state
+    /// of the circuit changes, which can be used to report metrics.
+    #[must_use]
+    pub fn on_state_change(self, f: impl Fn(CircuitState) + Send + Sync + 'static) -> Self {
+        Self {
+            on_state_change: Some(Arc::new(f)),
+            ..self
+        }
+    }
+}
+
+impl<E: Endpoint> Middleware<E> for CircuitBreaker {
+    type Output = CircuitBreakerEndpoint<E>;
+
+    fn transform(&self, ep: E) -> Self::Output {
+        CircuitBreakerEndpoint {
+            inner: ep,
+            config: self.clone(),
+            state: Mutex::new(State {
+                circuit: CircuitState::Closed,
+                outcomes: VecDeque::new(),
+                changed_at: Instant::now(),
+                probe_started_at: None,
+                generation: 0,
+            }),
+        }
+    }
+}
+
+struct State {
+    circuit: CircuitState,
+    /// The time and whether it failed of each completed request in the window.
+    outcomes: VecDeque<(Instant, bool)>,
+    changed_at: Instant,
+    probe_started_at: Option<Instant>,
+    /// Incremented when the state changes or a new probe starts, the results
+    /// of the requests started with an older generation are ignored.
+    generation: u64,
+}
+
+/// Endpoint for the `CircuitBreaker` middleware.
+pub struct CircuitBreakerEndpoint<E> {
+    inner: E,
+    config: CircuitBreaker,
+    state: Mutex<State>,
+}
+
+impl<E> CircuitBreakerEndpoint<E> {
+    /// Returns the current state of the circuit.
+    pub fn state(&self) -> CircuitState {
+        self.state.lock().circuit
+    }
+
+    /// Returns the new state if it has changed, the callback is called with
+    /// it after the lock is released.
+    fn set_state(state: &mut State, circuit: CircuitState, now: Instant) -> Option<CircuitState> {
+        if state.circuit == circuit {
+            return None;
+        }
+        state.circuit = circuit;
+        state.changed_at = now;
+        state.probe_started_at = None;
+        state.outcomes.clear();
+        state.generation += 1;
+        Some(circuit)
+    }
+
+    fn notify(&self, changed: Option<CircuitState>) {
+        if let (Some(circuit), Some(on_state_change)) = (changed, &self.config.on_state_change) {
+            on_state_change(circuit);
+        }
+    }
+
+    /// Returns the generation of the state if the request is allowed to call
+    /// the inner endpoint.
+    fn acquire(&self) -> Option<u64> {
+        let now = Instant::now();
+        let mut state = self.state.lock();
+
+        let (generation, changed) = match state.circuit {
+            CircuitState::Closed => (Some(state.generation), None),
+            CircuitState::Open => {
+                if now.duration_since(state.changed_at) < self.config.cooldown {
+                    return None;
+                }
+                let changed = Self::set_state(&mut state, CircuitState::HalfOpen, now);
+                state.probe_started_at = Some(now);
+                (Some(state.generation), changed)
+            }
+            CircuitState::HalfOpen => match state.probe_started_at {
+                // the probe may have been cancelled, so allow another one after the cooldown
+                Some(started_at) if now.duration_since(started_at) < self.config.cooldown => {
+                    return None
+                }
+                _ => {
+                    state.probe_started_at = Some(now);
+                    state.generation += 1;
+                    (Some(state.generation), None)
+                }
+            },
+        };
+        drop(state);
+        self.notify(changed);
+        generation
+    }
+
+    fn record(&self, generation: u64, failed: bool) {
+        let now = Instant::now();
+        let mut state = self.state.lock();
+        if state.generation != generation {
+            return;
+        }
+
+        let changed = match state.circuit {
+            CircuitState::Closed => {
+                while state
+                    .outcomes
+                    .front()
+                    .is_some_and(|(time, _)| now.duration_since(*time) > self.config.window)
+                {
+                    state.outcomes.pop_front();
+                }
+                state.outcomes.push_back((now, failed));
+
+                let total = state.outcomes.len();
+                let failures = state.outcomes.iter().filter(|(_, failed)| *failed).count();
+                if failed
+                    && total >= self.config.minimum_requests
+                    && failures as f64 / total as f64 >= self.config.failure_threshold
+                {
+                    Self::set_state(&mut state, CircuitState::Open, now)
+                } else {
+                    None
+                }
+            }
+            CircuitState::HalfOpen if failed => {
+                Self::set_state(&mut state, CircuitState::Open, now)
+            }
+            CircuitState::HalfOpen => Self::set_state(&mut state, CircuitState::Closed, now),
+            CircuitState::Open => None,
+        };
+        drop(state);
+        self.notify(changed);
+    }
+}
+
+impl<E: Endpoint> Endpoint for CircuitBreakerEndpoint<E> {
+    type Output = Response;
+
+    async fn call(&self, req: Request) -> Result<Self::Output> {
+        let Some(generation) = self.acquire() else {
+            return Err(CircuitOpenError.into());
+        };
+
+        match self.inner.call(req).await {
+            Ok(resp) => {
+                let resp = resp.into_response();
+                self.record(generation, resp.status().is_server_error());
+                Ok(resp)
+            }
+            Err(err) => {
+                self.record(generation, err.status().is_server_error());
+                Err(err)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+    use http::StatusCode;
+
+    use super::*;
+    use crate::{endpoint::make_sync, test::TestClient, EndpointExt};
+
+    #[tokio::test]
+    async fn test_circuit_breaker() {
+        let healthy = Arc::new(AtomicBool::new(false));
+        let calls = Arc::new(AtomicUsize::new(0));
+        let changes = Arc::new(Mutex::new(Vec::new()));
+
+        let ep = make_sync({
+            let healthy = healthy.clone();
+            let calls = calls.clone();
+            move |_| {
+                calls.fetch_add(1, Ordering::SeqCst);
+                if healthy.load(Ordering::SeqCst) {
+                    StatusCode::OK
+                } else {
+                    StatusCode::INTERNAL_SERVER_ERROR
+                }
+            }
+        })
+        .with(
+            CircuitBreaker::new()
+                .failure_threshold(0.5)
+                .minimum_requests(3)
+                .cooldown(Duration::from_millis(100))
+                .on_state_change({
+                    let changes = changes.clone();
+                    move |state| changes.lock().push(state)
+                }),
+        );
+        let cli = TestClient::new(ep);
+
+        for _ in 0..3 {
+            cli.get("/")
+                .send()
+                .await
+                .assert_status(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+        assert_eq!(*changes.lock(), vec![CircuitState::Open]);
+
+        // short-circuited without calling the handler
+        cli.get("/")
+            .send()
+            .await
+            .assert_status(StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+
+        // the probe fails
+        tokio::time::sleep(Duration::from_millis(150)).await;
+        cli.get("/")
+            .send()
+            .await
+            .assert_status(StatusCode::INTERNAL_SERVER_ERROR);
+        cli.get("/")
+            .send()
+            .await
+            .assert_status(StatusCode::SERVICE_UNAVAILABLE);
+
+        // the probe succeeds
+        healthy.store(true, Ordering::SeqCst);
+        tokio::time::sleep(Duration::from_millis(150)).await;
+        cli.get("/").send().await.assert_status_is_ok();
+        cli.get("/").send().await.assert_status_is_ok();
+        assert_eq!(
+            *changes.lock(),
+            vec![
+                CircuitState::Open,
+                CircuitState::HalfOpen,
+                CircuitState::Open,
+                CircuitState::HalfOpen,
+                CircuitState::Closed,
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_below_threshold() {
+        let ep = CircuitBreaker::new()
+            .failure_threshold(0.5)
+            .minimum_requests(2)
+            .transform(make_sync(|req| {
+                if req.uri().path() == "/error" {
+                    StatusCode::INTERNAL_SERVER_ERROR
+                } else {
+                    StatusCode::OK
+                }
+            }));
+
+        for path in ["/", "/", "/error"] {
+            let _ = ep.call(Request::builder().uri_str(path).finish()).await;
+        }
+        assert_eq!(ep.state(), CircuitState::Closed);
+
+        let _ = ep.call(Request::builder().uri_str("/error").finish()).await;
+        assert_eq!(ep.state(), CircuitState::Open);
+    }
+
+    #[tokio::test]
+    async fn test_stale_results() {
+        let ep = CircuitBreaker::new()
+            .minimum_requests(1)
+            .cooldown(Duration::ZERO)
+            .transform(make_sync(|_| StatusCode::INTERNAL_SERVER_ERROR));
+
+        let generation = ep.acquire().unwrap();
+        ep.record(generation, true);
+        assert_eq!(ep.state(), CircuitState::Open);
+
+        // a request started before the circuit opened does not close it
+        let probe = ep.acquire().unwrap();
+        assert_eq!(ep.state(), CircuitState::HalfOpen);
+        ep.record(generation, false);
+        assert_eq!(ep.state(), CircuitState::HalfOpen);
+
+        // the result of a probe replaced by a newer one is ignored
+        let newer_probe = ep.acquire().unwrap();
+        ep.record(probe, false);
+        assert_eq!(ep.state(), CircuitState::HalfOpen);
+        ep.record(newer_probe, false);
+        assert_eq!(ep.state(), CircuitState::Closed);
+    }
+
+    #[tokio::test]
+    async fn test_callback_without_lock() {
+        let ep = Arc::new(Mutex::new(None::<Arc<CircuitBreakerEndpoint<_>>>));
+        let states = Arc::new(Mutex::new(Vec::new()));
+        let endpoint = Arc::new(
+            CircuitBreaker::new()
+                .minimum_requests(1)
+                .on_state_change({
+                    let ep = ep.clone();
+                    let states = states.clone();
+                    // reading the state in the callback would deadlock if the lock is held
+                    move |_| {
+                        let ep = ep.lock().clone().unwrap();
+                        states.lock().push(ep.state());
+                    }
+                })
+                .transform(make_sync(|_| StatusCode::INTERNAL_SERVER_ERROR)),
+        );
+        *ep.lock() = Some(endpoint.clone());
+
+        let _ = endpoint.call(Request::default()).await;
+        assert_eq!(*states.lock(), vec![CircuitState::Open]);
+    }
+}
@@ -1,17 +1,45 @@
-use std::{collections::HashSet, str::FromStr};
+use std::{
+    collections::{HashMap, HashSet},
+    str::FromStr,
+};
 
 use headers::HeaderMap;
 
 use crate::{
-    http::header,
+    http::{header, HeaderValue, Method},
     web::{Compress, CompressionAlgo, CompressionLevel},
     Body, Endpoint, IntoResponse, Middleware, Request, Response, Result,
 };
 
+/// The content types which are not compressed by default, because they are
+/// already compressed.
+const DEFAULT_DENIED_CONTENT_TYPES: &[&str] = &[
+    "image/*",
+    "video/*",
+    "audio/*",
+    "font/woff",
+    "font/woff2",
+    "application/gzip",
+    "application/zip",
+    "application/zstd",
+    "application/x-7z-compressed",
+    "application/x-bzip2",
+    "application/x-rar-compressed",
+    "application/x-xz",
+];
+
+/// The content types which are compressed even if they match
+/// [`DEFAULT_DENIED_CONTENT_TYPES`].
+const DEFAULT_ALLOWED_CONTENT_TYPES: &[&str] = &["image/svg+xml"];
+
+#[derive(Copy, Clone, Eq, PartialEq)]
 enum ContentCoding {
     Brotli,
     Deflate,
     Gzip,
+    #[cfg(feature = "zstd")]
+    Zstd,
+    Identity,
     Star,
 }
 
@@ -19,12 +47,19 @@ impl FromStr for ContentCoding {
     type Err = ();
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
+        #[cfg(feature = "zstd")]
+        if s.eq_ignore_ascii_case("zstd") {
+            return Ok(ContentCoding::Zstd);
+        }
+
         if s.eq_ignore_ascii_case("deflate") {
             Ok(ContentCoding::Deflate)
         } else if s.eq_ignore_ascii_case("gzip") {
             Ok(ContentCoding::Gzip)
         } else if s.eq_ignore_ascii_case("br") {
             Ok(ContentCoding::Brotli)
+        } else if s.eq_ignore_ascii_case("identity") {
+            Ok(ContentCoding::Identity)
         } else if s == "*" {
             Ok(ContentCoding::Star)
         } else {
@@ -33,51 +68,167 @@ impl FromStr for ContentCoding {
     }
 }
 
+impl ContentCoding {
+    fn algo(self) -> Option<CompressionAlgo> {
+        match self {
+            ContentCoding::Brotli => Some(CompressionAlgo::BR),
+            ContentCoding::Deflate => Some(CompressionAlgo::DEFLATE),
+            ContentCoding::Gzip => Some(CompressionAlgo::GZIP),
+            #[cfg(feature = "zstd")]
+            ContentCoding::Zstd => Some(CompressionAlgo::ZSTD),
+            ContentCoding::Identity | ContentCoding::Star => None,
+        }
+    }
+}
+
+/// Parses a `coding;q=value` item, the quality is in thousandths.
+fn parse_coding(item: &str) -> Option<(ContentCoding, u16)> {
+    let mut parts = item.split(';').map(str::trim);
+    let coding = parts.next()?.parse().ok()?;
+    let mut q = 1000;
+    for param in parts {
+        if let Some((name, value)) = param.split_once('=') {
+            if name.trim().eq_ignore_ascii_case("q") {
+                let value = value.trim().parse::<f32>().ok()?;
+                q = (value.clamp(0.0, 1.0) * 1000.0) as u16;
+            }
+        }
+    }
+    Some((coding, q))
+}
+
+/// Selects the algorithm with the highest quality value, the algorithms with
+/// a zero quality are refused, and nothing is selected if `identity` has a
+/// higher quality than all the algorithms.
 fn parse_accept_encoding(
     headers: &HeaderMap,
     enabled_algorithms: &HashSet<CompressionAlgo>,
-) -> Option<ContentCoding> {
-    headers
+) -> Option<CompressionAlgo> {
+    let codings = headers
         .get_all(header::ACCEPT_ENCODING)
         .iter()
         .filter_map(|hval| hval.to_str().ok())
-        .flat_map(|s| s.split(',').map(str::trim))
-        .filter_map(|v| {
-            let (e, q) = match v.split_once(";q=") {
-                Some((e, q)) => (e, (q.parse::<f32>().ok()? * 1000.0) as i32),
-                None => (v, 1000),
-            };
-            let coding: ContentCoding = e.parse().ok()?;
-            Some((coding, q))
-        })
-        .filter(|(encoding, _)| {
-            if !enabled_algorithms.is_empty() {
-                match encoding {
-                    ContentCoding::Brotli => enabled_algorithms.contains(&CompressionAlgo::BR),
-                    ContentCoding::Deflate => {
-                        enabled_algorithms.contains(&CompressionAlgo::DEFLATE)
-                    }
-                    ContentCoding::Gzip => enabled_algorithms.contains(&CompressionAlgo::GZIP),
-                    _ => true,
-                }
-            } else {
-                true
-            }
+        .flat_map(|s| s.split(','))
+        .filter_map(parse_coding)
+        .collect::<Vec<_>>();
+    let quality =
+        |coding: ContentCoding| codings.iter().find(|(c, _)| *c == coding).map(|(_, q)| *q);
+
+    let (algo, q) = [
+        CompressionAlgo::BR,
+        #[cfg(feature = "zstd")]
+        CompressionAlgo::ZSTD,
+        CompressionAlgo::GZIP,
+        CompressionAlgo::DEFLATE,
+    ]
+    .into_iter()
+    .filter(|algo| enabled_algorithms.is_empty() || enabled_algorithms.contains(algo))
+    .filter_map(|algo| {
+        // `*` matches the codings which are not listed
+        let q = codings
+            .iter()
+            .find(|(coding, _)| coding.algo() == Some(algo))
+            .map(|(_, q)| *q)
+            .or_else(|| quality(ContentCoding::Star))?;
+        (q > 0).then_some((algo, q))
+    })
+    .max_by_key(|(algo, q)| (*q, coding_priority(*algo)))?;
+
+    match quality(ContentCoding::Identity) {
+        Some(identity) if identity > q => None,
+        _ => Some(algo),
+    }
+}
+
+/// Returns `true` if the response has the `no-transform` cache directive,
+/// which forbids changing the content encoding of the body.
+fn is_no_transform(headers: &HeaderMap) -> bool {
+    headers
+        .get_all(header::CACHE_CONTROL)
+        .iter()
+        .filter_map(|hval| hval.to_str().ok())
+        .flat_map(|s| s.split(','))
+        .any(|directive| directive.trim().eq_ignore_ascii_case("no-transform"))
+}
+
+/// Returns `true` if the content type matches one of the patterns, such as
+/// `text/html` or `image/*`.
+fn matches_any<T: AsRef<str>>(patterns: &[T], content_type: &str) -> bool {
+    let essence = content_type.split(';').next().unwrap_or_default().trim();
+    patterns
+        .iter()
+        .any(|pattern| match pattern.as_ref().strip_suffix("/*") {
+            Some(ty) => essence
+                .split_once('/')
+                .is_some_and(|(essence_ty, _)| essence_ty.eq_ignore_ascii_case(ty)),
+            None => essence.eq_ignore_ascii_case(pattern.as_ref()),
         })
-        .max_by_key(|(coding, q)| (*q, coding_priority(coding)))
-        .map(|(coding, _)| coding)
+}
+
+/// Adds `Accept-Encoding` to the `Vary` header of the response.
+fn add_vary(headers: &mut HeaderMap) {
+    let exists = headers
+        .get_all(header::VARY)
+        .iter()
+        .filter_map(|hval| hval.to_str().ok())
+        .flat_map(|s| s.split(','))
+        .map(str::trim)
+        .any(|name| name == "*" || name.eq_ignore_ascii_case("accept-encoding"));
+    if !exists {
+        headers.append(header::VARY, HeaderValue::from_static("accept-encoding"));
+    }
 }
 
 /// Middleware to decompress the request body and compress the response body.
 ///
 /// The decompression algorithm is selected according to the request
 /// `Content-Encoding` header, and the compression algorithm is selected
-/// according to the request `Accept-Encoding` header.
+/// according to the quality values of the request `Accept-Encoding` header.
+/// The algorithms with `q=0` are never used, and the response is not
+/// compressed if `identity` has a higher quality than all the supported
+/// algorithms, e.g. `identity;q=1, gzip;q=0.5`.
+///
+/// The responses are not compressed if their content type is already
+/// compressed, such as images and videos, see
+/// [`Compression::deny_content_types`], or if their length is known and
+/// smaller than [`Compression::min_size`]. `Vary: Accept-Encoding` is added
+/// to the other responses.
+///
+/// Responses with the `Cache-Control: no-transform` directive are never
+/// compressed, as required by [RFC 9111](https://www.rfc-editor.org/rfc/rfc9111#section-5.2.2.6),
+/// nor the responses which already have a `Content-Encoding`.
+///
+/// # Example
+///
+/// ```
+/// use poem::{
+///     handler,
+///     middleware::Compression,
+///     web::{CompressionAlgo, CompressionLevel},
+///     EndpointExt,
+/// };
+///
+/// #[handler]
+/// fn index() -> &'static str {
+///     "hello"
+/// }
+///
+/// let app = index.with(
+///     Compression::new()
+///         .min_size(1024)
+///         .allow_content_types(["text/*", "application/json"])
+///         .with_algorithm_quality(CompressionAlgo::BR, CompressionLevel::Precise(5)),
+/// );
+/// ```
 #[cfg_attr(docsrs, doc(cfg(feature = "compression")))]
-#[derive(Default)]
+#[derive(Default, Clone)]
 pub struct Compression {
     level: Option<CompressionLevel>,
+    algorithm_levels: HashMap<CompressionAlgo, CompressionLevel>,
     algorithms: HashSet<CompressionAlgo>,
+    allowed_content_types: Option<Vec<String>>,
+    denied_content_types: Option<Vec<String>>,
+    min_size: u64,
 }
 
 impl Compression {
@@ -97,6 +248,18 @@ impl Compression {
         }
     }
 
+    /// Specify the compression level of an algorithm, overrides the level
+    /// set by [`Compression::with_quality`].
+    #[must_use]
+    pub fn with_algorithm_quality(
+        mut self,
+        algo: CompressionAlgo,
+        level: CompressionLevel,
+    ) -> Self {
+        self.algorithm_levels.insert(algo, level);
+        self
+    }
+
     /// Specify the enabled algorithms (defaults to all)
     #[must_use]
     #[inline]
@@ -106,6 +269,79 @@ impl Compression {
             ..self
         }
     }
+
+    /// Only compresses the responses whose content type matches one of the
+    /// patterns, such as `text/html` or `text/*`, the responses without a
+    /// content type are not compressed.
+    ///
+    /// The default list of [`Compression::deny_content_types`] is not used
+    /// when the allowed content types are specified.
+    #[must_use]
+    pub fn allow_content_types<I, T>(self, patterns: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<String>,
+    {
+        Self {
+            allowed_content_types: Some(patterns.into_iter().map(Into::into).collect()),
+            ..self
+        }
+    }
+
+    /// Never compresses the responses whose content type matches one of the
+    /// patterns, such as `application/zip` or `image/*`, this replaces the
+    /// default list.
+    ///
+    /// By default, the images except SVG, videos, audios, web fonts and
+    /// common archive formats are not compressed.
+    #[must_use]
+    pub fn deny_content_types<I, T>(self, patterns: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<String>,
+    {
+        Self {
+            denied_content_types: Some(patterns.into_iter().map(Into::into).collect()),
+            ..self
+        }
+    }
+
+    /// Does not compress the responses whose body is known to be shorter than
+    /// `size` bytes, the streaming bodies of unknown length are always
+    /// compressed.
+    ///
+    /// Default is `0`.
+    #[must_use]
+    pub fn min_size(self, size: u64) -> Self {
+        Self {
+            min_size: size,
+            ..self
+        }
+    }
+
+    fn is_compressible(&self, content_type: Option<&str>) -> bool {
+        let Some(content_type) = content_type else {
+            return self.allowed_content_types.is_none();
+        };
+
+        if let Some(allowed) = &self.allowed_content_types {
+            if !matches_any(allowed, content_type) {
+                return false;
+            }
+        }
+        match &self.denied_content_types {
+            Some(denied) => !matches_any(denied, content_type),
+            None if self.allowed_content_types.is_some() => true,
+            None => {
+                !matches_any(DEFAULT_DENIED_CONTENT_TYPES, content_type)
+                    || matches_any(DEFAULT_ALLOWED_CONTENT_TYPES, content_type)
+            }
+        }
+    }
+
+    fn level(&self, algo: CompressionAlgo) -> Option<CompressionLevel> {
+        self.algorithm_levels.get(&algo).copied().or(self.level)
+    }
 }
 
 impl<E: Endpoint> Middleware<E> for Compression {
@@ -114,8 +350,7 @@ impl<E: Endpoint> Middleware<E> for Compression {
     fn transform(&self, ep: E) -> Self::Output {
         CompressionEndpoint {
             ep,
-            level: self.level,
-            algorithms: self.algorithms.clone(),
+            config: self.clone(),
         }
     }
 }
@@ -124,17 +359,17 @@ impl<E: Endpoint> Middleware<E> for Compression {
 #[cfg_attr(docsrs, doc(cfg(feature = "compression")))]
 pub struct CompressionEndpoint<E: Endpoint> {
     ep: E,
-    level: Option<CompressionLevel>,
-    algorithms: HashSet<CompressionAlgo>,
+    config: Compression,
 }
 
 #[inline]
-fn coding_priority(c: &ContentCoding) -> u8 {
-    match *c {
-        ContentCoding::Deflate => 1,
-        ContentCoding::Gzip => 2,
-        ContentCoding::Brotli => 3,
-        _ => 0,
+fn coding_priority(algo: CompressionAlgo) -> u8 {
+    match algo {
+        CompressionAlgo::DEFLATE => 1,
+        CompressionAlgo::GZIP => 2,
+        #[cfg(feature = "zstd")]
+        CompressionAlgo::ZSTD => 3,
+        CompressionAlgo::BR => 4,
     }
 }
 
@@ -151,26 +386,58 @@ impl<E: Endpoint> Endpoint for CompressionEndpoint<E> {
         {
             let new_body = algo.decompress(req.take_body().into_async_read());
             req.set_body(Body::from_async_read(new_body));
+            // the inner endpoints see the decompressed body
+            req.headers_mut().remove(header::CONTENT_ENCODING);
+            req.headers_mut().remove(header::CONTENT_LENGTH);
         }
 
         // negotiate content-encoding
-        let compress_algo =
-            parse_accept_encoding(req.headers(), &self.algorithms).map(|coding| match coding {
-                ContentCoding::Gzip => CompressionAlgo::GZIP,
-                ContentCoding::Deflate => CompressionAlgo::DEFLATE,
-                ContentCoding::Star | ContentCoding::Brotli => CompressionAlgo::BR,
+        let compress_algo = parse_accept_encoding(req.headers(), &self.config.algorithms);
+
+        let is_head = req.method() == Method::HEAD;
+        let mut resp = self.ep.call(req).await?.into_response();
+        if is_no_transform(resp.headers())
+            || resp.headers().contains_key(header::CONTENT_ENCODING)
+            || !self.config.is_compressible(resp.content_type())
+        {
+            return Ok(resp);
+        }
+
+        // the body of a `HEAD` response is empty, so only the `Content-Length` is used
+        let size = resp
+            .headers()
+            .get(header::CONTENT_LENGTH)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.parse::<u64>().ok())
+            .or_else(|| {
+                (!is_head)
+                    .then(|| hyper::body::Body::size_hint(&resp.body().0).exact())
+                    .flatten()
             });
+        if size.is_some_and(|size| size < self.config.min_size) {
+            return Ok(resp);
+        }
 
-        let resp = self.ep.call(req).await?;
+        add_vary(resp.headers_mut());
         match compress_algo {
+            Some(algo) if is_head => {
+                // the response to `HEAD` has no body, but its headers must match what
+                // a `GET` with the same `Accept-Encoding` would produce.
+                resp.headers_mut().append(
+                    header::CONTENT_ENCODING,
+                    HeaderValue::from_static(algo.as_str()),
+                );
+                resp.headers_mut().remove(header::CONTENT_LENGTH);
+                Ok(resp)
+            }
             Some(algo) => {
                 let mut compress = Compress::new(resp, algo);
-                if let Some(level) = self.level {
+                if let Some(level) = self.config.level(algo) {
                     compress = compress.with_quality(level);
                 }
                 Ok(compress.into_response())
             }
-            None => Ok(resp.into_response()),
+            None => Ok(resp),
         }
     }
 }
@@ -180,7 +447,7 @@ mod tests {
     use tokio::io::AsyncReadExt;
 
     use super::*;
-    use crate::{handler, test::TestClient, EndpointExt};
+    use crate::{get, handler, test::TestClient, EndpointExt, Route};
 
     const DATA: &str = "abcdefghijklmnopqrstuvwxyz1234567890";
     const DATA_REV: &str = "0987654321zyxwvutsrqponmlkjihgfedcba";
@@ -216,6 +483,33 @@ mod tests {
         test_algo(CompressionAlgo::BR).await;
         test_algo(CompressionAlgo::DEFLATE).await;
         test_algo(CompressionAlgo::GZIP).await;
+        #[cfg(feature = "zstd")]
+        test_algo(CompressionAlgo::ZSTD).await;
+    }
+
+    #[tokio::test]
+    async fn test_decompressed_request_headers() {
+        let ep = crate::endpoint::make_sync(|req| {
+            format!(
+                "{:?} {:?}",
+                req.header(header::CONTENT_ENCODING),
+                req.header(header::CONTENT_LENGTH)
+            )
+        })
+        .with(Compression::default());
+        let cli = TestClient::new(ep);
+
+        let resp = cli
+            .post("/")
+            .header("Content-Encoding", "gzip")
+            .header("Content-Length", "10")
+            .body(Body::from_async_read(
+                CompressionAlgo::GZIP.compress(DATA.as_bytes(), None),
+            ))
+            .send()
+            .await;
+        resp.assert_status_is_ok();
+        resp.assert_text("None None").await;
     }
 
     #[tokio::test]
@@ -245,15 +539,19 @@ mod tests {
 
         let resp = cli
             .post("/")
-            .header("Accept-Encoding", "identity; q=0.5, *;q=1.0, br;q=0.3")
+            .header(
+                "Accept-Encoding",
+                "identity; q=0.5, *;q=1.0, br;q=0.3, zstd;q=0.3",
+            )
             .body(DATA)
             .send()
             .await;
         resp.assert_status_is_ok();
-        resp.assert_header("Content-Encoding", "br");
+        // `*` matches the unlisted codings, `br` and `zstd` have a lower quality
+        resp.assert_header("Content-Encoding", "gzip");
 
         let mut data = Vec::new();
-        let mut reader = CompressionAlgo::BR.decompress(resp.0.into_body().into_async_read());
+        let mut reader = CompressionAlgo::GZIP.decompress(resp.0.into_body().into_async_read());
         reader.read_to_end(&mut data).await.unwrap();
         assert_eq!(data, DATA_REV.as_bytes());
     }
@@ -304,4 +602,238 @@ mod tests {
         resp.assert_status_is_ok();
         resp.assert_header("Content-Encoding", "br");
     }
+
+    #[tokio::test]
+    async fn test_head() {
+        #[handler(internal)]
+        async fn get_index() -> &'static str {
+            DATA
+        }
+
+        let cli = TestClient::new(
+            Route::new()
+                .at("/", get(get_index))
+                .with(Compression::default()),
+        );
+
+        let resp = cli.get("/").header("Accept-Encoding", "gzip").send().await;
+        resp.assert_status_is_ok();
+        resp.assert_header("Content-Encoding", "gzip");
+        resp.assert_header_is_not_exist(header::CONTENT_LENGTH);
+
+        let resp = cli.head("/").header("Accept-Encoding", "gzip").send().await;
+        resp.assert_status_is_ok();
+        resp.assert_header("Content-Encoding", "gzip");
+        resp.assert_header_is_not_exist(header::CONTENT_LENGTH);
+        resp.assert_bytes("").await;
+
+        let cli =
+            TestClient::new(Route::new().at("/", get(get_index.with(Compression::default()))));
+        let resp = cli.head("/").header("Accept-Encoding", "gzip").send().await;
+        resp.assert_status_is_ok();
+        resp.assert_header("Content-Encoding", "gzip");
+        resp.assert_header_is_not_exist(header::CONTENT_LENGTH);
+        resp.assert_bytes("").await;
+    }
+
+    #[tokio::test]
+    async fn test_head_min_size() {
+        #[handler(internal)]
+        async fn get_index() -> &'static str {
+            DATA
+        }
+
+        let cli = TestClient::new(
+            Route::new()
+                .at("/", get(get_index))
+                .with(Compression::new().min_size(1024)),
+        );
+
+        let resp = cli.get("/").header("Accept-Encoding", "gzip").send().await;
+        resp.assert_status_is_ok();
+        resp.assert_header_is_not_exist(header::CONTENT_ENCODING);
+
+        let resp = cli.head("/").header("Accept-Encoding", "gzip").send().await;
+        resp.assert_status_is_ok();
+        resp.assert_header_is_not_exist(header::CONTENT_ENCODING);
+        resp.assert_header(header::CONTENT_LENGTH, DATA.len().to_string());
+        resp.assert_bytes("").await;
+    }
+
+    #[tokio::test]
+    async fn test_no_transform() {
+        #[handler(internal)]
+        async fn no_transform() -> Response {
+            Response::builder()
+                .header(header::CACHE_CONTROL, "public, No-Transform")
+                .body(DATA)
+        }
+
+        let cli = TestClient::new(no_transform.with(Compression::default()));
+
+        let resp = cli.get("/").header("Accept-Encoding", "gzip").send().await;
+        resp.assert_status_is_ok();
+        resp.assert_header_is_not_exist(header::CONTENT_ENCODING);
+        resp.assert_text(DATA).await;
+
+        let resp = cli.head("/").header("Accept-Encoding", "gzip").send().await;
+        resp.assert_status_is_ok();
+        resp.assert_header_is_not_exist(header::CONTENT_ENCODING);
+    }
+
+    #[tokio::test]
+    async fn test_refused_codings() {
+        let ep = index.with(Compression::default());
+        let cli = TestClient::new(ep);
+
+        let resp = cli
+            .post("/")
+            .header("Accept-Encoding", "identity;q=1, gzip;q=0")
+            .body(DATA)
+            .send()
+            .await;
+        resp.assert_status_is_ok();
+        resp.assert_header_is_not_exist(header::CONTENT_ENCODING);
+        resp.assert_text(DATA_REV).await;
+
+        let resp = cli
+            .post("/")
+            .header("Accept-Encoding", "identity;q=1, gzip;q=0.5")
+            .body(DATA)
+            .send()
+            .await;
+        resp.assert_status_is_ok();
+        resp.assert_header_is_not_exist(header::CONTENT_ENCODING);
+
+        let resp = cli
+            .post("/")
+            .header("Accept-Encoding", "*, br;q=0, zstd;q=0")
+            .body(DATA)
+            .send()
+            .await;
+        resp.assert_status_is_ok();
+        resp.assert_header("Content-Encoding", "gzip");
+    }
+
+    #[tokio::test]
+    async fn test_vary() {
+        let cli = TestClient::new(index.with(Compression::default()));
+        let resp = cli
+            .post("/")
+            .header("Accept-Encoding", "gzip")
+            .body(DATA)
+            .send()
+            .await;
+        resp.assert_status_is_ok();
+        resp.assert_header("Vary", "accept-encoding");
+
+        // the response may be compressed for another request
+        let resp = cli.post("/").body(DATA).send().await;
+        resp.assert_status_is_ok();
+        resp.assert_header_is_not_exist(header::CONTENT_ENCODING);
+        resp.assert_header("Vary", "accept-encoding");
+    }
+
+    #[tokio::test]
+    async fn test_content_types() {
+        fn ep() -> impl Endpoint {
+            crate::endpoint::make_sync(|req| {
+                Response::builder()
+                    .content_type(req.uri().path().trim_start_matches('/'))
+                    .body(DATA)
+            })
+        }
+
+        let cli = TestClient::new(ep().with(Compression::default()));
+        for (content_type, compressed) in [
+            ("text/plain", true),
+            ("image/png", false),
+            ("image/svg+xml", true),
+            ("application/zip", false),
+        ] {
+            let resp = cli
+                .get(format!("/{content_type}"))
+                .header("Accept-Encoding", "gzip")
+                .send()
+                .await;
+            resp.assert_status_is_ok();
+            if compressed {
+                resp.assert_header("Content-Encoding", "gzip");
+            } else {
+                resp.assert_header_is_not_exist(header::CONTENT_ENCODING);
+                resp.assert_header_is_not_exist(header::VARY);
+            }
+        }
+
+        let cli = TestClient::new(
+            ep().with(Compression::default().allow_content_types(["text/*", "image/png"])),
+        );
+        for (content_type, compressed) in [
+            ("text/html", true),
+            ("image/png", true),
+            ("application/json", false),
+        ] {
+            let resp = cli
+                .get(format!("/{content_type}"))
+                .header("Accept-Encoding", "gzip")
+                .send()
+                .await;
+            resp.assert_status_is_ok();
+            if compressed {
+                resp.assert_header("Content-Encoding", "gzip");
+            } else {
+                resp.assert_header_is_not_exist(header::CONTENT_ENCODING);
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_min_size() {
+        let cli = TestClient::new(index.with(Compression::default().min_size(64)));
+
+        let resp = cli
+            .post("/")
+            .header("Accept-Encoding", "gzip")
+            .body(DATA)
+            .send()
+            .await;
+        resp.assert_status_is_ok();
+        resp.assert_header_is_not_exist(header::CONTENT_ENCODING);
+        resp.assert_text(DATA_REV).await;
+
+        let resp = cli
+            .post("/")
+            .header("Accept-Encoding", "gzip")
+            .body(DATA.repeat(2))
+            .send()
+            .await;
+        resp.assert_status_is_ok();
+        resp.assert_header("Content-Encoding", "gzip");
+    }
+
+    #[tokio::test]
+    async fn test_algorithm_quality() {
+        let ep = index.with(
+            Compression::default()
+                .with_quality(CompressionLevel::Fastest)
+                .with_algorithm_quality(CompressionAlgo::BR, CompressionLevel::Precise(5)),
+        );
+        let cli = TestClient::new(ep);
+
+        for algo in [CompressionAlgo::BR, CompressionAlgo::GZIP] {
+            let resp = cli
+                .post("/")
+                .header("Accept-Encoding", algo.as_str())
+                .body(DATA)
+                .send()
+                .await;
+            resp.assert_status_is_ok();
+            resp.assert_header("Content-Encoding", algo.as_str());
+
+            let mut data = Vec::new();
+            let mut reader = algo.decompress(resp.0.into_body().into_async_read());
+            reader.read_to_end(&mut data).await.unwrap();
+            assert_eq!(data, DATA_REV.as_bytes());
+        }
+    }
 }
@@ -197,7 +197,10 @@ impl Cors {
             .fold(self, |cors, header| cors.expose_header(header))
     }
 
-    /// Set max age.
+    /// Sets how long in seconds the results of a preflight request can be
+    /// cached, sent as `Access-Control-Max-Age` in the preflight responses.
+    ///
+    /// Default is `86400`.
     #[must_use]
     pub fn max_age(mut self, max_age: i32) -> Self {
         self.max_age = max_age;
@@ -280,7 +283,6 @@ impl<E: Endpoint> CorsEndpoint<E> {
     ) -> Response {
         let mut builder = Response::builder()
             .header(header::ACCESS_CONTROL_ALLOW_ORIGIN, origin)
-            .typed_header(self.expose_headers_header.clone())
             .header(header::ACCESS_CONTROL_MAX_AGE, self.max_age);
 
         if self.allow_methods.is_empty() {
@@ -467,11 +469,43 @@ mod tests {
             ["DELETE", "GET", "OPTIONS", "POST"],
         );
         resp.assert_header(header::ACCESS_CONTROL_ALLOW_HEADERS, "x-token");
-        resp.assert_header(header::ACCESS_CONTROL_EXPOSE_HEADERS, "x-my-custom-header");
+        resp.assert_header_is_not_exist(header::ACCESS_CONTROL_EXPOSE_HEADERS);
         resp.assert_header(header::ACCESS_CONTROL_MAX_AGE, "86400");
         resp.assert_header(header::ACCESS_CONTROL_ALLOW_CREDENTIALS, "true");
     }
 
+    #[tokio::test]
+    async fn max_age_and_expose_headers() {
+        let ep = make_sync(|_| "hello").with(
+            Cors::new()
+                .max_age(600)
+                .expose_headers(["x-request-id", "x-total-count"]),
+        );
+        let cli = TestClient::new(ep);
+
+        let resp = cli
+            .options("/")
+            .header(header::ORIGIN, ALLOW_ORIGIN)
+            .header(header::ACCESS_CONTROL_REQUEST_METHOD, "GET")
+            .send()
+            .await;
+        resp.assert_status_is_ok();
+        resp.assert_header(header::ACCESS_CONTROL_MAX_AGE, "600");
+        resp.assert_header_is_not_exist(header::ACCESS_CONTROL_EXPOSE_HEADERS);
+
+        let resp = cli
+            .get("/")
+            .header(header::ORIGIN, ALLOW_ORIGIN)
+            .send()
+            .await;
+        resp.assert_status_is_ok();
+        resp.assert_header_csv(
+            header::ACCESS_CONTROL_EXPOSE_HEADERS,
+            ["x-request-id", "x-total-count"],
+        );
+        resp.assert_header_is_not_exist(header::ACCESS_CONTROL_MAX_AGE);
+    }
+
     #[tokio::test]
     async fn default_cors() {
         let ep = make_sync(|_| "hello").with(Cors::new());
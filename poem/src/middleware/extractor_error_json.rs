@@ -0,0 +1,223 @@
+use serde::Serialize;
+
+use crate::{
+    error::{
+        ParseFormError, ParseJsonError, ParsePathError, ParseQueryError, ParseTypedHeaderError,
+    },
+    web::Json,
+    Endpoint, Error, IntoResponse, Middleware, Request, Response, Result,
+};
+
+/// Middleware that renders the errors of the built-in extractors as JSON.
+///
+/// The response keeps the status code of the original error, and the body
+/// looks like `{"error": "...", "field": "..."}`. The `field` is only present
+/// when it can be determined from the error.
+///
+/// The following errors are converted:
+///
+/// - [`ParsePathError`]
+/// - [`ParseQueryError`]
+/// - [`ParseFormError`]
+/// - [`ParseJsonError`]
+/// - [`ParseTypedHeaderError`]
+/// - [`ParseXmlError`](crate::error::ParseXmlError) (requires the `xml`
+///   feature)
+/// - [`ParseYamlError`](crate::error::ParseYamlError) (requires the `yaml`
+///   feature)
+/// - [`ParseCsvError`](crate::error::ParseCsvError) (requires the `csv`
+///   feature)
+/// - [`ParseMultipartError`](crate::error::ParseMultipartError) (requires the
+///   `multipart` feature)
+///
+/// # Example
+///
+/// ```
+/// use poem::{
+///     handler, http::StatusCode, middleware::ExtractorErrorJson, test::TestClient, web::Query,
+///     EndpointExt,
+/// };
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// struct Params {
+///     name: String,
+/// }
+///
+/// #[handler]
+/// fn index(Query(params): Query<Params>) -> String {
+///     params.name
+/// }
+///
+/// let cli = TestClient::new(index.with(ExtractorErrorJson));
+///
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// let resp = cli.get("/").send().await;
+/// resp.assert_status(StatusCode::BAD_REQUEST);
+/// resp.assert_json(serde_json::json!({
+///     "error": "missing field `name`",
+///     "field": "name",
+/// }))
+/// .await;
+/// # });
+/// ```
+#[derive(Default)]
+pub struct ExtractorErrorJson;
+
+impl<E: Endpoint> Middleware<E> for ExtractorErrorJson {
+    type Output = ExtractorErrorJsonEndpoint<E>;
+
+    fn transform(&self, ep: E) -> Self::Output {
+        ExtractorErrorJsonEndpoint { inner: ep }
+    }
+}
+
+/// Endpoint for the `ExtractorErrorJson` middleware.
+pub struct ExtractorErrorJsonEndpoint<E> {
+    inner: E,
+}
+
+impl<E: Endpoint> Endpoint for ExtractorErrorJsonEndpoint<E> {
+    type Output = Response;
+
+    async fn call(&self, req: Request) -> Result<Self::Output> {
+        match self.inner.call(req).await {
+            Ok(resp) => Ok(resp.into_response()),
+            Err(err) if is_extractor_error(&err) => Ok(render_error(&err)),
+            Err(err) => Err(err),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct ExtractorErrorBody {
+    error: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    field: Option<String>,
+}
+
+fn is_extractor_error(err: &Error) -> bool {
+    if err.is::<ParsePathError>()
+        || err.is::<ParseQueryError>()
+        || err.is::<ParseFormError>()
+        || err.is::<ParseJsonError>()
+        || err.is::<ParseTypedHeaderError>()
+    {
+        return true;
+    }
+    #[cfg(feature = "xml")]
+    if err.is::<crate::error::ParseXmlError>() {
+        return true;
+    }
+    #[cfg(feature = "yaml")]
+    if err.is::<crate::error::ParseYamlError>() {
+        return true;
+    }
+    #[cfg(feature = "csv")]
+    if err.is::<crate::error::ParseCsvError>() {
+        return true;
+    }
+    #[cfg(feature = "multipart")]
+    if err.is::<crate::error::ParseMultipartError>() {
+        return true;
+    }
+    false
+}
+
+fn render_error(err: &Error) -> Response {
+    let message = err.to_string();
+    let field = match err.downcast_ref::<ParseTypedHeaderError>() {
+        Some(ParseTypedHeaderError::HeaderRequired(name)) => Some(name.clone()),
+        _ => field_name(&message).map(ToString::to_string),
+    };
+
+    Json(ExtractorErrorBody {
+        error: message,
+        field,
+    })
+    .with_status(err.status())
+    .into_response()
+}
+
+/// Extracts the field name from serde messages such as ``missing field
+/// `name` ``.
+fn field_name(message: &str) -> Option<&str> {
+    let (_, rest) = message.split_once("field `")?;
+    let (name, _) = rest.split_once('`')?;
+    Some(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use http::StatusCode;
+    use serde::Deserialize;
+
+    use super::*;
+    use crate::{
+        handler,
+        test::TestClient,
+        web::{Path, Query},
+        EndpointExt, Route,
+    };
+
+    #[derive(Deserialize)]
+    #[allow(dead_code)]
+    struct Params {
+        name: String,
+    }
+
+    #[test]
+    fn test_field_name() {
+        assert_eq!(field_name("missing field `name`"), Some("name"));
+        assert_eq!(
+            field_name("unknown field `age`, expected `name`"),
+            Some("age")
+        );
+        assert_eq!(field_name("invalid type"), None);
+    }
+
+    #[tokio::test]
+    async fn test_extractor_error_json() {
+        #[handler(internal)]
+        fn query(_params: Query<Params>) {}
+
+        #[handler(internal)]
+        fn json(_params: Json<Params>) {}
+
+        #[handler(internal)]
+        fn path(_id: Path<i32>) {}
+
+        let cli = TestClient::new(
+            Route::new()
+                .at("/query", query)
+                .at("/json", json)
+                .at("/path/:id", path)
+                .with(ExtractorErrorJson),
+        );
+
+        let resp = cli.get("/query").send().await;
+        resp.assert_status(StatusCode::BAD_REQUEST);
+        resp.assert_content_type("application/json; charset=utf-8");
+        resp.assert_json(serde_json::json!({
+            "error": "missing field `name`",
+            "field": "name",
+        }))
+        .await;
+
+        let resp = cli.post("/json").body("{}").send().await;
+        resp.assert_status(StatusCode::UNSUPPORTED_MEDIA_TYPE);
+        resp.assert_json(serde_json::json!({
+            "error": "expect content type `application/json`",
+        }))
+        .await;
+
+        let resp = cli.get("/path/abc").send().await;
+        resp.assert_status(StatusCode::BAD_REQUEST);
+        resp.assert_content_type("application/json; charset=utf-8");
+
+        cli.get("/not-found")
+            .send()
+            .await
+            .assert_status(StatusCode::NOT_FOUND);
+    }
+}
@@ -0,0 +1,172 @@
+use std::{future::Future, sync::Arc};
+
+use crate::{
+    endpoint::{DynEndpoint, ToDynEndpoint},
+    Endpoint, EndpointExt, IntoResponse, Middleware, Request, Response, Result,
+};
+
+/// Create a middleware with an async closure.
+///
+/// The closure receives the request and a [`Next`] that calls the rest of the
+/// chain, so it can modify the request before calling [`Next::call`], and the
+/// response after it. The errors returned by [`Next::call`] can be propagated
+/// with `?`, or handled by the closure.
+///
+/// Unlike [`EndpointExt::around`], the returned middleware does not depend on
+/// the type of the endpoint, so it can be stored and applied to several
+/// endpoints.
+///
+/// # Example
+///
+/// ```
+/// use poem::{
+///     handler, http::StatusCode, middleware::from_fn, test::TestClient, EndpointExt, Error,
+///     IntoResponse, Route,
+/// };
+///
+/// #[handler]
+/// fn index() -> &'static str {
+///     "hello"
+/// }
+///
+/// let require_token = from_fn(|req, next| async move {
+///     if req.header("x-token") != Some("secret") {
+///         return Err(Error::from_status(StatusCode::UNAUTHORIZED));
+///     }
+///     let resp = next.call(req).await?;
+///     Ok(resp.with_header("x-checked", "true"))
+/// });
+///
+/// let cli = TestClient::new(Route::new().at("/", index.with(require_token)));
+///
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// cli.get("/")
+///     .send()
+///     .await
+///     .assert_status(StatusCode::UNAUTHORIZED);
+///
+/// let resp = cli.get("/").header("x-token", "secret").send().await;
+/// resp.assert_header("x-checked", "true");
+/// resp.assert_text("hello").await;
+/// # });
+/// ```
+pub fn from_fn<F, Fut, R>(f: F) -> FromFn<F>
+where
+    F: Fn(Request, Next) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = Result<R>> + Send,
+    R: IntoResponse,
+{
+    FromFn { f: Arc::new(f) }
+}
+
+/// Middleware for the [`from_fn`] function.
+pub struct FromFn<F> {
+    f: Arc<F>,
+}
+
+impl<E, F, Fut, R> Middleware<E> for FromFn<F>
+where
+    E: Endpoint + 'static,
+    F: Fn(Request, Next) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = Result<R>> + Send,
+    R: IntoResponse,
+{
+    type Output = FromFnEndpoint<F>;
+
+    fn transform(&self, ep: E) -> Self::Output {
+        FromFnEndpoint {
+            next: Next(Arc::new(ToDynEndpoint(ep.map_to_response()))),
+            f: self.f.clone(),
+        }
+    }
+}
+
+/// The rest of the middleware chain, passed to the closure of [`from_fn`].
+#[derive(Clone)]
+pub struct Next(Arc<dyn DynEndpoint<Output = Response>>);
+
+impl Next {
+    /// Calls the inner endpoint with the request.
+    pub async fn call(&self, req: Request) -> Result<Response> {
+        DynEndpoint::call(&*self.0, req).await
+    }
+}
+
+/// Endpoint for the [`from_fn`] middleware.
+pub struct FromFnEndpoint<F> {
+    next: Next,
+    f: Arc<F>,
+}
+
+impl<F, Fut, R> Endpoint for FromFnEndpoint<F>
+where
+    F: Fn(Request, Next) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = Result<R>> + Send,
+    R: IntoResponse,
+{
+    type Output = R;
+
+    async fn call(&self, req: Request) -> Result<Self::Output> {
+        (self.f)(req, self.next.clone()).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        endpoint::make_sync, handler, http::StatusCode, test::TestClient, web::Data, Error,
+    };
+
+    #[tokio::test]
+    async fn test_from_fn() {
+        #[handler(internal)]
+        fn index(data: Data<&i32>) -> String {
+            data.0.to_string()
+        }
+
+        let mw = from_fn(|mut req: Request, next: Next| async move {
+            req.extensions_mut().insert(10);
+            let resp = next.call(req).await?;
+            Ok(resp.with_header("x-after", "1"))
+        });
+        let cli = TestClient::new(index.with(mw));
+
+        let resp = cli.get("/").send().await;
+        resp.assert_status_is_ok();
+        resp.assert_header("x-after", "1");
+        resp.assert_text("10").await;
+    }
+
+    #[tokio::test]
+    async fn propagate_errors() {
+        let cli = TestClient::new(
+            make_sync(|_| Err::<(), _>(Error::from_status(StatusCode::CONFLICT))).with(from_fn(
+                |req, next| async move {
+                    let resp = next.call(req).await?;
+                    Ok(resp.with_header("x-after", "1"))
+                },
+            )),
+        );
+        let resp = cli.get("/").send().await;
+        resp.assert_status(StatusCode::CONFLICT);
+        resp.assert_header_is_not_exist("x-after");
+
+        let cli = TestClient::new(
+            make_sync(|_| Err::<(), _>(Error::from_status(StatusCode::CONFLICT))).with(from_fn(
+                |req, next| async move {
+                    match next.call(req).await {
+                        Ok(resp) => Ok(resp),
+                        Err(err) => Ok(err
+                            .into_response()
+                            .with_header("x-handled", "1")
+                            .into_response()),
+                    }
+                },
+            )),
+        );
+        let resp = cli.get("/").send().await;
+        resp.assert_status(StatusCode::CONFLICT);
+        resp.assert_header("x-handled", "1");
+    }
+}
@@ -0,0 +1,297 @@
+use std::time::Duration;
+
+use http::{
+    header,
+    uri::{Authority, Scheme},
+    HeaderValue, StatusCode,
+};
+
+use crate::{
+    web::{external_scheme, BaseUrl, Redirect},
+    Endpoint, Error, FromRequest, IntoResponse, Middleware, Request, Response, Result,
+};
+
+/// What the [`Hsts`] middleware does with the plaintext requests.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+enum PlaintextPolicy {
+    Allow,
+    Redirect,
+    Reject,
+}
+
+/// Middleware that sets the `Strict-Transport-Security` header, so the
+/// browsers only connect to the site with HTTPS.
+///
+/// The header is only set on the responses to the HTTPS requests, including
+/// the error responses, because the browsers ignore it on plaintext responses. By default the plaintext
+/// requests are served as usual, use [`Hsts::redirect_http`] to redirect them
+/// to HTTPS, or [`Hsts::reject_http`] to refuse them.
+///
+/// If the peer is trusted by the
+/// [`TrustedProxies`](crate::web::TrustedProxies) in the request data, the
+/// scheme is taken from the forwarding headers, the same way as
+/// [`RouteScheme`](crate::RouteScheme), so it works behind a reverse proxy
+/// terminating TLS.
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+///
+/// use poem::{
+///     handler, middleware::Hsts, test::TestClient, web::TrustedProxies, EndpointExt,
+/// };
+///
+/// #[handler]
+/// fn index() -> &'static str {
+///     "hello"
+/// }
+///
+/// let app = index
+///     .with(
+///         Hsts::new(Duration::from_secs(63072000))
+///             .include_subdomains()
+///             .preload(),
+///     )
+///     .data(TrustedProxies::new().trust_all());
+/// let cli = TestClient::new(app);
+///
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// let resp = cli
+///     .get("/")
+///     .header("X-Forwarded-Proto", "https")
+///     .send()
+///     .await;
+/// resp.assert_status_is_ok();
+/// resp.assert_header(
+///     "strict-transport-security",
+///     "max-age=63072000; includeSubDomains; preload",
+/// );
+/// # });
+/// ```
+#[derive(Debug, Clone)]
+pub struct Hsts {
+    max_age: Duration,
+    include_subdomains: bool,
+    preload: bool,
+    plaintext: PlaintextPolicy,
+}
+
+impl Hsts {
+    /// Create `Hsts` middleware with the time the browsers should remember to
+    /// only use HTTPS.
+    pub fn new(max_age: Duration) -> Self {
+        Self {
+            max_age,
+            include_subdomains: false,
+            preload: false,
+            plaintext: PlaintextPolicy::Allow,
+        }
+    }
+
+    /// Adds the `includeSubDomains` directive, the policy also applies to all
+    /// the subdomains.
+    #[must_use]
+    pub fn include_subdomains(self) -> Self {
+        Self {
+            include_subdomains: true,
+            ..self
+        }
+    }
+
+    /// Adds the `preload` directive, to consent to be included in the
+    /// browsers' preload lists.
+    ///
+    /// The preload lists also require `includeSubDomains` and a `max-age` of
+    /// at least one year.
+    #[must_use]
+    pub fn preload(self) -> Self {
+        Self {
+            preload: true,
+            ..self
+        }
+    }
+
+    /// Redirects the plaintext requests to the same URL with HTTPS, with a
+    /// `308 Permanent Redirect`.
+    ///
+    /// The port of the plaintext request is removed, so the browsers connect
+    /// to the default HTTPS port.
+    #[must_use]
+    pub fn redirect_http(self) -> Self {
+        Self {
+            plaintext: PlaintextPolicy::Redirect,
+            ..self
+        }
+    }
+
+    /// Refuses the plaintext requests with `403 Forbidden`.
+    #[must_use]
+    pub fn reject_http(self) -> Self {
+        Self {
+            plaintext: PlaintextPolicy::Reject,
+            ..self
+        }
+    }
+
+    fn header_value(&self) -> HeaderValue {
+        let mut value = format!("max-age={}", self.max_age.as_secs());
+        if self.include_subdomains {
+            value.push_str("; includeSubDomains");
+        }
+        if self.preload {
+            value.push_str("; preload");
+        }
+        HeaderValue::try_from(value).expect("valid header value")
+    }
+}
+
+impl<E: Endpoint> Middleware<E> for Hsts {
+    type Output = HstsEndpoint<E>;
+
+    fn transform(&self, ep: E) -> Self::Output {
+        HstsEndpoint {
+            inner: ep,
+            header_value: self.header_value(),
+            plaintext: self.plaintext,
+        }
+    }
+}
+
+/// Endpoint for the `Hsts` middleware.
+pub struct HstsEndpoint<E> {
+    inner: E,
+    header_value: HeaderValue,
+    plaintext: PlaintextPolicy,
+}
+
+impl<E: Endpoint> Endpoint for HstsEndpoint<E> {
+    type Output = Response;
+
+    async fn call(&self, req: Request) -> Result<Self::Output> {
+        if external_scheme(&req) == Scheme::HTTPS {
+            let mut resp = self.inner.get_response(req).await;
+            resp.headers_mut()
+                .insert(header::STRICT_TRANSPORT_SECURITY, self.header_value.clone());
+            return Ok(resp);
+        }
+
+        match self.plaintext {
+            PlaintextPolicy::Allow => self.inner.call(req).await.map(IntoResponse::into_response),
+            PlaintextPolicy::Redirect => {
+                let base_url = BaseUrl::from_request_without_body(&req).await?;
+                let host = match base_url.host().parse::<Authority>() {
+                    Ok(authority) => authority.host().to_string(),
+                    Err(_) => base_url.host().to_string(),
+                };
+                let path_and_query = req
+                    .original_uri()
+                    .path_and_query()
+                    .map(|path_and_query| path_and_query.as_str())
+                    .unwrap_or("/");
+                Ok(Redirect::permanent(format!("https://{host}{path_and_query}")).into_response())
+            }
+            PlaintextPolicy::Reject => Err(Error::from_string(
+                "HTTPS is required",
+                StatusCode::FORBIDDEN,
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        endpoint::{make, make_sync},
+        test::TestClient,
+        web::TrustedProxies,
+        EndpointExt,
+    };
+
+    const HSTS: &str = "strict-transport-security";
+
+    #[tokio::test]
+    async fn header_on_https_only() {
+        let ep = make_sync(|_| "hello").with(
+            Hsts::new(Duration::from_secs(31536000))
+                .include_subdomains()
+                .preload(),
+        );
+
+        let mut req = Request::builder().finish();
+        req.state_mut().scheme = Scheme::HTTPS;
+        let resp = ep.call(req).await.unwrap();
+        assert_eq!(
+            resp.headers().get(HSTS).unwrap(),
+            "max-age=31536000; includeSubDomains; preload"
+        );
+
+        let resp = ep.call(Request::builder().finish()).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert!(resp.headers().get(HSTS).is_none());
+    }
+
+    #[tokio::test]
+    async fn header_on_error() {
+        let ep = make(|_| async { Err::<(), _>(Error::from_status(StatusCode::NOT_FOUND)) })
+            .with(Hsts::new(Duration::from_secs(60)));
+
+        let mut req = Request::builder().finish();
+        req.state_mut().scheme = Scheme::HTTPS;
+        let resp = ep.call(req).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+        assert_eq!(resp.headers().get(HSTS).unwrap(), "max-age=60");
+    }
+
+    #[tokio::test]
+    async fn trusted_proxy() {
+        let cli = TestClient::new(
+            make_sync(|_| "hello")
+                .with(Hsts::new(Duration::from_secs(60)).reject_http())
+                .data(TrustedProxies::new().trust_all()),
+        );
+
+        let resp = cli
+            .get("/")
+            .header("x-forwarded-proto", "https")
+            .send()
+            .await;
+        resp.assert_status_is_ok();
+        resp.assert_header(HSTS, "max-age=60");
+
+        let resp = cli.get("/").send().await;
+        resp.assert_status(StatusCode::FORBIDDEN);
+        resp.assert_header_is_not_exist(HSTS);
+
+        // the forwarding headers of an untrusted peer are ignored
+        let cli = TestClient::new(
+            make_sync(|_| "hello").with(Hsts::new(Duration::from_secs(60)).reject_http()),
+        );
+        cli.get("/")
+            .header("x-forwarded-proto", "https")
+            .send()
+            .await
+            .assert_status(StatusCode::FORBIDDEN);
+    }
+
+    #[tokio::test]
+    async fn redirect_http() {
+        let cli = TestClient::new(
+            make_sync(|_| "hello").with(Hsts::new(Duration::from_secs(60)).redirect_http()),
+        );
+
+        let resp = cli
+            .get("/a/b?c=1")
+            .header("host", "example.com")
+            .send()
+            .await;
+        resp.assert_status(StatusCode::PERMANENT_REDIRECT);
+        resp.assert_header("location", "https://example.com/a/b?c=1");
+        resp.assert_header_is_not_exist(HSTS);
+
+        let resp = cli.get("/").header("host", "[::1]:8080").send().await;
+        resp.assert_status(StatusCode::PERMANENT_REDIRECT);
+        resp.assert_header("location", "https://[::1]/");
+    }
+}
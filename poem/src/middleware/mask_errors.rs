@@ -0,0 +1,184 @@
+use crate::{Endpoint, IntoResponse, Middleware, Request, Response, Result};
+
+/// Middleware that hides the messages of the errors that do not describe
+/// their own response.
+///
+/// The errors that implement [`ResponseError`](crate::error::ResponseError),
+/// such as the errors of the extractors, and the errors created by
+/// [`Error::from_status`](crate::Error::from_status),
+/// [`Error::from_string`](crate::Error::from_string) or
+/// [`Error::from_response`](crate::Error::from_response) are left untouched.
+/// The other errors, such as the ones wrapped with
+/// [`InternalServerError`](crate::error::InternalServerError) or converted
+/// from `anyhow::Error`, render their message as the response body, which
+/// can leak internal details to the clients. This middleware replaces the
+/// message with the canonical reason of the status code, e.g. `Internal
+/// Server Error`, or with the message specified by [`MaskErrors::message`].
+///
+/// The error itself is kept, so the outer middlewares can still inspect it.
+/// Apply [`Tracing`](crate::middleware::Tracing) before this middleware to
+/// log the original messages.
+///
+/// # Example
+///
+/// ```
+/// use poem::{
+///     error::InternalServerError, handler, http::StatusCode, middleware::MaskErrors,
+///     test::TestClient, EndpointExt, Result,
+/// };
+///
+/// #[handler]
+/// fn index() -> Result<String> {
+///     Ok(std::fs::read_to_string("/secret/path").map_err(InternalServerError)?)
+/// }
+///
+/// let cli = TestClient::new(index.with(MaskErrors::new().enabled(!cfg!(debug_assertions))));
+///
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// let resp = cli.get("/").send().await;
+/// resp.assert_status(StatusCode::INTERNAL_SERVER_ERROR);
+/// # });
+/// ```
+pub struct MaskErrors {
+    enabled: bool,
+    message: Option<String>,
+}
+
+impl Default for MaskErrors {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            message: None,
+        }
+    }
+}
+
+impl MaskErrors {
+    /// Create `MaskErrors` middleware.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Enables or disables masking, for example to show the detailed messages
+    /// in development.
+    ///
+    /// Default is `true`.
+    #[must_use]
+    pub fn enabled(self, enabled: bool) -> Self {
+        Self { enabled, ..self }
+    }
+
+    /// Sets the message that replaces the error messages.
+    ///
+    /// Default is the canonical reason of the status code.
+    #[must_use]
+    pub fn message(self, message: impl Into<String>) -> Self {
+        Self {
+            message: Some(message.into()),
+            ..self
+        }
+    }
+}
+
+impl<E: Endpoint> Middleware<E> for MaskErrors {
+    type Output = MaskErrorsEndpoint<E>;
+
+    fn transform(&self, ep: E) -> Self::Output {
+        MaskErrorsEndpoint {
+            inner: ep,
+            enabled: self.enabled,
+            message: self.message.clone(),
+        }
+    }
+}
+
+/// Endpoint for the `MaskErrors` middleware.
+pub struct MaskErrorsEndpoint<E> {
+    inner: E,
+    enabled: bool,
+    message: Option<String>,
+}
+
+impl<E: Endpoint> Endpoint for MaskErrorsEndpoint<E> {
+    type Output = Response;
+
+    async fn call(&self, req: Request) -> Result<Self::Output> {
+        match self.inner.call(req).await {
+            Ok(resp) => Ok(resp.into_response()),
+            Err(mut err) if self.enabled && err.is_opaque() => {
+                let message = match &self.message {
+                    Some(message) => message.clone(),
+                    None => err
+                        .status()
+                        .canonical_reason()
+                        .unwrap_or_default()
+                        .to_string(),
+                };
+                err.set_error_message(message);
+                Err(err)
+            }
+            Err(err) => Err(err),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        endpoint::make_sync,
+        error::{BadRequest, InternalServerError},
+        http::StatusCode,
+        test::TestClient,
+        EndpointExt, Error,
+    };
+
+    fn io_error() -> std::io::Error {
+        std::io::Error::other("secret")
+    }
+
+    #[tokio::test]
+    async fn mask_errors() {
+        let cli = TestClient::new(
+            make_sync(|_| Err::<(), _>(InternalServerError(io_error()))).with(MaskErrors::new()),
+        );
+        let resp = cli.get("/").send().await;
+        resp.assert_status(StatusCode::INTERNAL_SERVER_ERROR);
+        resp.assert_text("Internal Server Error").await;
+
+        let cli = TestClient::new(
+            make_sync(|_| Err::<(), _>(BadRequest(io_error())))
+                .with(MaskErrors::new().message("oops")),
+        );
+        let resp = cli.get("/").send().await;
+        resp.assert_status(StatusCode::BAD_REQUEST);
+        resp.assert_text("oops").await;
+
+        let cli = TestClient::new(
+            make_sync(|_| Err::<(), _>(InternalServerError(io_error())))
+                .with(MaskErrors::new().enabled(false)),
+        );
+        cli.get("/").send().await.assert_text("secret").await;
+    }
+
+    #[tokio::test]
+    async fn keep_mapped_errors() {
+        let cli = TestClient::new(
+            make_sync(|_| {
+                Err::<(), _>(Error::from_string("invalid name", StatusCode::BAD_REQUEST))
+            })
+            .with(MaskErrors::new()),
+        );
+        cli.get("/").send().await.assert_text("invalid name").await;
+
+        let cli = TestClient::new(
+            make_sync(|_| {
+                Err::<(), _>(Error::from_response(
+                    StatusCode::CONFLICT.with_body("conflict").into_response(),
+                ))
+            })
+            .with(MaskErrors::new()),
+        );
+        cli.get("/").send().await.assert_text("conflict").await;
+    }
+}
@@ -2,6 +2,7 @@
 
 mod add_data;
 mod catch_panic;
+mod circuit_breaker;
 #[cfg(feature = "compression")]
 mod compression;
 #[cfg(feature = "cookie")]
@@ -9,23 +10,35 @@ mod cookie_jar_manager;
 mod cors;
 #[cfg(feature = "csrf")]
 mod csrf;
+mod extractor_error_json;
 mod force_https;
+mod from_fn;
+mod hsts;
+mod mask_errors;
 mod normalize_path;
 #[cfg(feature = "opentelemetry")]
 mod opentelemetry_metrics;
 #[cfg(feature = "opentelemetry")]
 mod opentelemetry_tracing;
+mod passthrough;
 mod propagate_header;
+mod queue;
+mod request_body_limit;
+mod request_log;
 #[cfg(feature = "requestid")]
 mod requestid;
+mod response_cache;
 mod sensitive_header;
 mod set_header;
+mod single_flight;
 mod size_limit;
 #[cfg(feature = "tokio-metrics")]
 mod tokio_metrics_mw;
 #[cfg(feature = "tower-compat")]
 mod tower_compat;
 mod tracing_mw;
+#[cfg(feature = "digest")]
+mod verify_digest;
 
 use std::marker::PhantomData;
 
@@ -45,17 +58,35 @@ pub use self::requestid::{ReqId, RequestId, RequestIdEndpoint, ReuseId};
 pub use self::tokio_metrics_mw::{TokioMetrics, TokioMetricsEndpoint};
 #[cfg(feature = "tower-compat")]
 pub use self::tower_compat::TowerLayerCompatExt;
+#[cfg(feature = "digest")]
+pub use self::verify_digest::{VerifyDigest, VerifyDigestEndpoint};
 pub use self::{
     add_data::{AddData, AddDataEndpoint},
     catch_panic::{CatchPanic, CatchPanicEndpoint, PanicHandler},
+    circuit_breaker::{CircuitBreaker, CircuitBreakerEndpoint, CircuitState},
     cors::{Cors, CorsEndpoint},
+    extractor_error_json::{ExtractorErrorJson, ExtractorErrorJsonEndpoint},
     force_https::ForceHttps,
+    from_fn::{from_fn, FromFn, FromFnEndpoint, Next},
+    hsts::{Hsts, HstsEndpoint},
+    mask_errors::{MaskErrors, MaskErrorsEndpoint},
     normalize_path::{NormalizePath, NormalizePathEndpoint, TrailingSlash},
+    passthrough::{
+        extract_passthrough, Passthrough, PassthroughEndpoint, PassthroughExtractor,
+        PassthroughOutput,
+    },
     propagate_header::{PropagateHeader, PropagateHeaderEndpoint},
+    queue::{Queue, QueueEndpoint},
+    request_body_limit::{BodyLimit, RequestBodyLimit, RequestBodyLimitEndpoint},
+    request_log::{RequestLog, RequestLogEndpoint, RequestLogEntriesEndpoint, RequestLogEntry},
+    response_cache::{
+        CachedResponse, MemoryCacheStore, ResponseCache, ResponseCacheEndpoint, ResponseCacheStore,
+    },
     sensitive_header::{SensitiveHeader, SensitiveHeaderEndpoint},
     set_header::{SetHeader, SetHeaderEndpoint},
+    single_flight::{SingleFlight, SingleFlightEndpoint},
     size_limit::{SizeLimit, SizeLimitEndpoint},
-    tracing_mw::{Tracing, TracingEndpoint},
+    tracing_mw::{TraceSampled, Tracing, TracingConfig, TracingEndpoint},
 };
 use crate::endpoint::{EitherEndpoint, Endpoint};
 
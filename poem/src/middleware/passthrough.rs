@@ -0,0 +1,287 @@
+use std::future::Future;
+
+use futures_util::{future::BoxFuture, FutureExt};
+use http::{HeaderMap, Method, Uri, Version};
+
+use crate::{
+    web::{
+        Accept, BaseUrl, Cached, Data, Ext, LocalAddr, MatchedPath, Page, Path, Query, RealIp,
+        RemoteAddr, TypedHeader,
+    },
+    Endpoint, Error, FromRequest, IntoResponse, Request, Response, Result,
+};
+
+/// A check that only inspects the request head, generated by
+/// `#[handler(passthrough)]`.
+///
+/// The function runs before the inner endpoint with the extractors that do
+/// not read the body, such as headers, path or query parameters, which
+/// implement [`PassthroughExtractor`]. Using an extractor that reads the body
+/// is a compile error.
+///
+/// The function returns `()`, an [`Option`] of a response or a [`Result`] of
+/// them. If it returns an error or `Some(response)`, it is returned as the
+/// response, otherwise the untouched request, including its body, is passed
+/// to the inner endpoint.
+///
+/// The types generated by `#[handler(passthrough)]` are middlewares, so they
+/// are applied to an endpoint or a [`Route`](crate::Route) with
+/// [`EndpointExt::with`](crate::EndpointExt::with), rather than registered as
+/// routes.
+///
+/// # Example
+///
+/// ```
+/// use poem::{
+///     get, handler,
+///     http::{HeaderMap, StatusCode},
+///     test::TestClient,
+///     Error, EndpointExt, Result, Route,
+/// };
+///
+/// #[handler(passthrough)]
+/// fn require_token(headers: &HeaderMap) -> Result<()> {
+///     match headers.get("x-token") {
+///         Some(token) if token == "secret" => Ok(()),
+///         _ => Err(Error::from_status(StatusCode::UNAUTHORIZED)),
+///     }
+/// }
+///
+/// #[handler]
+/// fn echo(body: String) -> String {
+///     body
+/// }
+///
+/// let app = Route::new().at("/", echo.with(require_token));
+/// let cli = TestClient::new(app);
+///
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// cli.post("/")
+///     .body("hello")
+///     .send()
+///     .await
+///     .assert_status(StatusCode::UNAUTHORIZED);
+///
+/// let resp = cli
+///     .post("/")
+///     .header("x-token", "secret")
+///     .body("hello")
+///     .send()
+///     .await;
+/// resp.assert_status_is_ok();
+/// resp.assert_text("hello").await;
+/// # });
+/// ```
+pub trait Passthrough: Send + Sync {
+    /// Checks the request, the body of the request is not available.
+    ///
+    /// Returns `Some(response)` to respond without calling the inner
+    /// endpoint.
+    fn check(&self, req: &Request) -> impl Future<Output = Result<Option<Response>>> + Send;
+}
+
+/// Represents an extractor that does not read the request body, which can be
+/// used by `#[handler(passthrough)]`.
+///
+/// The extractors that read the body are rejected:
+///
+/// ```compile_fail
+/// use poem::handler;
+///
+/// #[handler(passthrough)]
+/// fn check(body: String) {}
+/// ```
+pub trait PassthroughExtractor<'a>: FromRequest<'a> {}
+
+#[doc(hidden)]
+pub fn extract_passthrough<'a, T>(req: &'a Request) -> BoxFuture<'a, Result<T>>
+where
+    T: PassthroughExtractor<'a> + 'a,
+{
+    // FIXME: remove the unnecessary boxed
+    // https://github.com/rust-lang/rust/issues/100013
+    T::from_request_without_body(req).boxed()
+}
+
+macro_rules! impl_passthrough_extractor {
+    ($($(#[$meta:meta])* [$($generics:tt)*] $ty:ty),* $(,)?) => {
+        $(
+            $(#[$meta])*
+            impl<'a, $($generics)*> PassthroughExtractor<'a> for $ty {}
+        )*
+    };
+}
+
+impl_passthrough_extractor!(
+    [] &'a Request,
+    [] &'a Uri,
+    [] Method,
+    [] Version,
+    [] &'a HeaderMap,
+    [] &'a RemoteAddr,
+    [] &'a LocalAddr,
+    [] Accept,
+    [] BaseUrl,
+    [] MatchedPath,
+    [] Page,
+    [] RealIp,
+    [T: serde::de::DeserializeOwned] Path<T>,
+    [T: serde::de::DeserializeOwned] Query<T>,
+    [T: Send + Sync + 'static] Data<&'a T>,
+    [T: Clone + Send + Sync + 'static] Ext<T>,
+    [T: headers::Header] TypedHeader<T>,
+    [T: PassthroughExtractor<'a>] Option<T>,
+    [T: PassthroughExtractor<'a>] Result<T>,
+    [E: for<'b> PassthroughExtractor<'b> + Clone + Send + Sync + 'static] Cached<E>,
+    #[cfg(feature = "cookie")]
+    [] crate::web::cookie::Cookie,
+    #[cfg(feature = "cookie")]
+    [] &'a crate::web::cookie::CookieJar,
+    #[cfg(feature = "csrf")]
+    [] &'a crate::web::CsrfToken,
+    #[cfg(feature = "csrf")]
+    [] &'a crate::web::CsrfVerifier,
+    #[cfg(feature = "jwt")]
+    [T: serde::de::DeserializeOwned] crate::web::jwt::Jwt<T>,
+    #[cfg(feature = "static-files")]
+    [] crate::web::StaticFileRequest,
+    #[cfg(feature = "session")]
+    [] &'a crate::session::Session,
+    #[cfg(feature = "i18n")]
+    [] crate::i18n::Locale,
+    #[cfg(feature = "requestid")]
+    [] crate::middleware::ReqId,
+);
+
+/// Represents a type that can be returned by a `#[handler(passthrough)]`
+/// function.
+pub trait PassthroughOutput: Send {
+    /// Returns `Some(response)` to respond without calling the inner
+    /// endpoint.
+    #[allow(clippy::result_large_err)]
+    fn into_passthrough(self) -> Result<Option<Response>>;
+}
+
+impl PassthroughOutput for () {
+    fn into_passthrough(self) -> Result<Option<Response>> {
+        Ok(None)
+    }
+}
+
+impl<T: IntoResponse> PassthroughOutput for Option<T> {
+    fn into_passthrough(self) -> Result<Option<Response>> {
+        Ok(self.map(IntoResponse::into_response))
+    }
+}
+
+impl<T, E> PassthroughOutput for std::result::Result<T, E>
+where
+    T: PassthroughOutput,
+    E: Into<Error> + Send,
+{
+    fn into_passthrough(self) -> Result<Option<Response>> {
+        self.map_err(Into::into)?.into_passthrough()
+    }
+}
+
+/// Endpoint for the middlewares generated by `#[handler(passthrough)]`.
+pub struct PassthroughEndpoint<P, E> {
+    passthrough: P,
+    inner: E,
+}
+
+impl<P, E> PassthroughEndpoint<P, E> {
+    #[doc(hidden)]
+    pub fn new(passthrough: P, inner: E) -> Self {
+        Self { passthrough, inner }
+    }
+}
+
+impl<P: Passthrough, E: Endpoint> Endpoint for PassthroughEndpoint<P, E> {
+    type Output = Response;
+
+    async fn call(&self, req: Request) -> Result<Self::Output> {
+        match self.passthrough.check(&req).await? {
+            Some(resp) => Ok(resp),
+            None => self.inner.call(req).await.map(IntoResponse::into_response),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        handler,
+        http::StatusCode,
+        test::TestClient,
+        web::{Path, Query},
+        EndpointExt, Error, Route,
+    };
+
+    #[tokio::test]
+    async fn test_passthrough() {
+        #[derive(serde::Deserialize)]
+        struct Params {
+            admin: bool,
+        }
+
+        #[handler(internal, passthrough)]
+        async fn check_admin(Path(id): Path<u32>, Query(params): Query<Params>) -> Result<()> {
+            if id != 1 || !params.admin {
+                return Err(Error::from_status(StatusCode::FORBIDDEN));
+            }
+            Ok(())
+        }
+
+        #[handler(internal, passthrough)]
+        fn never_fails() {}
+
+        #[handler(internal, passthrough)]
+        fn maintenance(headers: &HeaderMap) -> Option<StatusCode> {
+            headers
+                .contains_key("x-maintenance")
+                .then_some(StatusCode::SERVICE_UNAVAILABLE)
+        }
+
+        #[handler(internal)]
+        fn echo(body: String) -> String {
+            body
+        }
+
+        let cli = TestClient::new(Route::new().at(
+            "/:id",
+            echo.with(check_admin).with(never_fails).with(maintenance),
+        ));
+
+        let resp = cli
+            .post("/1")
+            .query("admin", &true)
+            .body("hello")
+            .send()
+            .await;
+        resp.assert_status_is_ok();
+        resp.assert_text("hello").await;
+
+        cli.post("/2")
+            .query("admin", &true)
+            .body("hello")
+            .send()
+            .await
+            .assert_status(StatusCode::FORBIDDEN);
+        cli.post("/1")
+            .query("admin", &false)
+            .body("hello")
+            .send()
+            .await
+            .assert_status(StatusCode::FORBIDDEN);
+
+        cli.post("/1")
+            .query("admin", &true)
+            .header("x-maintenance", "1")
+            .body("hello")
+            .send()
+            .await
+            .assert_status(StatusCode::SERVICE_UNAVAILABLE);
+    }
+}
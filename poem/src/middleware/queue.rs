@@ -0,0 +1,213 @@
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use tokio::sync::Semaphore;
+
+use crate::{
+    error::{QueueFullError, QueueTimeoutError},
+    Endpoint, Middleware, Request, Result,
+};
+
+/// Middleware that limits the number of requests handled concurrently by an
+/// endpoint, and queues the other requests.
+///
+/// Up to `concurrency` requests are passed to the inner endpoint in parallel,
+/// and up to `max_queue` more requests wait for their turn in FIFO order, so
+/// a request can not be starved by the requests that arrived after it. A
+/// request is rejected with `503 SERVICE UNAVAILABLE` if the queue is full,
+/// or if it has waited for longer than `max_wait`.
+///
+/// The slot of a request is released as soon as the inner endpoint returns,
+/// or when the request is dropped, for example because the client
+/// disconnected, whether it was queued or being handled.
+///
+/// Each endpoint transformed by this middleware has its own queue, so it can
+/// be applied to each route separately.
+///
+/// # Errors
+///
+/// - [`QueueFullError`]
+/// - [`QueueTimeoutError`]
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+///
+/// use poem::{get, handler, middleware::Queue, EndpointExt, Route};
+///
+/// #[handler]
+/// async fn report() -> &'static str {
+///     "expensive report"
+/// }
+///
+/// let app = Route::new().at(
+///     "/report",
+///     get(report).with(Queue::new(4, 100, Duration::from_secs(10))),
+/// );
+/// ```
+pub struct Queue {
+    concurrency: usize,
+    max_queue: usize,
+    max_wait: Duration,
+}
+
+impl Queue {
+    /// Create `Queue` middleware.
+    pub fn new(concurrency: usize, max_queue: usize, max_wait: Duration) -> Self {
+        Self {
+            concurrency: concurrency.max(1),
+            max_queue,
+            max_wait,
+        }
+    }
+}
+
+impl<E: Endpoint> Middleware<E> for Queue {
+    type Output = QueueEndpoint<E>;
+
+    fn transform(&self, ep: E) -> Self::Output {
+        QueueEndpoint {
+            inner: ep,
+            semaphore: Arc::new(Semaphore::new(self.concurrency)),
+            queued: Arc::new(AtomicUsize::new(0)),
+            max_queue: self.max_queue,
+            max_wait: self.max_wait,
+        }
+    }
+}
+
+/// Endpoint for the `Queue` middleware.
+pub struct QueueEndpoint<E> {
+    inner: E,
+    semaphore: Arc<Semaphore>,
+    queued: Arc<AtomicUsize>,
+    max_queue: usize,
+    max_wait: Duration,
+}
+
+/// Removes a request from the queue when it is dropped.
+struct QueuedGuard(Arc<AtomicUsize>);
+
+impl Drop for QueuedGuard {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+impl<E: Endpoint> Endpoint for QueueEndpoint<E> {
+    type Output = E::Output;
+
+    async fn call(&self, req: Request) -> Result<Self::Output> {
+        // the semaphore hands the released permits to the waiters in FIFO order,
+        // so a permit is only available when nobody is queued
+        let _permit = match self.semaphore.clone().try_acquire_owned() {
+            Ok(permit) => permit,
+            Err(_) => {
+                self.queued
+                    .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |queued| {
+                        (queued < self.max_queue).then_some(queued + 1)
+                    })
+                    .map_err(|_| QueueFullError)?;
+                let _guard = QueuedGuard(self.queued.clone());
+
+                tokio::time::timeout(self.max_wait, self.semaphore.clone().acquire_owned())
+                    .await
+                    .map_err(|_| QueueTimeoutError)?
+                    .expect("the semaphore is never closed")
+            }
+        };
+
+        self.inner.call(req).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use http::StatusCode;
+    use tokio::sync::oneshot;
+
+    use super::*;
+    use crate::{endpoint::make, test::TestClient, EndpointExt};
+
+    #[tokio::test]
+    async fn queue() {
+        let (tx, rx) = tokio::sync::watch::channel(false);
+        let order = Arc::new(parking_lot::Mutex::new(Vec::new()));
+        let ep = Arc::new(
+            make({
+                let order = order.clone();
+                move |req| {
+                    let mut rx = rx.clone();
+                    let order = order.clone();
+                    async move {
+                        order.lock().push(req.uri().path().to_string());
+                        rx.wait_for(|released| *released).await.unwrap();
+                        "done"
+                    }
+                }
+            })
+            .with(Queue::new(1, 2, Duration::from_secs(5))),
+        );
+        let cli = Arc::new(TestClient::new(ep));
+
+        let mut handles = Vec::new();
+        for path in ["/a", "/b", "/c"] {
+            let cli = cli.clone();
+            handles.push(tokio::spawn(async move {
+                cli.get(path).send().await.0.status()
+            }));
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+
+        // one request is handled and two are queued
+        cli.get("/d")
+            .send()
+            .await
+            .assert_status(StatusCode::SERVICE_UNAVAILABLE);
+
+        tx.send(true).unwrap();
+        for handle in handles {
+            assert_eq!(handle.await.unwrap(), StatusCode::OK);
+        }
+        assert_eq!(*order.lock(), ["/a", "/b", "/c"]);
+    }
+
+    #[tokio::test]
+    async fn timeout_and_disconnect() {
+        let (tx, rx) = oneshot::channel::<()>();
+        let rx = Arc::new(parking_lot::Mutex::new(Some(rx)));
+        let ep = make(move |_| {
+            let rx = rx.clone();
+            async move {
+                let rx = rx.lock().take();
+                if let Some(rx) = rx {
+                    let _ = rx.await;
+                }
+                "done"
+            }
+        })
+        .with(Queue::new(1, 1, Duration::from_millis(50)));
+        let ep = Arc::new(ep);
+
+        // the first request holds the only slot until it is dropped
+        let first = tokio::spawn({
+            let ep = ep.clone();
+            async move { ep.call(Request::default()).await }
+        });
+        tokio::time::sleep(Duration::from_millis(20)).await;
+
+        let err = ep.call(Request::default()).await.unwrap_err();
+        assert!(err.is::<QueueTimeoutError>());
+
+        first.abort();
+        let _ = first.await;
+        drop(tx);
+        assert_eq!(ep.call(Request::default()).await.unwrap(), "done");
+    }
+}
@@ -0,0 +1,246 @@
+use std::sync::{
+    atomic::{AtomicU64, Ordering},
+    Arc,
+};
+
+use crate::{Endpoint, Middleware, Request, Result};
+
+/// The maximum length of the request body, in bytes, set by the
+/// [`RequestBodyLimit`] middleware.
+///
+/// It is added to the request data, so the handlers can get it with the
+/// [`Data`](crate::web::Data) extractor.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct BodyLimit(pub u64);
+
+/// The limit shared with the body wrapped by the outermost middleware.
+#[derive(Clone)]
+struct SharedBodyLimit(Arc<AtomicU64>);
+
+/// Middleware to limit the length of the request body.
+///
+/// Reading a body whose `Content-Length` exceeds the limit fails before any
+/// data is received. The bodies without a `Content-Length`, such as the
+/// chunked uploads, are not buffered, reading them fails as soon as more
+/// bytes than the limit have been received, the same as
+/// [`Body::limit`](crate::Body::limit), so the upload is aborted in the
+/// middle.
+///
+/// When this middleware is nested in another `RequestBodyLimit`, the inner
+/// one overrides the limit, even if it is larger, so a subtree can allow
+/// larger bodies than the rest of the application. The limit is only checked
+/// when the body is read, so the `Content-Length` is compared with the limit
+/// of the innermost middleware. The limit of the request
+/// is available to the handlers as the [`BodyLimit`] request data.
+///
+/// Unlike [`SizeLimit`](super::SizeLimit), the `Content-Length` header is not
+/// required.
+///
+/// # Errors
+///
+/// - [`PayloadTooLargeError`](crate::error::PayloadTooLargeError)
+///
+/// # Example
+///
+/// ```
+/// use poem::{
+///     handler,
+///     http::StatusCode,
+///     middleware::{BodyLimit, RequestBodyLimit},
+///     post,
+///     test::TestClient,
+///     web::Data,
+///     Body, EndpointExt, Result, Route,
+/// };
+///
+/// #[handler]
+/// async fn upload(body: Body, limit: Data<&BodyLimit>) -> Result<String> {
+///     let data = body.into_bytes().await?;
+///     Ok(format!("{} of {} bytes", data.len(), limit.0 .0))
+/// }
+///
+/// let app = Route::new()
+///     .at("/comment", post(upload))
+///     .at(
+///         "/upload",
+///         post(upload).with(RequestBodyLimit::new(100 * 1024 * 1024)),
+///     )
+///     .with(RequestBodyLimit::new(256 * 1024));
+/// let cli = TestClient::new(app);
+///
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// let data = vec![0u8; 1024 * 1024];
+/// cli.post("/comment")
+///     .header("content-length", data.len())
+///     .body(data.clone())
+///     .send()
+///     .await
+///     .assert_status(StatusCode::PAYLOAD_TOO_LARGE);
+/// cli.post("/upload")
+///     .header("content-length", data.len())
+///     .body(data)
+///     .send()
+///     .await
+///     .assert_text("1048576 of 104857600 bytes")
+///     .await;
+/// # });
+/// ```
+pub struct RequestBodyLimit {
+    max_bytes: u64,
+}
+
+impl RequestBodyLimit {
+    /// Create `RequestBodyLimit` middleware.
+    pub fn new(max_bytes: u64) -> Self {
+        Self { max_bytes }
+    }
+}
+
+impl<E: Endpoint> Middleware<E> for RequestBodyLimit {
+    type Output = RequestBodyLimitEndpoint<E>;
+
+    fn transform(&self, ep: E) -> Self::Output {
+        RequestBodyLimitEndpoint {
+            inner: ep,
+            max_bytes: self.max_bytes,
+        }
+    }
+}
+
+/// Endpoint for the `RequestBodyLimit` middleware.
+pub struct RequestBodyLimitEndpoint<E> {
+    inner: E,
+    max_bytes: u64,
+}
+
+impl<E: Endpoint> Endpoint for RequestBodyLimitEndpoint<E> {
+    type Output = E::Output;
+
+    async fn call(&self, mut req: Request) -> Result<Self::Output> {
+        match req.data::<SharedBodyLimit>() {
+            // the body is already limited by an outer middleware
+            Some(limit) => limit.0.store(self.max_bytes, Ordering::Relaxed),
+            None => {
+                let limit = Arc::new(AtomicU64::new(self.max_bytes));
+                let body = req.take_body().shared_limit(limit.clone());
+                req.set_body(body);
+                req.set_data(SharedBodyLimit(limit));
+            }
+        }
+        req.set_data(BodyLimit(self.max_bytes));
+        self.inner.call(req).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures_util::stream;
+    use http::StatusCode;
+
+    use super::*;
+    use crate::{handler, test::TestClient, web::Data, Body, EndpointExt, Route};
+
+    #[handler(internal)]
+    async fn upload(body: Body, limit: Data<&BodyLimit>) -> Result<String> {
+        let data = body.into_bytes().await?;
+        Ok(format!("{}/{}", data.len(), limit.0 .0))
+    }
+
+    fn chunked(chunks: usize) -> Body {
+        Body::from_bytes_stream(stream::iter(
+            (0..chunks).map(|_| Ok::<_, std::io::Error>(bytes::Bytes::from_static(b"12345"))),
+        ))
+    }
+
+    #[tokio::test]
+    async fn content_length() {
+        let cli = TestClient::new(upload.with(RequestBodyLimit::new(5)));
+
+        cli.post("/")
+            .body("12345")
+            .send()
+            .await
+            .assert_text("5/5")
+            .await;
+        cli.post("/")
+            .header("content-length", 6)
+            .body("123456")
+            .send()
+            .await
+            .assert_status(StatusCode::PAYLOAD_TOO_LARGE);
+    }
+
+    #[tokio::test]
+    async fn streaming() {
+        let cli = TestClient::new(upload.with(RequestBodyLimit::new(10)));
+
+        cli.post("/")
+            .body(chunked(2))
+            .send()
+            .await
+            .assert_text("10/10")
+            .await;
+        cli.post("/")
+            .body(chunked(3))
+            .send()
+            .await
+            .assert_status(StatusCode::PAYLOAD_TOO_LARGE);
+    }
+
+    #[tokio::test]
+    async fn nested_override() {
+        let cli = TestClient::new(
+            Route::new()
+                .at("/small", upload)
+                .at("/large", upload.with(RequestBodyLimit::new(20)))
+                .with(RequestBodyLimit::new(5)),
+        );
+
+        cli.post("/small")
+            .body(chunked(2))
+            .send()
+            .await
+            .assert_status(StatusCode::PAYLOAD_TOO_LARGE);
+        cli.post("/large")
+            .body(chunked(3))
+            .send()
+            .await
+            .assert_text("15/20")
+            .await;
+        cli.post("/large")
+            .body(chunked(5))
+            .send()
+            .await
+            .assert_status(StatusCode::PAYLOAD_TOO_LARGE);
+    }
+
+    #[tokio::test]
+    async fn nested_override_content_length() {
+        let cli = TestClient::new(
+            Route::new()
+                .at("/small", upload)
+                .at("/large", upload.with(RequestBodyLimit::new(20)))
+                .with(RequestBodyLimit::new(5)),
+        );
+
+        cli.post("/small")
+            .header("content-length", 10)
+            .body("1234567890")
+            .send()
+            .await
+            .assert_status(StatusCode::PAYLOAD_TOO_LARGE);
+        cli.post("/large")
+            .header("content-length", 10)
+            .body("1234567890")
+            .send()
+            .await
+            .assert_text("10/20")
+            .await;
+        cli.post("/large")
+            .header("content-length", 25)
+            .body("1234567890123456789012345")
+            .send()
+            .await
+            .assert_status(StatusCode::PAYLOAD_TOO_LARGE);
+    }
+}
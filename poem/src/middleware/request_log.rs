@@ -0,0 +1,305 @@
+use std::{
+    collections::BTreeMap,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::{Instant, SystemTime, UNIX_EPOCH},
+};
+
+use parking_lot::Mutex;
+use serde::Serialize;
+
+use crate::{
+    http::header::HeaderName, web::Json, Endpoint, IntoResponse, Middleware, Request, Response,
+    Result,
+};
+
+type RedactPathFn = Arc<dyn Fn(&str) -> String + Send + Sync>;
+type Slot = Mutex<Option<(u64, RequestLogEntry)>>;
+
+/// A request recorded by the [`RequestLog`] middleware.
+#[derive(Debug, Clone, Serialize)]
+pub struct RequestLogEntry {
+    /// The method of the request.
+    pub method: String,
+    /// The path of the request, without the query string.
+    pub path: String,
+    /// The status code of the response.
+    pub status: u16,
+    /// The time taken to produce the response, in milliseconds.
+    pub duration_ms: f64,
+    /// The time the request was received, in milliseconds since the Unix
+    /// epoch.
+    pub timestamp: u64,
+    /// The recorded headers of the request.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub headers: BTreeMap<String, String>,
+}
+
+/// A fixed size ring buffer, each writer only locks the slot it overwrites,
+/// so the concurrent requests rarely contend.
+struct RingBuffer {
+    slots: Box<[Slot]>,
+    next: AtomicU64,
+}
+
+impl RingBuffer {
+    fn new(capacity: usize) -> Self {
+        Self {
+            slots: (0..capacity.max(1)).map(|_| Mutex::new(None)).collect(),
+            next: AtomicU64::new(0),
+        }
+    }
+
+    fn push(&self, entry: RequestLogEntry) {
+        let seq = self.next.fetch_add(1, Ordering::Relaxed);
+        *self.slots[(seq % self.slots.len() as u64) as usize].lock() = Some((seq, entry));
+    }
+
+    fn entries(&self) -> Vec<RequestLogEntry> {
+        let next = self.next.load(Ordering::Relaxed);
+        let start = next.saturating_sub(self.slots.len() as u64);
+        (start..next)
+            .rev()
+            .filter_map(
+                |seq| match &*self.slots[(seq % self.slots.len() as u64) as usize].lock() {
+                    Some((s, entry)) if *s == seq => Some(entry.clone()),
+                    _ => None,
+                },
+            )
+            .collect()
+    }
+}
+
+/// Middleware that keeps the last requests in memory, to inspect the recent
+/// traffic of a server without an observability stack.
+///
+/// The method, path, status, duration and timestamp of the last `capacity`
+/// requests are kept in a ring buffer, the oldest requests are dropped. The
+/// query string is never recorded, and the headers are only recorded if they
+/// are specified with [`RequestLog::record_header`]. Use
+/// [`RequestLog::redact_path`] to hide the sensitive parts of the paths.
+///
+/// The clones of this middleware share the same buffer, and
+/// [`RequestLog::entries_endpoint`] returns an endpoint that responds with
+/// the recorded requests as JSON, newest first. It should only be exposed on
+/// an admin route protected from the public.
+///
+/// # Example
+///
+/// ```
+/// use poem::{
+///     get, handler, middleware::RequestLog, test::TestClient, EndpointExt, Route,
+/// };
+///
+/// #[handler]
+/// fn index() -> &'static str {
+///     "hello"
+/// }
+///
+/// let log = RequestLog::new(100).record_header("user-agent");
+/// let app = Route::new()
+///     .at("/", get(index))
+///     .at("/admin/requests", log.entries_endpoint())
+///     .with(log);
+/// let cli = TestClient::new(app);
+///
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// cli.get("/").send().await.assert_status_is_ok();
+///
+/// let resp = cli.get("/admin/requests").send().await;
+/// resp.assert_status_is_ok();
+/// let json = resp.json().await;
+/// let entries = json.value().array();
+/// entries.assert_len(1);
+/// entries.get(0).object().get("path").assert_string("/");
+/// # });
+/// ```
+#[derive(Clone)]
+pub struct RequestLog {
+    buffer: Arc<RingBuffer>,
+    redact_path: Option<RedactPathFn>,
+    headers: Vec<HeaderName>,
+}
+
+impl RequestLog {
+    /// Create `RequestLog` middleware that keeps the last `capacity`
+    /// requests.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            buffer: Arc::new(RingBuffer::new(capacity)),
+            redact_path: None,
+            headers: Vec::new(),
+        }
+    }
+
+    /// Sets a function that rewrites the paths before they are recorded, e.g.
+    /// to hide the tokens in the paths.
+    #[must_use]
+    pub fn redact_path(self, f: impl Fn(&str) -> String + Send + Sync + 'static) -> Self {
+        Self {
+            redact_path: Some(Arc::new(f)),
+            ..self
+        }
+    }
+
+    /// Records a header of the requests, the headers are not recorded by
+    /// default.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the name is not a valid header name.
+    #[must_use]
+    pub fn record_header(mut self, name: impl AsRef<str>) -> Self {
+        self.headers
+            .push(HeaderName::try_from(name.as_ref()).unwrap());
+        self
+    }
+
+    /// Returns the recorded requests, newest first.
+    pub fn entries(&self) -> Vec<RequestLogEntry> {
+        self.buffer.entries()
+    }
+
+    /// Returns an endpoint that responds with the recorded requests as JSON,
+    /// newest first.
+    pub fn entries_endpoint(&self) -> RequestLogEntriesEndpoint {
+        RequestLogEntriesEndpoint {
+            buffer: self.buffer.clone(),
+        }
+    }
+}
+
+impl<E: Endpoint> Middleware<E> for RequestLog {
+    type Output = RequestLogEndpoint<E>;
+
+    fn transform(&self, ep: E) -> Self::Output {
+        RequestLogEndpoint {
+            inner: ep,
+            log: self.clone(),
+        }
+    }
+}
+
+/// Endpoint for the `RequestLog` middleware.
+pub struct RequestLogEndpoint<E> {
+    inner: E,
+    log: RequestLog,
+}
+
+impl<E: Endpoint> Endpoint for RequestLogEndpoint<E> {
+    type Output = Response;
+
+    async fn call(&self, req: Request) -> Result<Self::Output> {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or_default();
+        let method = req.method().to_string();
+        let path = match &self.log.redact_path {
+            Some(redact_path) => redact_path(req.original_uri().path()),
+            None => req.original_uri().path().to_string(),
+        };
+        let headers = self
+            .log
+            .headers
+            .iter()
+            .filter_map(|name| {
+                let value = req.headers().get(name)?.to_str().ok()?;
+                Some((name.to_string(), value.to_string()))
+            })
+            .collect();
+
+        let start = Instant::now();
+        let res = self.inner.call(req).await.map(IntoResponse::into_response);
+        let status = match &res {
+            Ok(resp) => resp.status(),
+            Err(err) => err.status(),
+        };
+
+        self.log.buffer.push(RequestLogEntry {
+            method,
+            path,
+            status: status.as_u16(),
+            duration_ms: start.elapsed().as_secs_f64() * 1000.0,
+            timestamp,
+            headers,
+        });
+        res
+    }
+}
+
+/// An endpoint that responds with the requests recorded by a [`RequestLog`]
+/// middleware.
+pub struct RequestLogEntriesEndpoint {
+    buffer: Arc<RingBuffer>,
+}
+
+impl Endpoint for RequestLogEntriesEndpoint {
+    type Output = Json<Vec<RequestLogEntry>>;
+
+    async fn call(&self, _req: Request) -> Result<Self::Output> {
+        Ok(Json(self.buffer.entries()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        endpoint::make_sync, http::StatusCode, test::TestClient, EndpointExt, Error, Route,
+    };
+
+    #[tokio::test]
+    async fn request_log() {
+        let log = RequestLog::new(2)
+            .redact_path(|path| path.replace("secret", "***"))
+            .record_header("x-client");
+        let cli = TestClient::new(
+            Route::new()
+                .at("/a", make_sync(|_| "a"))
+                .at("/token/:token", make_sync(|_| "b"))
+                .at(
+                    "/error",
+                    make_sync(|_| Err::<(), _>(Error::from_status(StatusCode::CONFLICT))),
+                )
+                .with(log.clone()),
+        );
+
+        cli.get("/a").send().await.assert_status_is_ok();
+        cli.get("/token/secret?q=1")
+            .header("x-client", "test")
+            .send()
+            .await
+            .assert_status_is_ok();
+        cli.post("/error")
+            .send()
+            .await
+            .assert_status(StatusCode::CONFLICT);
+
+        let entries = log.entries();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].method, "POST");
+        assert_eq!(entries[0].path, "/error");
+        assert_eq!(entries[0].status, 409);
+        assert!(entries[0].headers.is_empty());
+        assert_eq!(entries[1].method, "GET");
+        assert_eq!(entries[1].path, "/token/***");
+        assert_eq!(entries[1].status, 200);
+        assert_eq!(entries[1].headers["x-client"], "test");
+
+        let cli = TestClient::new(log.entries_endpoint());
+        let resp = cli.get("/").send().await;
+        resp.assert_status_is_ok();
+        let json = resp.json().await;
+        let entries = json.value().array();
+        entries.assert_len(2);
+        entries.get(0).object().get("status").assert_i64(409);
+        entries
+            .get(1)
+            .object()
+            .get("path")
+            .assert_string("/token/***");
+    }
+}
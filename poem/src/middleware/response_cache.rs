@@ -0,0 +1,642 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    future::Future,
+    sync::Arc,
+    time::{Duration, SystemTime},
+};
+
+use bytes::Bytes;
+use headers::{CacheControl, HeaderMapExt};
+use http::{header, HeaderMap, HeaderName, HeaderValue, Method, StatusCode};
+use parking_lot::Mutex;
+
+use crate::{Body, Endpoint, IntoResponse, Middleware, Request, Response, Result};
+
+/// A response stored in a [`ResponseCacheStore`].
+#[derive(Debug, Clone)]
+pub struct CachedResponse {
+    /// The status code of the response.
+    pub status: StatusCode,
+    /// The headers of the response.
+    pub headers: HeaderMap,
+    /// The body of the response.
+    pub body: Bytes,
+    /// The request headers listed in the `Vary` header of the response and
+    /// their values in the request that produced the response.
+    pub vary: Vec<(HeaderName, Option<HeaderValue>)>,
+    /// The time at which the response was stored or revalidated.
+    pub stored_at: SystemTime,
+    /// The time at which the response becomes stale.
+    pub expires_at: SystemTime,
+}
+
+impl CachedResponse {
+    fn is_fresh(&self, now: SystemTime) -> bool {
+        now < self.expires_at
+    }
+
+    fn matches(&self, req_headers: &HeaderMap) -> bool {
+        self.vary
+            .iter()
+            .all(|(name, value)| req_headers.get(name) == value.as_ref())
+    }
+
+    fn to_response(&self, now: SystemTime) -> Response {
+        let mut resp = Response::builder()
+            .status(self.status)
+            .body(self.body.clone());
+        *resp.headers_mut() = self.headers.clone();
+        let age = now
+            .duration_since(self.stored_at)
+            .unwrap_or_default()
+            .as_secs();
+        resp.headers_mut()
+            .insert(header::AGE, HeaderValue::from(age));
+        resp
+    }
+}
+
+/// Represents a back-end storage of the [`ResponseCache`] middleware.
+pub trait ResponseCacheStore: Send + Sync {
+    /// Load a cached response.
+    fn get<'a>(
+        &'a self,
+        key: &'a str,
+    ) -> impl Future<Output = Result<Option<CachedResponse>>> + Send + 'a;
+
+    /// Insert or update a cached response.
+    fn set<'a>(
+        &'a self,
+        key: &'a str,
+        resp: CachedResponse,
+    ) -> impl Future<Output = Result<()>> + Send + 'a;
+
+    /// Remove a cached response.
+    fn remove<'a>(&'a self, key: &'a str) -> impl Future<Output = Result<()>> + Send + 'a;
+}
+
+/// An in-memory [`ResponseCacheStore`] that evicts the least recently used
+/// responses when it is full.
+pub struct MemoryCacheStore {
+    capacity: usize,
+    inner: Mutex<MemoryCacheInner>,
+}
+
+#[derive(Default)]
+struct MemoryCacheInner {
+    tick: u64,
+    entries: HashMap<String, (u64, CachedResponse)>,
+    lru: BTreeMap<u64, String>,
+}
+
+impl MemoryCacheInner {
+    fn touch(&mut self, key: &str) -> Option<&CachedResponse> {
+        self.tick += 1;
+        let tick = self.tick;
+        let (last_used, resp) = self.entries.get_mut(key)?;
+        let key = self.lru.remove(&*last_used)?;
+        self.lru.insert(tick, key);
+        *last_used = tick;
+        Some(resp)
+    }
+}
+
+impl Default for MemoryCacheStore {
+    fn default() -> Self {
+        Self::new(1024)
+    }
+}
+
+impl MemoryCacheStore {
+    /// Create a `MemoryCacheStore` that holds at most `capacity` responses.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            inner: Default::default(),
+        }
+    }
+}
+
+impl ResponseCacheStore for MemoryCacheStore {
+    async fn get<'a>(&'a self, key: &'a str) -> Result<Option<CachedResponse>> {
+        Ok(self.inner.lock().touch(key).cloned())
+    }
+
+    async fn set<'a>(&'a self, key: &'a str, resp: CachedResponse) -> Result<()> {
+        let mut inner = self.inner.lock();
+        inner.tick += 1;
+        let tick = inner.tick;
+
+        if let Some((last_used, _)) = inner.entries.remove(key) {
+            inner.lru.remove(&last_used);
+        }
+        while inner.entries.len() >= self.capacity {
+            let Some((_, key)) = inner.lru.pop_first() else {
+                break;
+            };
+            inner.entries.remove(&key);
+        }
+
+        inner.entries.insert(key.to_string(), (tick, resp));
+        inner.lru.insert(tick, key.to_string());
+        Ok(())
+    }
+
+    async fn remove<'a>(&'a self, key: &'a str) -> Result<()> {
+        let mut inner = self.inner.lock();
+        if let Some((last_used, _)) = inner.entries.remove(key) {
+            inner.lru.remove(&last_used);
+        }
+        Ok(())
+    }
+}
+
+/// Middleware that caches successful `GET` responses.
+///
+/// Responses are keyed by the method and the URI of the request, and only
+/// reused for requests that have the same values of the headers listed in the
+/// `Vary` header of the response.
+///
+/// The lifetime of a response is taken from the `s-maxage` or `max-age`
+/// directive of its `Cache-Control` header, otherwise the default TTL is
+/// used. Responses with the `no-store`, `no-cache` or `private` directives,
+/// `Vary: *`, a `Set-Cookie` header, or a body whose length is not known in
+/// advance are not cached.
+///
+/// The requests with a `Cookie` or `Authorization` header bypass the cache,
+/// because their responses are usually personalised, unless
+/// [`ResponseCache::cache_credentials`] is enabled.
+///
+/// Fresh responses are served without calling the inner endpoint. When a
+/// response with an `ETag` becomes stale, it is revalidated by calling the
+/// inner endpoint with an `If-None-Match` header, and reused if the endpoint
+/// returns `304 Not Modified`.
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+///
+/// use poem::{
+///     get, handler,
+///     middleware::{MemoryCacheStore, ResponseCache},
+///     EndpointExt, Route,
+/// };
+///
+/// #[handler]
+/// fn index() -> &'static str {
+///     "hello"
+/// }
+///
+/// let app = Route::new().at(
+///     "/",
+///     get(index).with(
+///         ResponseCache::new(MemoryCacheStore::new(1000)).default_ttl(Duration::from_secs(30)),
+///     ),
+/// );
+/// ```
+pub struct ResponseCache<S> {
+    store: Arc<S>,
+    default_ttl: Duration,
+    max_body_size: usize,
+    cache_credentials: bool,
+}
+
+impl<S: ResponseCacheStore> ResponseCache<S> {
+    /// Create a `ResponseCache` middleware with the specified store.
+    pub fn new(store: S) -> Self {
+        Self {
+            store: Arc::new(store),
+            default_ttl: Duration::from_secs(60),
+            max_body_size: 1024 * 1024,
+            cache_credentials: false,
+        }
+    }
+
+    /// Sets the lifetime of the responses without `max-age` directive.
+    ///
+    /// Default is `60s`.
+    #[must_use]
+    pub fn default_ttl(self, ttl: Duration) -> Self {
+        Self {
+            default_ttl: ttl,
+            ..self
+        }
+    }
+
+    /// Sets the maximum size of the response body that can be cached.
+    ///
+    /// Default is `1MB`.
+    #[must_use]
+    pub fn max_body_size(self, size: usize) -> Self {
+        Self {
+            max_body_size: size,
+            ..self
+        }
+    }
+
+    /// Uses the cache for the requests with a `Cookie` or `Authorization`
+    /// header.
+    ///
+    /// Only enable this if the responses do not depend on the credentials,
+    /// or list these headers in the `Vary` header of the responses. The
+    /// responses to the requests with an `Authorization` header must still be
+    /// marked `public` to be cached.
+    ///
+    /// Default is `false`.
+    #[must_use]
+    pub fn cache_credentials(self, enable: bool) -> Self {
+        Self {
+            cache_credentials: enable,
+            ..self
+        }
+    }
+}
+
+impl<E: Endpoint, S: ResponseCacheStore + 'static> Middleware<E> for ResponseCache<S> {
+    type Output = ResponseCacheEndpoint<E, S>;
+
+    fn transform(&self, ep: E) -> Self::Output {
+        ResponseCacheEndpoint {
+            inner: ep,
+            store: self.store.clone(),
+            default_ttl: self.default_ttl,
+            max_body_size: self.max_body_size,
+            cache_credentials: self.cache_credentials,
+        }
+    }
+}
+
+/// Endpoint for the `ResponseCache` middleware.
+pub struct ResponseCacheEndpoint<E, S> {
+    inner: E,
+    store: Arc<S>,
+    default_ttl: Duration,
+    max_body_size: usize,
+    cache_credentials: bool,
+}
+
+impl<E, S> ResponseCacheEndpoint<E, S> {
+    /// Returns the lifetime of the response, or `None` if it cannot be
+    /// cached.
+    fn ttl(&self, req_headers: &HeaderMap, headers: &HeaderMap) -> Option<Duration> {
+        let cache_control = headers.typed_get::<CacheControl>();
+        if let Some(cache_control) = &cache_control {
+            if cache_control.no_store() || cache_control.no_cache() || cache_control.private() {
+                return None;
+            }
+        }
+        if headers.contains_key(header::SET_COOKIE) {
+            return None;
+        }
+        if req_headers.contains_key(header::AUTHORIZATION)
+            && !cache_control
+                .as_ref()
+                .is_some_and(|cache_control| cache_control.public())
+        {
+            return None;
+        }
+
+        let ttl = cache_control
+            .and_then(|cache_control| cache_control.s_max_age().or(cache_control.max_age()))
+            .unwrap_or(self.default_ttl);
+        (!ttl.is_zero()).then_some(ttl)
+    }
+}
+
+fn vary_headers(
+    req_headers: &HeaderMap,
+    headers: &HeaderMap,
+) -> Option<Vec<(HeaderName, Option<HeaderValue>)>> {
+    let mut vary = Vec::new();
+    for value in headers.get_all(header::VARY) {
+        for name in value.to_str().ok()?.split(',') {
+            let name = name.trim();
+            if name == "*" {
+                return None;
+            }
+            if name.is_empty() {
+                continue;
+            }
+            let name = HeaderName::try_from(name).ok()?;
+            let value = req_headers.get(&name).cloned();
+            vary.push((name, value));
+        }
+    }
+    Some(vary)
+}
+
+impl<E: Endpoint, S: ResponseCacheStore> Endpoint for ResponseCacheEndpoint<E, S> {
+    type Output = Response;
+
+    async fn call(&self, mut req: Request) -> Result<Self::Output> {
+        let has_credentials = req.headers().contains_key(header::COOKIE)
+            || req.headers().contains_key(header::AUTHORIZATION);
+        if req.method() != Method::GET || (has_credentials && !self.cache_credentials) {
+            return self.inner.call(req).await.map(IntoResponse::into_response);
+        }
+
+        let key = req.uri().to_string();
+        let req_headers = req.headers().clone();
+        let now = SystemTime::now();
+        let cached = match self.store.get(&key).await {
+            Ok(cached) => cached.filter(|cached| cached.matches(&req_headers)),
+            Err(err) => {
+                tracing::warn!(error = %err, "failed to load cached response");
+                None
+            }
+        };
+
+        let mut revalidating = None;
+        if let Some(cached) = cached {
+            if cached.is_fresh(now) {
+                return Ok(cached.to_response(now));
+            }
+            if let Some(etag) = cached.headers.get(header::ETAG) {
+                if !req.headers().contains_key(header::IF_NONE_MATCH)
+                    && !req.headers().contains_key(header::IF_MODIFIED_SINCE)
+                {
+                    req.headers_mut()
+                        .insert(header::IF_NONE_MATCH, etag.clone());
+                    revalidating = Some(cached);
+                }
+            }
+        }
+
+        let resp = self.inner.call(req).await?.into_response();
+        let now = SystemTime::now();
+
+        if let Some(mut cached) = revalidating {
+            if resp.status() == StatusCode::NOT_MODIFIED {
+                let ttl = self
+                    .ttl(&req_headers, resp.headers())
+                    .unwrap_or(self.default_ttl);
+                for (name, value) in resp.headers() {
+                    if name != header::CONTENT_LENGTH {
+                        cached.headers.insert(name.clone(), value.clone());
+                    }
+                }
+                cached.stored_at = now;
+                cached.expires_at = now + ttl;
+                let resp = cached.to_response(now);
+
+                // the cookies of a user must never be sent to another one
+                let result = if resp.headers().contains_key(header::SET_COOKIE) {
+                    self.store.remove(&key).await
+                } else {
+                    self.store.set(&key, cached).await
+                };
+                if let Err(err) = result {
+                    tracing::warn!(error = %err, "failed to store cached response");
+                }
+                return Ok(resp);
+            }
+        }
+
+        if resp.status() != StatusCode::OK {
+            return Ok(resp);
+        }
+        let (Some(ttl), Some(vary)) = (
+            self.ttl(&req_headers, resp.headers()),
+            vary_headers(&req_headers, resp.headers()),
+        ) else {
+            return Ok(resp);
+        };
+
+        let (parts, body) = resp.into_parts();
+        match hyper::body::Body::size_hint(&body.0).exact() {
+            Some(size) if size <= self.max_body_size as u64 => {}
+            _ => return Ok(Response::from_parts(parts, body)),
+        }
+        let body = body.into_bytes().await?;
+
+        let cached = CachedResponse {
+            status: parts.status,
+            headers: parts.headers.clone(),
+            body: body.clone(),
+            vary,
+            stored_at: now,
+            expires_at: now + ttl,
+        };
+        if let Err(err) = self.store.set(&key, cached).await {
+            tracing::warn!(error = %err, "failed to store cached response");
+        }
+        Ok(Response::from_parts(parts, Body::from_bytes(body)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+    use crate::{endpoint::make_sync, test::TestClient, EndpointExt};
+
+    #[tokio::test]
+    async fn test_response_cache() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let ep = make_sync({
+            let calls = calls.clone();
+            move |req| {
+                let n = calls.fetch_add(1, Ordering::SeqCst);
+                let builder = Response::builder();
+                let builder = match req.uri().path() {
+                    "/no-store" => builder.header(header::CACHE_CONTROL, "no-store"),
+                    "/max-age" => builder.header(header::CACHE_CONTROL, "max-age=100"),
+                    "/vary" => builder.header(header::VARY, "accept-language"),
+                    _ => builder,
+                };
+                builder.body(format!("{} {n}", req.uri().path()))
+            }
+        })
+        .with(ResponseCache::new(MemoryCacheStore::default()));
+        let cli = TestClient::new(ep);
+
+        cli.get("/a").send().await.assert_text("/a 0").await;
+        let resp = cli.get("/a").send().await;
+        resp.assert_text("/a 0").await;
+        cli.get("/b").send().await.assert_text("/b 1").await;
+        cli.post("/a").send().await.assert_text("/a 2").await;
+
+        cli.get("/no-store")
+            .send()
+            .await
+            .assert_text("/no-store 3")
+            .await;
+        cli.get("/no-store")
+            .send()
+            .await
+            .assert_text("/no-store 4")
+            .await;
+
+        cli.get("/max-age")
+            .send()
+            .await
+            .assert_text("/max-age 5")
+            .await;
+        cli.get("/max-age")
+            .send()
+            .await
+            .assert_text("/max-age 5")
+            .await;
+
+        cli.get("/c")
+            .header(header::AUTHORIZATION, "Bearer abc")
+            .send()
+            .await
+            .assert_text("/c 6")
+            .await;
+        cli.get("/c").send().await.assert_text("/c 7").await;
+
+        let resp = cli
+            .get("/vary")
+            .header("accept-language", "en")
+            .send()
+            .await;
+        resp.assert_text("/vary 8").await;
+        let resp = cli
+            .get("/vary")
+            .header("accept-language", "en")
+            .send()
+            .await;
+        resp.assert_text("/vary 8").await;
+        let resp = cli
+            .get("/vary")
+            .header("accept-language", "fr")
+            .send()
+            .await;
+        resp.assert_text("/vary 9").await;
+    }
+
+    #[tokio::test]
+    async fn test_credentials() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let ep = || {
+            make_sync({
+                let calls = calls.clone();
+                move |req| {
+                    let n = calls.fetch_add(1, Ordering::SeqCst);
+                    let builder = Response::builder();
+                    let builder = match req.uri().path() {
+                        "/login" => builder.header(header::SET_COOKIE, "session=secret"),
+                        "/public" => builder.header(header::CACHE_CONTROL, "public"),
+                        _ => builder,
+                    };
+                    builder.body(format!("{} {n}", req.uri().path()))
+                }
+            })
+        };
+
+        let cli = TestClient::new(ep().with(ResponseCache::new(MemoryCacheStore::default())));
+        cli.get("/login").send().await.assert_text("/login 0").await;
+        let resp = cli.get("/login").send().await;
+        resp.assert_header(header::SET_COOKIE, "session=secret");
+        resp.assert_text("/login 1").await;
+
+        cli.get("/a")
+            .header(header::COOKIE, "session=secret")
+            .send()
+            .await
+            .assert_text("/a 2")
+            .await;
+        cli.get("/a").send().await.assert_text("/a 3").await;
+        cli.get("/a")
+            .header(header::COOKIE, "session=secret")
+            .send()
+            .await
+            .assert_text("/a 4")
+            .await;
+
+        let cli = TestClient::new(
+            ep().with(ResponseCache::new(MemoryCacheStore::default()).cache_credentials(true)),
+        );
+        cli.get("/b")
+            .header(header::COOKIE, "session=secret")
+            .send()
+            .await
+            .assert_text("/b 5")
+            .await;
+        cli.get("/b").send().await.assert_text("/b 5").await;
+        cli.get("/c")
+            .header(header::AUTHORIZATION, "Bearer abc")
+            .send()
+            .await
+            .assert_text("/c 6")
+            .await;
+        cli.get("/c").send().await.assert_text("/c 7").await;
+        cli.get("/public")
+            .header(header::AUTHORIZATION, "Bearer abc")
+            .send()
+            .await
+            .assert_text("/public 8")
+            .await;
+        cli.get("/public")
+            .send()
+            .await
+            .assert_text("/public 8")
+            .await;
+    }
+
+    #[tokio::test]
+    async fn test_revalidate() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let ep = make_sync({
+            let calls = calls.clone();
+            move |req| {
+                let n = calls.fetch_add(1, Ordering::SeqCst);
+                if req.header(header::IF_NONE_MATCH) == Some("\"v1\"") {
+                    return Response::builder()
+                        .status(StatusCode::NOT_MODIFIED)
+                        .header(header::CACHE_CONTROL, "max-age=100")
+                        .finish();
+                }
+                Response::builder()
+                    .header(header::ETAG, "\"v1\"")
+                    .header(header::CACHE_CONTROL, "max-age=1")
+                    .body(format!("hello {n}"))
+            }
+        })
+        .with(ResponseCache::new(MemoryCacheStore::default()));
+        let cli = TestClient::new(ep);
+
+        cli.get("/").send().await.assert_text("hello 0").await;
+        cli.get("/").send().await.assert_text("hello 0").await;
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+        tokio::time::sleep(Duration::from_millis(1100)).await;
+        let resp = cli.get("/").send().await;
+        resp.assert_status_is_ok();
+        resp.assert_header(header::ETAG, "\"v1\"");
+        resp.assert_text("hello 0").await;
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+
+        // refreshed with the `max-age` of the `304` response
+        cli.get("/").send().await.assert_text("hello 0").await;
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_memory_store_lru() {
+        let store = MemoryCacheStore::new(2);
+        let resp = |body: &'static str| CachedResponse {
+            status: StatusCode::OK,
+            headers: HeaderMap::new(),
+            body: Bytes::from_static(body.as_bytes()),
+            vary: vec![],
+            stored_at: SystemTime::now(),
+            expires_at: SystemTime::now(),
+        };
+
+        store.set("a", resp("a")).await.unwrap();
+        store.set("b", resp("b")).await.unwrap();
+        store.get("a").await.unwrap().unwrap();
+        store.set("c", resp("c")).await.unwrap();
+
+        assert!(store.get("a").await.unwrap().is_some());
+        assert!(store.get("b").await.unwrap().is_none());
+        assert!(store.get("c").await.unwrap().is_some());
+
+        store.remove("a").await.unwrap();
+        assert!(store.get("a").await.unwrap().is_none());
+    }
+}
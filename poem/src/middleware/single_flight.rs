@@ -0,0 +1,374 @@
+use std::{collections::HashMap, sync::Arc};
+
+use bytes::Bytes;
+use http::{header, HeaderMap, Method, StatusCode, Version};
+use parking_lot::Mutex;
+use tokio::sync::broadcast;
+
+use crate::{Body, Endpoint, IntoResponse, Middleware, Request, Response, Result};
+
+type KeyFn = Arc<dyn Fn(&Request) -> Option<String> + Send + Sync>;
+
+/// Middleware that coalesces identical in-flight requests.
+///
+/// While a request is being handled, concurrent requests with the same key
+/// wait for it and share a copy of its response instead of calling the inner
+/// endpoint. The response extensions are not shared.
+///
+/// If the inner endpoint returns an error or a response whose body length is
+/// not known in advance (such as a streaming body), the waiting requests call
+/// the inner endpoint themselves.
+///
+/// By default only `GET` and `HEAD` requests are coalesced, keyed by the
+/// method, path and query.
+///
+/// The requests with a `Cookie` or `Authorization` header are not coalesced,
+/// because their responses are usually personalised, unless
+/// [`SingleFlight::coalesce_credentials`] is enabled. Responses with a
+/// `Set-Cookie` header are never shared.
+///
+/// # Example
+///
+/// ```
+/// use poem::{get, handler, middleware::SingleFlight, EndpointExt, Route};
+///
+/// #[handler]
+/// async fn report() -> String {
+///     // expensive computation
+///     "report".to_string()
+/// }
+///
+/// let app = Route::new().at("/report", get(report).with(SingleFlight::new()));
+/// ```
+#[derive(Clone)]
+pub struct SingleFlight {
+    key: KeyFn,
+    coalesce_credentials: bool,
+}
+
+impl Default for SingleFlight {
+    fn default() -> Self {
+        Self {
+            key: Arc::new(default_key),
+            coalesce_credentials: false,
+        }
+    }
+}
+
+impl SingleFlight {
+    /// Create new `SingleFlight` middleware.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the function that computes the key of a request.
+    ///
+    /// Requests with the same key are coalesced, and requests for which the
+    /// function returns `None` are never coalesced.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use poem::{http::Method, middleware::SingleFlight};
+    ///
+    /// let middleware = SingleFlight::new().key(|req| {
+    ///     (req.method() == Method::GET).then(|| req.uri().path().to_string())
+    /// });
+    /// ```
+    #[must_use]
+    pub fn key(self, f: impl Fn(&Request) -> Option<String> + Send + Sync + 'static) -> Self {
+        Self {
+            key: Arc::new(f),
+            ..self
+        }
+    }
+
+    /// Coalesces the requests with a `Cookie` or `Authorization` header.
+    ///
+    /// Only enable this if the responses do not depend on the credentials, or
+    /// the key function includes the identity of the user.
+    ///
+    /// Default is `false`.
+    #[must_use]
+    pub fn coalesce_credentials(self, enable: bool) -> Self {
+        Self {
+            coalesce_credentials: enable,
+            ..self
+        }
+    }
+}
+
+fn default_key(req: &Request) -> Option<String> {
+    if req.method() != Method::GET && req.method() != Method::HEAD {
+        return None;
+    }
+    let path_and_query = req
+        .uri()
+        .path_and_query()
+        .map(|path_and_query| path_and_query.as_str())
+        .unwrap_or_else(|| req.uri().path());
+    Some(format!("{} {}", req.method(), path_and_query))
+}
+
+impl<E: Endpoint> Middleware<E> for SingleFlight {
+    type Output = SingleFlightEndpoint<E>;
+
+    fn transform(&self, ep: E) -> Self::Output {
+        SingleFlightEndpoint {
+            inner: ep,
+            key: self.key.clone(),
+            coalesce_credentials: self.coalesce_credentials,
+            in_flight: Default::default(),
+        }
+    }
+}
+
+/// A copy of a response that can be shared with the waiting requests,
+/// `None` if the response cannot be shared.
+type Shared = Option<Arc<SharedResponse>>;
+
+struct SharedResponse {
+    status: StatusCode,
+    version: Version,
+    headers: HeaderMap,
+    body: Bytes,
+}
+
+impl SharedResponse {
+    fn to_response(&self) -> Response {
+        let mut resp = Response::builder()
+            .status(self.status)
+            .version(self.version)
+            .body(self.body.clone());
+        *resp.headers_mut() = self.headers.clone();
+        resp
+    }
+}
+
+/// Endpoint for the `SingleFlight` middleware.
+pub struct SingleFlightEndpoint<E> {
+    inner: E,
+    key: KeyFn,
+    coalesce_credentials: bool,
+    in_flight: Arc<Mutex<HashMap<String, broadcast::Sender<Shared>>>>,
+}
+
+/// Removes the in-flight entry when the leading request completes or is
+/// cancelled.
+struct InFlightGuard {
+    in_flight: Arc<Mutex<HashMap<String, broadcast::Sender<Shared>>>>,
+    key: String,
+}
+
+impl Drop for InFlightGuard {
+    fn drop(&mut self) {
+        self.in_flight.lock().remove(&self.key);
+    }
+}
+
+impl<E: Endpoint> Endpoint for SingleFlightEndpoint<E> {
+    type Output = Response;
+
+    async fn call(&self, req: Request) -> Result<Self::Output> {
+        let has_credentials = req.headers().contains_key(header::COOKIE)
+            || req.headers().contains_key(header::AUTHORIZATION);
+        let key = (self.key)(&req).filter(|_| self.coalesce_credentials || !has_credentials);
+        let Some(key) = key else {
+            return self.inner.call(req).await.map(IntoResponse::into_response);
+        };
+
+        let (tx, mut rx) = {
+            let mut in_flight = self.in_flight.lock();
+            match in_flight.get(&key) {
+                Some(tx) => (None, Some(tx.subscribe())),
+                None => {
+                    let (tx, _) = broadcast::channel(1);
+                    in_flight.insert(key.clone(), tx.clone());
+                    (Some(tx), None)
+                }
+            }
+        };
+
+        if let Some(rx) = &mut rx {
+            if let Ok(Some(shared)) = rx.recv().await {
+                return Ok(shared.to_response());
+            }
+            // the leading request failed, was cancelled or returned a
+            // response that cannot be shared
+            return self.inner.call(req).await.map(IntoResponse::into_response);
+        }
+
+        let tx = tx.expect("leading request");
+        let guard = InFlightGuard {
+            in_flight: self.in_flight.clone(),
+            key,
+        };
+
+        let resp = match self.inner.call(req).await {
+            Ok(resp) => resp.into_response(),
+            Err(err) => {
+                drop(guard);
+                let _ = tx.send(None);
+                return Err(err);
+            }
+        };
+
+        let (parts, body) = resp.into_parts();
+        if hyper::body::Body::size_hint(&body.0).exact().is_none()
+            || parts.headers.contains_key(header::SET_COOKIE)
+        {
+            drop(guard);
+            let _ = tx.send(None);
+            return Ok(Response::from_parts(parts, body));
+        }
+
+        let body = body.into_bytes().await;
+        drop(guard);
+        let body = match body {
+            Ok(body) => body,
+            Err(err) => {
+                let _ = tx.send(None);
+                return Err(err.into());
+            }
+        };
+
+        let shared = Arc::new(SharedResponse {
+            status: parts.status,
+            version: parts.version,
+            headers: parts.headers.clone(),
+            body: body.clone(),
+        });
+        let _ = tx.send(Some(shared));
+        Ok(Response::from_parts(parts, Body::from_bytes(body)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        sync::atomic::{AtomicUsize, Ordering},
+        time::Duration,
+    };
+
+    use futures_util::future::join_all;
+
+    use super::*;
+    use crate::{endpoint::make, test::TestClient, EndpointExt};
+
+    #[tokio::test]
+    async fn test_single_flight() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let ep = make({
+            let calls = calls.clone();
+            move |req| {
+                let calls = calls.clone();
+                async move {
+                    calls.fetch_add(1, Ordering::SeqCst);
+                    tokio::time::sleep(Duration::from_millis(100)).await;
+                    req.uri().path().to_string()
+                }
+            }
+        })
+        .with(SingleFlight::new());
+        let cli = TestClient::new(ep);
+
+        let resps = join_all((0..5).map(|_| cli.get("/a").send())).await;
+        for resp in resps {
+            resp.assert_status_is_ok();
+            resp.assert_text("/a").await;
+        }
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+        // different keys are not coalesced
+        join_all([cli.get("/a").send(), cli.get("/b").send()]).await;
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+
+        // only `GET` and `HEAD` requests are coalesced by default
+        join_all((0..2).map(|_| cli.post("/a").send())).await;
+        assert_eq!(calls.load(Ordering::SeqCst), 5);
+    }
+
+    #[tokio::test]
+    async fn test_streaming_bypass() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let ep = make({
+            let calls = calls.clone();
+            move |_| {
+                let calls = calls.clone();
+                async move {
+                    calls.fetch_add(1, Ordering::SeqCst);
+                    tokio::time::sleep(Duration::from_millis(100)).await;
+                    Body::from_bytes_stream(futures_util::stream::once(async {
+                        Ok::<_, std::io::Error>(Bytes::from_static(b"abc"))
+                    }))
+                }
+            }
+        })
+        .with(SingleFlight::new());
+        let cli = TestClient::new(ep);
+
+        let resps = join_all((0..3).map(|_| cli.get("/").send())).await;
+        for resp in resps {
+            resp.assert_status_is_ok();
+            resp.assert_text("abc").await;
+        }
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_credentials() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let ep = make({
+            let calls = calls.clone();
+            move |req| {
+                let calls = calls.clone();
+                async move {
+                    calls.fetch_add(1, Ordering::SeqCst);
+                    tokio::time::sleep(Duration::from_millis(100)).await;
+                    let user = req
+                        .headers()
+                        .get(header::COOKIE)
+                        .and_then(|value| value.to_str().ok())
+                        .unwrap_or_default()
+                        .to_string();
+                    let mut resp = Response::builder().body(user);
+                    if req.uri().path() == "/login" {
+                        resp.headers_mut()
+                            .insert(header::SET_COOKIE, "session=abc".parse().unwrap());
+                    }
+                    resp
+                }
+            }
+        })
+        .with(SingleFlight::new());
+        let cli = TestClient::new(ep);
+
+        // the requests of two users are not coalesced
+        let resps = join_all(
+            ["user=a", "user=b"]
+                .map(|cookie| cli.get("/profile").header(header::COOKIE, cookie).send()),
+        )
+        .await;
+        for (resp, user) in resps.into_iter().zip(["user=a", "user=b"]) {
+            resp.assert_status_is_ok();
+            resp.assert_text(user).await;
+        }
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+
+        join_all((0..2).map(|_| {
+            cli.get("/")
+                .header(header::AUTHORIZATION, "Bearer abc")
+                .send()
+        }))
+        .await;
+        assert_eq!(calls.load(Ordering::SeqCst), 4);
+
+        // the responses with a `Set-Cookie` header are not shared
+        let resps = join_all((0..3).map(|_| cli.get("/login").send())).await;
+        for resp in resps {
+            resp.assert_status_is_ok();
+            resp.assert_header(header::SET_COOKIE, "session=abc");
+        }
+        assert_eq!(calls.load(Ordering::SeqCst), 7);
+    }
+}
@@ -1,107 +1,546 @@
-use std::time::Instant;
+use std::{
+    collections::HashSet,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
 
-use tracing::{Instrument, Level};
+use bytes::BytesMut;
+use futures_util::StreamExt;
+use http::{header, HeaderMap, HeaderName, HeaderValue, Method, StatusCode, Uri, Version};
+use tracing::{Instrument, Level, Span};
 
 use crate::{
-    route::PathPattern, web::RealIp, Endpoint, FromRequest, IntoResponse, Middleware, Request,
-    Response, Result,
+    route::PathPattern, web::RealIp, Body, Endpoint, FromRequest, IntoResponse, Middleware,
+    Request, Response, Result,
 };
 
+const REDACTED: &str = "[REDACTED]";
+
+type UserFn = Arc<dyn Fn(&Request) -> Option<String> + Send + Sync>;
+
 /// Middleware for [`tracing`](https://crates.io/crates/tracing).
-#[derive(Default)]
+///
+/// # Request context
+///
+/// Each request is handled in a `request` span with the `remote_addr`,
+/// `version`, `method`, `uri`, `path`, `request_id` (with the `requestid`
+/// feature, if the `RequestId` middleware is applied before this one) and
+/// `user` fields. The span is entered whenever the inner endpoint is polled,
+/// including after `.await` points, so the events emitted by the handlers
+/// inherit these fields.
+///
+/// The `user` field is set by [`Tracing::user`], or it can be recorded by an
+/// inner endpoint after authentication:
+///
+/// ```
+/// use poem::handler;
+///
+/// #[handler]
+/// fn index() {
+///     tracing::Span::current().record("user", "alice");
+///     tracing::info!("this event has the `user` field");
+/// }
+/// ```
+///
+/// Tasks created with `tokio::spawn` do not inherit the span, it must be
+/// propagated explicitly with
+/// [`Instrument::in_current_span`](tracing::Instrument::in_current_span):
+///
+/// ```
+/// use poem::handler;
+/// use tracing::Instrument;
+///
+/// #[handler]
+/// async fn index() {
+///     tokio::spawn(async { tracing::info!("in the request span") }.in_current_span());
+/// }
+/// ```
+///
+/// # Sampling
+///
+/// By default every request is recorded. [`Tracing::sample_one_in`] and
+/// [`Tracing::sample_ratio`] only record a part of the requests, the other
+/// requests are handled without the `request` span and the request and
+/// response events. If the request has a `traceparent` header, the sampled
+/// flag of the caller is used instead, so a trace is recorded by all the
+/// services or by none of them.
+///
+/// With [`TracingConfig::always_record_errors`], a request that was not sampled is
+/// still recorded when it fails with a `5xx` status. As the span is created
+/// when the response is known, it only contains the `response` or `error`
+/// event.
+///
+/// The decision is added to the request data as [`TraceSampled`], so it can
+/// be propagated to the downstream services, for example as the flags of the
+/// `traceparent` header.
+///
+/// # Configuration
+///
+/// `Tracing` records the requests with the default settings. The associated
+/// functions, such as [`Tracing::with_bodies`], return a [`TracingConfig`]
+/// with the other settings.
+#[derive(Debug, Default, Copy, Clone)]
 pub struct Tracing;
 
+impl Tracing {
+    /// Creates a [`TracingConfig`] that records the request and response
+    /// bodies, see [`TracingConfig::with_bodies`].
+    #[must_use]
+    pub fn with_bodies(max_bytes: usize) -> TracingConfig {
+        TracingConfig::new().with_bodies(max_bytes)
+    }
+
+    /// Creates a [`TracingConfig`] that redacts the specified header, see
+    /// [`TracingConfig::redact_header`].
+    #[must_use]
+    pub fn redact_header<K>(key: K) -> TracingConfig
+    where
+        K: TryInto<HeaderName>,
+    {
+        TracingConfig::new().redact_header(key)
+    }
+
+    /// Creates a [`TracingConfig`] that redacts the specified JSON field, see
+    /// [`TracingConfig::redact_field`].
+    #[must_use]
+    pub fn redact_field(name: impl Into<String>) -> TracingConfig {
+        TracingConfig::new().redact_field(name)
+    }
+
+    /// Creates a [`TracingConfig`] that records the authenticated user, see
+    /// [`TracingConfig::user`].
+    #[must_use]
+    pub fn user(f: impl Fn(&Request) -> Option<String> + Send + Sync + 'static) -> TracingConfig {
+        TracingConfig::new().user(f)
+    }
+
+    /// Creates a [`TracingConfig`] that records one request out of `n`, see
+    /// [`TracingConfig::sample_one_in`].
+    #[must_use]
+    pub fn sample_one_in(n: u64) -> TracingConfig {
+        TracingConfig::new().sample_one_in(n)
+    }
+
+    /// Creates a [`TracingConfig`] that records the given ratio of the
+    /// requests, see [`TracingConfig::sample_ratio`].
+    #[must_use]
+    pub fn sample_ratio(ratio: f64) -> TracingConfig {
+        TracingConfig::new().sample_ratio(ratio)
+    }
+}
+
 impl<E: Endpoint> Middleware<E> for Tracing {
     type Output = TracingEndpoint<E>;
 
     fn transform(&self, ep: E) -> Self::Output {
-        TracingEndpoint { inner: ep }
+        TracingConfig::new().transform(ep)
+    }
+}
+
+/// The [`Tracing`] middleware with custom settings.
+#[derive(Default)]
+pub struct TracingConfig {
+    max_body_bytes: Option<usize>,
+    redact_headers: Vec<HeaderName>,
+    redact_fields: Vec<String>,
+    user: Option<UserFn>,
+    sampling: Sampling,
+    always_record_errors: bool,
+}
+
+/// Whether the request is recorded by the [`Tracing`] middleware, it is added
+/// to the request data.
+///
+/// # Example
+///
+/// ```
+/// use poem::{handler, middleware::TraceSampled, web::Data};
+///
+/// #[handler]
+/// fn index(Data(sampled): Data<&TraceSampled>) -> &'static str {
+///     // the flags of the `traceparent` header sent to the downstream services
+///     if sampled.0 {
+///         "01"
+///     } else {
+///         "00"
+///     }
+/// }
+/// ```
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct TraceSampled(pub bool);
+
+#[derive(Debug, Copy, Clone, Default)]
+enum Sampling {
+    #[default]
+    Always,
+    OneIn(u64),
+    Ratio(f64),
+}
+
+struct BodyConfig {
+    max_bytes: usize,
+    redact_headers: HashSet<HeaderName>,
+    redact_fields: HashSet<String>,
+}
+
+impl TracingConfig {
+    /// Create new `TracingConfig` with the default settings.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records the request headers and up to `max_bytes` of the request and
+    /// response bodies.
+    ///
+    /// The bodies are recorded while they are streamed, so the data is
+    /// forwarded immediately and at most `max_bytes` are kept in memory. The
+    /// `request body` and `response body` events are emitted when the limit is
+    /// reached or when the body ends. Bodies without a textual `Content-Type`
+    /// are not recorded.
+    ///
+    /// If some fields are redacted with [`TracingConfig::redact_field`], the
+    /// JSON bodies are redacted when they fit in `max_bytes`, otherwise they
+    /// are recorded as `[REDACTED]`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use poem::{handler, middleware::Tracing, EndpointExt, Route};
+    ///
+    /// #[handler]
+    /// fn index(body: String) -> String {
+    ///     body
+    /// }
+    ///
+    /// let app = Route::new().at("/", index).with(
+    ///     Tracing::with_bodies(4096)
+    ///         .redact_header("authorization")
+    ///         .redact_field("password"),
+    /// );
+    /// ```
+    #[must_use]
+    pub fn with_bodies(self, max_bytes: usize) -> Self {
+        Self {
+            max_body_bytes: Some(max_bytes),
+            ..self
+        }
+    }
+
+    /// Replaces the value of the specified header with `[REDACTED]` when
+    /// recording the request headers.
+    #[must_use]
+    pub fn redact_header<K>(mut self, key: K) -> Self
+    where
+        K: TryInto<HeaderName>,
+    {
+        if let Ok(key) = key.try_into() {
+            if !self.redact_headers.contains(&key) {
+                self.redact_headers.push(key);
+            }
+        }
+        self
+    }
+
+    /// Replaces the value of the specified field with `[REDACTED]` when
+    /// recording JSON bodies.
+    #[must_use]
+    pub fn redact_field(mut self, name: impl Into<String>) -> Self {
+        let name = name.into();
+        if !self.redact_fields.contains(&name) {
+            self.redact_fields.push(name);
+        }
+        self
+    }
+
+    /// Sets the function that returns the authenticated user of the request,
+    /// which is recorded as the `user` field of the request span.
+    ///
+    /// The function is called before the inner endpoint, so the user must be
+    /// set by the middlewares applied before this one, for example as request
+    /// data.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use poem::{handler, middleware::Tracing, EndpointExt, Route};
+    ///
+    /// #[derive(Clone)]
+    /// struct User(String);
+    ///
+    /// #[handler]
+    /// fn index() {}
+    ///
+    /// let app = Route::new().at("/", index).with(
+    ///     Tracing::user(|req| req.data::<User>().map(|user| user.0.clone())),
+    /// );
+    /// ```
+    #[must_use]
+    pub fn user(self, f: impl Fn(&Request) -> Option<String> + Send + Sync + 'static) -> Self {
+        Self {
+            user: Some(Arc::new(f)),
+            ..self
+        }
+    }
+
+    /// Records one request out of `n`, see [sampling](Tracing#sampling).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use poem::{handler, middleware::Tracing, EndpointExt, Route};
+    ///
+    /// #[handler]
+    /// fn index() {}
+    ///
+    /// let app = Route::new()
+    ///     .at("/", index)
+    ///     .with(Tracing::sample_one_in(100).always_record_errors());
+    /// ```
+    #[must_use]
+    pub fn sample_one_in(self, n: u64) -> Self {
+        Self {
+            sampling: Sampling::OneIn(n.max(1)),
+            ..self
+        }
+    }
+
+    /// Records the given ratio of the requests, between `0.0` and `1.0`, see
+    /// [sampling](Tracing#sampling).
+    ///
+    /// The requests are selected evenly rather than randomly, e.g. with a
+    /// ratio of `0.25` every fourth request is recorded.
+    #[must_use]
+    pub fn sample_ratio(self, ratio: f64) -> Self {
+        Self {
+            sampling: Sampling::Ratio(ratio.clamp(0.0, 1.0)),
+            ..self
+        }
+    }
+
+    /// Records the requests that were not sampled if they fail with a `5xx`
+    /// status.
+    #[must_use]
+    pub fn always_record_errors(self) -> Self {
+        Self {
+            always_record_errors: true,
+            ..self
+        }
+    }
+}
+
+impl Sampling {
+    fn is_sampled(&self, counter: &AtomicU64, req: &Request) -> bool {
+        if let Sampling::Always = self {
+            return true;
+        }
+        if let Some(sampled) = parent_sampled(req) {
+            return sampled;
+        }
+
+        let n = counter.fetch_add(1, Ordering::Relaxed);
+        match *self {
+            Sampling::Always => true,
+            Sampling::OneIn(one_in) => n % one_in == 0,
+            // sampled when the number of sampled requests increases
+            Sampling::Ratio(ratio) => ((n + 1) as f64 * ratio).floor() > (n as f64 * ratio).floor(),
+        }
+    }
+}
+
+/// Returns the sampled flag of the `traceparent` header.
+fn parent_sampled(req: &Request) -> Option<bool> {
+    let traceparent = req.headers().get("traceparent")?.to_str().ok()?;
+    let mut parts = traceparent.split('-');
+    let (_version, trace_id, parent_id, flags) =
+        (parts.next()?, parts.next()?, parts.next()?, parts.next()?);
+    if trace_id.len() != 32 || parent_id.len() != 16 || flags.len() != 2 {
+        return None;
+    }
+    let flags = u8::from_str_radix(flags, 16).ok()?;
+    Some(flags & 0x01 == 0x01)
+}
+
+impl<E: Endpoint> Middleware<E> for TracingConfig {
+    type Output = TracingEndpoint<E>;
+
+    fn transform(&self, ep: E) -> Self::Output {
+        TracingEndpoint {
+            inner: ep,
+            bodies: self.max_body_bytes.map(|max_bytes| {
+                Arc::new(BodyConfig {
+                    max_bytes,
+                    redact_headers: self.redact_headers.iter().cloned().collect(),
+                    redact_fields: self.redact_fields.iter().cloned().collect(),
+                })
+            }),
+            user: self.user.clone(),
+            sampling: self.sampling,
+            counter: AtomicU64::new(0),
+            always_record_errors: self.always_record_errors,
+        }
     }
 }
 
 /// Endpoint for the `Tracing` middleware.
 pub struct TracingEndpoint<E> {
     inner: E,
+    bodies: Option<Arc<BodyConfig>>,
+    user: Option<UserFn>,
+    sampling: Sampling,
+    counter: AtomicU64,
+    always_record_errors: bool,
 }
 
-impl<E: Endpoint> Endpoint for TracingEndpoint<E> {
-    type Output = Response;
+/// The fields of the `request` span.
+struct RequestInfo {
+    remote_addr: String,
+    version: Version,
+    method: Method,
+    uri: Uri,
+    request_id: Option<String>,
+    path_pattern: Option<Arc<str>>,
+    user: Option<String>,
+}
 
-    async fn call(&self, req: Request) -> Result<Self::Output> {
-        let remote_addr = RealIp::from_request_without_body(&req)
+impl RequestInfo {
+    async fn new(req: &Request, user: Option<&UserFn>) -> Self {
+        let remote_addr = RealIp::from_request_without_body(req)
             .await
             .ok()
             .and_then(|real_ip| real_ip.0)
             .map(|addr| addr.to_string())
             .unwrap_or_else(|| req.remote_addr().to_string());
 
+        #[cfg(feature = "requestid")]
+        let request_id = req
+            .extensions()
+            .get::<crate::middleware::requestid::ReqId>()
+            .map(ToString::to_string);
         #[cfg(not(feature = "requestid"))]
+        let request_id = None;
+
+        Self {
+            remote_addr,
+            version: req.version(),
+            method: req.method().clone(),
+            uri: req.original_uri().clone(),
+            request_id,
+            path_pattern: req
+                .data::<PathPattern>()
+                .map(|path_pattern| path_pattern.0.clone()),
+            user: user.and_then(|user| user(req)),
+        }
+    }
+
+    fn span(&self) -> Span {
         let span = tracing::span!(
             target: module_path!(),
             Level::INFO,
             "request",
-            remote_addr = %remote_addr,
-            version = ?req.version(),
-            method = %req.method(),
-            uri = %req.original_uri(),
+            remote_addr = %self.remote_addr,
+            version = ?self.version,
+            method = %self.method,
+            uri = %self.uri,
+            path = %self.uri.path(),
+            request_id = tracing::field::Empty,
+            path_pattern = tracing::field::Empty,
+            user = tracing::field::Empty,
         );
-        #[cfg(feature = "requestid")]
-        let span = {
-            req.extensions()
-                .get::<crate::middleware::requestid::ReqId>()
-                .map_or_else(
-                    || {
-                        tracing::span!(
-                            target: module_path!(),
-                            Level::INFO,
-                            "request",
-                            remote_addr = %remote_addr,
-                            version = ?req.version(),
-                            method = %req.method(),
-                            uri = %req.original_uri(),
-                        )
-                    },
-                    |request_id| {
-                        tracing::span!(
-                            target: module_path!(),
-                            Level::INFO,
-                            "request",
-                            remote_addr = %remote_addr,
-                            version = ?req.version(),
-                            method = %req.method(),
-                            uri = %req.original_uri(),
-                            %request_id
-                        )
-                    },
-                )
-        };
+        if let Some(request_id) = &self.request_id {
+            span.record("request_id", request_id.as_str());
+        }
+        if let Some(user) = &self.user {
+            span.record("user", user.as_str());
+        }
+        if let Some(path_pattern) = &self.path_pattern {
+            span.record("path_pattern", path_pattern.as_ref());
+        }
+        span
+    }
+}
+
+fn record_response(status: StatusCode, duration: Duration) {
+    tracing::info!(
+        status = %status,
+        duration = ?duration,
+        "response"
+    );
+}
+
+fn record_error(err: &crate::Error, duration: Duration) {
+    tracing::info!(
+        status = %err.status(),
+        error = %err,
+        duration = ?duration,
+        "error"
+    );
+}
+
+impl<E: Endpoint> Endpoint for TracingEndpoint<E> {
+    type Output = Response;
+
+    async fn call(&self, mut req: Request) -> Result<Self::Output> {
+        let sampled = self.sampling.is_sampled(&self.counter, &req);
+        req.set_data(TraceSampled(sampled));
+
+        if !sampled {
+            if !self.always_record_errors {
+                return self.inner.call(req).await.map(IntoResponse::into_response);
+            }
 
-        if let Some(path_pattern) = req.data::<PathPattern>() {
-            span.record("path_pattern", path_pattern.0.as_ref());
+            // the span is only created if the request fails
+            let info = RequestInfo::new(&req, self.user.as_ref()).await;
+            let now = Instant::now();
+            let res = self.inner.call(req).await.map(IntoResponse::into_response);
+            let duration = now.elapsed();
+            match &res {
+                Ok(resp) if resp.status().is_server_error() => {
+                    info.span()
+                        .in_scope(|| record_response(resp.status(), duration));
+                }
+                Err(err) if err.status().is_server_error() => {
+                    info.span().in_scope(|| record_error(err, duration));
+                }
+                _ => {}
+            }
+            return res;
         }
 
+        let span = RequestInfo::new(&req, self.user.as_ref()).await.span();
+
         async move {
+            if let Some(config) = &self.bodies {
+                tracing::info!(headers = ?config.redact(req.headers()), "request");
+                if config.is_textual(req.headers()) {
+                    let redact = config.is_redacted_json(req.headers());
+                    let body = config.record(req.take_body(), redact, BodyKind::Request);
+                    req.set_body(body);
+                }
+            }
+
             let now = Instant::now();
             let res = self.inner.call(req).await;
             let duration = now.elapsed();
 
             match res {
                 Ok(resp) => {
-                    let resp = resp.into_response();
-                    tracing::info!(
-                        status = %resp.status(),
-                        duration = ?duration,
-                        "response"
-                    );
+                    let mut resp = resp.into_response();
+                    if let Some(config) = &self.bodies {
+                        if config.is_textual(resp.headers()) {
+                            let redact = config.is_redacted_json(resp.headers());
+                            let body = config.record(resp.take_body(), redact, BodyKind::Response);
+                            resp.set_body(body);
+                        }
+                    }
+                    record_response(resp.status(), duration);
                     Ok(resp)
                 }
                 Err(err) => {
-                    tracing::info!(
-                        status = %err.status(),
-                        error = %err,
-                        duration = ?duration,
-                        "error"
-                    );
+                    record_error(&err, duration);
                     Err(err)
                 }
             }
@@ -110,3 +549,521 @@ impl<E: Endpoint> Endpoint for TracingEndpoint<E> {
         .await
     }
 }
+
+impl BodyConfig {
+    fn redact(&self, headers: &HeaderMap) -> HeaderMap {
+        let mut headers = headers.clone();
+        for (name, value) in headers.iter_mut() {
+            if self.redact_headers.contains(name) {
+                *value = HeaderValue::from_static(REDACTED);
+            }
+        }
+        headers
+    }
+
+    fn is_textual(&self, headers: &HeaderMap) -> bool {
+        let Some(mime) = content_type(headers) else {
+            return false;
+        };
+
+        mime.type_() == mime::TEXT
+            || matches!(
+                mime.subtype().as_str(),
+                "json" | "xml" | "yaml" | "x-www-form-urlencoded"
+            )
+            || mime
+                .suffix()
+                .is_some_and(|suffix| suffix == mime::JSON || suffix == mime::XML)
+    }
+
+    /// Returns `true` if the body is JSON and some fields are redacted.
+    fn is_redacted_json(&self, headers: &HeaderMap) -> bool {
+        !self.redact_fields.is_empty()
+            && content_type(headers).is_some_and(|mime| {
+                mime.subtype() == mime::JSON || mime.suffix() == Some(mime::JSON)
+            })
+    }
+
+    /// Returns a body that forwards the data of `body` and records up to
+    /// `max_bytes` of it.
+    fn record(self: &Arc<Self>, body: Body, redact: bool, kind: BodyKind) -> Body {
+        let mut recorder = BodyRecorder {
+            config: self.clone(),
+            redact,
+            kind,
+            span: Span::current(),
+            data: BytesMut::new(),
+            truncated: false,
+            finished: false,
+        };
+        Body::from_bytes_stream(body.into_bytes_stream().map(move |res| {
+            match &res {
+                Ok(data) => recorder.push(data),
+                Err(_) => recorder.finish(),
+            }
+            res
+        }))
+    }
+
+    fn format_body(&self, data: &[u8], truncated: bool, redact: bool) -> String {
+        if !redact {
+            return self.truncate(data, truncated);
+        }
+
+        // a truncated JSON body cannot be parsed, so none of it is recorded
+        match serde_json::from_slice::<serde_json::Value>(data) {
+            Ok(mut value) if !truncated => {
+                self.redact_value(&mut value);
+                self.truncate(value.to_string().as_bytes(), false)
+            }
+            _ => REDACTED.to_string(),
+        }
+    }
+
+    fn truncate(&self, data: &[u8], truncated: bool) -> String {
+        let (data, truncated) = match data.get(..self.max_bytes) {
+            Some(prefix) if prefix.len() < data.len() => (prefix, true),
+            _ => (data, truncated),
+        };
+        let text = match std::str::from_utf8(data) {
+            Ok(text) => text,
+            Err(err) => std::str::from_utf8(&data[..err.valid_up_to()]).unwrap_or_default(),
+        };
+        if truncated {
+            format!("{text}...")
+        } else {
+            text.to_string()
+        }
+    }
+
+    fn redact_value(&self, value: &mut serde_json::Value) {
+        match value {
+            serde_json::Value::Object(map) => {
+                for (name, value) in map.iter_mut() {
+                    if self.redact_fields.contains(name) {
+                        *value = serde_json::Value::String(REDACTED.to_string());
+                    } else {
+                        self.redact_value(value);
+                    }
+                }
+            }
+            serde_json::Value::Array(values) => {
+                values.iter_mut().for_each(|value| self.redact_value(value))
+            }
+            _ => {}
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+enum BodyKind {
+    Request,
+    Response,
+}
+
+/// Keeps up to `max_bytes` of a body while it is streamed, the event is
+/// emitted when the limit is reached, or when the body ends or is dropped.
+struct BodyRecorder {
+    config: Arc<BodyConfig>,
+    redact: bool,
+    kind: BodyKind,
+    span: Span,
+    data: BytesMut,
+    truncated: bool,
+    finished: bool,
+}
+
+impl BodyRecorder {
+    fn push(&mut self, data: &[u8]) {
+        if self.finished {
+            return;
+        }
+
+        // the body is truncated only if a chunk goes past the limit, so a body
+        // that ends exactly at the limit is recorded entirely
+        let len = data.len().min(self.config.max_bytes - self.data.len());
+        self.data.extend_from_slice(&data[..len]);
+        if len < data.len() {
+            self.truncated = true;
+            self.finish();
+        }
+    }
+
+    fn finish(&mut self) {
+        if std::mem::replace(&mut self.finished, true) {
+            return;
+        }
+
+        let body = self
+            .config
+            .format_body(&self.data, self.truncated, self.redact);
+        self.span.in_scope(|| match self.kind {
+            BodyKind::Request => tracing::info!(body = %body, "request body"),
+            BodyKind::Response => tracing::info!(body = %body, "response body"),
+        });
+    }
+}
+
+impl Drop for BodyRecorder {
+    fn drop(&mut self) {
+        self.finish();
+    }
+}
+
+fn content_type(headers: &HeaderMap) -> Option<mime::Mime> {
+    headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.parse().ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use futures_util::stream;
+
+    use super::*;
+    use crate::{handler, test::TestClient, EndpointExt};
+
+    #[tokio::test]
+    async fn test_with_bodies() {
+        #[handler(internal)]
+        async fn index(body: String) -> String {
+            body
+        }
+
+        let cli = TestClient::new(index.with(Tracing::with_bodies(4)));
+        let resp = cli
+            .post("/")
+            .content_type("text/plain")
+            .body("hello world")
+            .send()
+            .await;
+        resp.assert_status_is_ok();
+        resp.assert_text("hello world").await;
+    }
+
+    #[tokio::test]
+    async fn test_user() {
+        #[derive(Clone)]
+        struct User(&'static str);
+
+        #[handler(internal)]
+        fn index() {}
+
+        let calls = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let cli = TestClient::new(
+            index
+                .with(Tracing::user({
+                    let calls = calls.clone();
+                    move |req| {
+                        calls.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                        req.data::<User>().map(|user| user.0.to_string())
+                    }
+                }))
+                .data(User("alice")),
+        );
+        let users = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let _guard =
+            tracing::dispatcher::set_default(&tracing::Dispatch::new(UserRecorder(users.clone())));
+        cli.get("/").send().await.assert_status_is_ok();
+        assert_eq!(calls.load(std::sync::atomic::Ordering::SeqCst), 1);
+        assert_eq!(*users.lock().unwrap(), ["alice"]);
+    }
+
+    /// A subscriber that collects the values of the `user` field of the spans.
+    struct UserRecorder(Arc<std::sync::Mutex<Vec<String>>>);
+
+    impl tracing::field::Visit for &UserRecorder {
+        fn record_str(&mut self, field: &tracing::field::Field, value: &str) {
+            if field.name() == "user" {
+                self.0.lock().unwrap().push(value.to_string());
+            }
+        }
+
+        fn record_debug(&mut self, _field: &tracing::field::Field, _value: &dyn std::fmt::Debug) {}
+    }
+
+    impl tracing::Subscriber for UserRecorder {
+        fn enabled(&self, _metadata: &tracing::Metadata<'_>) -> bool {
+            true
+        }
+
+        fn new_span(&self, span: &tracing::span::Attributes<'_>) -> tracing::span::Id {
+            span.record(&mut &*self);
+            tracing::span::Id::from_u64(1)
+        }
+
+        fn record(&self, _span: &tracing::span::Id, values: &tracing::span::Record<'_>) {
+            values.record(&mut &*self);
+        }
+
+        fn record_follows_from(&self, _span: &tracing::span::Id, _follows: &tracing::span::Id) {}
+
+        fn event(&self, _event: &tracing::Event<'_>) {}
+
+        fn enter(&self, _span: &tracing::span::Id) {}
+
+        fn exit(&self, _span: &tracing::span::Id) {}
+    }
+
+    #[tokio::test]
+    async fn test_sampling() {
+        #[handler(internal)]
+        fn index(req: &Request) -> Result<String> {
+            let sampled = req.data::<TraceSampled>().unwrap().0;
+            if req.uri().path() == "/error" {
+                return Err(crate::Error::from_status(StatusCode::INTERNAL_SERVER_ERROR));
+            }
+            Ok(sampled.to_string())
+        }
+
+        let cli = TestClient::new(index.with(Tracing));
+        cli.get("/").send().await.assert_text("true").await;
+
+        let cli = TestClient::new(index.with(Tracing::sample_one_in(3)));
+        let mut decisions = Vec::new();
+        for _ in 0..6 {
+            decisions.push(
+                cli.get("/")
+                    .send()
+                    .await
+                    .0
+                    .into_body()
+                    .into_string()
+                    .await
+                    .unwrap(),
+            );
+        }
+        assert_eq!(
+            decisions,
+            ["true", "false", "false", "true", "false", "false"]
+        );
+
+        // the decision of the caller is used
+        cli.get("/")
+            .header(
+                "traceparent",
+                "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01",
+            )
+            .send()
+            .await
+            .assert_text("true")
+            .await;
+        cli.get("/")
+            .header(
+                "traceparent",
+                "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-00",
+            )
+            .send()
+            .await
+            .assert_text("false")
+            .await;
+
+        let cli = TestClient::new(index.with(Tracing::sample_ratio(0.25)));
+        let mut sampled = 0;
+        for _ in 0..8 {
+            if cli
+                .get("/")
+                .send()
+                .await
+                .0
+                .into_body()
+                .into_string()
+                .await
+                .unwrap()
+                == "true"
+            {
+                sampled += 1;
+            }
+        }
+        assert_eq!(sampled, 2);
+
+        let cli = TestClient::new(index.with(Tracing::sample_ratio(0.0).always_record_errors()));
+        cli.get("/").send().await.assert_text("false").await;
+        cli.get("/error")
+            .send()
+            .await
+            .assert_status(StatusCode::INTERNAL_SERVER_ERROR);
+    }
+
+    /// A subscriber that collects the values of the `body` field of the
+    /// events.
+    struct BodyEvents(Arc<std::sync::Mutex<Vec<String>>>);
+
+    impl tracing::field::Visit for &BodyEvents {
+        fn record_debug(&mut self, field: &tracing::field::Field, value: &dyn std::fmt::Debug) {
+            if field.name() == "body" {
+                self.0.lock().unwrap().push(format!("{value:?}"));
+            }
+        }
+    }
+
+    impl tracing::Subscriber for BodyEvents {
+        fn enabled(&self, _metadata: &tracing::Metadata<'_>) -> bool {
+            true
+        }
+
+        fn new_span(&self, _span: &tracing::span::Attributes<'_>) -> tracing::span::Id {
+            tracing::span::Id::from_u64(1)
+        }
+
+        fn record(&self, _span: &tracing::span::Id, _values: &tracing::span::Record<'_>) {}
+
+        fn record_follows_from(&self, _span: &tracing::span::Id, _follows: &tracing::span::Id) {}
+
+        fn event(&self, event: &tracing::Event<'_>) {
+            event.record(&mut &*self);
+        }
+
+        fn enter(&self, _span: &tracing::span::Id) {}
+
+        fn exit(&self, _span: &tracing::span::Id) {}
+    }
+
+    /// Reads the body through the recorder, returns the data that was read
+    /// and the recorded bodies.
+    async fn record(config: BodyConfig, body: Body, redact: bool) -> (String, Vec<String>) {
+        let events = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let _guard =
+            tracing::dispatcher::set_default(&tracing::Dispatch::new(BodyEvents(events.clone())));
+        let body = Arc::new(config).record(body, redact, BodyKind::Request);
+        let data = body.into_string().await.unwrap();
+        let events = events.lock().unwrap().clone();
+        (data, events)
+    }
+
+    #[tokio::test]
+    async fn test_capture() {
+        let config = || BodyConfig {
+            max_bytes: 5,
+            redact_headers: HashSet::new(),
+            redact_fields: HashSet::new(),
+        };
+        assert_eq!(
+            record(config(), Body::from("hello world"), false).await,
+            ("hello world".to_string(), vec!["hello...".to_string()])
+        );
+        assert_eq!(
+            record(config(), Body::from("hi"), false).await,
+            ("hi".to_string(), vec!["hi".to_string()])
+        );
+
+        // the limit is reached at the end of a chunk
+        let chunks = |chunks: Vec<&'static str>| {
+            Body::from_bytes_stream(stream::iter(chunks).map(Ok::<_, std::io::Error>))
+        };
+        assert_eq!(
+            record(config(), chunks(vec!["hel", "lo", " world"]), false).await,
+            ("hello world".to_string(), vec!["hello...".to_string()])
+        );
+        assert_eq!(
+            record(config(), chunks(vec!["hel", "lo"]), false).await,
+            ("hello".to_string(), vec!["hello".to_string()])
+        );
+    }
+
+    #[tokio::test]
+    async fn test_redact_truncated() {
+        let config = || BodyConfig {
+            max_bytes: 24,
+            redact_headers: HashSet::new(),
+            redact_fields: ["password".to_string()].into_iter().collect(),
+        };
+        let data = r#"{"password":"a secret longer than the limit"}"#;
+        assert_eq!(
+            record(config(), Body::from(data), true).await,
+            (data.to_string(), vec![REDACTED.to_string()])
+        );
+        assert_eq!(
+            record(config(), Body::from(r#"{"password":"a"}"#), true).await,
+            (
+                r#"{"password":"a"}"#.to_string(),
+                vec![r#"{"password":"[REDACTED]"..."#.to_string()]
+            )
+        );
+
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            header::CONTENT_TYPE,
+            HeaderValue::from_static("application/json"),
+        );
+        assert!(config().is_redacted_json(&headers));
+        headers.insert(header::CONTENT_TYPE, HeaderValue::from_static("text/plain"));
+        assert!(!config().is_redacted_json(&headers));
+    }
+
+    #[tokio::test]
+    async fn test_streaming_response() {
+        #[handler(internal)]
+        fn index() -> Response {
+            let events = stream::once(async { Ok::<_, std::io::Error>("data: hello\n\n") })
+                .chain(stream::pending());
+            Response::builder()
+                .content_type("text/event-stream")
+                .body(Body::from_bytes_stream(events))
+        }
+
+        // the first event is forwarded although the stream never ends
+        let cli = TestClient::new(index.with(Tracing::with_bodies(4096)));
+        let resp = cli.get("/").send().await;
+        let mut stream = resp.0.into_body().into_bytes_stream();
+        let data = tokio::time::timeout(Duration::from_secs(5), stream.next())
+            .await
+            .unwrap()
+            .unwrap()
+            .unwrap();
+        assert_eq!(data, "data: hello\n\n");
+    }
+
+    #[tokio::test]
+    async fn test_unit_compatible() {
+        #[handler(internal)]
+        fn index() -> &'static str {
+            "hello"
+        }
+
+        let cli = TestClient::new(index.with(Tracing));
+        cli.get("/").send().await.assert_text("hello").await;
+    }
+
+    #[test]
+    fn test_redact() {
+        let config = BodyConfig {
+            max_bytes: 1024,
+            redact_headers: [header::AUTHORIZATION].into_iter().collect(),
+            redact_fields: ["password".to_string()].into_iter().collect(),
+        };
+
+        let mut headers = HeaderMap::new();
+        headers.insert(header::AUTHORIZATION, HeaderValue::from_static("secret"));
+        headers.insert(header::ACCEPT, HeaderValue::from_static("*/*"));
+        let headers = config.redact(&headers);
+        assert_eq!(headers.get(header::AUTHORIZATION).unwrap(), REDACTED);
+        assert_eq!(headers.get(header::ACCEPT).unwrap(), "*/*");
+
+        assert_eq!(
+            config.format_body(br#"{"user":{"name":"a","password":"b"}}"#, false, true),
+            r#"{"user":{"name":"a","password":"[REDACTED]"}}"#
+        );
+    }
+
+    #[test]
+    fn test_is_textual() {
+        let config = BodyConfig {
+            max_bytes: 1024,
+            redact_headers: HashSet::new(),
+            redact_fields: HashSet::new(),
+        };
+        let content_type = |value| {
+            let mut headers = HeaderMap::new();
+            headers.insert(header::CONTENT_TYPE, HeaderValue::from_static(value));
+            headers
+        };
+
+        assert!(config.is_textual(&content_type("text/plain; charset=utf-8")));
+        assert!(config.is_textual(&content_type("application/json")));
+        assert!(config.is_textual(&content_type("application/problem+json")));
+        assert!(!config.is_textual(&content_type("image/png")));
+        assert!(!config.is_textual(&HeaderMap::new()));
+    }
+}
@@ -0,0 +1,322 @@
+use base64::{engine::general_purpose::STANDARD, Engine};
+use bytes::BytesMut;
+use futures_util::StreamExt;
+use md5::{Digest, Md5};
+use sha2::Sha256;
+
+use crate::{
+    error::{DigestError, ReadBodyError},
+    http::{header::HeaderName, HeaderMap},
+    Body, Endpoint, Middleware, Request, Result,
+};
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+enum Algorithm {
+    Md5,
+    Sha256,
+}
+
+impl Algorithm {
+    fn from_name(name: &str) -> Option<Self> {
+        if name.eq_ignore_ascii_case("md5") {
+            Some(Algorithm::Md5)
+        } else if name.eq_ignore_ascii_case("sha-256") {
+            Some(Algorithm::Sha256)
+        } else {
+            None
+        }
+    }
+}
+
+/// Parses the expected digests from the `Content-Digest`, `Digest` and
+/// `Content-MD5` headers, the unsupported algorithms are ignored.
+fn parse_digests(headers: &HeaderMap) -> Result<Vec<(Algorithm, Vec<u8>)>, DigestError> {
+    let decode = |value: &str| {
+        STANDARD
+            .decode(value.trim())
+            .map_err(|err| DigestError::InvalidDigest(err.to_string()))
+    };
+    let to_str = |name: &HeaderName| {
+        headers
+            .get_all(name)
+            .iter()
+            .map(|value| {
+                value
+                    .to_str()
+                    .map_err(|err| DigestError::InvalidDigest(err.to_string()))
+            })
+            .collect::<Result<Vec<_>, _>>()
+    };
+    let mut digests = Vec::new();
+
+    // `Content-Digest: sha-256=:<base64>:` (RFC 9530) and
+    // `Digest: sha-256=<base64>` (RFC 3230)
+    for (name, is_structured) in [
+        (HeaderName::from_static("content-digest"), true),
+        (HeaderName::from_static("digest"), false),
+    ] {
+        for value in to_str(&name)?.into_iter().flat_map(|s| s.split(',')) {
+            let (algo, value) = value
+                .split_once('=')
+                .ok_or_else(|| DigestError::InvalidDigest(value.to_string()))?;
+            let Some(algo) = Algorithm::from_name(algo.trim()) else {
+                continue;
+            };
+            let value = if is_structured {
+                value
+                    .trim()
+                    .strip_prefix(':')
+                    .and_then(|value| value.strip_suffix(':'))
+                    .ok_or_else(|| DigestError::InvalidDigest(value.to_string()))?
+            } else {
+                value
+            };
+            digests.push((algo, decode(value)?));
+        }
+    }
+
+    for value in to_str(&HeaderName::from_static("content-md5"))? {
+        digests.push((Algorithm::Md5, decode(value)?));
+    }
+
+    Ok(digests)
+}
+
+/// Middleware to verify the request body against the digest in the request
+/// headers.
+///
+/// The digest is read from the `Content-Digest`
+/// ([RFC 9530](https://www.rfc-editor.org/rfc/rfc9530)), `Digest`
+/// ([RFC 3230](https://www.rfc-editor.org/rfc/rfc3230)) or `Content-MD5`
+/// headers, and the `sha-256` and `md5` algorithms are supported.
+///
+/// The body is hashed while it is read, and then passed to the inner endpoint
+/// only if all digests match, so the whole body is buffered in memory. Add the
+/// [`RequestBodyLimit`](crate::middleware::RequestBodyLimit) middleware
+/// outside of this middleware to limit the number of bytes that are read,
+/// the requests exceeding it are rejected with `413 Payload Too Large`.
+/// [`SizeLimit`](crate::middleware::SizeLimit) is not enough, because it only
+/// checks the `Content-Length` header, which chunked requests do not have.
+///
+/// # Errors
+///
+/// - [`DigestError`]
+///
+/// # Example
+///
+/// ```
+/// use poem::{
+///     handler, http::StatusCode, middleware::VerifyDigest, test::TestClient, EndpointExt,
+/// };
+///
+/// #[handler]
+/// fn index(data: String) -> String {
+///     data
+/// }
+///
+/// let cli = TestClient::new(index.with(VerifyDigest::new()));
+///
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// let resp = cli
+///     .post("/")
+///     .header("Content-MD5", "XUFAKrxLKna5cZ2REBfFkg==")
+///     .body("hello")
+///     .send()
+///     .await;
+/// resp.assert_status_is_ok();
+/// resp.assert_text("hello").await;
+///
+/// let resp = cli
+///     .post("/")
+///     .header("Content-MD5", "XUFAKrxLKna5cZ2REBfFkg==")
+///     .body("world")
+///     .send()
+///     .await;
+/// resp.assert_status(StatusCode::UNPROCESSABLE_ENTITY);
+/// # });
+/// ```
+#[cfg_attr(docsrs, doc(cfg(feature = "digest")))]
+#[derive(Default)]
+pub struct VerifyDigest {
+    required: bool,
+}
+
+impl VerifyDigest {
+    /// Create `VerifyDigest` middleware.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Rejects the requests without a digest of a supported algorithm.
+    ///
+    /// Default is `false`, which passes these requests unchanged.
+    #[must_use]
+    pub fn required(self, required: bool) -> Self {
+        Self { required }
+    }
+}
+
+impl<E: Endpoint> Middleware<E> for VerifyDigest {
+    type Output = VerifyDigestEndpoint<E>;
+
+    fn transform(&self, ep: E) -> Self::Output {
+        VerifyDigestEndpoint {
+            inner: ep,
+            required: self.required,
+        }
+    }
+}
+
+/// Endpoint for the VerifyDigest middleware.
+#[cfg_attr(docsrs, doc(cfg(feature = "digest")))]
+pub struct VerifyDigestEndpoint<E> {
+    inner: E,
+    required: bool,
+}
+
+impl<E: Endpoint> Endpoint for VerifyDigestEndpoint<E> {
+    type Output = E::Output;
+
+    async fn call(&self, mut req: Request) -> Result<Self::Output> {
+        let digests = parse_digests(req.headers())?;
+        if digests.is_empty() {
+            if self.required {
+                return Err(DigestError::MissingDigest.into());
+            }
+            return self.inner.call(req).await;
+        }
+
+        let mut md5 = Md5::new();
+        let mut sha256 = Sha256::new();
+        let mut data = BytesMut::new();
+        let mut stream = req.take_body().into_bytes_stream();
+        while let Some(chunk) = stream.next().await {
+            let chunk = chunk
+                .map_err(ReadBodyError::Io)
+                .map_err(DigestError::from)?;
+            md5.update(&chunk);
+            sha256.update(&chunk);
+            data.extend_from_slice(&chunk);
+        }
+
+        let md5 = md5.finalize();
+        let sha256 = sha256.finalize();
+        for (algo, expected) in &digests {
+            let actual = match algo {
+                Algorithm::Md5 => md5.as_slice(),
+                Algorithm::Sha256 => sha256.as_slice(),
+            };
+            if actual != expected.as_slice() {
+                return Err(DigestError::Mismatch.into());
+            }
+        }
+
+        req.set_body(Body::from_bytes(data.freeze()));
+        self.inner.call(req).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use http::StatusCode;
+
+    use super::*;
+    use crate::{handler, middleware::RequestBodyLimit, test::TestClient, EndpointExt};
+
+    #[handler(internal)]
+    fn index(data: String) -> String {
+        data
+    }
+
+    // base64 digests of `hello`
+    const MD5: &str = "XUFAKrxLKna5cZ2REBfFkg==";
+    const SHA256: &str = "LPJNul+wow4m6DsqxbninhsWHlwfp0JecwQzYpOLmCQ=";
+
+    #[tokio::test]
+    async fn body_limit() {
+        let cli = TestClient::new(
+            index
+                .with(VerifyDigest::new())
+                .with(RequestBodyLimit::new(3)),
+        );
+        cli.post("/")
+            .header("Content-MD5", MD5)
+            .body("hello")
+            .send()
+            .await
+            .assert_status(StatusCode::PAYLOAD_TOO_LARGE);
+    }
+
+    #[tokio::test]
+    async fn verify_digest() {
+        let cli = TestClient::new(index.with(VerifyDigest::new()));
+
+        for (name, value) in [
+            ("Content-MD5", MD5.to_string()),
+            ("Digest", format!("SHA-256={SHA256}")),
+            ("Digest", format!("unixsum=30637, md5={MD5}")),
+            ("Content-Digest", format!("sha-256=:{SHA256}:")),
+        ] {
+            let resp = cli
+                .post("/")
+                .header(name, &value)
+                .body("hello")
+                .send()
+                .await;
+            resp.assert_status_is_ok();
+            resp.assert_text("hello").await;
+
+            cli.post("/")
+                .header(name, &value)
+                .body("hello!")
+                .send()
+                .await
+                .assert_status(StatusCode::UNPROCESSABLE_ENTITY);
+        }
+
+        cli.post("/")
+            .header("Content-MD5", "not base64")
+            .body("hello")
+            .send()
+            .await
+            .assert_status(StatusCode::BAD_REQUEST);
+
+        cli.post("/")
+            .header("Content-Digest", format!("sha-256={SHA256}"))
+            .body("hello")
+            .send()
+            .await
+            .assert_status(StatusCode::BAD_REQUEST);
+
+        cli.post("/")
+            .body("hello")
+            .send()
+            .await
+            .assert_status_is_ok();
+    }
+
+    #[tokio::test]
+    async fn required() {
+        let cli = TestClient::new(index.with(VerifyDigest::new().required(true)));
+
+        cli.post("/")
+            .body("hello")
+            .send()
+            .await
+            .assert_status(StatusCode::BAD_REQUEST);
+
+        cli.post("/")
+            .header("Digest", "unixsum=30637")
+            .body("hello")
+            .send()
+            .await
+            .assert_status(StatusCode::BAD_REQUEST);
+
+        cli.post("/")
+            .header("Content-MD5", MD5)
+            .body("hello")
+            .send()
+            .await
+            .assert_status_is_ok();
+    }
+}
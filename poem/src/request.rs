@@ -7,6 +7,8 @@ use std::{
     task::{Context, Poll},
 };
 
+use bytes::Bytes;
+use futures_util::StreamExt;
 use http::uri::Scheme;
 use http_body_util::BodyExt;
 use hyper::{body::Incoming, rt::Write as _};
@@ -18,7 +20,7 @@ use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
 use crate::web::cookie::CookieJar;
 use crate::{
     body::{Body, BoxBody},
-    error::{ParsePathError, ParseQueryError, UpgradeError},
+    error::{ParsePathError, ParseQueryError, ReadBodyError, UpgradeError},
     http::{
         header::{self, HeaderMap, HeaderName, HeaderValue},
         Extensions, Method, Uri, Version,
@@ -40,6 +42,7 @@ pub(crate) struct RequestState {
     #[cfg(feature = "cookie")]
     pub(crate) cookie_jar: Option<CookieJar>,
     pub(crate) on_upgrade: Mutex<Option<OnUpgrade>>,
+    pub(crate) cached: Mutex<Extensions>,
 }
 
 impl Default for RequestState {
@@ -53,6 +56,25 @@ impl Default for RequestState {
             #[cfg(feature = "cookie")]
             cookie_jar: None,
             on_upgrade: Default::default(),
+            cached: Default::default(),
+        }
+    }
+}
+
+impl RequestState {
+    /// Copies the state for a new request, the upgrade and the cached
+    /// extractors are not copied.
+    fn fork(&self) -> Self {
+        Self {
+            local_addr: self.local_addr.clone(),
+            remote_addr: self.remote_addr.clone(),
+            scheme: self.scheme.clone(),
+            original_uri: self.original_uri.clone(),
+            match_params: self.match_params.clone(),
+            #[cfg(feature = "cookie")]
+            cookie_jar: self.cookie_jar.clone(),
+            on_upgrade: Default::default(),
+            cached: Default::default(),
         }
     }
 }
@@ -142,6 +164,7 @@ impl From<(http::Request<Incoming>, LocalAddr, RemoteAddr, Scheme)> for Request
                 #[cfg(feature = "cookie")]
                 cookie_jar: None,
                 on_upgrade,
+                cached: Default::default(),
             },
         }
     }
@@ -377,6 +400,17 @@ impl Request {
         self.extensions.insert(data);
     }
 
+    /// Inserts a typed value to extensions, replacing the previous value of
+    /// the same type.
+    ///
+    /// A middleware can call it before calling the inner endpoint, then the
+    /// value can be extracted with [`Ext`](crate::web::Ext) or
+    /// [`Data`](crate::web::Data) by the inner endpoints.
+    #[inline]
+    pub fn set_ext<T: Clone + Send + Sync + 'static>(&mut self, value: T) -> Option<T> {
+        self.extensions.insert(value)
+    }
+
     /// Returns a reference to the remote address.
     #[inline]
     pub fn remote_addr(&self) -> &RemoteAddr {
@@ -455,6 +489,130 @@ impl Request {
             .take()
             .ok_or(UpgradeError::NoUpgrade)
     }
+
+    /// Buffers the body of this request, up to `limit` bytes, and returns a
+    /// [`RequestTemplate`] that creates copies of this request, so that it can
+    /// be sent again, e.g. to retry it.
+    ///
+    /// The body of this request is replaced with the buffered body, so this
+    /// request can still be sent. Returns `Ok(None)` if the body exceeds
+    /// `limit`, such a request is not retryable, but its body is left intact.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use poem::Request;
+    ///
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let mut req = Request::builder().uri_str("/a").body("hello");
+    /// let template = req.clone_for_retry(1024).await.unwrap().unwrap();
+    /// assert_eq!(req.into_body().into_string().await.unwrap(), "hello");
+    ///
+    /// let req = template.to_request();
+    /// assert_eq!(req.uri().path(), "/a");
+    /// assert_eq!(req.into_body().into_string().await.unwrap(), "hello");
+    ///
+    /// let mut req = Request::builder().body("hello");
+    /// assert!(req.clone_for_retry(4).await.unwrap().is_none());
+    /// assert_eq!(req.into_body().into_string().await.unwrap(), "hello");
+    /// # });
+    /// ```
+    pub async fn clone_for_retry(
+        &mut self,
+        limit: usize,
+    ) -> Result<Option<RequestTemplate>, ReadBodyError> {
+        let mut stream = self.take_body().into_bytes_stream();
+        let mut chunks = Vec::new();
+        let mut len = 0;
+
+        while let Some(chunk) = stream.next().await {
+            let chunk = chunk?;
+            len += chunk.len();
+            chunks.push(chunk);
+            if len > limit {
+                self.body = Body::from_bytes_stream(
+                    futures_util::stream::iter(chunks.into_iter().map(Ok)).chain(stream),
+                );
+                return Ok(None);
+            }
+        }
+
+        let body = match chunks.len() {
+            0 => Bytes::new(),
+            1 => chunks.remove(0),
+            _ => chunks.concat().into(),
+        };
+        self.body = Body::from_bytes(body.clone());
+
+        Ok(Some(RequestTemplate {
+            method: self.method.clone(),
+            uri: self.uri.clone(),
+            version: self.version,
+            headers: self.headers.clone(),
+            extensions: self.extensions.clone(),
+            body,
+            state: self.state.fork(),
+        }))
+    }
+}
+
+/// A copy of a request with a buffered body, created by
+/// [`Request::clone_for_retry`].
+pub struct RequestTemplate {
+    method: Method,
+    uri: Uri,
+    version: Version,
+    headers: HeaderMap,
+    extensions: Extensions,
+    body: Bytes,
+    state: RequestState,
+}
+
+impl Clone for RequestTemplate {
+    fn clone(&self) -> Self {
+        Self {
+            method: self.method.clone(),
+            uri: self.uri.clone(),
+            version: self.version,
+            headers: self.headers.clone(),
+            extensions: self.extensions.clone(),
+            body: self.body.clone(),
+            state: self.state.fork(),
+        }
+    }
+}
+
+impl Debug for RequestTemplate {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RequestTemplate")
+            .field("method", &self.method)
+            .field("uri", &self.uri)
+            .field("version", &self.version)
+            .field("headers", &self.headers)
+            .field("body", &self.body)
+            .finish()
+    }
+}
+
+impl RequestTemplate {
+    /// Returns a reference to the buffered body.
+    #[inline]
+    pub fn body(&self) -> &Bytes {
+        &self.body
+    }
+
+    /// Creates a new request from this template.
+    pub fn to_request(&self) -> Request {
+        Request {
+            method: self.method.clone(),
+            uri: self.uri.clone(),
+            version: self.version,
+            headers: self.headers.clone(),
+            extensions: self.extensions.clone(),
+            body: Body::from_bytes(self.body.clone()),
+            state: self.state.fork(),
+        }
+    }
 }
 
 pin_project_lite::pin_project! {
@@ -609,12 +767,15 @@ impl RequestBuilder {
     pub fn body(self, body: impl Into<Body>) -> Request {
         Request {
             method: self.method,
-            uri: self.uri,
+            uri: self.uri.clone(),
             version: self.version,
             headers: self.headers,
             extensions: self.extensions,
             body: body.into(),
-            state: Default::default(),
+            state: RequestState {
+                original_uri: self.uri,
+                ..Default::default()
+            },
         }
     }
 
@@ -624,3 +785,51 @@ impl RequestBuilder {
         self.body(Body::empty())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn clone_for_retry() {
+        let mut req = Request::builder()
+            .method(Method::POST)
+            .uri_str("/a?b=1")
+            .header("x-a", "1")
+            .extension(10i32)
+            .body(Body::from_bytes_stream(futures_util::stream::iter(vec![
+                Ok::<_, Error>("hel"),
+                Ok("lo"),
+            ])));
+        req.state_mut().match_params = vec![("id".to_string(), "1".to_string())];
+        let template = req.clone_for_retry(5).await.unwrap().unwrap();
+        assert_eq!(template.body(), &Bytes::from_static(b"hello"));
+        assert_eq!(req.take_body().into_string().await.unwrap(), "hello");
+
+        for _ in 0..2 {
+            let req = template.clone().to_request();
+            assert_eq!(req.method(), Method::POST);
+            assert_eq!(req.uri(), "/a?b=1");
+            assert_eq!(req.header("x-a"), Some("1"));
+            assert_eq!(req.data::<i32>(), Some(&10));
+            assert_eq!(req.raw_path_param("id"), Some("1"));
+            assert_eq!(req.into_body().into_string().await.unwrap(), "hello");
+        }
+
+        let mut req = Request::builder().finish();
+        let template = req.clone_for_retry(0).await.unwrap().unwrap();
+        assert!(template.body().is_empty());
+    }
+
+    #[tokio::test]
+    async fn clone_for_retry_too_large() {
+        let mut req =
+            Request::builder().body(Body::from_bytes_stream(futures_util::stream::iter(vec![
+                Ok::<_, Error>("hel"),
+                Ok("lo"),
+                Ok(" world"),
+            ])));
+        assert!(req.clone_for_retry(4).await.unwrap().is_none());
+        assert_eq!(req.into_body().into_string().await.unwrap(), "hello world");
+    }
+}
@@ -11,7 +11,7 @@ use crate::{
         Extensions, StatusCode, Version,
     },
     web::headers::Header,
-    Body,
+    Body, Error, Result,
 };
 
 /// Component parts of an HTTP Response.
@@ -150,6 +150,37 @@ impl Response {
         self.status.is_success()
     }
 
+    /// Returns `Err` if the status code is a client or server error (`4xx`
+    /// or `5xx`), otherwise returns this response.
+    ///
+    /// The error is created with [`Error::from_response`], so it carries the
+    /// status code and the body, and responds with this response. It allows
+    /// middleware to handle `Ok(response)` with an error status in the same
+    /// way as `Err(error)`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use poem::{http::StatusCode, Response};
+    ///
+    /// let resp = Response::builder().status(StatusCode::OK).finish();
+    /// assert!(resp.error_for_status().is_ok());
+    ///
+    /// let resp = Response::builder()
+    ///     .status(StatusCode::BAD_GATEWAY)
+    ///     .body("upstream failed");
+    /// let err = resp.error_for_status().unwrap_err();
+    /// assert_eq!(err.status(), StatusCode::BAD_GATEWAY);
+    /// ```
+    #[allow(clippy::result_large_err)]
+    pub fn error_for_status(self) -> Result<Response> {
+        if self.status.is_client_error() || self.status.is_server_error() {
+            Err(Error::from_response(self))
+        } else {
+            Ok(self)
+        }
+    }
+
     /// Sets the status code for this response.
     #[inline]
     pub fn set_status(&mut self, status: StatusCode) {
@@ -235,6 +266,12 @@ impl Response {
         self.body = body.into();
     }
 
+    /// Returns a reference to the body of this response.
+    #[inline]
+    pub(crate) fn body(&self) -> &Body {
+        &self.body
+    }
+
     /// Take the body from this response and sets the body to empty.
     #[inline]
     pub fn take_body(&mut self) -> Body {
@@ -364,4 +401,24 @@ mod tests {
         assert_eq!(resp.status(), StatusCode::BAD_GATEWAY);
         assert_eq!(resp.body.into_string().await.unwrap(), "abc");
     }
+
+    #[tokio::test]
+    async fn error_for_status() {
+        let resp = Response::builder()
+            .status(StatusCode::NOT_MODIFIED)
+            .finish();
+        assert_eq!(
+            resp.error_for_status().unwrap().status(),
+            StatusCode::NOT_MODIFIED
+        );
+
+        let resp = Response::builder()
+            .status(StatusCode::INTERNAL_SERVER_ERROR)
+            .body("abc");
+        let err = resp.error_for_status().unwrap_err();
+        assert_eq!(err.status(), StatusCode::INTERNAL_SERVER_ERROR);
+        let resp = err.into_response();
+        assert_eq!(resp.status(), StatusCode::INTERNAL_SERVER_ERROR);
+        assert_eq!(resp.body.into_string().await.unwrap(), "abc");
+    }
 }
@@ -5,9 +5,10 @@ mod router;
 mod router_domain;
 mod router_method;
 mod router_scheme;
+mod url_for;
 
 pub(crate) use internal::radix_tree::PathParams;
-pub use router::{PathPattern, Route};
+pub use router::{PathPattern, Route, RouteGroup};
 #[allow(unreachable_pub)]
 pub use router_domain::RouteDomain;
 #[allow(unreachable_pub)]
@@ -16,6 +17,7 @@ pub use router_method::{
 };
 #[allow(unreachable_pub)]
 pub use router_scheme::RouteScheme;
+pub use url_for::RouteNames;
 
 use crate::error::RouteError;
 
@@ -24,6 +26,7 @@ pub(crate) fn check_result<T>(res: Result<T, RouteError>) -> T {
         Ok(value) => value,
         Err(RouteError::InvalidPath(path)) => panic!("invalid path: {path}"),
         Err(RouteError::Duplicate(path)) => panic!("duplicate path: {path}"),
+        Err(RouteError::DuplicateName(name)) => panic!("duplicate route name: {name}"),
         Err(RouteError::InvalidRegex { path, regex }) => {
             panic!("invalid regex in path: {path} `{regex}`")
         }
@@ -1,13 +1,13 @@
-use std::{str::FromStr, sync::Arc};
+use std::{any::Any, str::FromStr, sync::Arc};
 
 use regex::Regex;
 
 use crate::{
-    endpoint::BoxEndpoint,
-    error::{NotFoundError, ParsePathError, RouteError},
+    endpoint::{BoxEndpoint, DynEndpoint, ToDynEndpoint},
+    error::{NotFoundError, ParsePathError, RouteError, UrlForError},
     http::{uri::PathAndQuery, Uri},
-    route::{check_result, internal::radix_tree::RadixTree},
-    Endpoint, EndpointExt, IntoEndpoint, IntoResponse, Request, Response, Result,
+    route::{check_result, internal::radix_tree::RadixTree, RouteNames},
+    Endpoint, EndpointExt, IntoEndpoint, IntoResponse, Middleware, Request, Response, Result,
 };
 
 #[derive(Debug, Clone, Copy)]
@@ -131,6 +131,10 @@ struct PathPrefix(usize);
 #[derive(Default)]
 pub struct Route {
     tree: RadixTree<BoxEndpoint<'static>>,
+    names: RouteNames,
+    // the fallback is called through `DynEndpoint`, because the future of a
+    // `Route` can not contain itself
+    fallback: Option<Box<ToDynEndpoint<Route>>>,
 }
 
 impl Route {
@@ -164,6 +168,71 @@ impl Route {
         Ok(self)
     }
 
+    /// Add an [Endpoint] to the specified path with a name, the name can be
+    /// used to build the URL with [`Route::url_for`] or [`RouteNames`].
+    ///
+    /// # Panics
+    ///
+    /// Panic when there are duplicates in the routing table or the name is
+    /// already used.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use poem::{handler, web::Path, Route};
+    ///
+    /// #[handler]
+    /// fn user(Path(id): Path<i32>) -> String {
+    ///     format!("user {id}")
+    /// }
+    ///
+    /// let app = Route::new().at_named("user", "/users/:id", user);
+    /// assert_eq!(app.url_for("user", [("id", 42)]).unwrap(), "/users/42");
+    /// ```
+    #[must_use]
+    pub fn at_named<E>(self, name: impl Into<String>, path: impl AsRef<str>, ep: E) -> Self
+    where
+        E: IntoEndpoint,
+        E::Endpoint: 'static,
+    {
+        check_result(self.try_at_named(name, path, ep))
+    }
+
+    /// Attempts to add an [Endpoint] to the specified path with a name.
+    pub fn try_at_named<E>(
+        self,
+        name: impl Into<String>,
+        path: impl AsRef<str>,
+        ep: E,
+    ) -> Result<Self, RouteError>
+    where
+        E: IntoEndpoint,
+        E::Endpoint: 'static,
+    {
+        let name = name.into();
+        if self.names.contains(&name) {
+            return Err(RouteError::DuplicateName(name));
+        }
+        let path = normalize_path(path.as_ref());
+        let mut route = self.try_at(&path, ep)?;
+        route.names.insert(name, path.into());
+        Ok(route)
+    }
+
+    /// Builds the URL of the specified route name.
+    ///
+    /// All parameters in the path pattern must be supplied, and the values
+    /// are percent-encoded. The names of the nested routes are included with
+    /// the prefix of the nest path.
+    pub fn url_for<I, K, V>(&self, name: &str, params: I) -> Result<String, UrlForError>
+    where
+        I: IntoIterator<Item = (K, V)>,
+        K: AsRef<str>,
+        V: ToString,
+    {
+        self.names.url_for(name, params)
+    }
+
     /// Add an [Endpoint] to the `/` path.
     ///
     /// Same as `self.at("/", ep)`.
@@ -226,17 +295,167 @@ impl Route {
         self.internal_nest(&normalize_path(path.as_ref()), ep, false)
     }
 
+    /// Nest a `Endpoint` to the specified path and strip the prefix, which
+    /// is only used if no other route of this object matches the request.
+    ///
+    /// The routes added with [`Route::at`] and [`Route::nest`] always take
+    /// precedence, whatever the order in which they are added. A request
+    /// matching a nested route is handled by it, even if it responds with
+    /// `404 Not Found`, e.g. `/api/unknown` is not served by a fallback
+    /// nested at `/` if an endpoint is nested at `/api`. The fallbacks are
+    /// matched with each other like the nested routes.
+    ///
+    /// # Panics
+    ///
+    /// Panic when there are duplicates in the fallbacks.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use poem::{endpoint::make_sync, handler, http::StatusCode, test::TestClient, Route};
+    ///
+    /// #[handler]
+    /// fn users() -> &'static str {
+    ///     "users"
+    /// }
+    ///
+    /// let api = Route::new().at("/users", users);
+    /// // e.g. `StaticFilesEndpoint::new("./dist").index_file("index.html")`
+    /// let static_files = make_sync(|_| "index.html");
+    ///
+    /// let app = Route::new()
+    ///     .nest_fallback("/", static_files)
+    ///     .nest("/api", api);
+    /// let cli = TestClient::new(app);
+    ///
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// cli.get("/api/users").send().await.assert_text("users").await;
+    /// cli.get("/login").send().await.assert_text("index.html").await;
+    /// cli.get("/api/unknown")
+    ///     .send()
+    ///     .await
+    ///     .assert_status(StatusCode::NOT_FOUND);
+    /// # });
+    /// ```
+    #[must_use]
+    pub fn nest_fallback<E>(self, path: impl AsRef<str>, ep: E) -> Self
+    where
+        E: IntoEndpoint,
+        E::Endpoint: 'static,
+    {
+        check_result(self.try_nest_fallback(path, ep))
+    }
+
+    /// Attempts to nest a `Endpoint` to the specified path as a fallback, see
+    /// [`Route::nest_fallback`].
+    pub fn try_nest_fallback<E>(mut self, path: impl AsRef<str>, ep: E) -> Result<Self, RouteError>
+    where
+        E: IntoEndpoint,
+        E::Endpoint: 'static,
+    {
+        let fallback = self
+            .fallback
+            .take()
+            .map(|route| route.0)
+            .unwrap_or_default();
+        self.fallback = Some(Box::new(ToDynEndpoint(fallback.try_nest(path, ep)?)));
+        Ok(self)
+    }
+
+    /// Create a group of routes that share a common prefix and middleware.
+    ///
+    /// The returned [`RouteGroup`] can be transformed with
+    /// [`RouteGroup::with`], and added to the parent with
+    /// [`Route::nest_group`]. This is equivalent to
+    /// `nest(prefix, f(Route::new()).with(middleware))`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use poem::{
+    ///     handler, http::StatusCode, middleware::SetHeader, test::TestClient, Endpoint, Route,
+    /// };
+    ///
+    /// #[handler]
+    /// fn users() -> &'static str {
+    ///     "users"
+    /// }
+    ///
+    /// #[handler]
+    /// fn logs() -> &'static str {
+    ///     "logs"
+    /// }
+    ///
+    /// let app = Route::new().nest_group(
+    ///     Route::group("/admin", |g| g.at("/users", users).at("/logs", logs))
+    ///         .with(SetHeader::new().overriding("X-Admin", "true")),
+    /// );
+    /// let cli = TestClient::new(app);
+    ///
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let resp = cli.get("/admin/users").send().await;
+    /// resp.assert_status_is_ok();
+    /// resp.assert_header("X-Admin", "true");
+    /// resp.assert_text("users").await;
+    /// # });
+    /// ```
+    pub fn group<F>(prefix: impl Into<String>, f: F) -> RouteGroup<Route>
+    where
+        F: FnOnce(Route) -> Route,
+    {
+        RouteGroup {
+            prefix: prefix.into(),
+            ep: f(Route::new()),
+        }
+    }
+
+    /// Add a [`RouteGroup`] to this route, the prefix of the group is
+    /// stripped.
+    ///
+    /// # Panics
+    ///
+    /// Panic when there are duplicates in the routing table.
+    #[must_use]
+    pub fn nest_group<E>(self, group: RouteGroup<E>) -> Self
+    where
+        E: IntoEndpoint,
+        E::Endpoint: 'static,
+    {
+        check_result(self.try_nest_group(group))
+    }
+
+    /// Attempts to add a [`RouteGroup`] to this route.
+    pub fn try_nest_group<E>(self, group: RouteGroup<E>) -> Result<Self, RouteError>
+    where
+        E: IntoEndpoint,
+        E::Endpoint: 'static,
+    {
+        self.try_nest(group.prefix, group.ep)
+    }
+
     fn internal_nest<E>(mut self, path: &str, ep: E, strip: bool) -> Result<Self, RouteError>
     where
         E: IntoEndpoint,
         E::Endpoint: 'static,
     {
-        let ep = Arc::new(ep.into_endpoint());
+        let ep = ep.into_endpoint();
         let mut path = path.to_string();
         if !path.ends_with('/') {
             path.push('/');
         }
 
+        if let Some(route) = (&ep as &dyn Any).downcast_ref::<Route>() {
+            let prefix = if strip { &path[..path.len() - 1] } else { "" };
+            for (name, pattern) in route.names.iter() {
+                if self.names.contains(name) {
+                    return Err(RouteError::DuplicateName(name.to_string()));
+                }
+                self.names
+                    .insert(name.to_string(), format!("{prefix}{pattern}").into());
+            }
+        }
+        let ep = Arc::new(ep);
+
         struct Nest<T> {
             inner: T,
             root: bool,
@@ -324,6 +543,32 @@ impl Route {
     }
 }
 
+/// A group of routes that share a common prefix, created by
+/// [`Route::group`].
+pub struct RouteGroup<E> {
+    prefix: String,
+    ep: E,
+}
+
+impl<E: IntoEndpoint> RouteGroup<E> {
+    /// Use middleware to transform all routes in this group.
+    #[must_use]
+    pub fn with<T>(self, middleware: T) -> RouteGroup<T::Output>
+    where
+        T: Middleware<E::Endpoint>,
+    {
+        RouteGroup {
+            prefix: self.prefix,
+            ep: middleware.transform(self.ep.into_endpoint()),
+        }
+    }
+
+    /// Returns the prefix of this group.
+    pub fn prefix(&self) -> &str {
+        &self.prefix
+    }
+}
+
 /// Container that can be used to obtain path pattern from the request.
 #[derive(Debug, Clone)]
 pub struct PathPattern(pub Arc<str>);
@@ -349,6 +594,9 @@ impl Endpoint for Route {
                     (None, None) => PathPattern(pattern),
                     (Some(parent), None) => PathPattern(format!("{}{}", parent.0, pattern).into()),
                 };
+                if !self.names.is_empty() && req.data::<RouteNames>().is_none() {
+                    req.set_data(self.names.clone());
+                }
                 req.set_data(pattern.clone());
 
                 let result = matches.data.data.call(req).await;
@@ -370,7 +618,10 @@ impl Endpoint for Route {
                     }
                 }
             }
-            None => Err(NotFoundError.into()),
+            None => match &self.fallback {
+                Some(fallback) => DynEndpoint::call(&**fallback, req).await,
+                None => Err(NotFoundError.into()),
+            },
         }
     }
 }
@@ -390,7 +641,9 @@ mod tests {
     use http::StatusCode;
 
     use super::*;
-    use crate::{endpoint::make_sync, handler, test::TestClient, Error};
+    use crate::{
+        endpoint::make_sync, handler, middleware::SetHeader, test::TestClient, web::Data, Error,
+    };
 
     #[test]
     fn test_normalize_path() {
@@ -442,6 +695,79 @@ mod tests {
         assert_eq!(get(&r, "/api/inner/c").await, "/c");
     }
 
+    #[tokio::test]
+    async fn group() {
+        let r = Route::new()
+            .at("/a", h)
+            .nest_group(Route::group("/admin", |g| g.at("/b", h).at("/c", h)))
+            .nest_group(
+                Route::group("/api", |g| g.at("/d", h))
+                    .with(SetHeader::new().overriding("X-Group", "api")),
+            );
+
+        assert_eq!(get(&r, "/a").await, "/a");
+        assert_eq!(get(&r, "/admin/b").await, "/b");
+        assert_eq!(get(&r, "/admin/c").await, "/c");
+        assert_eq!(get(&r, "/api/d").await, "/d");
+
+        let resp = r
+            .call(Request::builder().uri(Uri::from_static("/api/d")).finish())
+            .await
+            .unwrap();
+        assert_eq!(resp.headers().get("X-Group").unwrap(), "api");
+        let resp = r
+            .call(
+                Request::builder()
+                    .uri(Uri::from_static("/admin/b"))
+                    .finish(),
+            )
+            .await
+            .unwrap();
+        assert!(resp.headers().get("X-Group").is_none());
+    }
+
+    #[tokio::test]
+    async fn named() {
+        #[handler(internal)]
+        fn url_for(names: Data<&RouteNames>) -> String {
+            names.url_for("c", [("id", 1)]).unwrap()
+        }
+
+        let r = Route::new()
+            .at_named("a", "/a/:id", h)
+            .at("/b", url_for)
+            .nest(
+                "/api",
+                Route::new()
+                    .at_named("c", "/c/:id", h)
+                    .at("/url_for", url_for),
+            )
+            .nest_no_strip("/v2", Route::new().at_named("d", "/v2/d", h));
+
+        assert_eq!(r.url_for("a", [("id", "x y")]).unwrap(), "/a/x%20y");
+        assert_eq!(
+            r.url_for("a", None::<(&str, &str)>).unwrap_err(),
+            UrlForError::MissingParam("id".to_string())
+        );
+        assert_eq!(
+            r.url_for("e", [("id", 1)]).unwrap_err(),
+            UrlForError::UnknownRoute("e".to_string())
+        );
+        assert_eq!(r.url_for("c", [("id", 1)]).unwrap(), "/api/c/1");
+        assert_eq!(r.url_for("d", None::<(&str, &str)>).unwrap(), "/v2/d");
+        assert_eq!(get(&r, "/b").await, "/api/c/1");
+        assert_eq!(get(&r, "/api/url_for").await, "/api/c/1");
+        assert_eq!(get(&r, "/a/1").await, "/a/1");
+
+        assert_eq!(
+            Route::new()
+                .at_named("a", "/a", h)
+                .try_at_named("a", "/b", h)
+                .err(),
+            Some(RouteError::DuplicateName("a".to_string()))
+        );
+    }
+
     #[tokio::test]
     async fn nested_no_strip() {
         let r = Route::new().nest_no_strip(
@@ -497,6 +823,32 @@ mod tests {
         assert_eq!(get(&r, "/a?a=1").await, "/?a=1");
     }
 
+    #[tokio::test]
+    async fn nested_fallback() {
+        let r = Route::new()
+            .nest_fallback("/", make_sync(|req| format!("static {}", req.uri().path())))
+            .nest_fallback(
+                "/docs",
+                make_sync(|req| format!("docs {}", req.uri().path())),
+            )
+            .nest("/api", Route::new().at("/a", make_sync(|_| "api a")))
+            .at("/api2", make_sync(|_| "api2"));
+
+        assert_eq!(get(&r, "/api/a").await, "api a");
+        assert_eq!(get(&r, "/api2").await, "api2");
+        assert_eq!(get(&r, "/").await, "static /");
+        assert_eq!(get(&r, "/app/login").await, "static /app/login");
+        assert_eq!(get(&r, "/api2/a").await, "static /api2/a");
+        assert_eq!(get(&r, "/docs/a").await, "docs /a");
+
+        // the nested route handles all its paths
+        let cli = TestClient::new(r);
+        cli.get("/api/b")
+            .send()
+            .await
+            .assert_status(StatusCode::NOT_FOUND);
+    }
+
     #[test]
     #[should_panic]
     fn duplicate_1() {
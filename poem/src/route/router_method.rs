@@ -3,8 +3,10 @@ use std::future::Future;
 use futures_util::{future::Either, FutureExt};
 
 use crate::{
-    endpoint::BoxEndpoint, error::MethodNotAllowedError, http::Method, Endpoint, EndpointExt,
-    IntoEndpoint, Request, Response, Result,
+    endpoint::BoxEndpoint,
+    error::MethodNotAllowedError,
+    http::{header, Method},
+    Endpoint, EndpointExt, IntoEndpoint, Request, Response, Result,
 };
 
 /// Routing object for HTTP methods
@@ -184,6 +186,15 @@ impl Endpoint for RouteMethod {
                         async move {
                             req.set_method(Method::GET);
                             let mut resp = self.call(req).await?;
+                            // keep the `Content-Length` that the `GET` response would have
+                            if !resp.headers().contains_key(header::CONTENT_LENGTH) {
+                                if let Some(len) =
+                                    hyper::body::Body::size_hint(&resp.body().0).exact()
+                                {
+                                    resp.headers_mut()
+                                        .insert(header::CONTENT_LENGTH, len.into());
+                                }
+                            }
                             resp.set_body(());
                             Ok(resp)
                         }
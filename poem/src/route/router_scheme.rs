@@ -1,12 +1,15 @@
 use http::uri::Scheme;
 
 use crate::{
-    endpoint::BoxEndpoint, error::NotFoundError, Endpoint, EndpointExt, IntoEndpoint, Request,
-    Response,
+    endpoint::BoxEndpoint, error::NotFoundError, web::external_scheme, Endpoint, EndpointExt,
+    IntoEndpoint, Request, Response,
 };
 
 /// Routing object for request scheme
 ///
+/// If the peer is trusted by the [`TrustedProxies`](crate::web::TrustedProxies)
+/// in the request data, the scheme is taken from the forwarding headers.
+///
 /// # Errors
 ///
 /// - [`NotFoundError`]
@@ -76,10 +79,11 @@ impl Endpoint for RouteScheme {
     type Output = Response;
 
     async fn call(&self, req: Request) -> crate::Result<Self::Output> {
+        let req_scheme = external_scheme(&req);
         match self
             .schemes
             .iter()
-            .find(|(scheme, _)| scheme == req.scheme())
+            .find(|(scheme, _)| *scheme == req_scheme)
             .map(|(_, ep)| ep)
         {
             Some(ep) => ep.call(req).await,
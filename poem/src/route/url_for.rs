@@ -0,0 +1,209 @@
+use std::{collections::HashMap, sync::Arc};
+
+use percent_encoding::{utf8_percent_encode, AsciiSet, CONTROLS};
+
+use crate::error::UrlForError;
+
+const PATH: &AsciiSet = &CONTROLS
+    .add(b' ')
+    .add(b'"')
+    .add(b'#')
+    .add(b'%')
+    .add(b'<')
+    .add(b'>')
+    .add(b'?')
+    .add(b'`')
+    .add(b'{')
+    .add(b'}');
+
+const PATH_SEGMENT: &AsciiSet = &PATH.add(b'/');
+
+/// The named routes, used to build URLs from route names.
+///
+/// The outermost [`Route`](crate::Route) attaches the named routes to the
+/// request data, so they can be extracted with
+/// [`Data<&RouteNames>`](crate::web::Data). The names of the routes nested with
+/// [`Route::nest`](crate::Route::nest) are included with the prefix of the nest
+/// path, but the names of a route wrapped by a middleware before nesting are
+/// not visible to the parent.
+///
+/// # Example
+///
+/// ```
+/// use poem::{
+///     handler,
+///     test::TestClient,
+///     web::{Data, Path},
+///     Result, Route, RouteNames,
+/// };
+///
+/// #[handler]
+/// fn user(Path(id): Path<i32>) -> String {
+///     format!("user {id}")
+/// }
+///
+/// #[handler]
+/// fn index(names: Data<&RouteNames>) -> Result<String> {
+///     Ok(names.url_for("user", [("id", 42)])?)
+/// }
+///
+/// let app = Route::new()
+///     .at("/", index)
+///     .nest("/api", Route::new().at_named("user", "/users/:id", user));
+///
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// let resp = TestClient::new(app).get("/").send().await;
+/// resp.assert_status_is_ok();
+/// resp.assert_text("/api/users/42").await;
+/// # });
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct RouteNames(Arc<HashMap<String, Arc<str>>>);
+
+impl RouteNames {
+    /// Returns the path pattern of the specified route name.
+    pub fn pattern(&self, name: &str) -> Option<&str> {
+        self.0.get(name).map(AsRef::as_ref)
+    }
+
+    /// Builds the URL of the specified route name.
+    ///
+    /// All parameters in the path pattern must be supplied, and the values
+    /// are percent-encoded.
+    pub fn url_for<I, K, V>(&self, name: &str, params: I) -> Result<String, UrlForError>
+    where
+        I: IntoIterator<Item = (K, V)>,
+        K: AsRef<str>,
+        V: ToString,
+    {
+        let pattern = self
+            .pattern(name)
+            .ok_or_else(|| UrlForError::UnknownRoute(name.to_string()))?;
+        let params = params
+            .into_iter()
+            .map(|(name, value)| (name.as_ref().to_string(), value.to_string()))
+            .collect::<HashMap<_, _>>();
+        build_url(pattern, &params)
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub(crate) fn contains(&self, name: &str) -> bool {
+        self.0.contains_key(name)
+    }
+
+    pub(crate) fn insert(&mut self, name: String, pattern: Arc<str>) {
+        Arc::make_mut(&mut self.0).insert(name, pattern);
+    }
+
+    pub(crate) fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.0
+            .iter()
+            .map(|(name, pattern)| (name.as_str(), pattern.as_ref()))
+    }
+}
+
+fn build_url(pattern: &str, params: &HashMap<String, String>) -> Result<String, UrlForError> {
+    let get_param = |name: &str| {
+        params
+            .get(name)
+            .ok_or_else(|| UrlForError::MissingParam(name.to_string()))
+    };
+    let mut url = String::with_capacity(pattern.len());
+    let mut s = pattern;
+
+    while !s.is_empty() {
+        let idx = s.find([':', '*', '<']).unwrap_or(s.len());
+        url.push_str(&s[..idx]);
+        s = &s[idx..];
+
+        if let Some(rest) = s.strip_prefix(':') {
+            let end = rest.find(['/', '<', '*']).unwrap_or(rest.len());
+            let name = &rest[..end];
+            url.extend(utf8_percent_encode(get_param(name)?, PATH_SEGMENT));
+            s = &rest[end..];
+            if s.starts_with('<') {
+                s = skip_regex(s);
+            }
+        } else if let Some(name) = s.strip_prefix('*') {
+            if name.is_empty() {
+                return Err(UrlForError::UnnamedParam(pattern.to_string()));
+            }
+            url.extend(utf8_percent_encode(get_param(name)?, PATH));
+            s = "";
+        } else if s.starts_with('<') {
+            return Err(UrlForError::UnnamedParam(pattern.to_string()));
+        }
+    }
+
+    Ok(url)
+}
+
+fn skip_regex(s: &str) -> &str {
+    match s.find('>') {
+        Some(idx) => &s[idx + 1..],
+        None => "",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn params(params: &[(&str, &str)]) -> HashMap<String, String> {
+        params
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_build_url() {
+        assert_eq!(build_url("/a/b", &params(&[])).unwrap(), "/a/b");
+        assert_eq!(
+            build_url("/users/:id", &params(&[("id", "42")])).unwrap(),
+            "/users/42"
+        );
+        assert_eq!(
+            build_url(
+                "/users/:id/:name",
+                &params(&[("id", "1"), ("name", "a b/c")])
+            )
+            .unwrap(),
+            "/users/1/a%20b%2Fc"
+        );
+        assert_eq!(
+            build_url("/e/:name<\\d+>/x", &params(&[("name", "123")])).unwrap(),
+            "/e/123/x"
+        );
+        assert_eq!(
+            build_url("/files/*path", &params(&[("path", "a/b c")])).unwrap(),
+            "/files/a/b%20c"
+        );
+        assert_eq!(
+            build_url("/users/:id", &params(&[])).unwrap_err(),
+            UrlForError::MissingParam("id".to_string())
+        );
+        assert_eq!(
+            build_url("/d/<\\d+>", &params(&[])).unwrap_err(),
+            UrlForError::UnnamedParam("/d/<\\d+>".to_string())
+        );
+        assert_eq!(
+            build_url("/files/*", &params(&[])).unwrap_err(),
+            UrlForError::UnnamedParam("/files/*".to_string())
+        );
+    }
+
+    #[test]
+    fn test_url_for() {
+        let mut names = RouteNames::default();
+        names.insert("user".to_string(), "/users/:id".into());
+        assert_eq!(names.url_for("user", [("id", 42)]).unwrap(), "/users/42");
+        assert_eq!(
+            names.url_for("post", [("id", 42)]).unwrap_err(),
+            UrlForError::UnknownRoute("post".to_string())
+        );
+    }
+}
@@ -7,13 +7,13 @@ use std::{
     pin::Pin,
     sync::{
         atomic::{AtomicUsize, Ordering},
-        Arc,
+        Arc, OnceLock,
     },
     task::{Context, Poll},
 };
 
 use futures_util::FutureExt;
-use http::uri::Scheme;
+use http::{uri::Scheme, Extensions, HeaderMap, StatusCode};
 use hyper::body::Incoming;
 use hyper_util::server::conn::auto;
 use pin_project_lite::pin_project;
@@ -25,10 +25,11 @@ use tokio::{
 use tokio_util::sync::CancellationToken;
 
 use crate::{
+    connection_metrics::{ConnectionGuard, ConnectionMetrics},
     endpoint::{DynEndpoint, ToDynEndpoint},
     listener::{Acceptor, AcceptorExt, Listener},
-    web::{LocalAddr, RemoteAddr},
-    Endpoint, EndpointExt, IntoEndpoint, Response,
+    web::{LocalAddr, RemoteAddr, TlsInfo},
+    Endpoint, EndpointExt, IntoEndpoint, IntoResponse, Request, Response,
 };
 
 enum Either<L, A> {
@@ -36,7 +37,105 @@ enum Either<L, A> {
     Acceptor(A),
 }
 
+type BindCallback = Box<dyn FnOnce(&[LocalAddr]) + Send>;
+type ConnectionCallback = Arc<dyn Fn(&ConnectionInfo) + Send + Sync>;
+type TlsSlot = Arc<OnceLock<TlsInfo>>;
+
+tokio::task_local! {
+    /// The TLS session of the connection served by the current task, it is set
+    /// by the TLS listeners when the handshake completes.
+    pub(crate) static CONNECTION_TLS: TlsSlot;
+}
+
+/// The information of a connection accepted by the [`Server`], passed to the
+/// [`Server::on_connect`] and [`Server::on_disconnect`] callbacks.
+#[derive(Debug, Clone)]
+pub struct ConnectionInfo {
+    id: u64,
+    local_addr: LocalAddr,
+    remote_addr: RemoteAddr,
+    scheme: Scheme,
+    tls: TlsSlot,
+}
+
+impl ConnectionInfo {
+    /// Returns the identifier of the connection, which is unique for the
+    /// lifetime of the server.
+    #[inline]
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    /// Returns the local address of the connection.
+    #[inline]
+    pub fn local_addr(&self) -> &LocalAddr {
+        &self.local_addr
+    }
+
+    /// Returns the remote address of the connection.
+    #[inline]
+    pub fn remote_addr(&self) -> &RemoteAddr {
+        &self.remote_addr
+    }
+
+    /// Returns the scheme of the connection, `https` if the connection is
+    /// secured with TLS.
+    #[inline]
+    pub fn scheme(&self) -> &Scheme {
+        &self.scheme
+    }
+
+    /// Returns the TLS session of the connection, if it is accepted by one of
+    /// the TLS listeners of `poem`.
+    ///
+    /// The handshake is performed after the connection is accepted, so it is
+    /// always `None` in the [`Server::on_connect`] callback, and it is `None`
+    /// in the [`Server::on_disconnect`] callback if the handshake failed.
+    #[inline]
+    pub fn tls(&self) -> Option<&TlsInfo> {
+        self.tls.get()
+    }
+}
+
+/// Calls the `on_disconnect` callback when the connection task ends, even if
+/// it is cancelled or panics.
+struct DisconnectGuard {
+    info: ConnectionInfo,
+    callback: Option<ConnectionCallback>,
+}
+
+impl Drop for DisconnectGuard {
+    fn drop(&mut self) {
+        if let Some(callback) = &self.callback {
+            callback(&self.info);
+        }
+    }
+}
+
 /// An HTTP Server.
+///
+/// # Expect: 100-continue
+///
+/// If an HTTP/1.1 request contains `Expect: 100-continue`, the client waits
+/// for `100 Continue` before sending the body. The server sends it when the
+/// endpoint starts reading the body, so an endpoint can respond early,
+/// without reading the body, e.g. with `413 Payload Too Large` from the
+/// [`SizeLimit`](crate::middleware::SizeLimit) middleware or with
+/// `401 Unauthorized` from an extractor of the headers, and the client does
+/// not send the body at all.
+///
+/// # Metrics
+///
+/// When the `opentelemetry` feature is enabled, the following connection
+/// metrics are recorded with the global meter, labeled by the local address
+/// of the listener (`listener`):
+///
+/// - `poem_connections_accepted`: the accepted connection count.
+/// - `poem_connections_open`: the currently open connection count.
+/// - `poem_connection_requests`: the histogram of the request count per
+///   connection, recorded when the connection is closed.
+/// - `poem_tls_handshakes`: the TLS handshake count, labeled by `result`
+///   (`success` or `failure`).
 #[cfg_attr(docsrs, doc(cfg(feature = "server")))]
 pub struct Server<L, A> {
     listener: Either<L, A>,
@@ -45,6 +144,11 @@ pub struct Server<L, A> {
     http2_max_concurrent_streams: Option<u32>,
     http2_max_pending_accept_reset_streams: Option<u32>,
     http2_max_header_list_size: u32,
+    header_limits: HeaderLimits,
+    on_bind: Option<BindCallback>,
+    on_connect: Option<ConnectionCallback>,
+    on_disconnect: Option<ConnectionCallback>,
+    data: Extensions,
 }
 
 impl<L: Listener> Server<L, Infallible> {
@@ -57,6 +161,11 @@ impl<L: Listener> Server<L, Infallible> {
             http2_max_concurrent_streams: None,
             http2_max_pending_accept_reset_streams: Some(20),
             http2_max_header_list_size: 16384,
+            header_limits: HeaderLimits::default(),
+            on_bind: None,
+            on_connect: None,
+            on_disconnect: None,
+            data: Extensions::new(),
         }
     }
 }
@@ -71,6 +180,11 @@ impl<A: Acceptor> Server<Infallible, A> {
             http2_max_concurrent_streams: None,
             http2_max_pending_accept_reset_streams: Some(20),
             http2_max_header_list_size: 16384,
+            header_limits: HeaderLimits::default(),
+            on_bind: None,
+            on_connect: None,
+            on_disconnect: None,
+            data: Extensions::new(),
         }
     }
 }
@@ -135,6 +249,174 @@ where
         }
     }
 
+    /// Sets the maximum number of header fields of a request, the requests
+    /// with more header fields are rejected with `431 Request Header Fields
+    /// Too Large`.
+    ///
+    /// For HTTP/1 connections, a limit other than `100` is also passed to the
+    /// parser, which then allocates the parsed headers on the heap instead of
+    /// the stack.
+    ///
+    /// Default is `100`. Passing `None` will remove the limit, the HTTP/1
+    /// parser still rejects the requests with more than 100 header fields.
+    #[must_use]
+    pub fn max_header_count(self, max: impl Into<Option<usize>>) -> Self {
+        Self {
+            header_limits: HeaderLimits {
+                count: max.into(),
+                ..self.header_limits
+            },
+            ..self
+        }
+    }
+
+    /// Sets the maximum total size of the header fields of a request, the
+    /// sum of the lengths of the names and the values, the requests with
+    /// larger headers are rejected with `431 Request Header Fields Too
+    /// Large`.
+    ///
+    /// The limits are checked once the headers have been parsed, the memory
+    /// used while parsing them is bounded by the read buffer of the HTTP/1
+    /// connections, and by [`Server::http2_max_header_list_size`] for the
+    /// HTTP/2 connections.
+    ///
+    /// Default is `32768` bytes. Passing `None` will remove the limit.
+    #[must_use]
+    pub fn max_header_bytes(self, max: impl Into<Option<usize>>) -> Self {
+        Self {
+            header_limits: HeaderLimits {
+                bytes: max.into(),
+                ..self.header_limits
+            },
+            ..self
+        }
+    }
+
+    /// Specify a callback that is called with the local addresses once the
+    /// listener is bound, before the server starts accepting connections.
+    ///
+    /// This is useful to discover the port assigned by the operating system
+    /// when binding to port `0`. Alternatively, the listener can be bound
+    /// with [`Listener::into_acceptor`] and the acceptor passed to
+    /// [`Server::new_with_acceptor`] after reading
+    /// [`Acceptor::local_addr`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use poem::{listener::TcpListener, Server};
+    ///
+    /// let server = Server::new(TcpListener::bind("127.0.0.1:0")).on_bind(|addrs| {
+    ///     for addr in addrs {
+    ///         println!("listening on {addr}");
+    ///     }
+    /// });
+    /// ```
+    #[must_use]
+    pub fn on_bind(self, f: impl FnOnce(&[LocalAddr]) + Send + 'static) -> Self {
+        Self {
+            on_bind: Some(Box::new(f)),
+            ..self
+        }
+    }
+
+    /// Specify a callback that is called when a connection is accepted,
+    /// before any request of the connection is served.
+    ///
+    /// Unlike a middleware, it is called once per connection, regardless of
+    /// the number of requests sent with the connection, e.g. to track the
+    /// connections of each peer for abuse detection. It is called in the task
+    /// of the connection, so it does not delay the other connections, but it
+    /// should not block.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use poem::{listener::TcpListener, Server};
+    ///
+    /// let server = Server::new(TcpListener::bind("127.0.0.1:0"))
+    ///     .on_connect(|conn| {
+    ///         println!("#{} connected from {}", conn.id(), conn.remote_addr());
+    ///     })
+    ///     .on_disconnect(|conn| {
+    ///         println!("#{} disconnected", conn.id());
+    ///     });
+    /// ```
+    #[must_use]
+    pub fn on_connect(self, f: impl Fn(&ConnectionInfo) + Send + Sync + 'static) -> Self {
+        Self {
+            on_connect: Some(Arc::new(f)),
+            ..self
+        }
+    }
+
+    /// Specify a callback that is called when a connection is closed, after
+    /// all the requests of the connection have been served.
+    ///
+    /// It is called exactly once for each connection passed to
+    /// [`Server::on_connect`], including the connections closed by the
+    /// graceful shutdown timeout.
+    #[must_use]
+    pub fn on_disconnect(self, f: impl Fn(&ConnectionInfo) + Send + Sync + 'static) -> Self {
+        Self {
+            on_disconnect: Some(Arc::new(f)),
+            ..self
+        }
+    }
+
+    /// Adds a value to the extensions of every request received by this
+    /// server, it can be extracted with [`Data`](crate::web::Data).
+    ///
+    /// The value is added before the request reaches the endpoint, so it is
+    /// visible to every nested endpoint, including the fallback endpoints and
+    /// the `404 Not Found` handlers of [`Route`](crate::Route), regardless of
+    /// which endpoints are wrapped with [`EndpointExt::data`]. A value of the
+    /// same type added by [`EndpointExt::data`] or
+    /// [`AddData`](crate::middleware::AddData) overrides it for the wrapped
+    /// endpoint.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use poem::{handler, listener::TcpListener, web::Data, Route, Server};
+    ///
+    /// #[derive(Clone)]
+    /// struct AppState {
+    ///     name: String,
+    /// }
+    ///
+    /// #[handler]
+    /// fn index(state: Data<&AppState>) -> String {
+    ///     format!("hello from {}", state.name)
+    /// }
+    ///
+    /// let app = Route::new().at("/", index);
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let (tx, rx) = tokio::sync::oneshot::channel();
+    /// let server = Server::new(TcpListener::bind("127.0.0.1:0"))
+    ///     .data(AppState {
+    ///         name: "poem".to_string(),
+    ///     })
+    ///     .on_bind(move |addrs| {
+    ///         let _ = tx.send(addrs[0].as_socket_addr().cloned().unwrap());
+    ///     });
+    /// let handle = tokio::spawn(server.run(app));
+    ///
+    /// # use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    /// # let mut stream = tokio::net::TcpStream::connect(rx.await.unwrap()).await.unwrap();
+    /// # stream.write_all(b"GET / HTTP/1.1\r\nhost: localhost\r\nconnection: close\r\n\r\n").await.unwrap();
+    /// # let mut resp = String::new();
+    /// # stream.read_to_string(&mut resp).await.unwrap();
+    /// assert!(resp.ends_with("hello from poem"));
+    /// # handle.abort();
+    /// # });
+    /// ```
+    #[must_use]
+    pub fn data<T: Clone + Send + Sync + 'static>(mut self, data: T) -> Self {
+        self.data.insert(data);
+        self
+    }
+
     /// Run this server.
     pub async fn run<E>(self, ep: E) -> IoResult<()>
     where
@@ -164,9 +446,17 @@ where
             http2_max_concurrent_streams,
             http2_max_pending_accept_reset_streams,
             http2_max_header_list_size,
+            header_limits,
+            on_bind,
+            on_connect,
+            on_disconnect,
+            data,
         } = self;
+        let data = Arc::new(data);
         let name = name.as_deref();
         let alive_connections = Arc::new(AtomicUsize::new(0));
+        let mut next_connection_id = 0u64;
+        let connection_metrics = ConnectionMetrics::new();
         let notify = Arc::new(Notify::new());
         let timeout_token = CancellationToken::new();
         let server_graceful_shutdown_token = CancellationToken::new();
@@ -178,9 +468,13 @@ where
 
         tokio::pin!(signal);
 
-        for addr in acceptor.local_addr() {
+        let local_addrs = acceptor.local_addr();
+        for addr in &local_addrs {
             tracing::info!(name = name, addr = %addr, "listening");
         }
+        if let Some(on_bind) = on_bind {
+            on_bind(&local_addrs);
+        }
         tracing::info!(name = name, "server started");
 
         loop {
@@ -207,8 +501,21 @@ where
                 res = acceptor.accept() => {
                     if let Ok((socket, local_addr, remote_addr, scheme)) = res {
                         alive_connections.fetch_add(1, Ordering::Release);
+                        let connection = Arc::new(connection_metrics.accepted(&local_addr));
+                        let tls = TlsSlot::default();
+                        let info = ConnectionInfo {
+                            id: next_connection_id,
+                            local_addr: local_addr.clone(),
+                            remote_addr: remote_addr.clone(),
+                            scheme: scheme.clone(),
+                            tls: tls.clone(),
+                        };
+                        next_connection_id += 1;
+                        let on_connect = on_connect.clone();
+                        let on_disconnect = on_disconnect.clone();
 
                         let ep = ep.clone();
+                        let data = data.clone();
                         let alive_connections = alive_connections.clone();
                         let notify = notify.clone();
                         let timeout_token = timeout_token.clone();
@@ -216,19 +523,34 @@ where
                         let server_graceful_shutdown_token_clone = server_graceful_shutdown_token.clone();
 
                         let spawn_fut = AssertUnwindSafe(async move {
+                            if let Some(on_connect) = &on_connect {
+                                on_connect(&info);
+                            }
+                            let _disconnect_guard = DisconnectGuard {
+                                info,
+                                callback: on_disconnect,
+                            };
+
                             let serve_connection = serve_connection(ConnectionOptions{
                                 socket,
                                 local_addr,
                                 remote_addr,
                                 scheme,
+                                tls: tls.clone(),
                                 ep,
+                                data,
+                                connection,
                                 server_graceful_shutdown_token: server_graceful_shutdown_token.clone(),
                                 idle_connection_close_timeout: idle_timeout,
                                 http2_max_concurrent_streams,
                                 http2_max_pending_accept_reset_streams,
                                 http2_max_header_list_size,
+                                header_limits,
                             });
 
+                            // the handshake of the TLS listeners runs when the
+                            // connection is first read
+                            let serve_connection = CONNECTION_TLS.scope(tls, serve_connection);
                             if timeout.is_some() {
                                 tokio::select! {
                                     _ = serve_connection => {}
@@ -386,12 +708,50 @@ struct ConnectionOptions<Io> {
     local_addr: LocalAddr,
     remote_addr: RemoteAddr,
     scheme: Scheme,
+    tls: TlsSlot,
     ep: Arc<dyn DynEndpoint<Output = Response>>,
+    data: Arc<Extensions>,
+    connection: Arc<ConnectionGuard>,
     server_graceful_shutdown_token: CancellationToken,
     idle_connection_close_timeout: Option<Duration>,
     http2_max_concurrent_streams: Option<u32>,
     http2_max_pending_accept_reset_streams: Option<u32>,
     http2_max_header_list_size: u32,
+    header_limits: HeaderLimits,
+}
+
+#[derive(Debug, Copy, Clone)]
+struct HeaderLimits {
+    count: Option<usize>,
+    bytes: Option<usize>,
+}
+
+/// The default maximum number of header fields, which is also the limit of
+/// the HTTP/1 parser.
+const DEFAULT_MAX_HEADER_COUNT: usize = 100;
+
+impl Default for HeaderLimits {
+    fn default() -> Self {
+        Self {
+            count: Some(DEFAULT_MAX_HEADER_COUNT),
+            bytes: Some(32768),
+        }
+    }
+}
+
+impl HeaderLimits {
+    fn is_exceeded(&self, headers: &HeaderMap) -> bool {
+        if self.count.is_some_and(|count| headers.len() > count) {
+            return true;
+        }
+        self.bytes.is_some_and(|bytes| {
+            let size: usize = headers
+                .iter()
+                .map(|(name, value)| name.as_str().len() + value.len())
+                .sum();
+            size > bytes
+        })
+    }
 }
 
 async fn serve_connection<Io>(opts: ConnectionOptions<Io>)
@@ -403,12 +763,16 @@ where
         local_addr,
         remote_addr,
         scheme,
+        tls,
         ep,
+        data,
+        connection,
         server_graceful_shutdown_token,
         idle_connection_close_timeout,
         http2_max_concurrent_streams,
         http2_max_pending_accept_reset_streams,
         http2_max_header_list_size,
+        header_limits,
     } = opts;
 
     let connection_shutdown_token = CancellationToken::new();
@@ -417,16 +781,24 @@ where
         let remote_addr = remote_addr.clone();
 
         move |req: http::Request<Incoming>| {
+            connection.request_received();
             let ep = ep.clone();
-            let local_addr = local_addr.clone();
-            let remote_addr = remote_addr.clone();
-            let scheme = scheme.clone();
+            let too_large = header_limits.is_exceeded(req.headers());
+            let mut req: Request =
+                (req, local_addr.clone(), remote_addr.clone(), scheme.clone()).into();
+            if !data.is_empty() {
+                req.extensions_mut().extend((*data).clone());
+            }
+            if let Some(tls) = tls.get() {
+                req.extensions_mut().insert(tls.clone());
+            }
             async move {
-                Ok::<http::Response<_>, Infallible>(
-                    ep.get_response((req, local_addr, remote_addr, scheme).into())
-                        .await
-                        .into(),
-                )
+                let resp = if too_large {
+                    StatusCode::REQUEST_HEADER_FIELDS_TOO_LARGE.into_response()
+                } else {
+                    ep.get_response(req).await
+                };
+                Ok::<http::Response<_>, Infallible>(resp.into())
             }
         }
     });
@@ -448,6 +820,12 @@ where
     };
 
     let mut builder = auto::Builder::new(hyper_util::rt::TokioExecutor::new());
+    if let Some(count) = header_limits
+        .count
+        .filter(|count| *count != DEFAULT_MAX_HEADER_COUNT)
+    {
+        builder.http1().max_headers(count);
+    }
     let mut builder = builder.http2();
     let builder = builder
         .max_concurrent_streams(http2_max_concurrent_streams)
@@ -476,3 +854,298 @@ where
     // requests.
     let _ = conn.await;
 }
+
+#[cfg(test)]
+mod tests {
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::TcpStream,
+    };
+
+    use super::*;
+    use crate::{endpoint::make, listener::TcpListener, middleware::SizeLimit, Route};
+
+    async fn read_head(stream: &mut TcpStream) -> String {
+        let mut head = Vec::new();
+        while !head.ends_with(b"\r\n\r\n") {
+            let mut byte = [0u8];
+            stream.read_exact(&mut byte).await.unwrap();
+            head.push(byte[0]);
+        }
+        String::from_utf8(head).unwrap()
+    }
+
+    #[tokio::test]
+    async fn expect_continue() {
+        let acceptor = TcpListener::bind("127.0.0.1:0")
+            .into_acceptor()
+            .await
+            .unwrap();
+        let addr = acceptor
+            .local_addr()
+            .remove(0)
+            .as_socket_addr()
+            .cloned()
+            .unwrap();
+        let app = Route::new()
+            .at(
+                "/echo",
+                make(|req: Request| async move { req.into_body().into_vec().await }),
+            )
+            .at(
+                "/limited",
+                make(|req: Request| async move { req.into_body().into_vec().await })
+                    .with(SizeLimit::new(4)),
+            );
+        let handle = tokio::spawn(async move {
+            let _ = Server::new_with_acceptor(acceptor).run(app).await;
+        });
+
+        // the server sends `100 Continue` when the endpoint reads the body
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        stream
+            .write_all(
+                b"POST /echo HTTP/1.1\r\nhost: localhost\r\ncontent-length: 5\r\nexpect: 100-continue\r\n\r\n",
+            )
+            .await
+            .unwrap();
+        assert!(read_head(&mut stream)
+            .await
+            .starts_with("HTTP/1.1 100 Continue"));
+        stream.write_all(b"hello").await.unwrap();
+        let head = read_head(&mut stream).await;
+        assert!(head.starts_with("HTTP/1.1 200 OK"), "{head}");
+        let mut body = [0u8; 5];
+        stream.read_exact(&mut body).await.unwrap();
+        assert_eq!(&body, b"hello");
+
+        // the endpoint responds early without reading the body
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        stream
+            .write_all(
+                b"POST /limited HTTP/1.1\r\nhost: localhost\r\ncontent-length: 5\r\nexpect: 100-continue\r\n\r\n",
+            )
+            .await
+            .unwrap();
+        let head = read_head(&mut stream).await;
+        assert!(head.starts_with("HTTP/1.1 413 Payload Too Large"), "{head}");
+
+        handle.abort();
+    }
+
+    #[tokio::test]
+    async fn body_on_finish() {
+        let acceptor = TcpListener::bind("127.0.0.1:0")
+            .into_acceptor()
+            .await
+            .unwrap();
+        let addr = acceptor
+            .local_addr()
+            .remove(0)
+            .as_socket_addr()
+            .cloned()
+            .unwrap();
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        let app = make(move |_| {
+            let tx = tx.clone();
+            async move {
+                crate::Body::from("hello").on_finish(move |outcome| {
+                    let _ = tx.send(outcome);
+                })
+            }
+        });
+        let handle = tokio::spawn(async move {
+            let _ = Server::new_with_acceptor(acceptor).run(app).await;
+        });
+
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        stream
+            .write_all(b"GET / HTTP/1.1\r\nhost: localhost\r\n\r\n")
+            .await
+            .unwrap();
+        let head = read_head(&mut stream).await;
+        assert!(head.starts_with("HTTP/1.1 200 OK"), "{head}");
+        let mut body = [0u8; 5];
+        stream.read_exact(&mut body).await.unwrap();
+        assert_eq!(&body, b"hello");
+        assert_eq!(
+            rx.recv().await.unwrap(),
+            crate::BodyOutcome {
+                bytes: 5,
+                completed: true
+            }
+        );
+
+        handle.abort();
+    }
+
+    #[tokio::test]
+    async fn header_limits() {
+        let acceptor = TcpListener::bind("127.0.0.1:0")
+            .into_acceptor()
+            .await
+            .unwrap();
+        let addr = acceptor
+            .local_addr()
+            .remove(0)
+            .as_socket_addr()
+            .cloned()
+            .unwrap();
+        let handle = tokio::spawn(async move {
+            let _ = Server::new_with_acceptor(acceptor)
+                .max_header_count(150)
+                .max_header_bytes(1024)
+                .run(make(|_| async { "ok" }))
+                .await;
+        });
+
+        async fn send(addr: std::net::SocketAddr, headers: &str) -> String {
+            let mut stream = TcpStream::connect(addr).await.unwrap();
+            stream
+                .write_all(format!("GET / HTTP/1.1\r\nhost: localhost\r\n{headers}\r\n").as_bytes())
+                .await
+                .unwrap();
+            read_head(&mut stream).await
+        }
+
+        // more than the 100 header fields of the default HTTP/1 parser
+        let headers = (0..120)
+            .map(|i| format!("x-{i}: 1\r\n"))
+            .collect::<String>();
+        let head = send(addr, &headers).await;
+        assert!(head.starts_with("HTTP/1.1 200 OK"), "{head}");
+
+        let headers = (0..160)
+            .map(|i| format!("x-{i}: 1\r\n"))
+            .collect::<String>();
+        let head = send(addr, &headers).await;
+        assert!(
+            head.starts_with("HTTP/1.1 431 Request Header Fields Too Large"),
+            "{head}"
+        );
+
+        let head = send(addr, &format!("x-large: {}\r\n", "a".repeat(2048))).await;
+        assert!(
+            head.starts_with("HTTP/1.1 431 Request Header Fields Too Large"),
+            "{head}"
+        );
+
+        handle.abort();
+    }
+
+    async fn get(addr: std::net::SocketAddr, path: &str) -> String {
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        stream
+            .write_all(
+                format!("GET {path} HTTP/1.1\r\nhost: localhost\r\nconnection: close\r\n\r\n")
+                    .as_bytes(),
+            )
+            .await
+            .unwrap();
+        let mut resp = String::new();
+        stream.read_to_string(&mut resp).await.unwrap();
+        resp
+    }
+
+    #[tokio::test]
+    async fn on_bind() {
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        let handle = tokio::spawn(
+            Server::new(TcpListener::bind("127.0.0.1:0"))
+                .on_bind(move |addrs| {
+                    let _ = tx.send(addrs.to_vec());
+                })
+                .run(make(|_| async { "hello" })),
+        );
+
+        let addrs = rx.await.unwrap();
+        assert_eq!(addrs.len(), 1);
+        let addr = *addrs[0].as_socket_addr().unwrap();
+        assert_ne!(addr.port(), 0);
+        assert!(get(addr, "/").await.ends_with("hello"));
+
+        handle.abort();
+    }
+
+    #[tokio::test]
+    async fn server_data() {
+        #[derive(Clone)]
+        struct Name(&'static str);
+
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        let name = |req: Request| async move { req.data::<Name>().unwrap().0 };
+        let app = Route::new()
+            .at("/server", make(name))
+            .at("/route", make(name).data(Name("route")));
+        let handle = tokio::spawn(
+            Server::new(TcpListener::bind("127.0.0.1:0"))
+                .data(Name("server"))
+                .on_bind(move |addrs| {
+                    let _ = tx.send(*addrs[0].as_socket_addr().unwrap());
+                })
+                .run(app),
+        );
+
+        let addr = rx.await.unwrap();
+        assert!(get(addr, "/server").await.ends_with("server"));
+        // the data added to the endpoint overrides the server data
+        assert!(get(addr, "/route").await.ends_with("route"));
+
+        handle.abort();
+    }
+
+    #[tokio::test]
+    async fn connection_callbacks() {
+        let acceptor = TcpListener::bind("127.0.0.1:0")
+            .into_acceptor()
+            .await
+            .unwrap();
+        let addr = acceptor
+            .local_addr()
+            .remove(0)
+            .as_socket_addr()
+            .cloned()
+            .unwrap();
+        let events = Arc::new(parking_lot::Mutex::new(Vec::new()));
+        let server = Server::new_with_acceptor(acceptor)
+            .on_connect({
+                let events = events.clone();
+                move |conn| events.lock().push(format!("connect {}", conn.id()))
+            })
+            .on_disconnect({
+                let events = events.clone();
+                move |conn| events.lock().push(format!("disconnect {}", conn.id()))
+            });
+        let handle = tokio::spawn(async move {
+            let _ = server.run(make(|_| async { "hello" })).await;
+        });
+
+        for _ in 0..2 {
+            let mut stream = TcpStream::connect(addr).await.unwrap();
+            for _ in 0..2 {
+                stream
+                    .write_all(b"GET / HTTP/1.1\r\nhost: localhost\r\n\r\n")
+                    .await
+                    .unwrap();
+                let head = read_head(&mut stream).await;
+                assert!(head.starts_with("HTTP/1.1 200 OK"), "{head}");
+                let mut body = [0u8; 5];
+                stream.read_exact(&mut body).await.unwrap();
+            }
+            drop(stream);
+
+            for _ in 0..100 {
+                if events.lock().len() % 2 == 0 {
+                    break;
+                }
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        }
+
+        assert_eq!(
+            *events.lock(),
+            vec!["connect 0", "disconnect 0", "connect 1", "disconnect 1"]
+        );
+        handle.abort();
+    }
+}
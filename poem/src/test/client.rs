@@ -1,4 +1,6 @@
-use http::{header, header::HeaderName, HeaderMap, HeaderValue, Method};
+use std::time::Duration;
+
+use http::{header, header::HeaderName, Extensions, HeaderMap, HeaderValue, Method};
 
 use crate::{test::TestRequestBuilder, Endpoint, IntoEndpoint};
 
@@ -17,6 +19,8 @@ macro_rules! impl_methods {
 pub struct TestClient<E> {
     pub(crate) ep: E,
     pub(crate) default_headers: HeaderMap,
+    pub(crate) default_extensions: Extensions,
+    pub(crate) timeout: Option<Duration>,
 }
 
 impl<E: Endpoint> TestClient<E> {
@@ -28,6 +32,8 @@ impl<E: Endpoint> TestClient<E> {
         TestClient {
             ep: ep.into_endpoint(),
             default_headers: Default::default(),
+            default_extensions: Default::default(),
+            timeout: None,
         }
     }
 
@@ -123,6 +129,84 @@ impl<E: Endpoint> TestClient<E> {
         self.default_header(header::CONTENT_TYPE, content_type.as_ref())
     }
 
+    /// Sets the default extension data for each requests, such as the values
+    /// which are added by the middlewares in front of the endpoint, overridden
+    /// by [`TestRequestBuilder::data`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use poem::{handler, test::TestClient, web::Data, Route};
+    ///
+    /// #[derive(Clone)]
+    /// struct User {
+    ///     name: String,
+    /// }
+    ///
+    /// #[handler]
+    /// fn index(Data(user): Data<&User>) -> String {
+    ///     format!("hello, {}", user.name)
+    /// }
+    ///
+    /// let app = Route::new().at("/", index);
+    /// let cli = TestClient::new(app).default_data(User {
+    ///     name: "sunli".to_string(),
+    /// });
+    ///
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let resp = cli.get("/").send().await;
+    /// resp.assert_status_is_ok();
+    /// resp.assert_text("hello, sunli").await;
+    /// # });
+    /// ```
+    #[must_use]
+    pub fn default_data<T>(mut self, data: T) -> Self
+    where
+        T: Clone + Send + Sync + 'static,
+    {
+        self.default_extensions.insert(data);
+        self
+    }
+
+    /// Sets the default timeout for each requests.
+    ///
+    /// The timeout covers both getting the response and reading its body, so
+    /// a test that reads an endless stream, such as an SSE response with
+    /// keep-alive, panics when the time is up instead of hanging.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::time::Duration;
+    ///
+    /// use futures_util::{stream, StreamExt};
+    /// use poem::{
+    ///     handler,
+    ///     test::TestClient,
+    ///     web::sse::{Event, SSE},
+    /// };
+    ///
+    /// #[handler]
+    /// fn index() -> SSE {
+    ///     SSE::new(stream::repeat_with(|| Event::message("ping")))
+    /// }
+    ///
+    /// let cli = TestClient::new(index).timeout(Duration::from_secs(5));
+    ///
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let resp = cli.get("/").send().await;
+    /// let events = resp.sse_stream().take(3).collect::<Vec<_>>().await;
+    /// assert_eq!(events.len(), 3);
+    /// # });
+    /// ```
+    #[must_use]
+    pub fn timeout(self, timeout: Duration) -> Self {
+        Self {
+            timeout: Some(timeout),
+            ..self
+        }
+    }
+
     /// Create a [`TestRequestBuilder`].
     pub fn request(&self, method: Method, uri: impl Into<String>) -> TestRequestBuilder<'_, E> {
         TestRequestBuilder::new(self, method, uri.into())
@@ -1,3 +1,6 @@
+use std::{future::Future, task::Poll, time::Duration};
+
+use futures_util::StreamExt;
 use headers::{Header, HeaderMapExt};
 use http::{header, header::HeaderName, Extensions, HeaderMap, HeaderValue, Method};
 use serde::Serialize;
@@ -17,6 +20,7 @@ pub struct TestRequestBuilder<'a, E> {
     headers: HeaderMap,
     body: Body,
     extensions: Extensions,
+    timeout: Option<Duration>,
 }
 
 impl<'a, E> TestRequestBuilder<'a, E> {
@@ -29,6 +33,7 @@ impl<'a, E> TestRequestBuilder<'a, E> {
             headers: Default::default(),
             body: Body::empty(),
             extensions: Default::default(),
+            timeout: cli.timeout,
         }
     }
 
@@ -170,13 +175,18 @@ impl<'a, E> TestRequestBuilder<'a, E> {
             .finish();
         req.headers_mut().extend(self.cli.default_headers.clone());
         req.headers_mut().extend(self.headers);
-        *req.extensions_mut() = self.extensions;
+        *req.extensions_mut() = self.cli.default_extensions.clone();
+        req.extensions_mut().extend(self.extensions);
         req.set_body(self.body);
 
         req
     }
 
-    /// Sets the extension data for this request.
+    /// Sets the extension data for this request, to simulate the values which
+    /// are added by the middlewares in front of the endpoint, such as an
+    /// authenticated user.
+    ///
+    /// See also [`TestClient::default_data`].
     ///
     /// # Example
     ///
@@ -206,14 +216,112 @@ impl<'a, E> TestRequestBuilder<'a, E> {
         self
     }
 
+    /// Sets the timeout for this request, overrides the timeout of the
+    /// client.
+    ///
+    /// See also [`TestClient::timeout`].
+    #[must_use]
+    pub fn timeout(self, timeout: Duration) -> Self {
+        Self {
+            timeout: Some(timeout),
+            ..self
+        }
+    }
+
     /// Send this request to endpoint to get the response.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the timeout is set, and the response or its body is not
+    /// completed in time.
     pub async fn send(self) -> TestResponse
     where
         E: Endpoint,
     {
         let ep = &self.cli.ep;
+        let timeout = self.timeout;
         let req = self.make_request();
-        let resp = ep.get_response(req).await;
+
+        let Some(timeout) = timeout else {
+            return TestResponse::new(ep.get_response(req).await);
+        };
+
+        let deadline = tokio::time::Instant::now() + timeout;
+        let mut resp = tokio::time::timeout_at(deadline, ep.get_response(req))
+            .await
+            .unwrap_or_else(|_| panic!("request timed out after {timeout:?}"));
+        let mut body = resp.take_body().into_bytes_stream().boxed();
+        let mut sleep = Box::pin(tokio::time::sleep_until(deadline));
+        resp.set_body(Body::from_bytes_stream(futures_util::stream::poll_fn(
+            move |cx| {
+                if let Poll::Ready(item) = body.poll_next_unpin(cx) {
+                    return Poll::Ready(item);
+                }
+                if sleep.as_mut().poll(cx).is_ready() {
+                    panic!("reading the response body timed out after {timeout:?}");
+                }
+                Poll::Pending
+            },
+        )));
         TestResponse::new(resp)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::{
+        endpoint::make, handler, middleware::SetHeader, test::TestClient, web::Data, Body,
+        EndpointExt,
+    };
+
+    #[tokio::test]
+    #[should_panic(expected = "request timed out")]
+    async fn timeout() {
+        let cli = TestClient::new(make(|_| async {
+            tokio::time::sleep(Duration::from_secs(10)).await;
+        }));
+        cli.get("/").timeout(Duration::from_millis(10)).send().await;
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "reading the response body timed out")]
+    async fn timeout_body() {
+        #[handler(internal)]
+        fn index() -> Body {
+            Body::from_bytes_stream(futures_util::stream::pending::<
+                Result<bytes::Bytes, std::io::Error>,
+            >())
+        }
+
+        let cli = TestClient::new(index).timeout(Duration::from_millis(10));
+        let resp = cli.get("/").send().await;
+        resp.assert_status_is_ok();
+        resp.0.into_body().into_bytes().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn data() {
+        #[handler(internal)]
+        fn index(Data(user): Data<&String>, Data(id): Data<&i32>) -> String {
+            format!("{user}:{id}")
+        }
+
+        let cli = TestClient::new(index.with(SetHeader::new().overriding("x-mw", "1")))
+            .default_data("sunli".to_string())
+            .default_data(1i32);
+
+        let resp = cli.get("/").send().await;
+        resp.assert_header("x-mw", "1");
+        resp.assert_text("sunli:1").await;
+
+        // the data of the request overrides the default data
+        cli.get("/")
+            .data(2i32)
+            .send()
+            .await
+            .assert_text("sunli:2")
+            .await;
+    }
+}
@@ -0,0 +1,171 @@
+use futures_util::FutureExt;
+
+use crate::{error::AnyOfError, FromRequest, Request, RequestBody, Result};
+
+/// The tuples of extractors accepted by [`AnyOf`].
+pub trait AnyOfExtractors {
+    /// A tuple with an `Option` of each extractor.
+    type Output;
+}
+
+/// An extractor that tries several extractors in order, and succeeds with the
+/// first one that succeeds, e.g. to accept either a session cookie or a
+/// bearer token.
+///
+/// It contains a tuple with an `Option` of each extractor, only the first
+/// extractor that succeeded is `Some`. If all the extractors fail, an
+/// [`AnyOfError`] with the error of each extractor is returned, which
+/// responds with `401 Unauthorized`.
+///
+/// The extractors that read the body should be placed last, because the body
+/// can only be read once.
+///
+/// # Errors
+///
+/// - [`AnyOfError`]
+///
+/// # Example
+///
+/// ```
+/// use poem::{
+///     handler,
+///     http::StatusCode,
+///     test::TestClient,
+///     web::{
+///         headers::{authorization::Bearer, Authorization},
+///         AnyOf, TypedHeader,
+///     },
+///     Error, FromRequest, Request, RequestBody, Result,
+/// };
+///
+/// struct Session(String);
+///
+/// impl<'a> FromRequest<'a> for Session {
+///     async fn from_request(req: &'a Request, _body: &mut RequestBody) -> Result<Self> {
+///         req.header("x-session")
+///             .map(|id| Session(id.to_string()))
+///             .ok_or_else(|| Error::from_string("missing session", StatusCode::UNAUTHORIZED))
+///     }
+/// }
+///
+/// #[handler]
+/// fn index(auth: AnyOf<(Session, TypedHeader<Authorization<Bearer>>)>) -> String {
+///     match auth.0 {
+///         (Some(session), _) => format!("session: {}", session.0),
+///         (_, Some(bearer)) => format!("token: {}", bearer.token()),
+///         _ => unreachable!(),
+///     }
+/// }
+///
+/// let cli = TestClient::new(index);
+///
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// cli.get("/")
+///     .header("x-session", "abc")
+///     .send()
+///     .await
+///     .assert_text("session: abc")
+///     .await;
+///
+/// cli.get("/")
+///     .header("authorization", "Bearer xyz")
+///     .send()
+///     .await
+///     .assert_text("token: xyz")
+///     .await;
+///
+/// cli.get("/")
+///     .send()
+///     .await
+///     .assert_status(StatusCode::UNAUTHORIZED);
+/// # });
+/// ```
+pub struct AnyOf<T: AnyOfExtractors>(pub T::Output);
+
+macro_rules! impl_any_of {
+    ($tuple:ty; $($ty:ident => $idx:tt),*) => {
+        impl<$($ty),*> AnyOfExtractors for ($($ty,)*) {
+            type Output = ($(Option<$ty>,)*);
+        }
+
+        impl<'a, $($ty),*> FromRequest<'a> for AnyOf<($($ty,)*)>
+        where
+            $($ty: FromRequest<'a> + Send,)*
+        {
+            async fn from_request(req: &'a Request, body: &mut RequestBody) -> Result<Self> {
+                let mut errors = Vec::new();
+                $(
+                // FIXME: remove the unnecessary boxed
+                // https://github.com/rust-lang/rust/issues/100013
+                match <$ty as FromRequest<'a>>::from_request(req, body).boxed().await {
+                    Ok(value) => {
+                        let mut output: <$tuple as AnyOfExtractors>::Output = Default::default();
+                        output.$idx = Some(value);
+                        return Ok(AnyOf(output));
+                    }
+                    Err(err) => errors.push(err),
+                }
+                )*
+                Err(AnyOfError(errors).into())
+            }
+        }
+    };
+}
+
+impl_any_of!((A, B); A => 0, B => 1);
+impl_any_of!((A, B, C); A => 0, B => 1, C => 2);
+impl_any_of!((A, B, C, D); A => 0, B => 1, C => 2, D => 3);
+impl_any_of!((A, B, C, D, E); A => 0, B => 1, C => 2, D => 3, E => 4);
+impl_any_of!((A, B, C, D, E, F); A => 0, B => 1, C => 2, D => 3, E => 4, F => 5);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        handler,
+        http::StatusCode,
+        test::TestClient,
+        web::{Json, Query},
+    };
+
+    #[derive(serde::Deserialize)]
+    struct Token {
+        token: String,
+    }
+
+    #[tokio::test]
+    async fn any_of() {
+        #[handler(internal)]
+        fn index(auth: AnyOf<(Query<Token>, Json<Token>)>) -> String {
+            match auth.0 {
+                (Some(query), None) => format!("query: {}", query.token),
+                (None, Some(json)) => format!("json: {}", json.token),
+                _ => unreachable!(),
+            }
+        }
+
+        let cli = TestClient::new(index);
+        cli.get("/")
+            .query("token", &"a")
+            .body_json(&serde_json::json!({"token": "b"}))
+            .send()
+            .await
+            .assert_text("query: a")
+            .await;
+        cli.get("/")
+            .body_json(&serde_json::json!({"token": "b"}))
+            .send()
+            .await
+            .assert_text("json: b")
+            .await;
+
+        let resp = cli.get("/").send().await;
+        resp.assert_status(StatusCode::UNAUTHORIZED);
+        let text = resp.0.into_body().into_string().await.unwrap();
+        assert!(
+            text.starts_with("none of the extractors succeeded: "),
+            "{text}"
+        );
+        assert_eq!(text.matches("; ").count(), 1);
+    }
+}
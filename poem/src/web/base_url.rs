@@ -0,0 +1,415 @@
+use std::{
+    collections::HashSet,
+    fmt::{self, Display, Formatter},
+    net::IpAddr,
+};
+
+use http::{header, uri::Scheme};
+
+use super::forwarded::parse_forwarded;
+use crate::{error::MissingHostError, Addr, FromRequest, Request, RequestBody, Result};
+
+/// The proxies whose forwarding headers are trusted by [`BaseUrl`] and
+/// [`RealIp`](super::RealIp).
+///
+/// It can be attached to an endpoint with
+/// [`EndpointExt::data`](crate::EndpointExt::data).
+#[derive(Debug, Clone, Default)]
+pub struct TrustedProxies {
+    all: bool,
+    addrs: HashSet<IpAddr>,
+    prefer_x_forwarded: bool,
+}
+
+impl TrustedProxies {
+    /// Create a new `TrustedProxies` that trusts no proxy.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Trusts the forwarding headers from any peer.
+    #[must_use]
+    pub fn trust_all(self) -> Self {
+        Self { all: true, ..self }
+    }
+
+    /// Trusts the forwarding headers from the specified proxy address.
+    #[must_use]
+    pub fn trust(mut self, addr: impl Into<IpAddr>) -> Self {
+        self.addrs.insert(addr.into());
+        self
+    }
+
+    /// Prefers the `X-Forwarded-*` headers to the standard `Forwarded` header
+    /// when a request has both.
+    ///
+    /// By default, the `Forwarded` header is preferred.
+    #[must_use]
+    pub fn prefer_x_forwarded(self) -> Self {
+        Self {
+            prefer_x_forwarded: true,
+            ..self
+        }
+    }
+
+    #[inline]
+    pub(crate) fn prefers_x_forwarded(&self) -> bool {
+        self.prefer_x_forwarded
+    }
+
+    /// Returns `true` if the forwarding headers from the specified peer are
+    /// trusted.
+    pub fn is_trusted(&self, addr: &Addr) -> bool {
+        self.all
+            || addr
+                .as_socket_addr()
+                .is_some_and(|addr| self.addrs.contains(&addr.ip()))
+    }
+
+    #[inline]
+    pub(crate) fn is_trusted_ip(&self, ip: IpAddr) -> bool {
+        self.all || self.addrs.contains(&ip)
+    }
+}
+
+/// An extractor that reconstructs the external base URL of the request, such
+/// as `https://example.com`.
+///
+/// If the peer is trusted by the [`TrustedProxies`] in the request data, the
+/// scheme and host are taken from the last element of the `Forwarded` header,
+/// or from the last values of the `X-Forwarded-Proto` and `X-Forwarded-Host`
+/// headers, see [`TrustedProxies::prefer_x_forwarded`]. These are the values
+/// appended by the trusted proxy, the previous ones are ignored because they
+/// can be sent by the client. Only one family of headers is used: if the
+/// element of the `Forwarded` header has no `proto` or `host`, the scheme of
+/// the connection or the `Host` header is used rather than the
+/// `X-Forwarded-*` headers. Otherwise the scheme of the connection and the
+/// `Host` header are used.
+///
+/// # Errors
+///
+/// - [`MissingHostError`]
+///
+/// # Example
+///
+/// ```
+/// use poem::{
+///     handler,
+///     test::TestClient,
+///     web::{BaseUrl, TrustedProxies},
+///     EndpointExt,
+/// };
+///
+/// #[handler]
+/// fn index(base_url: BaseUrl) -> String {
+///     base_url.join("/users/1")
+/// }
+///
+/// let cli = TestClient::new(index.data(TrustedProxies::new().trust_all()));
+///
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// let resp = cli
+///     .get("/")
+///     .header("X-Forwarded-Proto", "https")
+///     .header("X-Forwarded-Host", "example.com")
+///     .send()
+///     .await;
+/// resp.assert_status_is_ok();
+/// resp.assert_text("https://example.com/users/1").await;
+/// # });
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct BaseUrl {
+    scheme: String,
+    host: String,
+}
+
+impl BaseUrl {
+    /// Create a `BaseUrl` from the scheme and host.
+    pub fn new(scheme: impl Into<String>, host: impl Into<String>) -> Self {
+        Self {
+            scheme: scheme.into(),
+            host: host.into(),
+        }
+    }
+
+    /// Returns the scheme, such as `https`.
+    #[inline]
+    pub fn scheme(&self) -> &str {
+        &self.scheme
+    }
+
+    /// Returns the host, including the port if present.
+    #[inline]
+    pub fn host(&self) -> &str {
+        &self.host
+    }
+
+    /// Builds an absolute URL for the specified path.
+    pub fn join(&self, path: impl AsRef<str>) -> String {
+        let path = path.as_ref();
+        if path.starts_with('/') {
+            format!("{self}{path}")
+        } else {
+            format!("{self}/{path}")
+        }
+    }
+}
+
+impl Display for BaseUrl {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}://{}", self.scheme, self.host)
+    }
+}
+
+impl<'a> FromRequest<'a> for BaseUrl {
+    async fn from_request(req: &'a Request, _body: &mut RequestBody) -> Result<Self> {
+        let (mut scheme, mut host) = (None, None);
+
+        if let Some(proxies) = trusted_proxies(req) {
+            (scheme, host) = forwarded(req, proxies);
+        }
+
+        let scheme = scheme.unwrap_or_else(|| req.scheme().as_str().to_string());
+        let host = match host {
+            Some(host) => host,
+            None => req
+                .headers()
+                .get(header::HOST)
+                .and_then(|value| value.to_str().ok())
+                .or_else(|| req.uri().authority().map(|authority| authority.as_str()))
+                .map(ToString::to_string)
+                .ok_or(MissingHostError)?,
+        };
+
+        Ok(BaseUrl { scheme, host })
+    }
+}
+
+/// Returns the scheme of the request seen by the client, which is taken from
+/// the forwarding headers if the peer is trusted by the [`TrustedProxies`] in
+/// the request data.
+pub(crate) fn external_scheme(req: &Request) -> Scheme {
+    if let Some(proxies) = trusted_proxies(req) {
+        if let Some(scheme) = forwarded(req, proxies)
+            .0
+            .and_then(|scheme| Scheme::try_from(scheme.as_str()).ok())
+        {
+            return scheme;
+        }
+    }
+    req.scheme().clone()
+}
+
+/// Returns the [`TrustedProxies`] in the request data if they trust the peer.
+fn trusted_proxies(req: &Request) -> Option<&TrustedProxies> {
+    req.data::<TrustedProxies>()
+        .filter(|proxies| proxies.is_trusted(req.remote_addr()))
+}
+
+/// Returns the scheme and host from the rightmost forwarding values, which are
+/// appended by the trusted proxy, the values on their left are sent by the
+/// client and can not be trusted.
+fn forwarded(req: &Request, proxies: &TrustedProxies) -> (Option<String>, Option<String>) {
+    // the values of the other header family are sent by the client, so they are
+    // ignored even if the proxy does not forward the scheme or the host
+    let standard = || {
+        if !req.headers().contains_key(header::FORWARDED) {
+            return None;
+        }
+        match parse_forwarded(req.headers()) {
+            Some(mut items) => Some(
+                items
+                    .pop()
+                    .map(|item| (item.proto, item.host))
+                    .unwrap_or_default(),
+            ),
+            // an invalid header can not tell which element is appended by the
+            // proxy, so no forwarding values are used
+            None => Some((None, None)),
+        }
+    };
+    let x_forwarded = || {
+        let names = ["x-forwarded-proto", "x-forwarded-host", "x-forwarded-for"];
+        if !names.iter().any(|name| req.headers().contains_key(*name)) {
+            return None;
+        }
+        let last_value = |name: &str| {
+            req.headers()
+                .get_all(name)
+                .iter()
+                .next_back()
+                .and_then(|value| value.to_str().ok())
+                .and_then(|value| value.rsplit(',').next())
+                .map(|value| value.trim().to_string())
+                .filter(|value| !value.is_empty())
+        };
+        Some((
+            last_value("x-forwarded-proto"),
+            last_value("x-forwarded-host"),
+        ))
+    };
+
+    let found = if proxies.prefers_x_forwarded() {
+        x_forwarded().or_else(standard)
+    } else {
+        standard().or_else(x_forwarded)
+    };
+    found.unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::SocketAddr;
+
+    use http::StatusCode;
+
+    use super::*;
+    use crate::web::RemoteAddr;
+
+    fn create_request(headers: &[(&str, &str)]) -> Request {
+        let mut builder = Request::builder();
+        for (name, value) in headers {
+            builder = builder.header(*name, *value);
+        }
+        builder.finish()
+    }
+
+    #[tokio::test]
+    async fn test_base_url() {
+        let req = create_request(&[("host", "example.com:8080")]);
+        assert_eq!(
+            BaseUrl::from_request_without_body(&req).await.unwrap(),
+            BaseUrl::new("http", "example.com:8080")
+        );
+
+        // forwarding headers are ignored by default
+        let req = create_request(&[
+            ("host", "internal"),
+            ("x-forwarded-proto", "https"),
+            ("x-forwarded-host", "example.com"),
+        ]);
+        assert_eq!(
+            BaseUrl::from_request_without_body(&req).await.unwrap(),
+            BaseUrl::new("http", "internal")
+        );
+
+        let req = Request::builder().finish();
+        assert_eq!(
+            BaseUrl::from_request_without_body(&req)
+                .await
+                .unwrap_err()
+                .status(),
+            StatusCode::BAD_REQUEST
+        );
+    }
+
+    #[tokio::test]
+    async fn test_base_url_trusted() {
+        let mut req = create_request(&[
+            ("host", "internal"),
+            ("x-forwarded-proto", "http, https"),
+            ("x-forwarded-host", "example.com"),
+        ]);
+        req.set_data(TrustedProxies::new().trust_all());
+        assert_eq!(
+            BaseUrl::from_request_without_body(&req).await.unwrap(),
+            BaseUrl::new("https", "example.com")
+        );
+
+        // the values sent by the client are on the left
+        let mut req = create_request(&[
+            ("host", "internal"),
+            ("x-forwarded-host", "evil.com"),
+            ("x-forwarded-host", "evil.com, example.com"),
+        ]);
+        req.set_data(TrustedProxies::new().trust_all());
+        assert_eq!(
+            BaseUrl::from_request_without_body(&req).await.unwrap(),
+            BaseUrl::new("http", "example.com")
+        );
+
+        let mut req = create_request(&[
+            ("host", "internal"),
+            ("forwarded", "for=192.0.2.43;proto=https;host=example.org"),
+            ("x-forwarded-host", "example.com"),
+        ]);
+        req.set_data(TrustedProxies::new().trust_all());
+        assert_eq!(
+            BaseUrl::from_request_without_body(&req).await.unwrap(),
+            BaseUrl::new("https", "example.org")
+        );
+        req.set_data(TrustedProxies::new().trust_all().prefer_x_forwarded());
+        assert_eq!(
+            BaseUrl::from_request_without_body(&req).await.unwrap(),
+            BaseUrl::new("http", "example.com")
+        );
+
+        let mut req = create_request(&[
+            ("host", "internal"),
+            ("forwarded", "for=_client;proto=http;host=evil.com"),
+            (
+                "forwarded",
+                "for=\"[2001:db8::1]:1234\";host=\"example.org:8443\"",
+            ),
+        ]);
+        req.set_data(TrustedProxies::new().trust_all());
+        assert_eq!(
+            BaseUrl::from_request_without_body(&req).await.unwrap(),
+            BaseUrl::new("http", "example.org:8443")
+        );
+
+        // the proxy only sets the `Forwarded` header
+        let mut req = create_request(&[
+            ("host", "internal"),
+            ("forwarded", "for=192.0.2.43"),
+            ("x-forwarded-proto", "https"),
+            ("x-forwarded-host", "evil.com"),
+        ]);
+        req.set_data(TrustedProxies::new().trust_all());
+        assert_eq!(
+            BaseUrl::from_request_without_body(&req).await.unwrap(),
+            BaseUrl::new("http", "internal")
+        );
+
+        let mut req = create_request(&[("host", "internal"), ("x-forwarded-proto", "https")]);
+        req.state_mut().remote_addr = RemoteAddr(SocketAddr::from(([10, 0, 0, 1], 1234)).into());
+        req.set_data(TrustedProxies::new().trust([10, 0, 0, 2]));
+        assert_eq!(
+            BaseUrl::from_request_without_body(&req).await.unwrap(),
+            BaseUrl::new("http", "internal")
+        );
+        req.set_data(TrustedProxies::new().trust([10, 0, 0, 1]));
+        assert_eq!(
+            BaseUrl::from_request_without_body(&req).await.unwrap(),
+            BaseUrl::new("https", "internal")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_base_url_invalid_forwarded() {
+        // the unterminated quoted string swallows the element appended by the
+        // proxy
+        let mut req = create_request(&[
+            ("host", "internal"),
+            (
+                "forwarded",
+                "proto=https;host=evil.com, for=\"x, for=10.1.1.1;proto=http;host=example.com",
+            ),
+            ("x-forwarded-host", "evil.com"),
+        ]);
+        req.set_data(TrustedProxies::new().trust_all());
+        assert_eq!(
+            BaseUrl::from_request_without_body(&req).await.unwrap(),
+            BaseUrl::new("http", "internal")
+        );
+    }
+
+    #[test]
+    fn test_join() {
+        let base_url = BaseUrl::new("https", "example.com");
+        assert_eq!(base_url.to_string(), "https://example.com");
+        assert_eq!(base_url.join("/a/b"), "https://example.com/a/b");
+        assert_eq!(base_url.join("a/b"), "https://example.com/a/b");
+    }
+}
@@ -0,0 +1,142 @@
+use std::ops::{Deref, DerefMut};
+
+use futures_util::FutureExt;
+
+use crate::{FromRequest, Request, RequestBody, Result};
+
+/// An extractor that runs the extractor `E` at most once per request.
+///
+/// The first successful extraction is stored in the request, and the
+/// subsequent `Cached<E>` extractions of the same request return a clone of
+/// it, so an expensive lookup, such as loading the current user or session,
+/// can be referenced by both a middleware and the handler. The errors are not
+/// cached, the extractor runs again on the next extraction.
+///
+/// # Example
+///
+/// ```
+/// use std::sync::atomic::{AtomicUsize, Ordering};
+///
+/// use poem::{
+///     handler, test::TestClient, web::Cached, EndpointExt, FromRequest, Request, RequestBody,
+///     Result,
+/// };
+///
+/// static LOOKUPS: AtomicUsize = AtomicUsize::new(0);
+///
+/// #[derive(Clone)]
+/// struct User(String);
+///
+/// impl<'a> FromRequest<'a> for User {
+///     async fn from_request(req: &'a Request, _body: &mut RequestBody) -> Result<Self> {
+///         LOOKUPS.fetch_add(1, Ordering::SeqCst);
+///         Ok(User(req.header("x-user").unwrap_or_default().to_string()))
+///     }
+/// }
+///
+/// #[handler]
+/// fn index(Cached(user): Cached<User>) -> String {
+///     user.0
+/// }
+///
+/// let app = index.before(|req| async move {
+///     Cached::<User>::from_request_without_body(&req).await?;
+///     Ok(req)
+/// });
+/// let cli = TestClient::new(app);
+///
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// let resp = cli.get("/").header("x-user", "sunli").send().await;
+/// resp.assert_text("sunli").await;
+/// assert_eq!(LOOKUPS.load(Ordering::SeqCst), 1);
+/// # });
+/// ```
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub struct Cached<E>(pub E);
+
+impl<E> Deref for Cached<E> {
+    type Target = E;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<E> DerefMut for Cached<E> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl<'a, E> FromRequest<'a> for Cached<E>
+where
+    E: for<'b> FromRequest<'b> + Clone + Send + Sync + 'static,
+{
+    async fn from_request(req: &'a Request, body: &mut RequestBody) -> Result<Self> {
+        if let Some(value) = req.state().cached.lock().get::<E>() {
+            return Ok(Cached(value.clone()));
+        }
+
+        // FIXME: remove the unnecessary boxed
+        // https://github.com/rust-lang/rust/issues/100013
+        let value = E::from_request(req, body).boxed().await?;
+        req.state().cached.lock().insert(value.clone());
+        Ok(Cached(value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    use super::*;
+    use crate::{handler, http::StatusCode, test::TestClient, EndpointExt, Error};
+
+    #[derive(Clone)]
+    struct Counter(usize);
+
+    impl<'a> FromRequest<'a> for Counter {
+        async fn from_request(req: &'a Request, _body: &mut RequestBody) -> Result<Self> {
+            let calls = req.data::<Arc<AtomicUsize>>().unwrap();
+            let n = calls.fetch_add(1, Ordering::SeqCst) + 1;
+            if req.header("fail").is_some() {
+                return Err(Error::from_status(StatusCode::UNAUTHORIZED));
+            }
+            Ok(Counter(n))
+        }
+    }
+
+    #[tokio::test]
+    async fn cached() {
+        #[handler(internal)]
+        fn index(a: Cached<Counter>, b: Cached<Counter>) -> String {
+            format!("{} {}", a.0 .0, b.0 .0)
+        }
+
+        let calls = Arc::new(AtomicUsize::new(0));
+        let cli = TestClient::new(
+            index
+                .before(|req| async move {
+                    Cached::<Counter>::from_request_without_body(&req).await?;
+                    Ok(req)
+                })
+                .data(calls.clone()),
+        );
+
+        cli.get("/").send().await.assert_text("1 1").await;
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+        // the cache is per request
+        cli.get("/").send().await.assert_text("2 2").await;
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+
+        cli.get("/")
+            .header("fail", "1")
+            .send()
+            .await
+            .assert_status(StatusCode::UNAUTHORIZED);
+    }
+}
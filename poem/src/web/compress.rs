@@ -15,6 +15,7 @@ use crate::{
 /// The compression algorithms.
 #[cfg_attr(docsrs, doc(cfg(feature = "compression")))]
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+#[non_exhaustive]
 pub enum CompressionAlgo {
     /// brotli
     BR,
@@ -22,6 +23,10 @@ pub enum CompressionAlgo {
     DEFLATE,
     /// gzip
     GZIP,
+    /// zstd
+    #[cfg(feature = "zstd")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "zstd")))]
+    ZSTD,
 }
 
 impl FromStr for CompressionAlgo {
@@ -32,6 +37,8 @@ impl FromStr for CompressionAlgo {
             "br" => CompressionAlgo::BR,
             "deflate" => CompressionAlgo::DEFLATE,
             "gzip" => CompressionAlgo::GZIP,
+            #[cfg(feature = "zstd")]
+            "zstd" => CompressionAlgo::ZSTD,
             _ => return Err(()),
         })
     }
@@ -44,6 +51,8 @@ impl CompressionAlgo {
             CompressionAlgo::BR => "br",
             CompressionAlgo::DEFLATE => "deflate",
             CompressionAlgo::GZIP => "gzip",
+            #[cfg(feature = "zstd")]
+            CompressionAlgo::ZSTD => "zstd",
         }
     }
 
@@ -71,6 +80,13 @@ impl CompressionAlgo {
                     level.unwrap_or(CompressionLevel::Default),
                 ),
             ),
+            #[cfg(feature = "zstd")]
+            CompressionAlgo::ZSTD => Box::pin(
+                async_compression::tokio::bufread::ZstdEncoder::with_quality(
+                    BufReader::new(reader),
+                    level.unwrap_or(CompressionLevel::Default),
+                ),
+            ),
         }
     }
 
@@ -88,8 +104,18 @@ impl CompressionAlgo {
             CompressionAlgo::GZIP => Box::pin(async_compression::tokio::bufread::GzipDecoder::new(
                 BufReader::new(reader),
             )),
+            #[cfg(feature = "zstd")]
+            CompressionAlgo::ZSTD => Box::pin(async_compression::tokio::bufread::ZstdDecoder::new(
+                BufReader::new(reader),
+            )),
         }
     }
+
+    /// Returns a body that decompresses the specified body with this
+    /// algorithm.
+    pub fn decompress_body(&self, body: Body) -> Body {
+        Body::from_async_read(self.decompress(body.into_async_read()))
+    }
 }
 
 impl Display for CompressionAlgo {
@@ -203,5 +229,7 @@ mod tests {
         test_algo(CompressionAlgo::BR).await;
         test_algo(CompressionAlgo::DEFLATE).await;
         test_algo(CompressionAlgo::GZIP).await;
+        #[cfg(feature = "zstd")]
+        test_algo(CompressionAlgo::ZSTD).await;
     }
 }
@@ -0,0 +1,516 @@
+use std::ops::{Deref, DerefMut};
+
+use bytes::Bytes;
+use futures_util::{stream::BoxStream, Stream, StreamExt};
+use http::StatusCode;
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::{
+    error::ParseCsvError, http::header, web::RequestBody, Body, FromRequest, IntoResponse, Request,
+    Response, Result,
+};
+
+/// The quoting style to use when writing CSV.
+pub use csv::QuoteStyle as CsvQuoteStyle;
+
+/// Configuration for reading and writing CSV.
+///
+/// When extracting a [`Csv`], the configuration is read from the request data
+/// if present, so it can be attached to an endpoint with
+/// [`EndpointExt::data`](crate::EndpointExt::data).
+///
+/// # Example
+///
+/// ```
+/// use poem::{
+///     handler, post,
+///     test::TestClient,
+///     web::{Csv, CsvConfig},
+///     EndpointExt, Route,
+/// };
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// struct User {
+///     name: String,
+/// }
+///
+/// #[handler]
+/// async fn index(Csv(users): Csv<User>) -> String {
+///     users
+///         .iter()
+///         .map(|user| user.name.as_str())
+///         .collect::<Vec<_>>()
+///         .join(",")
+/// }
+///
+/// let app = Route::new()
+///     .at("/", post(index))
+///     .data(CsvConfig::new().delimiter(b';'));
+/// let cli = TestClient::new(app);
+///
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// let resp = cli
+///     .post("/")
+///     .content_type("text/csv")
+///     .body("name\nfoo\nbar\n")
+///     .send()
+///     .await;
+/// resp.assert_status_is_ok();
+/// resp.assert_text("foo,bar").await;
+/// # });
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct CsvConfig {
+    delimiter: u8,
+    has_headers: bool,
+    quote: u8,
+    quote_style: CsvQuoteStyle,
+}
+
+impl Default for CsvConfig {
+    fn default() -> Self {
+        Self {
+            delimiter: b',',
+            has_headers: true,
+            quote: b'"',
+            quote_style: CsvQuoteStyle::Necessary,
+        }
+    }
+}
+
+impl CsvConfig {
+    /// Create a new `CsvConfig` with the default settings.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the field delimiter, defaults to `,`.
+    #[must_use]
+    pub fn delimiter(self, delimiter: u8) -> Self {
+        Self { delimiter, ..self }
+    }
+
+    /// Sets whether the first row is a header row, defaults to `true`.
+    #[must_use]
+    pub fn has_headers(self, has_headers: bool) -> Self {
+        Self {
+            has_headers,
+            ..self
+        }
+    }
+
+    /// Sets the quote character, defaults to `"`.
+    #[must_use]
+    pub fn quote(self, quote: u8) -> Self {
+        Self { quote, ..self }
+    }
+
+    /// Sets the quoting style used when writing, defaults to
+    /// [`CsvQuoteStyle::Necessary`].
+    #[must_use]
+    pub fn quote_style(self, quote_style: CsvQuoteStyle) -> Self {
+        Self {
+            quote_style,
+            ..self
+        }
+    }
+
+    fn reader_builder(&self) -> csv::ReaderBuilder {
+        let mut builder = csv::ReaderBuilder::new();
+        builder
+            .delimiter(self.delimiter)
+            .has_headers(self.has_headers)
+            .quote(self.quote);
+        builder
+    }
+
+    fn writer_builder(&self, has_headers: bool) -> csv::WriterBuilder {
+        let mut builder = csv::WriterBuilder::new();
+        builder
+            .delimiter(self.delimiter)
+            .has_headers(has_headers)
+            .quote(self.quote)
+            .quote_style(self.quote_style);
+        builder
+    }
+
+    fn serialize_rows<'a, T: Serialize + 'a>(
+        &self,
+        rows: impl IntoIterator<Item = &'a T>,
+        has_headers: bool,
+    ) -> Result<Vec<u8>, csv::Error> {
+        let mut writer = self.writer_builder(has_headers).from_writer(Vec::new());
+        for row in rows {
+            writer.serialize(row)?;
+        }
+        writer
+            .into_inner()
+            .map_err(|err| csv::Error::from(err.into_error()))
+    }
+}
+
+/// CSV extractor and response.
+///
+/// To extract the rows from the body, `T` must implement
+/// [`serde::Deserialize`]. The content type must be `text/csv`, and the
+/// [`CsvConfig`] from the request data is used if present.
+///
+/// # Errors
+///
+/// - [`ReadBodyError`](crate::error::ReadBodyError)
+/// - [`ParseCsvError`]
+///
+/// ```
+/// use poem::{
+///     handler, http::header, post, test::TestClient, web::Csv, Endpoint, Request, Route,
+/// };
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// struct User {
+///     name: String,
+///     age: u32,
+/// }
+///
+/// #[handler]
+/// async fn index(Csv(users): Csv<User>) -> String {
+///     format!("{} users", users.len())
+/// }
+///
+/// let app = Route::new().at("/", post(index));
+/// let cli = TestClient::new(app);
+///
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// let resp = cli
+///     .post("/")
+///     .header(header::CONTENT_TYPE, "text/csv")
+///     .body("name,age\nfoo,10\nbar,20\n")
+///     .send()
+///     .await;
+/// resp.assert_status_is_ok();
+/// resp.assert_text("2 users").await;
+/// # });
+/// ```
+///
+/// # Response
+///
+/// To serialize the rows to CSV, `T` must implement [`serde::Serialize`]. The
+/// rows are written with the default [`CsvConfig`], use [`Csv::with_config`]
+/// to customize the format, or [`CsvStream`] to export large datasets.
+///
+/// ```
+/// use poem::{get, handler, test::TestClient, web::Csv, Endpoint, Request, Route};
+/// use serde::Serialize;
+///
+/// #[derive(Serialize)]
+/// struct User {
+///     name: String,
+///     age: u32,
+/// }
+///
+/// #[handler]
+/// async fn index() -> Csv<User> {
+///     Csv(vec![User {
+///         name: "foo".to_string(),
+///         age: 10,
+///     }])
+/// }
+///
+/// let app = Route::new().at("/", get(index));
+/// let cli = TestClient::new(app);
+///
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// let resp = cli.get("/").send().await;
+/// resp.assert_status_is_ok();
+/// resp.assert_content_type("text/csv; charset=utf-8");
+/// resp.assert_text("name,age\nfoo,10\n").await;
+/// # });
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq, Default)]
+pub struct Csv<T>(pub Vec<T>);
+
+impl<T> Csv<T> {
+    /// Serializes the rows with the specified configuration when used as a
+    /// response.
+    pub fn with_config(self, config: CsvConfig) -> CsvWithConfig<T> {
+        CsvWithConfig { rows: self, config }
+    }
+}
+
+impl<T> Deref for Csv<T> {
+    type Target = Vec<T>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for Csv<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl<'a, T: DeserializeOwned> FromRequest<'a> for Csv<T> {
+    async fn from_request(req: &'a Request, body: &mut RequestBody) -> Result<Self> {
+        let content_type = req
+            .headers()
+            .get(header::CONTENT_TYPE)
+            .and_then(|content_type| content_type.to_str().ok())
+            .ok_or(ParseCsvError::ContentTypeRequired)?;
+        if !is_csv_content_type(content_type) {
+            return Err(ParseCsvError::InvalidContentType(content_type.into()).into());
+        }
+
+        let config = req.data::<CsvConfig>().copied().unwrap_or_default();
+        let data = body.take()?.into_bytes().await?;
+        let rows = config
+            .reader_builder()
+            .from_reader(data.as_ref())
+            .deserialize()
+            .collect::<Result<Vec<T>, _>>()
+            .map_err(ParseCsvError::Parse)?;
+        Ok(Self(rows))
+    }
+}
+
+fn is_csv_content_type(content_type: &str) -> bool {
+    matches!(content_type.parse::<mime::Mime>(),
+        Ok(content_type) if content_type.type_() == "text"
+        && content_type.subtype() == "csv")
+}
+
+impl<T: Serialize + Send> IntoResponse for Csv<T> {
+    fn into_response(self) -> Response {
+        self.with_config(CsvConfig::default()).into_response()
+    }
+}
+
+/// A CSV response with a [`CsvConfig`], returned by [`Csv::with_config`].
+#[derive(Debug, Clone)]
+pub struct CsvWithConfig<T> {
+    rows: Csv<T>,
+    config: CsvConfig,
+}
+
+impl<T: Serialize + Send> IntoResponse for CsvWithConfig<T> {
+    fn into_response(self) -> Response {
+        let config = self.config;
+        let data = match config.serialize_rows(&self.rows.0, config.has_headers) {
+            Ok(data) => data,
+            Err(err) => {
+                return Response::builder()
+                    .status(StatusCode::INTERNAL_SERVER_ERROR)
+                    .body(err.to_string())
+            }
+        };
+        Response::builder()
+            .header(header::CONTENT_TYPE, "text/csv; charset=utf-8")
+            .body(data)
+    }
+}
+
+/// A streaming CSV response.
+///
+/// Each row is serialized as it is produced by the stream, so the whole
+/// dataset never needs to be held in memory.
+///
+/// # Example
+///
+/// ```
+/// use futures_util::stream;
+/// use poem::{
+///     handler,
+///     test::TestClient,
+///     web::{CsvConfig, CsvStream},
+/// };
+/// use serde::Serialize;
+///
+/// #[derive(Serialize)]
+/// struct User {
+///     name: &'static str,
+///     age: u32,
+/// }
+///
+/// #[handler]
+/// fn index() -> CsvStream<User> {
+///     CsvStream::new(stream::iter(vec![
+///         User { name: "foo", age: 10 },
+///         User { name: "bar", age: 20 },
+///     ]))
+///     .config(CsvConfig::new().delimiter(b';'))
+/// }
+///
+/// let cli = TestClient::new(index);
+///
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// let resp = cli.get("/").send().await;
+/// resp.assert_status_is_ok();
+/// resp.assert_text("name;age\nfoo;10\nbar;20\n").await;
+/// # });
+/// ```
+pub struct CsvStream<T> {
+    stream: BoxStream<'static, T>,
+    config: CsvConfig,
+}
+
+impl<T> CsvStream<T> {
+    /// Create a streaming CSV response using a row stream.
+    pub fn new(stream: impl Stream<Item = T> + Send + 'static) -> Self {
+        Self {
+            stream: stream.boxed(),
+            config: CsvConfig::default(),
+        }
+    }
+
+    /// Sets the configuration used to write the rows.
+    #[must_use]
+    pub fn config(self, config: CsvConfig) -> Self {
+        Self { config, ..self }
+    }
+}
+
+impl<T: Serialize + Send + 'static> IntoResponse for CsvStream<T> {
+    fn into_response(self) -> Response {
+        let config = self.config;
+        let mut is_first = true;
+        let stream = self.stream.map(move |row| {
+            let has_headers = is_first && config.has_headers;
+            is_first = false;
+            config
+                .serialize_rows([&row], has_headers)
+                .map(Bytes::from)
+                .map_err(std::io::Error::other)
+        });
+
+        Response::builder()
+            .header(header::CONTENT_TYPE, "text/csv; charset=utf-8")
+            .body(Body::from_bytes_stream(stream))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures_util::stream;
+    use serde::{Deserialize, Serialize};
+
+    use super::*;
+    use crate::{handler, test::TestClient, web::Data, EndpointExt};
+
+    #[derive(Deserialize, Serialize, Debug, Eq, PartialEq)]
+    struct CreateResource {
+        name: String,
+        value: i32,
+    }
+
+    #[tokio::test]
+    async fn test_csv_extractor() {
+        #[handler(internal)]
+        async fn index(rows: Csv<CreateResource>) {
+            assert_eq!(
+                rows.0,
+                vec![
+                    CreateResource {
+                        name: "abc".to_string(),
+                        value: 100,
+                    },
+                    CreateResource {
+                        name: "d;e".to_string(),
+                        value: 200,
+                    }
+                ]
+            );
+        }
+
+        let cli = TestClient::new(index.data(CsvConfig::new().delimiter(b';')));
+        cli.post("/")
+            .content_type("text/csv")
+            .body("name;value\nabc;100\n\"d;e\";200\n")
+            .send()
+            .await
+            .assert_status_is_ok();
+    }
+
+    #[tokio::test]
+    async fn test_csv_extractor_fail() {
+        #[handler(internal)]
+        async fn index(_rows: Csv<CreateResource>) {}
+
+        let cli = TestClient::new(index);
+        cli.post("/")
+            .body("name,value\nabc,100\n")
+            .send()
+            .await
+            .assert_status(StatusCode::UNSUPPORTED_MEDIA_TYPE);
+
+        cli.post("/")
+            .content_type("text/csv")
+            .body("name,value\nabc,xyz\n")
+            .send()
+            .await
+            .assert_status(StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn test_csv_response() {
+        #[handler(internal)]
+        async fn index() -> Csv<CreateResource> {
+            Csv(vec![CreateResource {
+                name: "abc".to_string(),
+                value: 100,
+            }])
+        }
+
+        let cli = TestClient::new(index);
+        let resp = cli.get("/").send().await;
+        resp.assert_status_is_ok();
+        resp.assert_content_type("text/csv; charset=utf-8");
+        resp.assert_text("name,value\nabc,100\n").await;
+    }
+
+    #[tokio::test]
+    async fn test_csv_response_with_config() {
+        #[handler(internal)]
+        async fn index(config: Data<&CsvConfig>) -> CsvWithConfig<CreateResource> {
+            Csv(vec![CreateResource {
+                name: "a;b".to_string(),
+                value: 100,
+            }])
+            .with_config(*config.0)
+        }
+
+        let cli = TestClient::new(index.data(CsvConfig::new().delimiter(b';')));
+        let resp = cli.get("/").send().await;
+        resp.assert_status_is_ok();
+        resp.assert_content_type("text/csv; charset=utf-8");
+        resp.assert_text("name;value\n\"a;b\";100\n").await;
+    }
+
+    #[tokio::test]
+    async fn test_csv_stream() {
+        #[handler(internal)]
+        async fn index() -> CsvStream<CreateResource> {
+            CsvStream::new(stream::iter(vec![
+                CreateResource {
+                    name: "a b".to_string(),
+                    value: 1,
+                },
+                CreateResource {
+                    name: "c".to_string(),
+                    value: 2,
+                },
+            ]))
+            .config(
+                CsvConfig::new()
+                    .has_headers(false)
+                    .quote_style(CsvQuoteStyle::NonNumeric),
+            )
+        }
+
+        let cli = TestClient::new(index);
+        let resp = cli.get("/").send().await;
+        resp.assert_status_is_ok();
+        resp.assert_text("\"a b\",1\n\"c\",2\n").await;
+    }
+}
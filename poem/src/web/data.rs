@@ -4,6 +4,11 @@ use crate::{error::GetDataError, FromRequest, Request, RequestBody, Result};
 
 /// An extractor that can extract data from the request extension.
 ///
+/// The data inserted by a middleware with [`Request::set_data`] or
+/// [`Request::set_ext`] before calling the inner endpoint is always visible to
+/// the extractors of the inner endpoints, because the extractors run when the
+/// innermost endpoint is called.
+///
 /// # Errors
 ///
 /// - [`GetDataError`]
@@ -47,6 +52,65 @@ impl<'a, T: Send + Sync + 'static> FromRequest<'a> for Data<&'a T> {
     }
 }
 
+/// An extractor that clones a typed value from the request extensions.
+///
+/// Unlike [`Data`], it does not borrow the request, so the value can be moved
+/// into spawned tasks. The value is usually inserted by a middleware with
+/// [`Request::set_ext`].
+///
+/// # Errors
+///
+/// - [`GetDataError`]
+///
+/// # Example
+///
+/// ```
+/// use poem::{handler, test::TestClient, web::Ext, EndpointExt, Request};
+///
+/// #[derive(Clone)]
+/// struct User {
+///     name: String,
+/// }
+///
+/// #[handler]
+/// fn index(Ext(user): Ext<User>) -> String {
+///     format!("hello {}", user.name)
+/// }
+///
+/// let app = index.before(|mut req: Request| async move {
+///     req.set_ext(User {
+///         name: "alice".to_string(),
+///     });
+///     Ok(req)
+/// });
+///
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// let resp = TestClient::new(app).get("/").send().await;
+/// resp.assert_status_is_ok();
+/// resp.assert_text("hello alice").await;
+/// # });
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Ext<T>(pub T);
+
+impl<T> Deref for Ext<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<'a, T: Clone + Send + Sync + 'static> FromRequest<'a> for Ext<T> {
+    async fn from_request(req: &'a Request, _body: &mut RequestBody) -> Result<Self> {
+        Ok(Ext(req
+            .extensions()
+            .get::<T>()
+            .cloned()
+            .ok_or_else(|| GetDataError(std::any::type_name::<T>()))?))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use http::StatusCode;
@@ -96,4 +160,24 @@ mod tests {
             .await
             .assert_status_is_ok();
     }
+
+    #[tokio::test]
+    async fn test_ext_extractor() {
+        #[handler(internal)]
+        async fn index(value: Ext<i32>) -> String {
+            value.0.to_string()
+        }
+
+        let app = index.before(|mut req: Request| async move {
+            req.set_ext(100i32);
+            Ok(req)
+        });
+        let resp = TestClient::new(app).get("/").send().await;
+        resp.assert_status_is_ok();
+        resp.assert_text("100").await;
+
+        let resp = TestClient::new(index).get("/").send().await;
+        resp.assert_status(StatusCode::INTERNAL_SERVER_ERROR);
+        resp.assert_text("data of type `i32` was not found.").await;
+    }
 }
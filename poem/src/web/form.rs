@@ -112,7 +112,7 @@ impl<'a, T: DeserializeOwned> FromRequest<'a> for Form<T> {
     }
 }
 
-fn is_form_content_type(content_type: &str) -> bool {
+pub(crate) fn is_form_content_type(content_type: &str) -> bool {
     matches!(content_type.parse::<mime::Mime>(), 
         Ok(content_type) if content_type.type_() == "application" 
         && (content_type.subtype() == "x-www-form-urlencoded"
@@ -0,0 +1,233 @@
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+use http::{header, HeaderMap};
+
+/// The node identifier of the `for` parameter of the `Forwarded` header.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub(crate) enum ForwardedNode {
+    /// An IP address, the port is ignored.
+    Ip(IpAddr),
+    /// The `unknown` identifier, or an identifier which is not valid.
+    Unknown,
+    /// An obfuscated identifier, such as `_hidden`.
+    Obfuscated,
+}
+
+/// An element of the `Forwarded` header, which is added by one proxy.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub(crate) struct ForwardedElement {
+    pub(crate) for_node: Option<ForwardedNode>,
+    pub(crate) proto: Option<String>,
+    pub(crate) host: Option<String>,
+}
+
+/// Parses the `Forwarded` headers of a request, see
+/// [RFC 7239](https://www.rfc-editor.org/rfc/rfc7239).
+///
+/// The elements of all the headers are returned in order, so the first one
+/// describes the client. The values can be quoted strings, such as
+/// `for="[2001:db8::1]:1234"`, and the unknown parameters are ignored.
+///
+/// Returns `None` if any of the headers is invalid, such as an unterminated
+/// quoted string. An invalid element is never skipped, because it would shift
+/// the position of the element appended by a trusted proxy.
+pub(crate) fn parse_forwarded(headers: &HeaderMap) -> Option<Vec<ForwardedElement>> {
+    let mut elements = Vec::new();
+
+    for value in headers.get_all(header::FORWARDED) {
+        for element in split_unquoted(value.to_str().ok()?, ',')? {
+            // empty list elements are allowed
+            if !element.trim().is_empty() {
+                elements.push(parse_element(element)?);
+            }
+        }
+    }
+
+    Some(elements)
+}
+
+/// Splits the value at the separators which are not in a quoted string,
+/// returns `None` if a quoted string is not terminated.
+fn split_unquoted(value: &str, sep: char) -> Option<Vec<&str>> {
+    let mut parts = Vec::new();
+    let (mut start, mut quoted, mut escaped) = (0, false, false);
+
+    for (idx, c) in value.char_indices() {
+        if escaped {
+            escaped = false;
+        } else if quoted && c == '\\' {
+            escaped = true;
+        } else if c == '"' {
+            quoted = !quoted;
+        } else if c == sep && !quoted {
+            parts.push(&value[start..idx]);
+            start = idx + 1;
+        }
+    }
+    if quoted {
+        return None;
+    }
+    parts.push(&value[start..]);
+    Some(parts)
+}
+
+fn unquote(value: &str) -> Option<String> {
+    let Some(value) = value.strip_prefix('"') else {
+        return (!value.contains('"')).then(|| value.to_string());
+    };
+
+    let mut chars = value.strip_suffix('"')?.chars();
+    let mut s = String::with_capacity(value.len());
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => s.push(chars.next()?),
+            '"' => return None,
+            _ => s.push(c),
+        }
+    }
+    Some(s)
+}
+
+fn parse_element(element: &str) -> Option<ForwardedElement> {
+    let mut item = ForwardedElement::default();
+
+    for pair in split_unquoted(element, ';')? {
+        if pair.trim().is_empty() {
+            continue;
+        }
+        let (name, value) = pair.split_once('=')?;
+        let value = unquote(value.trim()).filter(|value| !value.is_empty())?;
+        let name = name.trim();
+
+        if name.eq_ignore_ascii_case("for") {
+            item.for_node = Some(parse_node(&value));
+        } else if name.eq_ignore_ascii_case("proto") {
+            item.proto = Some(value);
+        } else if name.eq_ignore_ascii_case("host") {
+            item.host = Some(value);
+        }
+    }
+
+    Some(item)
+}
+
+fn parse_node(value: &str) -> ForwardedNode {
+    if value.eq_ignore_ascii_case("unknown") {
+        return ForwardedNode::Unknown;
+    } else if value.starts_with('_') {
+        return ForwardedNode::Obfuscated;
+    }
+
+    let ip = match value.strip_prefix('[') {
+        Some(value) => value
+            .split_once(']')
+            .and_then(|(ip, _)| ip.parse::<Ipv6Addr>().ok())
+            .map(IpAddr::V6),
+        // some proxies do not put the IPv6 addresses in brackets
+        None => value.parse::<IpAddr>().ok().or_else(|| {
+            value
+                .split_once(':')
+                .and_then(|(ip, _)| ip.parse::<Ipv4Addr>().ok())
+                .map(IpAddr::V4)
+        }),
+    };
+    ip.map(ForwardedNode::Ip).unwrap_or(ForwardedNode::Unknown)
+}
+
+#[cfg(test)]
+mod tests {
+    use http::HeaderValue;
+
+    use super::*;
+
+    fn parse(values: &[&str]) -> Option<Vec<ForwardedElement>> {
+        let mut headers = HeaderMap::new();
+        for value in values {
+            headers.append(header::FORWARDED, HeaderValue::from_str(value).unwrap());
+        }
+        parse_forwarded(&headers)
+    }
+
+    fn ip(ip: &str) -> Option<ForwardedNode> {
+        Some(ForwardedNode::Ip(ip.parse().unwrap()))
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(
+            parse(&["for=192.0.2.60;Proto=http;by=203.0.113.43;host=\"example.com:8080\""]),
+            Some(vec![ForwardedElement {
+                for_node: ip("192.0.2.60"),
+                proto: Some("http".to_string()),
+                host: Some("example.com:8080".to_string()),
+            }])
+        );
+
+        assert_eq!(
+            parse(&[
+                "for=\"[2001:db8:cafe::17]:4711\", for=192.0.2.43:47011",
+                "FOR=unknown,, for=_hidden;host=\"a,b;c=d\\\"\"",
+            ]),
+            Some(vec![
+                ForwardedElement {
+                    for_node: ip("2001:db8:cafe::17"),
+                    ..Default::default()
+                },
+                ForwardedElement {
+                    for_node: ip("192.0.2.43"),
+                    ..Default::default()
+                },
+                ForwardedElement {
+                    for_node: Some(ForwardedNode::Unknown),
+                    ..Default::default()
+                },
+                ForwardedElement {
+                    for_node: Some(ForwardedNode::Obfuscated),
+                    host: Some("a,b;c=d\"".to_string()),
+                    ..Default::default()
+                },
+            ])
+        );
+
+        // the elements without the known parameters are kept
+        assert_eq!(
+            parse(&["for=192.0.2.43, by=203.0.113.43"]),
+            Some(vec![
+                ForwardedElement {
+                    for_node: ip("192.0.2.43"),
+                    ..Default::default()
+                },
+                ForwardedElement::default(),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        for value in [
+            "for=\"[2001:db8::1\";proto;host=\"unterminated, for=2001:db8::2",
+            "for=192.0.2.43, for=\"x, for=10.1.1.1;proto=http",
+            "for=192.0.2.43;proto",
+            "for=192.0.2.43;host=",
+            "for=192.0.2.43;host=a\"b\"",
+            "for=\"192.0.2.43\"x",
+        ] {
+            assert_eq!(parse(&[value]), None, "{value}");
+        }
+        assert_eq!(parse(&["for=192.0.2.43", "for=\"x"]), None);
+
+        assert_eq!(
+            parse(&["for=2001:db8::2, for=not-an-ip"]),
+            Some(vec![
+                ForwardedElement {
+                    for_node: ip("2001:db8::2"),
+                    ..Default::default()
+                },
+                ForwardedElement {
+                    for_node: Some(ForwardedNode::Unknown),
+                    ..Default::default()
+                },
+            ])
+        );
+    }
+}
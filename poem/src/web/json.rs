@@ -86,6 +86,23 @@ use crate::{
 /// resp.assert_text(r#"{"name":"foo"}"#).await;
 /// # });
 /// ```
+///
+/// # Precision and key order
+///
+/// By default, the objects deserialized to `serde_json::Value` are sorted by
+/// key, and the numbers are converted to `i64`, `u64` or `f64`, so the large
+/// integers and the decimals can lose precision. The following crate features
+/// enable the corresponding features of `serde_json`:
+///
+/// - `json-preserve-order`: `serde_json::Map` keeps the insertion order of
+///   the keys. It is backed by an `IndexMap` instead of a `BTreeMap`, which
+///   uses more memory and makes removing keys slower.
+/// - `json-raw-value`: `Box<serde_json::value::RawValue>` captures the exact
+///   text of a JSON value without parsing it, so the numbers keep their
+///   precision, e.g. to verify a signature over the body. It is the cheapest
+///   option, since the value is only validated.
+///
+/// These features have no effect with the `sonic-rs` feature.
 #[derive(Debug, Clone, Eq, PartialEq, Default)]
 pub struct Json<T>(pub T);
 
@@ -232,4 +249,42 @@ mod tests {
         })
         .await;
     }
+
+    #[cfg(all(feature = "json-preserve-order", not(feature = "sonic-rs")))]
+    #[tokio::test]
+    async fn test_json_round_trip() {
+        #[handler(internal)]
+        async fn index(value: Json<serde_json::Value>) -> Json<serde_json::Value> {
+            value
+        }
+
+        let body = r#"{"z":1,"a":[2,3],"m":{"y":true,"b":null}}"#;
+        let cli = TestClient::new(index);
+        cli.post("/")
+            .content_type("application/json")
+            .body(body)
+            .send()
+            .await
+            .assert_text(body)
+            .await;
+    }
+
+    #[cfg(all(feature = "json-raw-value", not(feature = "sonic-rs")))]
+    #[tokio::test]
+    async fn test_json_raw_value() {
+        #[handler(internal)]
+        async fn index(value: Json<Box<serde_json::value::RawValue>>) -> String {
+            value.get().to_string()
+        }
+
+        let body = r#"{ "z": 1.50, "a": [1, 2] }"#;
+        let cli = TestClient::new(index);
+        cli.post("/")
+            .content_type("application/json")
+            .body(body)
+            .send()
+            .await
+            .assert_text(body)
+            .await;
+    }
 }
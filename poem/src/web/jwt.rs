@@ -0,0 +1,381 @@
+//! JWT related types.
+
+use std::{
+    collections::HashMap,
+    ops::{Deref, DerefMut},
+    sync::Arc,
+};
+
+pub use jsonwebtoken::{encode, Algorithm, DecodingKey, EncodingKey, Header};
+use jsonwebtoken::{errors::ErrorKind, Validation};
+use serde::de::DeserializeOwned;
+
+use crate::{error::JwtError, http::header, FromRequest, Request, RequestBody, Result};
+
+/// The verification settings for the [`Jwt`] extractor.
+///
+/// The extractor reads the configuration from the request data, so it can be
+/// attached to an endpoint with [`EndpointExt::data`](crate::EndpointExt::data).
+///
+/// # Example
+///
+/// ```
+/// use poem::{
+///     handler,
+///     web::{
+///         jwt::{Algorithm, DecodingKey},
+///         Jwt, JwtConfig,
+///     },
+///     EndpointExt,
+/// };
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// struct Claims {
+///     sub: String,
+/// }
+///
+/// #[handler]
+/// fn index(claims: Jwt<Claims>) -> String {
+///     claims.sub.clone()
+/// }
+///
+/// let app = index.data(
+///     JwtConfig::new(DecodingKey::from_secret(b"secret"), Algorithm::HS256)
+///         .audience(["my-api"])
+///         .issuer(["https://auth.example.com"]),
+/// );
+/// ```
+#[derive(Clone)]
+pub struct JwtConfig {
+    key: Option<DecodingKey>,
+    keys: Arc<HashMap<String, DecodingKey>>,
+    validation: Validation,
+}
+
+impl JwtConfig {
+    /// Create a `JwtConfig` that verifies tokens with the specified key and
+    /// algorithm.
+    pub fn new(key: DecodingKey, algorithm: Algorithm) -> Self {
+        Self {
+            key: Some(key),
+            keys: Default::default(),
+            validation: Validation::new(algorithm),
+        }
+    }
+
+    /// Create a `JwtConfig` that selects the verification key by the `kid`
+    /// header of the token, add the keys with [`JwtConfig::key`].
+    pub fn with_key_ids(algorithm: Algorithm) -> Self {
+        Self {
+            key: None,
+            keys: Default::default(),
+            validation: Validation::new(algorithm),
+        }
+    }
+
+    /// Adds a verification key for the tokens whose `kid` header is the
+    /// specified key id.
+    #[must_use]
+    pub fn key(mut self, kid: impl Into<String>, key: DecodingKey) -> Self {
+        Arc::make_mut(&mut self.keys).insert(kid.into(), key);
+        self
+    }
+
+    /// Sets the accepted algorithms.
+    ///
+    /// Default is the algorithm passed to [`JwtConfig::new`].
+    #[must_use]
+    pub fn algorithms(mut self, algorithms: impl IntoIterator<Item = Algorithm>) -> Self {
+        self.validation.algorithms = algorithms.into_iter().collect();
+        self
+    }
+
+    /// Sets the expected audiences, the `aud` claim must contain one of them.
+    #[must_use]
+    pub fn audience<T: ToString>(mut self, audience: impl IntoIterator<Item = T>) -> Self {
+        self.validation
+            .set_audience(&audience.into_iter().collect::<Vec<_>>());
+        self
+    }
+
+    /// Sets the expected issuers, the `iss` claim must be one of them.
+    #[must_use]
+    pub fn issuer<T: ToString>(mut self, issuer: impl IntoIterator<Item = T>) -> Self {
+        self.validation
+            .set_issuer(&issuer.into_iter().collect::<Vec<_>>());
+        self
+    }
+
+    /// Sets the leeway in seconds when checking the `exp` and `nbf` claims.
+    ///
+    /// Default is `60`.
+    #[must_use]
+    pub fn leeway(mut self, leeway: u64) -> Self {
+        self.validation.leeway = leeway;
+        self
+    }
+
+    /// Sets the claims that must be present in the token, such as `exp`.
+    ///
+    /// Default is `["exp"]`.
+    #[must_use]
+    pub fn required_claims<T: ToString>(mut self, claims: impl IntoIterator<Item = T>) -> Self {
+        self.validation
+            .set_required_spec_claims(&claims.into_iter().collect::<Vec<_>>());
+        self
+    }
+
+    fn decode<T: DeserializeOwned>(&self, token: &str) -> Result<T, JwtError> {
+        let key = match &self.key {
+            Some(key) => key,
+            None => {
+                let header = jsonwebtoken::decode_header(token).map_err(JwtError::from)?;
+                header
+                    .kid
+                    .as_ref()
+                    .and_then(|kid| self.keys.get(kid))
+                    .ok_or(JwtError::UnknownKey)?
+            }
+        };
+        let data = jsonwebtoken::decode(token, key, &self.validation).map_err(JwtError::from)?;
+        Ok(data.claims)
+    }
+}
+
+impl From<jsonwebtoken::errors::Error> for JwtError {
+    fn from(err: jsonwebtoken::errors::Error) -> Self {
+        match err.kind() {
+            ErrorKind::ExpiredSignature => JwtError::Expired,
+            ErrorKind::ImmatureSignature => JwtError::NotYetValid,
+            ErrorKind::InvalidSignature => JwtError::InvalidSignature,
+            ErrorKind::InvalidAudience => JwtError::InvalidAudience,
+            ErrorKind::InvalidIssuer => JwtError::InvalidIssuer,
+            ErrorKind::InvalidAlgorithm => JwtError::InvalidAlgorithm,
+            _ => JwtError::Invalid(err.to_string()),
+        }
+    }
+}
+
+/// An extractor that validates the bearer token in the `Authorization` header
+/// and deserializes its claims.
+///
+/// The token is verified with the [`JwtConfig`] in the request data.
+///
+/// # Errors
+///
+/// - [`JwtError`]
+///
+/// # Example
+///
+/// ```
+/// use poem::{
+///     handler,
+///     http::StatusCode,
+///     test::TestClient,
+///     web::{
+///         jwt::{encode, Algorithm, DecodingKey, EncodingKey, Header},
+///         Jwt, JwtConfig,
+///     },
+///     EndpointExt,
+/// };
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(Serialize, Deserialize)]
+/// struct Claims {
+///     sub: String,
+///     exp: u64,
+/// }
+///
+/// #[handler]
+/// fn index(claims: Jwt<Claims>) -> String {
+///     format!("hello {}", claims.sub)
+/// }
+///
+/// let cli = TestClient::new(index.data(JwtConfig::new(
+///     DecodingKey::from_secret(b"secret"),
+///     Algorithm::HS256,
+/// )));
+///
+/// let token = encode(
+///     &Header::default(),
+///     &Claims {
+///         sub: "alice".to_string(),
+///         exp: u64::MAX / 2,
+///     },
+///     &EncodingKey::from_secret(b"secret"),
+/// )
+/// .unwrap();
+///
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// let resp = cli
+///     .get("/")
+///     .header("authorization", format!("Bearer {token}"))
+///     .send()
+///     .await;
+/// resp.assert_status_is_ok();
+/// resp.assert_text("hello alice").await;
+///
+/// let resp = cli.get("/").send().await;
+/// resp.assert_status(StatusCode::UNAUTHORIZED);
+/// # });
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Jwt<T>(pub T);
+
+impl<T> Deref for Jwt<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for Jwt<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl<'a, T: DeserializeOwned> FromRequest<'a> for Jwt<T> {
+    async fn from_request(req: &'a Request, _body: &mut RequestBody) -> Result<Self> {
+        let config = req.data::<JwtConfig>().ok_or(JwtError::ConfigRequired)?;
+        let token = req
+            .headers()
+            .get(header::AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| {
+                let (scheme, token) = value.split_once(' ')?;
+                scheme.eq_ignore_ascii_case("bearer").then(|| token.trim())
+            })
+            .ok_or(JwtError::MissingToken)?;
+        Ok(Jwt(config.decode(token)?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use http::StatusCode;
+    use serde::{Deserialize, Serialize};
+
+    use super::*;
+    use crate::{handler, test::TestClient, EndpointExt};
+
+    #[derive(Debug, Serialize, Deserialize, PartialEq)]
+    struct Claims {
+        sub: String,
+        aud: String,
+        exp: u64,
+    }
+
+    fn now() -> u64 {
+        jsonwebtoken::get_current_timestamp()
+    }
+
+    fn token(header: Header, claims: &Claims, secret: &[u8]) -> String {
+        jsonwebtoken::encode(&header, claims, &EncodingKey::from_secret(secret)).unwrap()
+    }
+
+    fn claims(aud: &str, exp: u64) -> Claims {
+        Claims {
+            sub: "alice".to_string(),
+            aud: aud.to_string(),
+            exp,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_jwt() {
+        #[handler(internal)]
+        fn index(claims: Jwt<Claims>) -> String {
+            claims.0.sub
+        }
+
+        let cli = TestClient::new(index.data(
+            JwtConfig::new(DecodingKey::from_secret(b"secret"), Algorithm::HS256).audience(["api"]),
+        ));
+        let check = |token: Option<String>, status: StatusCode, body: &'static str| {
+            let req = cli.get("/");
+            let req = match token {
+                Some(token) => req.header(header::AUTHORIZATION, format!("Bearer {token}")),
+                None => req,
+            };
+            async move {
+                let resp = req.send().await;
+                resp.assert_status(status);
+                resp.assert_text(body).await;
+            }
+        };
+
+        check(
+            Some(token(
+                Header::default(),
+                &claims("api", now() + 60),
+                b"secret",
+            )),
+            StatusCode::OK,
+            "alice",
+        )
+        .await;
+        check(None, StatusCode::UNAUTHORIZED, "missing bearer token").await;
+        check(
+            Some(token(
+                Header::default(),
+                &claims("api", now() - 3600),
+                b"secret",
+            )),
+            StatusCode::UNAUTHORIZED,
+            "token has expired",
+        )
+        .await;
+        check(
+            Some(token(
+                Header::default(),
+                &claims("api", now() + 60),
+                b"other",
+            )),
+            StatusCode::UNAUTHORIZED,
+            "invalid token signature",
+        )
+        .await;
+        check(
+            Some(token(
+                Header::default(),
+                &claims("web", now() + 60),
+                b"secret",
+            )),
+            StatusCode::UNAUTHORIZED,
+            "invalid token audience",
+        )
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_key_ids() {
+        let config = JwtConfig::with_key_ids(Algorithm::HS256)
+            .key("a", DecodingKey::from_secret(b"secret-a"))
+            .key("b", DecodingKey::from_secret(b"secret-b"))
+            .audience(["api"]);
+
+        let header = |kid: &str| Header {
+            kid: Some(kid.to_string()),
+            ..Header::default()
+        };
+        let claims = claims("api", now() + 60);
+
+        assert_eq!(
+            config
+                .decode::<Claims>(&token(header("b"), &claims, b"secret-b"))
+                .unwrap(),
+            claims
+        );
+        assert!(matches!(
+            config.decode::<Claims>(&token(header("a"), &claims, b"secret-b")),
+            Err(JwtError::InvalidSignature)
+        ));
+        assert!(matches!(
+            config.decode::<Claims>(&token(header("c"), &claims, b"secret-b")),
+            Err(JwtError::UnknownKey)
+        ));
+    }
+}
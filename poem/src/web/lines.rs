@@ -0,0 +1,146 @@
+use std::io::Result as IoResult;
+
+use bytes::Bytes;
+use futures_util::Stream;
+use tokio::io::{AsyncBufRead, AsyncBufReadExt};
+
+use crate::{Body, IntoResponse, Response};
+
+/// A response that streams the output of a reader line by line.
+///
+/// Each line is sent to the client as soon as it is read, so it is suitable
+/// for tailing a log file or the standard output of a child process. The
+/// response ends when the reader reaches EOF, for example when the process
+/// exits.
+///
+/// The response is sent as `text/plain; charset=utf-8` with chunked encoding,
+/// use [`Lines::into_sse`] to send each line as a server-sent event instead.
+///
+/// # Example
+///
+/// ```
+/// use poem::{handler, test::TestClient, web::Lines};
+///
+/// #[handler]
+/// fn index() -> Lines<&'static [u8]> {
+///     // e.g. `BufReader::new(child.stdout.take().unwrap())`
+///     Lines::new(b"a\nb\nc".as_ref())
+/// }
+///
+/// let cli = TestClient::new(index);
+///
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// let resp = cli.get("/").send().await;
+/// resp.assert_status_is_ok();
+/// resp.assert_content_type("text/plain; charset=utf-8");
+/// resp.assert_text("a\nb\nc").await;
+/// # });
+/// ```
+pub struct Lines<R> {
+    reader: R,
+    delimiter: u8,
+}
+
+impl<R: AsyncBufRead + Unpin + Send + 'static> Lines<R> {
+    /// Create a `Lines` response from a buffered reader.
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader,
+            delimiter: b'\n',
+        }
+    }
+
+    /// Sets the byte that terminates each chunk.
+    ///
+    /// Default is `\n`.
+    #[must_use]
+    pub fn delimiter(self, delimiter: u8) -> Self {
+        Self { delimiter, ..self }
+    }
+
+    /// Consumes this object to return a stream of the chunks, each chunk
+    /// includes its delimiter, except the last one if the reader does not end
+    /// with a delimiter.
+    pub fn into_stream(self) -> impl Stream<Item = IoResult<Bytes>> + Send + 'static {
+        let delimiter = self.delimiter;
+        futures_util::stream::try_unfold(self.reader, move |mut reader| async move {
+            let mut buf = Vec::new();
+            if reader.read_until(delimiter, &mut buf).await? == 0 {
+                return Ok(None);
+            }
+            Ok(Some((Bytes::from(buf), reader)))
+        })
+    }
+
+    /// Consumes this object to return an SSE response that sends each line
+    /// as a message event.
+    ///
+    /// The delimiter and the trailing `\r` are removed from the lines, and
+    /// the stream ends at the first read error.
+    #[cfg(feature = "sse")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "sse")))]
+    pub fn into_sse(self) -> super::sse::SSE {
+        use std::future::ready;
+
+        use futures_util::StreamExt;
+
+        let delimiter = self.delimiter;
+        let lines = self
+            .into_stream()
+            .take_while(|line| ready(line.is_ok()))
+            .filter_map(move |line| {
+                ready(line.ok().map(|line| {
+                    let line = line.strip_suffix(&[delimiter]).unwrap_or(&line[..]);
+                    let line = line.strip_suffix(b"\r").unwrap_or(line);
+                    super::sse::Event::message(String::from_utf8_lossy(line))
+                }))
+            });
+        super::sse::SSE::new(lines)
+    }
+}
+
+impl<R: AsyncBufRead + Unpin + Send + 'static> IntoResponse for Lines<R> {
+    fn into_response(self) -> Response {
+        Response::builder()
+            .content_type("text/plain; charset=utf-8")
+            .body(Body::from_bytes_stream(self.into_stream()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures_util::TryStreamExt;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn lines() {
+        let chunks: Vec<Bytes> = Lines::new(b"a\nbc\n\nd".as_ref())
+            .into_stream()
+            .try_collect()
+            .await
+            .unwrap();
+        assert_eq!(chunks, vec!["a\n", "bc\n", "\n", "d"]);
+
+        let chunks: Vec<Bytes> = Lines::new(b"a\0b\0".as_ref())
+            .delimiter(0)
+            .into_stream()
+            .try_collect()
+            .await
+            .unwrap();
+        assert_eq!(chunks, vec!["a\0", "b\0"]);
+
+        let resp = Lines::new(b"".as_ref()).into_response();
+        assert_eq!(resp.into_body().into_string().await.unwrap(), "");
+    }
+
+    #[cfg(feature = "sse")]
+    #[tokio::test]
+    async fn into_sse() {
+        let resp = Lines::new(b"a\r\nb\nc".as_ref()).into_sse().into_response();
+        assert_eq!(
+            resp.into_body().into_string().await.unwrap(),
+            "data: a\n\ndata: b\n\ndata: c\n\n"
+        );
+    }
+}
@@ -0,0 +1,118 @@
+use std::{
+    fmt::{self, Display, Formatter},
+    ops::Deref,
+    sync::Arc,
+};
+
+use crate::{
+    error::MissingMatchedPathError, route::PathPattern, FromRequest, Request, RequestBody, Result,
+};
+
+/// An extractor that returns the route template that matched the request,
+/// such as `/users/:id`, rather than the concrete path.
+///
+/// The template of the nested routes includes the prefixes of the parent
+/// routes. It has a bounded number of values, which makes it suitable as a
+/// label of the metrics or a field of the access logs, the same value is used
+/// by the [`Tracing`](crate::middleware::Tracing) and `OpenTelemetryMetrics`
+/// middlewares.
+///
+/// # Errors
+///
+/// - [`MissingMatchedPathError`]
+///
+/// # Example
+///
+/// ```
+/// use poem::{get, handler, test::TestClient, web::MatchedPath, Route};
+///
+/// #[handler]
+/// fn user(path: MatchedPath) -> String {
+///     path.to_string()
+/// }
+///
+/// let app = Route::new().nest("/api", Route::new().at("/users/:id", get(user)));
+/// let cli = TestClient::new(app);
+///
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// let resp = cli.get("/api/users/42").send().await;
+/// resp.assert_status_is_ok();
+/// resp.assert_text("/api/users/:id").await;
+/// # });
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct MatchedPath(Arc<str>);
+
+impl MatchedPath {
+    /// Returns the route template as a string slice.
+    #[inline]
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Deref for MatchedPath {
+    type Target = str;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl Display for MatchedPath {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl<'a> FromRequest<'a> for MatchedPath {
+    async fn from_request(req: &'a Request, _body: &mut RequestBody) -> Result<Self> {
+        Ok(req
+            .data::<PathPattern>()
+            .map(|pattern| MatchedPath(pattern.0.clone()))
+            .ok_or(MissingMatchedPathError)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{handler, http::StatusCode, test::TestClient, Route};
+
+    #[handler(internal)]
+    fn index(path: MatchedPath) -> String {
+        path.to_string()
+    }
+
+    #[tokio::test]
+    async fn matched_path() {
+        let cli = TestClient::new(
+            Route::new()
+                .at("/users/:id", index)
+                .nest("/files", Route::new().at("/*path", index))
+                .nest_no_strip("/static", index),
+        );
+
+        cli.get("/users/1")
+            .send()
+            .await
+            .assert_text("/users/:id")
+            .await;
+        cli.get("/files/a/b.txt")
+            .send()
+            .await
+            .assert_text("/files/*path")
+            .await;
+        cli.get("/static/a")
+            .send()
+            .await
+            .assert_text("/static")
+            .await;
+
+        TestClient::new(index)
+            .get("/")
+            .send()
+            .await
+            .assert_status(StatusCode::INTERNAL_SERVER_ERROR);
+    }
+}
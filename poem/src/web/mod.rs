@@ -2,16 +2,30 @@
 
 mod accept;
 mod addr;
+mod any_of;
+mod base_url;
+mod cached;
 #[cfg(feature = "compression")]
 mod compress;
 #[cfg(feature = "cookie")]
 #[cfg_attr(docsrs, doc(cfg(feature = "cookie")))]
 pub mod cookie;
+#[cfg(feature = "csv")]
+mod csv;
 mod data;
 mod form;
+mod forwarded;
 mod json;
+#[cfg(feature = "jwt")]
+#[cfg_attr(docsrs, doc(cfg(feature = "jwt")))]
+pub mod jwt;
+mod lines;
+mod matched_path;
 #[cfg(feature = "multipart")]
 mod multipart;
+mod multipart_response;
+mod pagination;
+mod params;
 mod path;
 mod query;
 mod real_ip;
@@ -23,6 +37,8 @@ pub mod sse;
 mod static_file;
 #[cfg(feature = "tempfile")]
 mod tempfile;
+mod tls_info;
+mod upstream_timeout;
 #[cfg(feature = "xml")]
 mod xml;
 #[cfg(feature = "yaml")]
@@ -48,9 +64,12 @@ use http::header;
 pub use self::compress::{Compress, CompressionAlgo};
 #[cfg(feature = "csrf")]
 pub use self::csrf::{CsrfToken, CsrfVerifier};
+#[cfg(feature = "csv")]
+pub use self::csv::{Csv, CsvConfig, CsvQuoteStyle, CsvStream, CsvWithConfig};
+#[cfg(feature = "jwt")]
+pub use self::jwt::{Jwt, JwtConfig};
 #[cfg(feature = "multipart")]
 pub use self::multipart::{Field, Multipart};
-pub(crate) use self::path::PathDeserializer;
 #[cfg(feature = "static-files")]
 pub use self::static_file::{StaticFileRequest, StaticFileResponse};
 #[cfg(feature = "tempfile")]
@@ -62,17 +81,29 @@ pub use self::yaml::Yaml;
 pub use self::{
     accept::Accept,
     addr::{LocalAddr, RemoteAddr},
-    data::Data,
+    any_of::{AnyOf, AnyOfExtractors},
+    base_url::{BaseUrl, TrustedProxies},
+    cached::Cached,
+    data::{Data, Ext},
     form::Form,
     json::Json,
+    lines::Lines,
+    matched_path::MatchedPath,
+    multipart_response::{MultipartPart, MultipartResponse},
+    pagination::{Page, PageConfig, PaginatedResponse},
+    params::{Params, ParamsConfig},
     path::Path,
     query::Query,
     real_ip::RealIp,
     redirect::Redirect,
+    tls_info::TlsInfo,
     typed_header::TypedHeader,
+    upstream_timeout::with_upstream_timeout,
 };
+pub(crate) use self::{base_url::external_scheme, path::PathDeserializer};
 use crate::{
     body::Body,
+    endpoint::DefaultContentType,
     error::{ReadBodyError, Result},
     http::{
         header::{HeaderMap, HeaderName},
@@ -261,9 +292,10 @@ impl RequestBody {
 ///
 ///    Extracts the `Accept` header from the incoming request.
 ///
-/// - **PathPattern**
+/// - **MatchedPath**
 ///
-///    Extracts the matched path pattern from the incoming request.
+///    Extracts the route template that matched the incoming request, such as
+///    `/users/:id`.
 ///
 /// # Create your own extractor
 ///
@@ -374,6 +406,11 @@ pub trait FromRequest<'a>: Sized {
 ///    Sets the status to `OK` and the `Content-Type` to `application/xml`. Use
 ///    [`quick-xml`](https://crates.io/crates/quick-xml) to serialize `T` into a xml string.
 ///
+/// - **Csv&lt;T>**
+///
+///    Sets the status to `OK` and the `Content-Type` to `text/csv`. Use
+///    [`csv`](https://crates.io/crates/csv) to serialize the rows into a csv string.
+///
 /// - **Bytes**
 ///
 ///    Sets the status to `OK` and the `Content-Type` to
@@ -420,6 +457,11 @@ pub trait FromRequest<'a>: Sized {
 ///    with an event stream body. Use the [`SSE::new`](sse::SSE::new) function
 ///    to create it.
 ///
+/// - **Lines&lt;R: AsyncBufRead>**
+///
+///    Sets the `Content-Type` to `text/plain; charset=utf-8` with a body that
+///    sends each line of the reader as soon as it is read.
+///
 /// # Create you own response
 ///
 /// ```
@@ -661,6 +703,7 @@ impl IntoResponse for &'static [u8] {
     fn into_response(self) -> Response {
         Response::builder()
             .content_type("application/octet-stream")
+            .extension(DefaultContentType)
             .body(self)
     }
 }
@@ -669,6 +712,7 @@ impl IntoResponse for Bytes {
     fn into_response(self) -> Response {
         Response::builder()
             .content_type("application/octet-stream")
+            .extension(DefaultContentType)
             .body(self)
     }
 }
@@ -677,6 +721,7 @@ impl IntoResponse for Vec<u8> {
     fn into_response(self) -> Response {
         Response::builder()
             .content_type("application/octet-stream")
+            .extension(DefaultContentType)
             .body(self)
     }
 }
@@ -0,0 +1,224 @@
+use std::hash::{BuildHasher, Hasher};
+
+use bytes::Bytes;
+use futures_util::{stream, StreamExt};
+
+use crate::{
+    http::{
+        header::{self, HeaderName},
+        HeaderMap, HeaderValue,
+    },
+    Body, IntoResponse, Response,
+};
+
+/// A single part of a [`MultipartResponse`].
+pub struct MultipartPart {
+    headers: HeaderMap,
+    body: Body,
+}
+
+impl MultipartPart {
+    /// Create a part with the specified body.
+    pub fn new(body: impl Into<Body>) -> Self {
+        Self {
+            headers: HeaderMap::new(),
+            body: body.into(),
+        }
+    }
+
+    /// Appends a header to this part.
+    #[must_use]
+    pub fn header<K, V>(mut self, key: K, value: V) -> Self
+    where
+        K: TryInto<HeaderName>,
+        V: TryInto<HeaderValue>,
+    {
+        let key = key.try_into();
+        let value = value.try_into();
+        if let (Ok(key), Ok(value)) = (key, value) {
+            self.headers.append(key, value);
+        }
+        self
+    }
+
+    /// Sets the `Content-Type` header of this part.
+    #[must_use]
+    pub fn content_type(self, content_type: impl AsRef<str>) -> Self {
+        self.header(header::CONTENT_TYPE, content_type.as_ref())
+    }
+
+    fn into_stream(
+        self,
+        boundary: &str,
+    ) -> impl futures_util::Stream<Item = Result<Bytes, std::io::Error>> + Send + 'static {
+        let mut head = format!("--{boundary}\r\n").into_bytes();
+        for (name, value) in &self.headers {
+            head.extend_from_slice(name.as_str().as_bytes());
+            head.extend_from_slice(b": ");
+            head.extend_from_slice(value.as_bytes());
+            head.extend_from_slice(b"\r\n");
+        }
+        head.extend_from_slice(b"\r\n");
+
+        stream::once(async move { Ok(Bytes::from(head)) })
+            .chain(self.body.into_bytes_stream())
+            .chain(stream::once(async { Ok(Bytes::from_static(b"\r\n")) }))
+    }
+}
+
+/// A `multipart/mixed` response, or another multipart subtype.
+///
+/// The parts are sent in order, and the body of each part can be a stream.
+/// A random boundary is generated unless specified with
+/// [`MultipartResponse::boundary`].
+///
+/// # Example
+///
+/// ```
+/// use poem::{
+///     handler,
+///     test::TestClient,
+///     web::{MultipartPart, MultipartResponse},
+/// };
+///
+/// #[handler]
+/// fn index() -> MultipartResponse {
+///     MultipartResponse::new()
+///         .boundary("boundary")
+///         .part(MultipartPart::new("hello").content_type("text/plain"))
+///         .part(MultipartPart::new(r#"{"a":1}"#).content_type("application/json"))
+/// }
+///
+/// let cli = TestClient::new(index);
+///
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// let resp = cli.get("/").send().await;
+/// resp.assert_status_is_ok();
+/// resp.assert_content_type("multipart/mixed; boundary=boundary");
+/// resp.assert_text(
+///     "--boundary\r\ncontent-type: text/plain\r\n\r\nhello\r\n\
+///      --boundary\r\ncontent-type: application/json\r\n\r\n{\"a\":1}\r\n\
+///      --boundary--\r\n",
+/// )
+/// .await;
+/// # });
+/// ```
+pub struct MultipartResponse {
+    subtype: String,
+    boundary: Option<String>,
+    parts: Vec<MultipartPart>,
+}
+
+impl Default for MultipartResponse {
+    fn default() -> Self {
+        Self {
+            subtype: "mixed".to_string(),
+            boundary: None,
+            parts: Vec::new(),
+        }
+    }
+}
+
+impl MultipartResponse {
+    /// Create an empty `multipart/mixed` response.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the multipart subtype, such as `related` or `form-data`.
+    ///
+    /// Default is `mixed`.
+    #[must_use]
+    pub fn subtype(self, subtype: impl Into<String>) -> Self {
+        Self {
+            subtype: subtype.into(),
+            ..self
+        }
+    }
+
+    /// Sets the boundary that separates the parts.
+    ///
+    /// The boundary must not occur in the content of the parts, by default a
+    /// random boundary is generated.
+    #[must_use]
+    pub fn boundary(self, boundary: impl Into<String>) -> Self {
+        Self {
+            boundary: Some(boundary.into()),
+            ..self
+        }
+    }
+
+    /// Appends a part to this response.
+    #[must_use]
+    pub fn part(mut self, part: MultipartPart) -> Self {
+        self.parts.push(part);
+        self
+    }
+}
+
+fn generate_boundary() -> String {
+    let state = std::collections::hash_map::RandomState::new();
+    let a = state.build_hasher().finish();
+    let mut hasher = state.build_hasher();
+    hasher.write_u64(a);
+    format!("{:016x}{:016x}", a, hasher.finish())
+}
+
+impl IntoResponse for MultipartResponse {
+    fn into_response(self) -> Response {
+        let boundary = self.boundary.unwrap_or_else(generate_boundary);
+        let content_type = format!("multipart/{}; boundary={}", self.subtype, boundary);
+        let end = Bytes::from(format!("--{boundary}--\r\n"));
+
+        let parts = self
+            .parts
+            .into_iter()
+            .map(|part| part.into_stream(&boundary))
+            .collect::<Vec<_>>();
+        let body = stream::iter(parts)
+            .flatten()
+            .chain(stream::once(async move { Ok(end) }));
+
+        Response::builder()
+            .header(header::CONTENT_TYPE, content_type)
+            .body(Body::from_bytes_stream(body))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{handler, test::TestClient};
+
+    #[tokio::test]
+    async fn test_multipart_response() {
+        #[handler(internal)]
+        fn index() -> MultipartResponse {
+            MultipartResponse::new()
+                .subtype("related")
+                .boundary("abc")
+                .part(
+                    MultipartPart::new(Body::from_bytes_stream(stream::iter([
+                        Ok::<_, std::io::Error>("hello "),
+                        Ok("world"),
+                    ])))
+                    .header("content-id", "<a>"),
+                )
+                .part(MultipartPart::new(""))
+        }
+
+        let resp = TestClient::new(index).get("/").send().await;
+        resp.assert_status_is_ok();
+        resp.assert_content_type("multipart/related; boundary=abc");
+        resp.assert_text(
+            "--abc\r\ncontent-id: <a>\r\n\r\nhello world\r\n--abc\r\n\r\n\r\n--abc--\r\n",
+        )
+        .await;
+    }
+
+    #[test]
+    fn random_boundary() {
+        assert_ne!(generate_boundary(), generate_boundary());
+        assert_eq!(generate_boundary().len(), 32);
+    }
+}
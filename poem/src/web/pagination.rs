@@ -0,0 +1,482 @@
+use http::{header, HeaderValue, Uri};
+use serde::{de::Error as _, Deserialize, Serialize};
+
+use crate::{
+    error::ParseQueryError, web::Json, FromRequest, IntoResponse, Request, RequestBody, Response,
+    Result,
+};
+
+/// The pagination settings for the [`Page`] extractor.
+///
+/// The extractor reads the configuration from the request data, so it can be
+/// attached to an endpoint with [`EndpointExt::data`](crate::EndpointExt::data),
+/// the default configuration is used if it is absent.
+///
+/// # Example
+///
+/// ```
+/// use poem::{
+///     handler,
+///     web::{Page, PageConfig},
+///     EndpointExt,
+/// };
+///
+/// #[handler]
+/// fn index(page: Page) -> String {
+///     format!("limit={} offset={}", page.limit(), page.offset())
+/// }
+///
+/// let app = index.data(PageConfig::new().default_limit(10).max_limit(50));
+/// ```
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct PageConfig {
+    default_limit: u32,
+    max_limit: u32,
+}
+
+impl Default for PageConfig {
+    fn default() -> Self {
+        Self {
+            default_limit: 20,
+            max_limit: 100,
+        }
+    }
+}
+
+impl PageConfig {
+    /// Create a `PageConfig` with the default settings.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the page size used when the request does not specify one.
+    ///
+    /// Default is `20`.
+    #[must_use]
+    pub fn default_limit(self, default_limit: u32) -> Self {
+        Self {
+            default_limit,
+            ..self
+        }
+    }
+
+    /// Sets the maximum page size, larger sizes requested by the client are
+    /// reduced to it.
+    ///
+    /// Default is `100`.
+    #[must_use]
+    pub fn max_limit(self, max_limit: u32) -> Self {
+        Self { max_limit, ..self }
+    }
+}
+
+#[derive(Deserialize)]
+struct PageParams {
+    limit: Option<u32>,
+    per_page: Option<u32>,
+    cursor: Option<String>,
+    page: Option<u64>,
+}
+
+/// An extractor that parses the pagination parameters from the query string.
+///
+/// The following parameters are supported:
+///
+/// - `limit` or `per_page`: the page size, it is clamped to
+///   [`PageConfig::max_limit`].
+/// - `cursor`: an opaque cursor returned by the previous page, for cursor
+///   based pagination.
+/// - `page`: the page number starting from `1`, for offset based pagination.
+///
+/// `cursor` and `page` cannot be used together.
+///
+/// # Errors
+///
+/// - [`ParseQueryError`]
+///
+/// # Example
+///
+/// ```
+/// use poem::{
+///     get, handler,
+///     test::TestClient,
+///     web::{Page, PaginatedResponse},
+///     Route,
+/// };
+///
+/// #[handler]
+/// fn index(page: Page) -> PaginatedResponse<u64> {
+///     let items = (page.offset()..95).take(page.limit() as usize).collect();
+///     PaginatedResponse::new(&page, items).total(95)
+/// }
+///
+/// let app = Route::new().at("/", get(index));
+/// let cli = TestClient::new(app);
+///
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// let resp = cli.get("/").query("page", &2).send().await;
+/// resp.assert_status_is_ok();
+/// resp.assert_header(
+///     "link",
+///     r#"</?limit=20&page=1>; rel="first", </?limit=20&page=1>; rel="prev", </?limit=20&page=3>; rel="next", </?limit=20&page=5>; rel="last""#,
+/// );
+/// # });
+/// ```
+#[derive(Debug, Clone)]
+pub struct Page {
+    limit: u32,
+    cursor: Option<String>,
+    page: Option<u64>,
+    uri: Uri,
+}
+
+impl Page {
+    /// Returns the page size.
+    #[inline]
+    pub fn limit(&self) -> u32 {
+        self.limit
+    }
+
+    /// Returns the cursor if the request uses cursor based pagination.
+    #[inline]
+    pub fn cursor(&self) -> Option<&str> {
+        self.cursor.as_deref()
+    }
+
+    /// Returns the page number if the request uses offset based pagination.
+    #[inline]
+    pub fn page(&self) -> Option<u64> {
+        self.page
+    }
+
+    /// Returns the number of items to skip for offset based pagination, it is
+    /// `0` if the request does not specify a page number.
+    ///
+    /// The offset saturates at [`u64::MAX`] for very large page numbers.
+    pub fn offset(&self) -> u64 {
+        self.page
+            .unwrap_or(1)
+            .saturating_sub(1)
+            .saturating_mul(self.limit as u64)
+    }
+
+    fn internal_from_request(req: &Request) -> Result<Self, ParseQueryError> {
+        let config = req.data::<PageConfig>().copied().unwrap_or_default();
+        let params: PageParams = serde_urlencoded::from_str(req.uri().query().unwrap_or_default())?;
+
+        if params.cursor.is_some() && params.page.is_some() {
+            return Err(ParseQueryError(serde_urlencoded::de::Error::custom(
+                "`cursor` and `page` cannot be used together",
+            )));
+        }
+        if params.page == Some(0) {
+            return Err(ParseQueryError(serde_urlencoded::de::Error::custom(
+                "`page` must be greater than 0",
+            )));
+        }
+
+        let limit = params
+            .limit
+            .or(params.per_page)
+            .unwrap_or(config.default_limit)
+            .clamp(1, config.max_limit.max(1));
+        Ok(Self {
+            limit,
+            cursor: params.cursor,
+            page: params.page,
+            uri: req.original_uri().clone(),
+        })
+    }
+
+    fn link(&self, key: &str, value: impl ToString, rel: &str) -> String {
+        let mut pairs = self
+            .uri
+            .query()
+            .map(|query| {
+                serde_urlencoded::from_str::<Vec<(String, String)>>(query).unwrap_or_default()
+            })
+            .unwrap_or_default();
+        pairs
+            .retain(|(name, _)| !matches!(name.as_str(), "limit" | "per_page" | "cursor" | "page"));
+        pairs.push(("limit".to_string(), self.limit.to_string()));
+        pairs.push((key.to_string(), value.to_string()));
+        let query = serde_urlencoded::to_string(&pairs).unwrap_or_default();
+        format!("<{}?{}>; rel=\"{}\"", self.uri.path(), query, rel)
+    }
+}
+
+impl<'a> FromRequest<'a> for Page {
+    async fn from_request(req: &'a Request, _body: &mut RequestBody) -> Result<Self> {
+        Self::internal_from_request(req).map_err(Into::into)
+    }
+}
+
+#[derive(Serialize)]
+struct PaginatedBody<T> {
+    items: Vec<T>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    next_cursor: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    total: Option<u64>,
+}
+
+/// A page of items that is serialized as JSON with the `Link` header
+/// ([RFC 5988](https://datatracker.ietf.org/doc/html/rfc5988)) set.
+///
+/// The body is an object with the `items`, and the optional `next_cursor` and
+/// `total` fields.
+///
+/// For cursor based pagination, the `next` link is set if
+/// [`PaginatedResponse::next_cursor`] is called. For offset based pagination,
+/// the `first`, `prev` and `next` links are set, and the `last` link is set if
+/// [`PaginatedResponse::total`] is called. If the total is unknown, the `next`
+/// link is set when the page is full.
+///
+/// See [`Page`] for an example.
+pub struct PaginatedResponse<T> {
+    page: Page,
+    items: Vec<T>,
+    next_cursor: Option<String>,
+    total: Option<u64>,
+}
+
+impl<T> PaginatedResponse<T> {
+    /// Create a response with the items of the requested page.
+    pub fn new(page: &Page, items: Vec<T>) -> Self {
+        Self {
+            page: page.clone(),
+            items,
+            next_cursor: None,
+            total: None,
+        }
+    }
+
+    /// Sets the cursor of the next page.
+    #[must_use]
+    pub fn next_cursor(self, cursor: impl Into<String>) -> Self {
+        Self {
+            next_cursor: Some(cursor.into()),
+            ..self
+        }
+    }
+
+    /// Sets the total number of items.
+    #[must_use]
+    pub fn total(self, total: u64) -> Self {
+        Self {
+            total: Some(total),
+            ..self
+        }
+    }
+
+    fn links(&self) -> Vec<String> {
+        let page = &self.page;
+        let mut links = Vec::new();
+
+        match page.page {
+            Some(current) => {
+                links.push(page.link("page", 1, "first"));
+                if current > 1 {
+                    links.push(page.link("page", current - 1, "prev"));
+                }
+                let has_next = match self.total {
+                    Some(total) => current.saturating_mul(page.limit as u64) < total,
+                    None => self.items.len() >= page.limit as usize,
+                };
+                if let Some(next) = current.checked_add(1).filter(|_| has_next) {
+                    links.push(page.link("page", next, "next"));
+                }
+                if let Some(total) = self.total {
+                    let last = total.div_ceil(page.limit as u64).max(1);
+                    links.push(page.link("page", last, "last"));
+                }
+            }
+            None => {
+                if let Some(cursor) = &self.next_cursor {
+                    links.push(page.link("cursor", cursor, "next"));
+                }
+            }
+        }
+
+        links
+    }
+
+    /// Creates the response with the body that is created by `f` from the
+    /// items, the cursor of the next page and the total, and sets the `Link`
+    /// header.
+    #[doc(hidden)]
+    pub fn into_response_with(
+        self,
+        f: impl FnOnce(Vec<T>, Option<String>, Option<u64>) -> Response,
+    ) -> Response {
+        let links = self.links();
+        let mut resp = f(self.items, self.next_cursor, self.total);
+
+        if !links.is_empty() {
+            if let Ok(value) = HeaderValue::from_str(&links.join(", ")) {
+                resp.headers_mut().insert(header::LINK, value);
+            }
+        }
+        resp
+    }
+}
+
+impl<T: Serialize + Send> IntoResponse for PaginatedResponse<T> {
+    fn into_response(self) -> Response {
+        self.into_response_with(|items, next_cursor, total| {
+            Json(PaginatedBody {
+                items,
+                next_cursor,
+                total,
+            })
+            .into_response()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use http::StatusCode;
+    use serde_json::json;
+
+    use super::*;
+    use crate::{handler, test::TestClient, EndpointExt};
+
+    #[tokio::test]
+    async fn test_page_extractor() {
+        #[handler(internal)]
+        fn index(page: Page) -> String {
+            format!(
+                "{} {:?} {:?} {}",
+                page.limit(),
+                page.cursor(),
+                page.page(),
+                page.offset()
+            )
+        }
+
+        let cli = TestClient::new(index.data(PageConfig::new().default_limit(10).max_limit(50)));
+
+        let resp = cli.get("/").send().await;
+        resp.assert_status_is_ok();
+        resp.assert_text("10 None None 0").await;
+
+        let resp = cli
+            .get("/")
+            .query("per_page", &30)
+            .query("page", &3)
+            .send()
+            .await;
+        resp.assert_status_is_ok();
+        resp.assert_text("30 None Some(3) 60").await;
+
+        let resp = cli
+            .get("/")
+            .query("limit", &500)
+            .query("cursor", &"abc")
+            .send()
+            .await;
+        resp.assert_status_is_ok();
+        resp.assert_text("50 Some(\"abc\") None 0").await;
+
+        cli.get("/")
+            .query("cursor", &"abc")
+            .query("page", &1)
+            .send()
+            .await
+            .assert_status(StatusCode::BAD_REQUEST);
+        cli.get("/")
+            .query("page", &0)
+            .send()
+            .await
+            .assert_status(StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn test_cursor_pagination() {
+        #[handler(internal)]
+        fn index(page: Page) -> PaginatedResponse<i32> {
+            let resp = PaginatedResponse::new(&page, vec![1, 2]);
+            match page.cursor() {
+                None => resp.next_cursor("b"),
+                Some(_) => resp,
+            }
+        }
+
+        let cli = TestClient::new(index);
+
+        let resp = cli.get("/items").query("q", &"x").send().await;
+        resp.assert_status_is_ok();
+        resp.assert_header("link", r#"</items?q=x&limit=20&cursor=b>; rel="next""#);
+        resp.assert_json(json!({ "items": [1, 2], "next_cursor": "b" }))
+            .await;
+
+        let resp = cli.get("/items").query("cursor", &"b").send().await;
+        resp.assert_status_is_ok();
+        resp.assert_header_is_not_exist("link");
+        resp.assert_json(json!({ "items": [1, 2] })).await;
+    }
+
+    #[tokio::test]
+    async fn test_offset_pagination() {
+        #[handler(internal)]
+        fn index(page: Page) -> PaginatedResponse<u64> {
+            let items = (page.offset()..25).take(page.limit() as usize).collect();
+            PaginatedResponse::new(&page, items).total(25)
+        }
+
+        let cli = TestClient::new(index);
+
+        let resp = cli
+            .get("/")
+            .query("page", &1)
+            .query("limit", &10)
+            .send()
+            .await;
+        resp.assert_status_is_ok();
+        resp.assert_header(
+            "link",
+            r#"</?limit=10&page=1>; rel="first", </?limit=10&page=2>; rel="next", </?limit=10&page=3>; rel="last""#,
+        );
+
+        let resp = cli
+            .get("/")
+            .query("page", &3)
+            .query("limit", &10)
+            .send()
+            .await;
+        resp.assert_status_is_ok();
+        resp.assert_header(
+            "link",
+            r#"</?limit=10&page=1>; rel="first", </?limit=10&page=2>; rel="prev", </?limit=10&page=3>; rel="last""#,
+        );
+        resp.assert_json(json!({ "items": [20, 21, 22, 23, 24], "total": 25 }))
+            .await;
+    }
+
+    #[tokio::test]
+    async fn test_page_overflow() {
+        #[handler(internal)]
+        fn index(page: Page) -> PaginatedResponse<u64> {
+            assert_eq!(page.offset(), u64::MAX);
+            PaginatedResponse::new(&page, vec![0; page.limit() as usize])
+        }
+
+        let cli = TestClient::new(index);
+
+        let resp = cli
+            .get("/")
+            .query("page", &u64::MAX)
+            .query("limit", &10)
+            .send()
+            .await;
+        resp.assert_status_is_ok();
+        resp.assert_header(
+            "link",
+            format!(
+                r#"</?limit=10&page=1>; rel="first", </?limit=10&page={}>; rel="prev""#,
+                u64::MAX - 1
+            ),
+        );
+    }
+}
@@ -0,0 +1,465 @@
+use std::{
+    collections::{HashMap, HashSet},
+    ops::{Deref, DerefMut},
+};
+
+use serde::{
+    de::{
+        value::{Error as DeError, MapDeserializer, SeqDeserializer},
+        DeserializeOwned, Deserializer, Error as _, IntoDeserializer, Visitor,
+    },
+    forward_to_deserialize_any,
+};
+
+use crate::{
+    error::ParseFormError, http::header, web::form::is_form_content_type, FromRequest, Request,
+    RequestBody, Result,
+};
+
+/// The configuration of the [`Params`] extractor, it is read from the request
+/// data if present.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ParamsConfig {
+    prefer_body: bool,
+}
+
+impl ParamsConfig {
+    /// Create a new `ParamsConfig` with the default settings.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets whether the body parameters take precedence over the query
+    /// parameters with the same name, defaults to `false`.
+    #[must_use]
+    pub fn prefer_body(self, prefer_body: bool) -> Self {
+        Self { prefer_body }
+    }
+}
+
+/// An extractor that deserializes some type from both the query string and the
+/// urlencoded body.
+///
+/// The body is only read if the `Content-Type` is
+/// `application/x-www-form-urlencoded`, with any method, otherwise the body is
+/// left untouched and only the query string is used. This allows a search
+/// endpoint to accept both `GET /search?q=...` and `POST /search` with the
+/// parameters in the body.
+///
+/// If both the query string and the body define a parameter, all the values
+/// of the query string are used and the values of the body are ignored, or
+/// the other way round if [`ParamsConfig::prefer_body`] is set. The values of
+/// a parameter are never mixed from both sources. A parameter can be repeated
+/// if it is deserialized to a sequence such as `Vec<T>`.
+///
+/// # Errors
+///
+/// - [`ReadBodyError`](crate::error::ReadBodyError)
+/// - [`ParseFormError`]
+///
+/// # Example
+///
+/// ```
+/// use poem::{handler, post, test::TestClient, web::Params, Route};
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// struct Search {
+///     q: String,
+///     page: Option<u32>,
+/// }
+///
+/// #[handler]
+/// fn search(Params(Search { q, page }): Params<Search>) -> String {
+///     format!("{}:{}", q, page.unwrap_or(1))
+/// }
+///
+/// let app = Route::new().at("/search", post(search).get(search));
+/// let cli = TestClient::new(app);
+///
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// let resp = cli.get("/search").query("q", &"poem").send().await;
+/// resp.assert_text("poem:1").await;
+///
+/// let resp = cli
+///     .post("/search")
+///     .query("page", &2)
+///     .form(&[("q", "poem")])
+///     .send()
+///     .await;
+/// resp.assert_text("poem:2").await;
+/// # });
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq, Default)]
+pub struct Params<T>(pub T);
+
+impl<T> Deref for Params<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for Params<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl<'a, T: DeserializeOwned> FromRequest<'a> for Params<T> {
+    async fn from_request(req: &'a Request, body: &mut RequestBody) -> Result<Self> {
+        let query = group_values(req.uri().query().unwrap_or_default().as_bytes())?;
+        let has_form_body = req
+            .headers()
+            .get(header::CONTENT_TYPE)
+            .and_then(|content_type| content_type.to_str().ok())
+            .is_some_and(is_form_content_type);
+        if !has_form_body {
+            return Ok(deserialize_params(query).map(Self)?);
+        }
+
+        let body = group_values(&body.take()?.into_vec().await?)?;
+        let config = req.data::<ParamsConfig>().copied().unwrap_or_default();
+        let (mut params, other) = if config.prefer_body {
+            (body, query)
+        } else {
+            (query, body)
+        };
+        let names = params
+            .iter()
+            .map(|(name, _)| name.clone())
+            .collect::<HashSet<_>>();
+        params.extend(other.into_iter().filter(|(name, _)| !names.contains(name)));
+
+        Ok(deserialize_params(params).map(Self)?)
+    }
+}
+
+/// Parses an urlencoded string, and groups the values of the parameters with
+/// the same name, in the order of their first occurrence.
+fn group_values(input: &[u8]) -> Result<Vec<(String, Values)>, ParseFormError> {
+    let pairs: Vec<(String, String)> =
+        serde_urlencoded::from_bytes(input).map_err(ParseFormError::UrlDecode)?;
+    let mut params: Vec<(String, Values)> = Vec::new();
+    let mut indices: HashMap<String, usize> = HashMap::new();
+    for (name, value) in pairs {
+        match indices.get(&name) {
+            Some(idx) => {
+                let (_, values) = &mut params[*idx];
+                values.0.push(value);
+            }
+            None => {
+                indices.insert(name.clone(), params.len());
+                params.push((name, Values(vec![value])));
+            }
+        }
+    }
+    Ok(params)
+}
+
+fn deserialize_params<T: DeserializeOwned>(
+    params: Vec<(String, Values)>,
+) -> Result<T, ParseFormError> {
+    T::deserialize(MapDeserializer::new(params.into_iter())).map_err(ParseFormError::UrlDecode)
+}
+
+/// The values of a parameter, deserialized as a sequence, or as a single value
+/// if the parameter occurs only once.
+struct Values(Vec<String>);
+
+impl Values {
+    fn single(mut self) -> Result<Value, DeError> {
+        if self.0.len() != 1 {
+            return Err(DeError::custom("expected a single value"));
+        }
+        Ok(Value(self.0.remove(0)))
+    }
+}
+
+impl IntoDeserializer<'_, DeError> for Values {
+    type Deserializer = Self;
+
+    fn into_deserializer(self) -> Self::Deserializer {
+        self
+    }
+}
+
+macro_rules! forward_to_single {
+    ($($method:ident)*) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+                self.single()?.$method(visitor)
+            }
+        )*
+    };
+}
+
+impl<'de> Deserializer<'de> for Values {
+    type Error = DeError;
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        visitor.visit_seq(SeqDeserializer::new(self.0.into_iter().map(Value)))
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(
+        self,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, DeError> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, DeError> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, DeError> {
+        self.single()?.deserialize_enum(name, variants, visitor)
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        visitor.visit_unit()
+    }
+
+    forward_to_single! {
+        deserialize_any deserialize_bool deserialize_i8 deserialize_i16 deserialize_i32
+        deserialize_i64 deserialize_u8 deserialize_u16 deserialize_u32 deserialize_u64
+        deserialize_f32 deserialize_f64 deserialize_char deserialize_str deserialize_string
+    }
+
+    forward_to_deserialize_any! {
+        i128 u128 bytes byte_buf unit unit_struct tuple_struct map struct identifier
+    }
+}
+
+/// A single value of a parameter, the numbers and booleans are parsed from
+/// the text.
+struct Value(String);
+
+impl IntoDeserializer<'_, DeError> for Value {
+    type Deserializer = Self;
+
+    fn into_deserializer(self) -> Self::Deserializer {
+        self
+    }
+}
+
+macro_rules! parse_value {
+    ($($method:ident => $visit:ident,)*) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+                visitor.$visit(self.0.parse().map_err(DeError::custom)?)
+            }
+        )*
+    };
+}
+
+impl<'de> Deserializer<'de> for Value {
+    type Error = DeError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        visitor.visit_string(self.0)
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, DeError> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, DeError> {
+        visitor.visit_enum(self.0.into_deserializer())
+    }
+
+    parse_value! {
+        deserialize_bool => visit_bool,
+        deserialize_i8 => visit_i8,
+        deserialize_i16 => visit_i16,
+        deserialize_i32 => visit_i32,
+        deserialize_i64 => visit_i64,
+        deserialize_u8 => visit_u8,
+        deserialize_u16 => visit_u16,
+        deserialize_u32 => visit_u32,
+        deserialize_u64 => visit_u64,
+        deserialize_f32 => visit_f32,
+        deserialize_f64 => visit_f64,
+        deserialize_char => visit_char,
+    }
+
+    forward_to_deserialize_any! {
+        i128 u128 str string bytes byte_buf unit unit_struct seq tuple tuple_struct map
+        struct identifier ignored_any
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::Deserialize;
+
+    use super::*;
+    use crate::{handler, http::StatusCode, test::TestClient, EndpointExt};
+
+    #[derive(Deserialize)]
+    struct Search {
+        q: String,
+        #[serde(default)]
+        tag: Vec<String>,
+    }
+
+    #[handler(internal)]
+    fn index(Params(search): Params<Search>) -> String {
+        format!("{}:{}", search.q, search.tag.join(","))
+    }
+
+    #[tokio::test]
+    async fn merge_query_and_body() {
+        let cli = TestClient::new(index);
+
+        cli.get("/")
+            .query("q", &"a")
+            .query("tag", &"x")
+            .send()
+            .await
+            .assert_text("a:x")
+            .await;
+
+        cli.post("/")
+            .form(&[("q", "a"), ("tag", "x"), ("tag", "y")])
+            .send()
+            .await
+            .assert_text("a:x,y")
+            .await;
+
+        // the query string takes precedence, the values are not mixed
+        cli.post("/")
+            .query("tag", &"z")
+            .form(&[("q", "a"), ("tag", "x"), ("tag", "y")])
+            .send()
+            .await
+            .assert_text("a:z")
+            .await;
+
+        // the body is ignored if it is not urlencoded
+        cli.post("/")
+            .query("q", &"a")
+            .content_type("application/json")
+            .body(r#"{"q":"b"}"#)
+            .send()
+            .await
+            .assert_text("a:")
+            .await;
+
+        cli.post("/")
+            .form(&[("tag", "x")])
+            .send()
+            .await
+            .assert_status(StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn ignore_unknown() {
+        #[derive(Deserialize)]
+        struct Search {
+            q: String,
+        }
+
+        #[handler(internal)]
+        fn index(Params(search): Params<Search>) -> String {
+            search.q
+        }
+
+        TestClient::new(index)
+            .get("/")
+            .query("q", &"poem")
+            .query("tag", &1)
+            .query("tag", &2)
+            .send()
+            .await
+            .assert_text("poem")
+            .await;
+    }
+
+    #[tokio::test]
+    async fn prefer_body() {
+        let cli = TestClient::new(index.data(ParamsConfig::new().prefer_body(true)));
+
+        cli.post("/")
+            .query("q", &"a")
+            .query("tag", &"z")
+            .form(&[("q", "b")])
+            .send()
+            .await
+            .assert_text("b:z")
+            .await;
+    }
+
+    #[tokio::test]
+    async fn typed_values() {
+        #[derive(Deserialize)]
+        struct Filter {
+            id: Vec<u32>,
+            active: Option<bool>,
+        }
+
+        #[handler(internal)]
+        fn index(Params(filter): Params<Filter>) -> String {
+            format!("{:?}:{:?}", filter.id, filter.active)
+        }
+
+        let cli = TestClient::new(index);
+
+        cli.get("/")
+            .query("id", &1)
+            .send()
+            .await
+            .assert_text("[1]:None")
+            .await;
+
+        cli.post("/")
+            .query("active", &true)
+            .form(&[("id", "1"), ("id", "2")])
+            .send()
+            .await
+            .assert_text("[1, 2]:Some(true)")
+            .await;
+
+        cli.get("/")
+            .query("id", &"a")
+            .send()
+            .await
+            .assert_status(StatusCode::BAD_REQUEST);
+
+        cli.get("/")
+            .query("id", &1)
+            .query("active", &true)
+            .query("active", &false)
+            .send()
+            .await
+            .assert_status(StatusCode::BAD_REQUEST);
+    }
+}
@@ -1,58 +1,46 @@
 use std::net::IpAddr;
 
-use rfc7239::{NodeIdentifier, NodeName};
+use http::{header, HeaderMap};
 
+use super::{
+    forwarded::{parse_forwarded, ForwardedNode},
+    TrustedProxies,
+};
 use crate::{Addr, FromRequest, Request, RequestBody, Result};
 
 /// An extractor that can extracts the real ip from request headers
+///
+/// The ip is taken from the `X-Real-IP` header, the `for` parameters of the
+/// `Forwarded` header, or the addresses of the `X-Forwarded-For` header. If
+/// none of them is present, or the `Forwarded` header is invalid, the address
+/// of the peer is used.
+///
+/// Without [`TrustedProxies`] in the request data, the first address of the
+/// headers is used. Otherwise the headers are only used if the peer is
+/// trusted, the addresses are walked from the right and the trusted proxies
+/// are skipped, because the addresses on the left can be sent by the client.
+/// The `X-Forwarded-For` header is preferred to the `Forwarded` header
+/// according to [`TrustedProxies::prefer_x_forwarded`].
+///
+/// If the `Forwarded` header hides the client with an obfuscated identifier
+/// or `unknown`, the ip is `None`.
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
 pub struct RealIp(pub Option<IpAddr>);
 
 impl<'a> FromRequest<'a> for RealIp {
     async fn from_request(req: &'a Request, _body: &mut RequestBody) -> Result<Self> {
-        if let Some(real_ip) = req
-            .headers()
-            .get("x-real-ip")
-            .and_then(|value| value.to_str().ok())
-            .and_then(|value| value.parse::<IpAddr>().ok())
-        {
-            return Ok(RealIp(Some(real_ip)));
-        }
+        let proxies = req.data::<TrustedProxies>();
+        let trusted = match proxies {
+            Some(proxies) => proxies.is_trusted(req.remote_addr()),
+            None => true,
+        };
 
-        if let Some(forwarded) = req
-            .headers()
-            .get("forwarded")
-            .and_then(|value| value.to_str().ok())
-            .and_then(|value| rfc7239::parse(value).collect::<Result<Vec<_>, _>>().ok())
-        {
-            if let Some(real_ip) = forwarded
-                .into_iter()
-                .find_map(|item| match item.forwarded_for {
-                    Some(NodeIdentifier {
-                        name: NodeName::Ip(ip_addr),
-                        ..
-                    }) => Some(ip_addr),
-                    _ => None,
-                })
-            {
-                return Ok(RealIp(Some(real_ip)));
+        if trusted {
+            if let Some(real_ip) = forwarded_ip(req.headers(), proxies) {
+                return Ok(RealIp(real_ip));
             }
         }
 
-        if let Some(real_ip) = req
-            .headers()
-            .get("x-forwarded-for")
-            .and_then(|value| value.to_str().ok())
-            .and_then(|value| {
-                value
-                    .split(',')
-                    .map(|value| value.trim())
-                    .find_map(|value| value.parse::<IpAddr>().ok())
-            })
-        {
-            return Ok(RealIp(Some(real_ip)));
-        }
-
         match req.remote_addr().0 {
             Addr::SocketAddr(addr) => Ok(RealIp(Some(addr.ip()))),
             _ => Ok(RealIp(None)),
@@ -60,9 +48,67 @@ impl<'a> FromRequest<'a> for RealIp {
     }
 }
 
+/// Returns `Some(None)` if the client is hidden by the forwarding headers.
+fn forwarded_ip(headers: &HeaderMap, proxies: Option<&TrustedProxies>) -> Option<Option<IpAddr>> {
+    let x_real_ip = headers
+        .get("x-real-ip")
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.parse::<IpAddr>().ok());
+    if let Some(ip) = x_real_ip {
+        return Some(Some(ip));
+    }
+
+    let standard = || {
+        headers.contains_key(header::FORWARDED).then(|| {
+            // an invalid header is ignored as a whole
+            parse_forwarded(headers)
+                .unwrap_or_default()
+                .into_iter()
+                .filter_map(|item| item.for_node)
+                .map(|node| match node {
+                    ForwardedNode::Ip(ip) => Some(ip),
+                    ForwardedNode::Unknown | ForwardedNode::Obfuscated => None,
+                })
+                .collect::<Vec<_>>()
+        })
+    };
+    let x_forwarded_for = || {
+        headers.contains_key("x-forwarded-for").then(|| {
+            headers
+                .get_all("x-forwarded-for")
+                .iter()
+                .filter_map(|value| value.to_str().ok())
+                .flat_map(|value| value.split(','))
+                .map(|value| value.trim().parse::<IpAddr>().ok())
+                .collect::<Vec<_>>()
+        })
+    };
+
+    let nodes = if proxies.is_some_and(TrustedProxies::prefers_x_forwarded) {
+        x_forwarded_for().or_else(standard)
+    } else {
+        standard().or_else(x_forwarded_for)
+    }?;
+
+    match proxies {
+        // the rightmost address which is not a trusted proxy is the client,
+        // a hidden client is not replaced by the address of a proxy
+        Some(proxies) => nodes
+            .iter()
+            .rev()
+            .find(|node| !node.is_some_and(|ip| proxies.is_trusted_ip(ip)))
+            .or_else(|| nodes.first())
+            .copied(),
+        None => nodes.first().copied(),
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    use std::net::SocketAddr;
+
     use super::*;
+    use crate::web::RemoteAddr;
 
     fn create_request(header: &str, value: &str) -> Request {
         Request::builder().header(header, value).finish()
@@ -97,4 +143,119 @@ mod tests {
             RealIp(Some("192.0.2.43".parse().unwrap()))
         );
     }
+
+    #[tokio::test]
+    async fn test_realip_forwarded() {
+        assert_eq!(
+            RealIp::from_request_without_body(&create_request(
+                "forwarded",
+                "for=\"[2001:db8:cafe::17]:4711\";proto=https, for=192.0.2.43"
+            ))
+            .await
+            .unwrap(),
+            RealIp(Some("2001:db8:cafe::17".parse().unwrap()))
+        );
+
+        assert_eq!(
+            RealIp::from_request_without_body(&create_request(
+                "forwarded",
+                "for=_hidden, for=198.51.100.17"
+            ))
+            .await
+            .unwrap(),
+            RealIp(None)
+        );
+
+        // a hidden client is not replaced by the address of the peer
+        for value in ["for=_hidden, for=198.51.100.17", "for=unknown"] {
+            let mut req = create_request("forwarded", value);
+            req.state_mut().remote_addr =
+                RemoteAddr(SocketAddr::from(([10, 0, 0, 1], 1234)).into());
+            assert_eq!(
+                RealIp::from_request_without_body(&req).await.unwrap(),
+                RealIp(None)
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_realip_trusted_proxies() {
+        let mut req = Request::builder()
+            .header("forwarded", "for=192.0.2.43")
+            .header("x-forwarded-for", "203.0.113.195")
+            .finish();
+        req.state_mut().remote_addr = RemoteAddr(SocketAddr::from(([10, 0, 0, 1], 1234)).into());
+
+        req.set_data(TrustedProxies::new().trust([10, 0, 0, 1]));
+        assert_eq!(
+            RealIp::from_request_without_body(&req).await.unwrap(),
+            RealIp(Some("192.0.2.43".parse().unwrap()))
+        );
+
+        req.set_data(
+            TrustedProxies::new()
+                .trust([10, 0, 0, 1])
+                .prefer_x_forwarded(),
+        );
+        assert_eq!(
+            RealIp::from_request_without_body(&req).await.unwrap(),
+            RealIp(Some("203.0.113.195".parse().unwrap()))
+        );
+
+        // the addresses on the left of the trusted proxies can be spoofed
+        let mut req = Request::builder()
+            .header("forwarded", "for=203.0.113.1, for=192.0.2.43, for=10.0.0.2")
+            .header("x-forwarded-for", "203.0.113.1, 203.0.113.195, 10.0.0.2")
+            .finish();
+        req.state_mut().remote_addr = RemoteAddr(SocketAddr::from(([10, 0, 0, 1], 1234)).into());
+        req.set_data(
+            TrustedProxies::new()
+                .trust([10, 0, 0, 1])
+                .trust([10, 0, 0, 2]),
+        );
+        assert_eq!(
+            RealIp::from_request_without_body(&req).await.unwrap(),
+            RealIp(Some("192.0.2.43".parse().unwrap()))
+        );
+        req.set_data(
+            TrustedProxies::new()
+                .trust([10, 0, 0, 1])
+                .trust([10, 0, 0, 2])
+                .prefer_x_forwarded(),
+        );
+        assert_eq!(
+            RealIp::from_request_without_body(&req).await.unwrap(),
+            RealIp(Some("203.0.113.195".parse().unwrap()))
+        );
+
+        // all the proxies are trusted
+        req.set_data(TrustedProxies::new().trust_all());
+        assert_eq!(
+            RealIp::from_request_without_body(&req).await.unwrap(),
+            RealIp(Some("203.0.113.1".parse().unwrap()))
+        );
+
+        // an invalid header is ignored
+        let mut req = Request::builder()
+            .header("forwarded", "for=192.0.2.43, for=\"x, for=10.0.0.2")
+            .header("x-forwarded-for", "203.0.113.195")
+            .finish();
+        req.state_mut().remote_addr = RemoteAddr(SocketAddr::from(([10, 0, 0, 1], 1234)).into());
+        req.set_data(TrustedProxies::new().trust([10, 0, 0, 1]));
+        assert_eq!(
+            RealIp::from_request_without_body(&req).await.unwrap(),
+            RealIp(Some("10.0.0.1".parse().unwrap()))
+        );
+
+        // the forwarding headers of an untrusted peer are ignored
+        let mut req = Request::builder()
+            .header("forwarded", "for=192.0.2.43")
+            .finish();
+        req.state_mut().remote_addr = RemoteAddr(SocketAddr::from(([10, 0, 0, 1], 1234)).into());
+        req.set_data(TrustedProxies::new().trust([10, 0, 0, 2]));
+        assert_eq!(
+            RealIp::from_request_without_body(&req).await.unwrap(),
+            RealIp(Some("10.0.0.1".parse().unwrap()))
+        );
+    }
 }
@@ -1,6 +1,8 @@
 use std::{
-    collections::Bound,
+    collections::{hash_map::RandomState, Bound},
     fs::Metadata,
+    future::ready,
+    hash::{BuildHasher, Hasher},
     io::{Seek, SeekFrom},
     path::Path,
     str::FromStr,
@@ -8,9 +10,10 @@ use std::{
 };
 
 use bytes::Bytes;
+use futures_util::{stream, stream::BoxStream, StreamExt};
 use headers::{
-    ContentRange, ETag, HeaderMapExt, IfMatch, IfModifiedSince, IfNoneMatch, IfUnmodifiedSince,
-    Range,
+    ContentRange, ETag, HeaderMapExt, IfMatch, IfModifiedSince, IfNoneMatch, IfRange,
+    IfUnmodifiedSince, LastModified, Range,
 };
 use http::{header, StatusCode};
 use httpdate::HttpDate;
@@ -23,6 +26,7 @@ use crate::{
 
 /// A response for static file extractor.
 #[derive(Debug)]
+#[non_exhaustive]
 pub enum StaticFileResponse {
     /// 200 OK
     Ok {
@@ -39,6 +43,20 @@ pub enum StaticFileResponse {
         /// `Content-Range` header value
         content_range: Option<(std::ops::Range<u64>, u64)>,
     },
+    /// 206 PARTIAL CONTENT with multiple ranges, which are sent as a
+    /// `multipart/byteranges` body
+    MultipleRanges {
+        /// The ranges and their bodies
+        parts: Vec<(std::ops::Range<u64>, Body)>,
+        /// The length of the complete content
+        size: u64,
+        /// Content type of each part
+        content_type: Option<String>,
+        /// `ETag` header value
+        etag: Option<String>,
+        /// `Last-Modified` header value
+        last_modified: Option<String>,
+    },
     /// 304 NOT MODIFIED
     NotModified,
 }
@@ -46,7 +64,9 @@ pub enum StaticFileResponse {
 impl StaticFileResponse {
     /// Set the content type
     pub fn with_content_type(mut self, ct: impl Into<String>) -> Self {
-        if let StaticFileResponse::Ok { content_type, .. } = &mut self {
+        if let StaticFileResponse::Ok { content_type, .. }
+        | StaticFileResponse::MultipleRanges { content_type, .. } = &mut self
+        {
             *content_type = Some(ct.into());
         }
         self
@@ -86,12 +106,67 @@ impl IntoResponse for StaticFileResponse {
 
                 builder.body(body)
             }
+            StaticFileResponse::MultipleRanges {
+                parts,
+                size,
+                content_type,
+                etag,
+                last_modified,
+            } => {
+                let boundary = format!("{:016x}", RandomState::new().build_hasher().finish());
+                let mut content_length = 0;
+                let mut streams: Vec<BoxStream<'static, Result<Bytes, std::io::Error>>> =
+                    Vec::with_capacity(parts.len() * 2 + 1);
+
+                for (range, body) in parts {
+                    let mut head = format!("--{boundary}\r\n");
+                    if let Some(content_type) = &content_type {
+                        head.push_str(&format!("content-type: {content_type}\r\n"));
+                    }
+                    head.push_str(&format!(
+                        "content-range: bytes {}-{}/{}\r\n\r\n",
+                        range.start,
+                        range.end - 1,
+                        size
+                    ));
+                    content_length += head.len() as u64 + range.end - range.start + 2;
+                    streams.push(stream::once(ready(Ok(Bytes::from(head)))).boxed());
+                    streams.push(
+                        body.into_bytes_stream()
+                            .chain(stream::once(ready(Ok(Bytes::from_static(b"\r\n")))))
+                            .boxed(),
+                    );
+                }
+                let tail = format!("--{boundary}--\r\n");
+                content_length += tail.len() as u64;
+                streams.push(stream::once(ready(Ok(Bytes::from(tail)))).boxed());
+
+                let mut builder = Response::builder()
+                    .status(StatusCode::PARTIAL_CONTENT)
+                    .header(header::ACCEPT_RANGES, "bytes")
+                    .header(header::CONTENT_LENGTH, content_length)
+                    .content_type(format!("multipart/byteranges; boundary={boundary}"));
+                if let Some(etag) = etag {
+                    builder = builder.header(header::ETAG, etag);
+                }
+                if let Some(last_modified) = last_modified {
+                    builder = builder.header(header::LAST_MODIFIED, last_modified);
+                }
+                builder.body(Body::from_bytes_stream(stream::iter(streams).flatten()))
+            }
             StaticFileResponse::NotModified => StatusCode::NOT_MODIFIED.into(),
         }
     }
 }
 
 /// An extractor for responding static files.
+///
+/// It handles the conditional requests, and the `Range` requests with
+/// `206 Partial Content`, the multiple ranges are sent as a
+/// `multipart/byteranges` body. The ranges are ignored if the `If-Range`
+/// validator does not match the current `ETag` or `Last-Modified` of the
+/// file, and the requests without any satisfiable range fail with
+/// `416 Range Not Satisfiable`.
 #[derive(Debug)]
 pub struct StaticFileRequest {
     if_match: Option<IfMatch>,
@@ -99,6 +174,7 @@ pub struct StaticFileRequest {
     if_none_match: Option<IfNoneMatch>,
     if_modified_since: Option<IfModifiedSince>,
     range: Option<Range>,
+    if_range: Option<IfRange>,
 }
 
 impl<'a> FromRequest<'a> for StaticFileRequest {
@@ -109,6 +185,7 @@ impl<'a> FromRequest<'a> for StaticFileRequest {
             if_none_match: req.headers().typed_get::<IfNoneMatch>(),
             if_modified_since: req.headers().typed_get::<IfModifiedSince>(),
             range: req.headers().typed_get::<Range>(),
+            if_range: req.headers().typed_get::<IfRange>(),
         })
     }
 }
@@ -123,41 +200,37 @@ impl StaticFileRequest {
         data: impl AsRef<[u8]>,
     ) -> Result<StaticFileResponse, StaticFileError> {
         let data = data.as_ref();
+        let size = data.len() as u64;
 
-        // content length
-        let mut content_length = data.len() as u64;
-        let mut content_range = None;
-
-        let body = if let Some((start, end)) = self
-            .range
-            .and_then(|range| range.satisfiable_ranges(data.len() as u64).next())
-        {
-            let start = match start {
-                Bound::Included(n) => n,
-                Bound::Excluded(n) => n + 1,
-                Bound::Unbounded => 0,
-            };
-            let end = match end {
-                Bound::Included(n) => n + 1,
-                Bound::Excluded(n) => n,
-                Bound::Unbounded => content_length,
-            };
-            if end < start || end > content_length {
-                return Err(StaticFileError::RangeNotSatisfiable {
-                    size: content_length,
-                });
-            }
-
-            if start != 0 || end != content_length {
-                content_range = Some((start..end, content_length));
-            }
-
-            content_length = end - start;
+        // the content can not be validated, so the ranges are only used
+        // without `If-Range`
+        let range = self.range.filter(|_| self.if_range.is_none());
+        let mut ranges = resolve_ranges(range.as_ref(), size)?;
+        let slice = |range: &std::ops::Range<u64>| {
             Body::from_bytes(Bytes::copy_from_slice(
-                &data[start as usize..(start + content_length) as usize],
+                &data[range.start as usize..range.end as usize],
             ))
-        } else {
-            Body::from_bytes(Bytes::copy_from_slice(data))
+        };
+
+        if ranges.len() > 1 {
+            return Ok(StaticFileResponse::MultipleRanges {
+                parts: ranges
+                    .into_iter()
+                    .map(|range| {
+                        let body = slice(&range);
+                        (range, body)
+                    })
+                    .collect(),
+                size,
+                content_type: None,
+                etag: None,
+                last_modified: None,
+            });
+        }
+
+        let (body, content_length, content_range) = match ranges.pop() {
+            Some(range) => (slice(&range), range.end - range.start, Some((range, size))),
+            None => (Body::from_bytes(Bytes::copy_from_slice(data)), size, None),
         };
 
         Ok(StaticFileResponse::Ok {
@@ -187,8 +260,7 @@ impl StaticFileRequest {
         let mut file = std::fs::File::open(path)?;
         let metadata = file.metadata()?;
 
-        // content length
-        let mut content_length = metadata.len();
+        let size = metadata.len();
 
         // content type
         let content_type = guess.first().map(|mime| {
@@ -202,6 +274,7 @@ impl StaticFileRequest {
         // etag and last modified
         let mut etag_str = String::new();
         let mut last_modified_str = String::new();
+        let mut range = self.range;
 
         if let Ok(modified) = metadata.modified() {
             etag_str = etag(ino(&metadata), &modified, metadata.len());
@@ -229,15 +302,85 @@ impl StaticFileRequest {
                 }
             }
 
+            // the ranges of a stale partial content are not sent
+            if let Some(if_range) = &self.if_range {
+                if if_range.is_modified(Some(&etag), Some(&LastModified::from(modified))) {
+                    range = None;
+                }
+            }
+
             last_modified_str = HttpDate::from(modified).to_string();
+        } else if self.if_range.is_some() {
+            range = None;
         }
 
-        let mut content_range = None;
+        let etag = (!etag_str.is_empty()).then_some(etag_str);
+        let last_modified = (!last_modified_str.is_empty()).then_some(last_modified_str);
+        let mut ranges = resolve_ranges(range.as_ref(), size)?;
+
+        if ranges.len() > 1 {
+            let mut parts = Vec::with_capacity(ranges.len());
+            for range in ranges {
+                let mut file = std::fs::File::open(path)?;
+                file.seek(SeekFrom::Start(range.start))?;
+                let body =
+                    Body::from_async_read(File::from_std(file).take(range.end - range.start));
+                parts.push((range, body));
+            }
+            return Ok(StaticFileResponse::MultipleRanges {
+                parts,
+                size,
+                content_type,
+                etag,
+                last_modified,
+            });
+        }
 
-        let body = if let Some((start, end)) = self
-            .range
-            .and_then(|range| range.satisfiable_ranges(metadata.len()).next())
-        {
+        let (body, content_length, content_range) = match ranges.pop() {
+            Some(range) => {
+                file.seek(SeekFrom::Start(range.start))?;
+                let len = range.end - range.start;
+                (
+                    Body::from_async_read(File::from_std(file).take(len)),
+                    len,
+                    Some((range, size)),
+                )
+            }
+            None => (Body::from_async_read(File::from_std(file)), size, None),
+        };
+
+        Ok(StaticFileResponse::Ok {
+            body,
+            content_length,
+            content_type,
+            etag,
+            last_modified,
+            content_range,
+        })
+    }
+}
+
+/// The maximum number of ranges of a request, the `Range` header is ignored if
+/// it has more ranges, so a request can not open a file for each of many tiny
+/// ranges.
+const MAX_RANGES: usize = 16;
+
+/// Resolves the ranges of the `Range` header, returns an empty list if the
+/// complete content should be sent.
+///
+/// The overlapping and adjacent ranges are coalesced, so the same bytes are
+/// not sent more than once.
+fn resolve_ranges(
+    range: Option<&Range>,
+    size: u64,
+) -> Result<Vec<std::ops::Range<u64>>, StaticFileError> {
+    let Some(range) = range else {
+        return Ok(Vec::new());
+    };
+
+    let mut ranges = range
+        .satisfiable_ranges(size)
+        .filter_map(|(start, end)| {
             let start = match start {
                 Bound::Included(n) => n,
                 Bound::Excluded(n) => n + 1,
@@ -246,41 +389,28 @@ impl StaticFileRequest {
             let end = match end {
                 Bound::Included(n) => n + 1,
                 Bound::Excluded(n) => n,
-                Bound::Unbounded => metadata.len(),
+                Bound::Unbounded => size,
             };
-            if end < start || end > metadata.len() {
-                return Err(StaticFileError::RangeNotSatisfiable {
-                    size: metadata.len(),
-                });
-            }
-
-            if start != 0 || end != metadata.len() {
-                content_range = Some((start..end, metadata.len()));
+            (start < end && end <= size).then_some(start..end)
+        })
+        .collect::<Vec<_>>();
+    if ranges.len() > 1 {
+        ranges.sort_by_key(|range| range.start);
+        ranges.dedup_by(|next, prev| {
+            if next.start <= prev.end {
+                prev.end = prev.end.max(next.end);
+                true
+            } else {
+                false
             }
+        });
+    }
 
-            content_length = end - start;
-            file.seek(SeekFrom::Start(start))?;
-            Body::from_async_read(File::from_std(file).take(end - start))
-        } else {
-            Body::from_async_read(File::from_std(file))
-        };
-
-        Ok(StaticFileResponse::Ok {
-            body,
-            content_length,
-            content_type,
-            etag: if !etag_str.is_empty() {
-                Some(etag_str)
-            } else {
-                None
-            },
-            last_modified: if !last_modified_str.is_empty() {
-                Some(last_modified_str)
-            } else {
-                None
-            },
-            content_range,
-        })
+    match ranges.as_slice() {
+        [] => Err(StaticFileError::RangeNotSatisfiable { size }),
+        [range] if range.start == 0 && range.end == size => Ok(Vec::new()),
+        _ if ranges.len() > MAX_RANGES => Ok(Vec::new()),
+        _ => Ok(ranges),
     }
 }
 
@@ -343,6 +473,7 @@ mod tests {
     use std::{path::Path, time::Duration};
 
     use super::*;
+    use crate::error::ResponseError;
 
     impl StaticFileResponse {
         fn etag(&self) -> String {
@@ -514,7 +645,7 @@ mod tests {
             StaticFileResponse::Ok { content_range, .. } => {
                 assert_eq!(content_range.unwrap().0, 0..10);
             }
-            StaticFileResponse::NotModified => panic!(),
+            _ => panic!(),
         }
     }
 
@@ -536,7 +667,7 @@ mod tests {
             StaticFileResponse::Ok { content_range, .. } => {
                 assert!(content_range.is_none());
             }
-            StaticFileResponse::NotModified => panic!(),
+            _ => panic!(),
         }
     }
 
@@ -560,4 +691,143 @@ mod tests {
             _ => panic!(),
         }
     }
+
+    async fn range_request(header: &str, if_range: Option<&str>) -> StaticFileRequest {
+        let mut req = Request::builder().header("range", header);
+        if let Some(if_range) = if_range {
+            req = req.header("if-range", if_range);
+        }
+        StaticFileRequest::from_request_without_body(&req.finish())
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_range_multiple() {
+        let resp = range_request("bytes=0-1, 6-", None)
+            .await
+            .create_response_from_data("hello world")
+            .unwrap()
+            .with_content_type("text/plain")
+            .into_response();
+        assert_eq!(resp.status(), StatusCode::PARTIAL_CONTENT);
+
+        let content_type = resp.headers()[header::CONTENT_TYPE].to_str().unwrap();
+        let boundary = content_type
+            .strip_prefix("multipart/byteranges; boundary=")
+            .unwrap()
+            .to_string();
+        let content_length: usize = resp.headers()[header::CONTENT_LENGTH]
+            .to_str()
+            .unwrap()
+            .parse()
+            .unwrap();
+        let body = resp.into_body().into_string().await.unwrap();
+        assert_eq!(body.len(), content_length);
+        assert_eq!(
+            body,
+            format!(
+                "--{boundary}\r\ncontent-type: text/plain\r\ncontent-range: bytes 0-1/11\r\n\r\nhe\r\n\
+                 --{boundary}\r\ncontent-type: text/plain\r\ncontent-range: bytes 6-10/11\r\n\r\nworld\r\n\
+                 --{boundary}--\r\n"
+            )
+        );
+    }
+
+    #[tokio::test]
+    async fn test_range_multiple_file() {
+        let resp = range_request("bytes=0-0, 2-3", None)
+            .await
+            .create_response(Path::new("Cargo.toml"), false)
+            .unwrap();
+        match resp {
+            StaticFileResponse::MultipleRanges { parts, size, .. } => {
+                assert_eq!(size, std::fs::metadata("Cargo.toml").unwrap().len());
+                assert_eq!(
+                    parts
+                        .iter()
+                        .map(|(range, _)| range.clone())
+                        .collect::<Vec<_>>(),
+                    vec![0..1, 2..4]
+                );
+            }
+            _ => panic!(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_range_coalesce() {
+        let resp = range_request(&format!("bytes={}", ["0-"; 16].join(",")), None)
+            .await
+            .create_response_from_data("hello world")
+            .unwrap();
+        assert!(matches!(
+            resp,
+            StaticFileResponse::Ok {
+                content_range: None,
+                ..
+            }
+        ));
+
+        let resp = range_request("bytes=6-, 0-1, 1-2, 3-4", None)
+            .await
+            .create_response_from_data("hello world")
+            .unwrap();
+        match resp {
+            StaticFileResponse::MultipleRanges { parts, .. } => assert_eq!(
+                parts
+                    .iter()
+                    .map(|(range, _)| range.clone())
+                    .collect::<Vec<_>>(),
+                vec![0..5, 6..11]
+            ),
+            _ => panic!(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_range_not_satisfiable() {
+        let err = range_request("bytes=20-30", None)
+            .await
+            .create_response_from_data("hello world")
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            StaticFileError::RangeNotSatisfiable { size: 11 }
+        ));
+
+        let resp = err.as_response();
+        assert_eq!(resp.status(), StatusCode::RANGE_NOT_SATISFIABLE);
+        assert_eq!(resp.headers()[header::CONTENT_RANGE], "bytes */11");
+    }
+
+    #[tokio::test]
+    async fn test_if_range() {
+        let etag = check_response(Request::default()).await.unwrap().etag();
+
+        let resp = range_request("bytes=0-9", Some(&etag))
+            .await
+            .create_response(Path::new("Cargo.toml"), false)
+            .unwrap();
+        assert!(matches!(
+            resp,
+            StaticFileResponse::Ok {
+                content_range: Some(_),
+                ..
+            }
+        ));
+
+        // the ranges of a modified file are ignored
+        let resp = range_request("bytes=0-9", Some("\"abc\""))
+            .await
+            .create_response(Path::new("Cargo.toml"), false)
+            .unwrap();
+        assert!(matches!(
+            resp,
+            StaticFileResponse::Ok {
+                content_range: None,
+                ..
+            }
+        ));
+    }
 }
@@ -0,0 +1,46 @@
+/// The information of the TLS session of a connection.
+///
+/// It is added to the request data by the TLS listeners of `poem` when the
+/// handshake is complete, so it can be extracted with
+/// [`Data<&TlsInfo>`](crate::web::Data) or read with
+/// [`Request::data`](crate::Request::data). It is also returned by
+/// [`ConnectionInfo::tls`](crate::ConnectionInfo::tls).
+///
+/// # Example
+///
+/// ```
+/// use poem::{handler, web::TlsInfo};
+///
+/// #[handler]
+/// fn index(tls: Option<poem::web::Data<&TlsInfo>>) -> String {
+///     match tls.and_then(|tls| tls.0.peer_certificate().map(<[u8]>::len)) {
+///         Some(len) => format!("client certificate of {len} bytes"),
+///         None => "no client certificate".to_string(),
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct TlsInfo {
+    /// The negotiated application protocol, such as `h2`.
+    ///
+    /// It is not available with the `native-tls` listener.
+    pub alpn_protocol: Option<Vec<u8>>,
+    /// The negotiated protocol version, such as `TLSv1.3`.
+    ///
+    /// It is not available with the `native-tls` listener.
+    pub protocol_version: Option<String>,
+    /// The DER-encoded certificates presented by the client, starting with
+    /// the certificate of the client.
+    ///
+    /// They are only present if the listener requests a client certificate,
+    /// and they are verified by the listener during the handshake.
+    pub peer_certificates: Vec<Vec<u8>>,
+}
+
+impl TlsInfo {
+    /// Returns the DER-encoded certificate of the client, if any.
+    #[inline]
+    pub fn peer_certificate(&self) -> Option<&[u8]> {
+        self.peer_certificates.first().map(Vec::as_slice)
+    }
+}
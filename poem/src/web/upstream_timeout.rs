@@ -0,0 +1,90 @@
+use std::{future::Future, time::Duration};
+
+use crate::error::GatewayTimeoutError;
+
+/// Runs a future that depends on an upstream service, and returns
+/// [`GatewayTimeoutError`] (`504 Gateway Timeout`) if it does not complete
+/// within the specified duration.
+///
+/// Unlike a timeout for the whole handler, this only time-boxes the upstream
+/// call, so the error can be distinguished from the server being overloaded.
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+///
+/// use poem::{handler, http::StatusCode, test::TestClient, web::with_upstream_timeout, Result};
+///
+/// #[handler]
+/// async fn index() -> Result<String> {
+///     let value = with_upstream_timeout(Duration::from_millis(10), async {
+///         tokio::time::sleep(Duration::from_secs(1)).await;
+///         "upstream".to_string()
+///     })
+///     .await?;
+///     Ok(value)
+/// }
+///
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// let cli = TestClient::new(index);
+/// cli.get("/")
+///     .send()
+///     .await
+///     .assert_status(StatusCode::GATEWAY_TIMEOUT);
+/// # });
+/// ```
+pub async fn with_upstream_timeout<F>(
+    duration: Duration,
+    fut: F,
+) -> Result<F::Output, GatewayTimeoutError>
+where
+    F: Future,
+{
+    tokio::time::timeout(duration, fut)
+        .await
+        .map_err(|_| GatewayTimeoutError)
+}
+
+#[cfg(test)]
+mod tests {
+    use http::StatusCode;
+
+    use super::*;
+    use crate::{error::ResponseError, handler, test::TestClient, Result};
+
+    #[tokio::test]
+    async fn test_upstream_timeout() {
+        assert_eq!(
+            with_upstream_timeout(Duration::from_secs(1), async { 1 }).await,
+            Ok(1)
+        );
+
+        let err = with_upstream_timeout(
+            Duration::from_millis(10),
+            tokio::time::sleep(Duration::from_secs(1)),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(err.status(), StatusCode::GATEWAY_TIMEOUT);
+    }
+
+    #[tokio::test]
+    async fn test_upstream_timeout_handler() {
+        #[handler(internal)]
+        async fn index() -> Result<()> {
+            with_upstream_timeout(
+                Duration::from_millis(10),
+                tokio::time::sleep(Duration::from_secs(1)),
+            )
+            .await?;
+            Ok(())
+        }
+
+        TestClient::new(index)
+            .get("/")
+            .send()
+            .await
+            .assert_status(StatusCode::GATEWAY_TIMEOUT);
+    }
+}